@@ -100,7 +100,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                 },
             };
         let path = CanonicalizedComponentFunctionPath {
-            component: component_path,
+            component: component_path.clone(),
             udf_path: CanonicalizedUdfPath::new(
                 HTTP_MODULE_PATH.clone(),
                 FunctionName::default_export(),
@@ -154,6 +154,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
         let send_log_line = |log_line| {
             self.function_log.log_http_action_progress(
                 route.clone(),
+                component_path.clone(),
                 unix_timestamp,
                 context_.clone(),
                 vec![log_line].into(),
@@ -181,6 +182,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                 self.function_log
                     .log_http_action(
                         outcome,
+                        component_path,
                         result_for_logging,
                         log_lines,
                         start.elapsed(),
@@ -231,6 +233,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                         self.function_log
                             .log_http_action(
                                 outcome.clone(),
+                                component_path,
                                 Ok(r),
                                 log_lines,
                                 start.elapsed(),
@@ -257,6 +260,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                         self.function_log
                             .log_http_action(
                                 outcome.clone(),
+                                component_path,
                                 Err(js_err),
                                 log_lines,
                                 start.elapsed(),
@@ -275,6 +279,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                     .log_http_action_system_error(
                         &e,
                         request_head,
+                        component_path,
                         identity.into(),
                         start,
                         caller,
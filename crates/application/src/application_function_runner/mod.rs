@@ -161,6 +161,7 @@ use storage::Storage;
 use sync_types::{
     types::SerializedArgs,
     CanonicalizedModulePath,
+    CanonicalizedUdfPath,
 };
 use tokio::{
     select,
@@ -218,6 +219,7 @@ use crate::{
     audit_logging::AuditLogClient,
     cache::{
         CacheManager,
+        CacheStats,
         QueryCache,
     },
     function_log::{
@@ -732,6 +734,17 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
         Ok(())
     }
 
+    /// Size and recent hit rate of the query cache.
+    pub fn query_cache_stats(&self) -> CacheStats {
+        self.cache_manager.stats()
+    }
+
+    /// Drop every cached result of `udf_path`, returning how many cache
+    /// entries were removed.
+    pub fn invalidate_cached_udf(&self, udf_path: &CanonicalizedUdfPath) -> usize {
+        self.cache_manager.invalidate_udf(udf_path)
+    }
+
     // Only used for running queries from REPLs.
     pub async fn run_query_without_caching(
         &self,
@@ -1923,6 +1936,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                 journal,
                 caller.clone(),
                 usage_tracker.clone(),
+                None,
             )
             .await;
 
@@ -172,6 +172,24 @@ pub fn log_cache_size(size: usize) {
     log_gauge(&CACHE_SIZE_BYTES, size as f64)
 }
 
+// n.b. this gauge is safe in a multi-instance context because it is shared
+// across all instances.
+register_convex_gauge!(
+    CACHE_OLDEST_WAITING_ENTRY_AGE_SECONDS,
+    "Age of the oldest cache entry waiting on an executor's result"
+);
+pub fn log_oldest_waiting_entry_age(age: Duration) {
+    log_gauge(&CACHE_OLDEST_WAITING_ENTRY_AGE_SECONDS, age.as_secs_f64())
+}
+
+register_convex_counter!(
+    CACHE_SIZE_DRIFT_BYTES_TOTAL,
+    "Bytes of drift between the cache's tracked size and its recomputed size"
+);
+pub fn log_cache_size_drift(drift_bytes: usize) {
+    log_counter(&CACHE_SIZE_DRIFT_BYTES_TOTAL, drift_bytes as u64)
+}
+
 register_convex_counter!(
     QUERY_BANDWIDTH_BYTES,
     "Database bandwidth used for queries",
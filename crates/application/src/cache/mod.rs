@@ -1,6 +1,9 @@
 use std::{
     cmp,
-    collections::BTreeMap,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
     iter,
     mem,
     sync::{
@@ -33,6 +36,7 @@ use common::{
     knobs::{
         DATABASE_UDF_SYSTEM_TIMEOUT,
         DATABASE_UDF_USER_TIMEOUT,
+        UDF_CACHE_HIT_RATE_WINDOW,
     },
     query_journal::QueryJournal,
     runtime::Runtime,
@@ -46,6 +50,7 @@ use common::{
     },
 };
 use database::{
+    timestamp_in_future_error,
     Database,
     Token,
 };
@@ -59,7 +64,9 @@ use lru::LruCache;
 use metrics::{
     get_timer,
     log_cache_size,
+    log_cache_size_drift,
     log_drop_cache_result_too_old,
+    log_oldest_waiting_entry_age,
     log_perform_go,
     log_perform_wait_peer_timeout,
     log_perform_wait_self_timeout,
@@ -82,7 +89,10 @@ use smallvec::{
     smallvec,
     SmallVec,
 };
-use sync_types::types::SerializedArgs;
+use sync_types::{
+    types::SerializedArgs,
+    CanonicalizedUdfPath,
+};
 use udf::{
     validation::ValidatedPathAndArgs,
     FunctionOutcome,
@@ -102,6 +112,22 @@ use crate::{
 };
 
 mod metrics;
+mod refresh;
+mod seed;
+mod stats;
+
+use refresh::TokenRefreshes;
+pub use seed::SerializedCacheEntry;
+pub use stats::{
+    CacheStats,
+    IdentityCategory,
+    IdentityCategoryStats,
+};
+use stats::{
+    HitRateWindow,
+    IdentityHitRates,
+    IdentityUsage,
+};
 
 static TOTAL_QUERY_TIMEOUT: LazyLock<Duration> =
     LazyLock::new(|| *DATABASE_UDF_USER_TIMEOUT + *DATABASE_UDF_SYSTEM_TIMEOUT);
@@ -112,6 +138,11 @@ static TOTAL_QUERY_TIMEOUT: LazyLock<Duration> =
 static MAX_CACHE_AGE: LazyLock<Duration> =
     LazyLock::new(|| *TOTAL_QUERY_TIMEOUT + Duration::from_secs(1));
 
+/// How long to wait for a requested timestamp past the latest commit to become
+/// readable before rejecting the request.
+const FUTURE_TS_GRACE_PERIOD: Duration = Duration::from_secs(1);
+const FUTURE_TS_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 #[derive(Clone)]
 pub struct CacheManager<RT: Runtime> {
     rt: RT,
@@ -122,6 +153,9 @@ pub struct CacheManager<RT: Runtime> {
 
     tenant_id: QueryCacheTenantId,
     cache: QueryCache,
+    hit_rate: Arc<HitRateWindow>,
+    identity_hit_rates: Arc<IdentityHitRates>,
+    token_refreshes: TokenRefreshes,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
@@ -148,6 +182,8 @@ pub struct RequestedCacheKey {
     identity: IdentityCacheKey,
     journal: QueryJournal,
     allowed_visibility: AllowedVisibility,
+    /// Overrides `MAX_CACHE_AGE` for results that observed system time.
+    max_cache_age: Option<Duration>,
 }
 
 impl RequestedCacheKey {
@@ -163,6 +199,7 @@ impl RequestedCacheKey {
                 identity: None,
                 journal: self.journal.clone(),
                 allowed_visibility: self.allowed_visibility,
+                max_cache_age: self.max_cache_age,
             },
         ]
     }
@@ -175,6 +212,7 @@ impl RequestedCacheKey {
             identity: Some(self.identity.clone()),
             journal: self.journal.clone(),
             allowed_visibility: self.allowed_visibility,
+            max_cache_age: self.max_cache_age,
         }
     }
 
@@ -209,6 +247,7 @@ impl RequestedCacheKey {
             identity,
             journal: self.journal.clone(),
             allowed_visibility: self.allowed_visibility,
+            max_cache_age: self.max_cache_age,
         };
         if self.journal != outcome.journal {
             // Record the result under *both* the original journal and the new
@@ -247,9 +286,16 @@ pub struct StoredCacheKey {
     identity: Option<IdentityCacheKey>,
     journal: QueryJournal,
     allowed_visibility: AllowedVisibility,
+    // Part of the key so that calls tolerating different staleness don't
+    // serve each other's results.
+    max_cache_age: Option<Duration>,
 }
 
 impl StoredCacheKey {
+    fn identity_category(&self) -> IdentityCategory {
+        IdentityCategory::of(self.identity.as_ref())
+    }
+
     /// Approximate size in-memory of the CacheEntry structure, including stack
     /// and heap allocated memory.
     fn size(&self) -> usize {
@@ -294,6 +340,21 @@ struct CacheResult {
     token: Token,
 }
 
+impl CacheResult {
+    /// Results are ordered by `original_ts` and then by the token's validity
+    /// timestamp, so of two results computed at the same timestamp the one
+    /// whose read set is known to be valid for longer is fresher.
+    fn freshness(&self) -> (Timestamp, Timestamp) {
+        (self.original_ts, self.token.ts())
+    }
+}
+
+/// Whether a result with `new` freshness should replace a cached result with
+/// `existing` freshness. Exact ties keep the existing result.
+fn replaces_cached_result(existing: (Timestamp, Timestamp), new: (Timestamp, Timestamp)) -> bool {
+    existing < new
+}
+
 impl HeapSize for CacheResult {
     fn heap_size(&self) -> usize {
         self.outcome.heap_size() + self.original_ts.heap_size() + self.token.heap_size()
@@ -320,13 +381,99 @@ impl<RT: Runtime> CacheManager<RT> {
             audit_log_client,
             tenant_id,
             cache,
+            hit_rate: Arc::new(HitRateWindow::new(*UDF_CACHE_HIT_RATE_WINDOW)),
+            identity_hit_rates: Arc::new(IdentityHitRates::new(*UDF_CACHE_HIT_RATE_WINDOW)),
+            token_refreshes: TokenRefreshes::default(),
         }
     }
 
+    /// Serialize this deployment's ready cache entries, e.g. on a warm standby
+    /// so that a starting backend can load them with
+    /// [`Self::import_entries`]. Entries that can't be serialized, like those
+    /// whose token includes search reads, are skipped.
+    pub fn export_entries(&self) -> Vec<SerializedCacheEntry> {
+        self.cache.export_entries(self.tenant_id)
+    }
+
+    /// Load entries exported by [`Self::export_entries`]. An entry is only
+    /// added once `validate_cache_result` has refreshed its token to the
+    /// current timestamp, as for any cache hit. Entries that can't be parsed
+    /// or are no longer valid are skipped. Returns the number of entries
+    /// added.
+    pub async fn import_entries(&self, entries: Vec<SerializedCacheEntry>) -> usize {
+        let ts = *self.database.now_ts_for_reads();
+        self.cache
+            .import_entries(self.tenant_id, entries, async |key, result| {
+                self.validate_cache_result(key, ts, result).await
+            })
+            .await
+    }
+
+    /// Recompute the cache's tracked size from its entries, correcting any
+    /// drift in the incremental accounting. The cache is shared by every
+    /// deployment in the process, so this covers all of their entries.
+    pub fn recompute_size(&self) -> RecomputedCacheSize {
+        self.cache.inner.lock().recompute_size()
+    }
+
+    /// Remove this deployment's cached results for `udf_path`, whatever their
+    /// arguments, identity or component, and return how many entries were
+    /// removed. Queries that are already running may still cache their
+    /// results when they finish.
+    pub fn invalidate_udf(&self, udf_path: &CanonicalizedUdfPath) -> usize {
+        self.cache.inner.lock().remove_udf(self.tenant_id, udf_path)
+    }
+
+    /// Report the cache's size and this deployment's recent hit rate. Like
+    /// [`Self::recompute_size`], the entry counts and size cover every
+    /// deployment sharing the cache.
+    pub fn stats(&self) -> CacheStats {
+        let (num_entries, num_waiting, size_bytes) = {
+            let inner = self.cache.inner.lock();
+            (inner.cache.len(), inner.waiting_started.len(), inner.size)
+        };
+        let (recent_gets, recent_hit_rate) = self.hit_rate.rate();
+        CacheStats {
+            num_entries,
+            num_ready: num_entries.saturating_sub(num_waiting),
+            num_waiting,
+            size_bytes,
+            recent_gets,
+            recent_hit_rate,
+        }
+    }
+
+    /// Break [`Self::stats`] down by the identity category of each entry's
+    /// cache key. Reads count towards the category of the key their result
+    /// is cached under, so a user's read of a query that doesn't read
+    /// `ctx.auth` counts as [`IdentityCategory::Public`].
+    pub fn stats_by_identity(&self) -> BTreeMap<IdentityCategory, IdentityCategoryStats> {
+        let usage = self.cache.inner.lock().identity_usage;
+        IdentityCategory::ALL
+            .into_iter()
+            .map(|category| {
+                let (num_entries, size_bytes) = usage.get(category);
+                let (recent_gets, recent_hit_rate) = self.identity_hit_rates.get(category).rate();
+                let stats = IdentityCategoryStats {
+                    num_entries,
+                    size_bytes,
+                    recent_gets,
+                    recent_hit_rate,
+                };
+                (category, stats)
+            })
+            .collect()
+    }
+
     /// Execute a UDF with the given arguments and identity at a particular
     /// timestamp. This function internally handles LRU caching these
     /// function executions and ensuring that served cache values are
     /// consistent as of the given timestamp.
+    ///
+    /// `max_cache_age` bounds how old a cached result that observed system
+    /// time may be, defaulting to `MAX_CACHE_AGE`. Overrides shorter than the
+    /// query timeout can make slow time-dependent queries retry until they
+    /// time out.
     #[fastrace::trace]
     pub async fn get(
         &self,
@@ -338,6 +485,7 @@ impl<RT: Runtime> CacheManager<RT> {
         journal: Option<QueryJournal>,
         caller: FunctionCaller,
         usage_tracker: FunctionUsageTracker,
+        max_cache_age: Option<Duration>,
     ) -> anyhow::Result<QueryReturn> {
         let timer = get_timer();
         let result = self
@@ -350,10 +498,13 @@ impl<RT: Runtime> CacheManager<RT> {
                 journal,
                 caller,
                 usage_tracker,
+                max_cache_age,
             )
             .await;
         match &result {
-            Ok((query_return, is_cache_hit)) => {
+            Ok((query_return, is_cache_hit, category)) => {
+                self.hit_rate.record(*is_cache_hit);
+                self.identity_hit_rates.get(*category).record(*is_cache_hit);
                 succeed_get_timer(
                     timer,
                     *is_cache_hit,
@@ -377,8 +528,22 @@ impl<RT: Runtime> CacheManager<RT> {
         journal: Option<QueryJournal>,
         caller: FunctionCaller,
         usage_tracker: FunctionUsageTracker,
-    ) -> anyhow::Result<(QueryReturn, bool)> {
+        max_cache_age: Option<Duration>,
+    ) -> anyhow::Result<(QueryReturn, bool, IdentityCategory)> {
         let start = self.rt.monotonic_now();
+        // The timestamp may come from a commit this backend hasn't observed yet,
+        // so give the latest timestamp a moment to catch up before rejecting it.
+        loop {
+            let latest_ts = *self.database.now_ts_for_reads();
+            if ts <= latest_ts {
+                break;
+            }
+            anyhow::ensure!(
+                self.rt.monotonic_now() - start < FUTURE_TS_GRACE_PERIOD,
+                timestamp_in_future_error(ts, latest_ts)
+            );
+            self.rt.wait(FUTURE_TS_POLL_INTERVAL).await;
+        }
         let identity_cache_key = identity.cache_key();
         let requested_key = RequestedCacheKey {
             tenant_id: self.tenant_id,
@@ -387,6 +552,7 @@ impl<RT: Runtime> CacheManager<RT> {
             identity: identity_cache_key,
             journal: journal.unwrap_or_else(QueryJournal::new),
             allowed_visibility: caller.allowed_visibility(),
+            max_cache_age,
         };
         let context = ExecutionContext::new(request_context, &caller);
         // If the query exists at some cache key, but the cached entry is invalid,
@@ -516,13 +682,20 @@ impl<RT: Runtime> CacheManager<RT> {
                     context.clone(),
                 )
                 .await;
+            // Hits and misses alike are filed under the key the result is
+            // cached under.
+            let category = if cache_result.outcome.observed_identity {
+                IdentityCategory::of(Some(&requested_key.identity))
+            } else {
+                IdentityCategory::Public
+            };
             let result = QueryReturn {
                 result: cache_result.outcome.result.clone(),
                 log_lines: cache_result.outcome.log_lines.clone(),
                 token: cache_result.token,
                 journal: cache_result.outcome.journal.clone(),
             };
-            return Ok((result, is_cache_hit));
+            return Ok((result, is_cache_hit, category));
         }
     }
 
@@ -691,7 +864,11 @@ impl<RT: Runtime> CacheManager<RT> {
             log_validate_ts_too_old();
             return Ok(None);
         }
-        result.token = match self.database.refresh_token(result.token, ts).await? {
+        let refreshed = self
+            .token_refreshes
+            .refresh(&self.database, key, result.original_ts, result.token, ts)
+            .await?;
+        result.token = match refreshed {
             Ok(t) => t,
             Err(_invalid_ts) => {
                 tracing::debug!(
@@ -705,10 +882,11 @@ impl<RT: Runtime> CacheManager<RT> {
             },
         };
         if result.outcome.observed_time {
+            let max_cache_age = key.max_cache_age.unwrap_or(*MAX_CACHE_AGE);
             let sys_now = self.rt.unix_timestamp();
             let cached_time = result.outcome.unix_timestamp;
             match sys_now.checked_sub(cached_time) {
-                Some(entry_age) if entry_age > *MAX_CACHE_AGE => {
+                Some(entry_age) if entry_age > max_cache_age => {
                     tracing::debug!(
                         "Log entry for {:?} used system time and is too old ({:?}), retrying...",
                         key,
@@ -771,12 +949,26 @@ impl Drop for WaitingEntryGuard<'_> {
     }
 }
 
+/// The result of [`CacheManager::recompute_size`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RecomputedCacheSize {
+    /// The size the cache was tracking before it was recomputed.
+    pub tracked_bytes: usize,
+    /// The size of the cache's entries, which it now tracks.
+    pub actual_bytes: usize,
+}
+
 struct Inner {
     cache: LruCache<StoredCacheKey, CacheEntry>,
     size: usize,
     size_limit: usize,
 
     next_waiting_id: u64,
+    // `(started, id)` for every `CacheEntry::Waiting` in `cache`, so the oldest
+    // one can be found without scanning the cache.
+    waiting_started: BTreeSet<(tokio::time::Instant, u64)>,
+    // `size` and the number of entries, broken down by identity category.
+    identity_usage: IdentityUsage,
 }
 
 #[derive(Clone)]
@@ -791,6 +983,8 @@ impl QueryCache {
             size: 0,
             next_waiting_id: 0,
             size_limit,
+            waiting_started: BTreeSet::new(),
+            identity_usage: IdentityUsage::default(),
         };
         Self {
             inner: Arc::new(Mutex::new(inner)),
@@ -830,6 +1024,7 @@ impl QueryCache {
             }
         };
         let mut inner = self.inner.lock();
+        inner.log_oldest_waiting_entry_age(now);
         let (entry, stored_key) = key.get_cache_entry(&mut inner.cache, stored_key_hint);
         let op = match entry {
             Some(CacheEntry::Ready(r)) => {
@@ -906,6 +1101,67 @@ impl QueryCache {
             inner.put_ready(key, result);
         }
     }
+
+    fn export_entries(&self, tenant_id: QueryCacheTenantId) -> Vec<SerializedCacheEntry> {
+        let ready: Vec<_> = {
+            let inner = self.inner.lock();
+            inner
+                .cache
+                .iter()
+                .filter_map(|(key, entry)| match entry {
+                    CacheEntry::Ready(result) if key.tenant_id == tenant_id => {
+                        Some((key.clone(), result.clone()))
+                    },
+                    CacheEntry::Ready(_) | CacheEntry::Waiting { .. } => None,
+                })
+                .collect()
+        };
+        ready
+            .into_iter()
+            .filter_map(
+                |(key, result)| match SerializedCacheEntry::new(key.clone(), result) {
+                    Ok(entry) => Some(entry),
+                    Err(e) => {
+                        tracing::debug!("Not exporting cache entry for {key:?}: {e:#}");
+                        None
+                    },
+                },
+            )
+            .collect()
+    }
+
+    /// Adds `entries` as ready entries for `tenant_id` once `validate` has
+    /// refreshed them, returning how many were added.
+    async fn import_entries(
+        &self,
+        tenant_id: QueryCacheTenantId,
+        entries: Vec<SerializedCacheEntry>,
+        validate: impl AsyncFn(&StoredCacheKey, CacheResult) -> anyhow::Result<Option<CacheResult>>,
+    ) -> usize {
+        let mut imported = 0;
+        for entry in entries {
+            let (key, result) = match entry.into_cache_entry(tenant_id) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable cache entry: {e:#}");
+                    continue;
+                },
+            };
+            match validate(&key, result).await {
+                Ok(Some(result)) => {
+                    self.put_ready(smallvec![key], result);
+                    imported += 1;
+                },
+                Ok(None) => {},
+                Err(e) => {
+                    tracing::warn!(
+                        "Skipping cache entry for {key:?} that failed to validate: {e:#}"
+                    );
+                },
+            }
+        }
+        imported
+    }
 }
 
 impl Inner {
@@ -915,11 +1171,11 @@ impl Inner {
         match self.cache.get(key) {
             Some(CacheEntry::Waiting { id, .. }) if *id == entry_id => {
                 let (actual_key, entry) = self.cache.pop_entry(key).unwrap();
-                self.size -= actual_key.size() + entry.size();
+                self.forget_entry(&actual_key, &entry);
             },
             _ => (),
         }
-        log_cache_size(self.size)
+        self.log_metrics()
     }
 
     // Remove only a `CacheEntry::Ready` from the cache, predicated on its
@@ -928,11 +1184,30 @@ impl Inner {
         match self.cache.get(key) {
             Some(CacheEntry::Ready(result)) if result.original_ts == original_ts => {
                 let (actual_key, entry) = self.cache.pop_entry(key).unwrap();
-                self.size -= actual_key.size() + entry.size();
+                self.forget_entry(&actual_key, &entry);
             },
             _ => (),
         }
-        log_cache_size(self.size);
+        self.log_metrics();
+    }
+
+    fn remove_udf(
+        &mut self,
+        tenant_id: QueryCacheTenantId,
+        udf_path: &CanonicalizedUdfPath,
+    ) -> usize {
+        let keys: Vec<_> = self
+            .cache
+            .iter()
+            .filter(|(key, _)| key.tenant_id == tenant_id && key.path.udf_path() == udf_path)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &keys {
+            let (actual_key, entry) = self.cache.pop_entry(key).unwrap();
+            self.forget_entry(&actual_key, &entry);
+        }
+        self.log_metrics();
+        keys.len()
     }
 
     fn put_waiting(
@@ -953,11 +1228,18 @@ impl Inner {
             ts,
         };
         let new_size = key.size() + new_entry.size();
-        let old_size = self
-            .cache
-            .push(key, new_entry)
-            .map(|(old_key, old_value)| old_key.size() + old_value.size())
-            .unwrap_or(0);
+        self.waiting_started.insert((now, id));
+        self.identity_usage.add(key.identity_category(), new_size);
+        let old_size = match self.cache.push(key, new_entry) {
+            Some((old_key, old_value)) => {
+                self.forget_waiting(&old_value);
+                let old_size = old_key.size() + old_value.size();
+                self.identity_usage
+                    .remove(old_key.identity_category(), old_size);
+                old_size
+            },
+            None => 0,
+        };
 
         // N.B.: `self.size - old_size` could be _negative_ if `key.size()` was larger
         // than the size of the preexisting key; therefore add before subtracting
@@ -968,29 +1250,37 @@ impl Inner {
     }
 
     // Put a `CacheEntry::Ready` into the cache, potentially dropping it if there's
-    // already a value with a higher `original_ts`.
+    // already a value that is at least as fresh (see `CacheResult::freshness`).
+    // Exact ties keep the existing entry.
     fn put_ready(&mut self, key: StoredCacheKey, result: CacheResult) {
         match self.cache.get_mut(&key) {
             Some(entry @ CacheEntry::Waiting { .. }) => {
                 let new_entry = CacheEntry::Ready(result);
+                self.identity_usage
+                    .resize(key.identity_category(), entry.size(), new_entry.size());
                 self.size -= entry.size();
                 self.size += new_entry.size();
-                *entry = new_entry;
+                let old_entry = mem::replace(entry, new_entry);
+                self.forget_waiting(&old_entry);
             },
             Some(CacheEntry::Ready(existing_result)) => {
-                if existing_result.original_ts < result.original_ts
-                    || (existing_result.original_ts == result.original_ts
-                        && existing_result.token.ts() < result.token.ts())
-                {
+                let existing_freshness = existing_result.freshness();
+                let new_freshness = result.freshness();
+                if replaces_cached_result(existing_freshness, new_freshness) {
+                    self.identity_usage.resize(
+                        key.identity_category(),
+                        existing_result.heap_size(),
+                        result.heap_size(),
+                    );
                     self.size -= existing_result.heap_size();
                     self.size += result.heap_size();
                     *existing_result = result;
                 } else {
                     tracing::debug!(
-                        "dropping cache result for {key:?} because result timestamp {} <= cached \
-                         timestamp {}",
-                        result.original_ts,
-                        existing_result.original_ts
+                        "dropping cache result for {key:?} because result timestamps {:?} <= \
+                         cached timestamps {:?}",
+                        new_freshness,
+                        existing_freshness,
                     );
                     log_drop_cache_result_too_old();
                 }
@@ -998,12 +1288,40 @@ impl Inner {
             None => {
                 let new_entry = CacheEntry::Ready(result);
                 self.size += key.size() + new_entry.size();
+                self.identity_usage
+                    .add(key.identity_category(), key.size() + new_entry.size());
                 self.cache.put(key, new_entry);
             },
         }
         self.enforce_size_limit();
     }
 
+    fn recompute_size(&mut self) -> RecomputedCacheSize {
+        let tracked_bytes = self.size;
+        let mut actual_bytes = 0;
+        let mut identity_usage = IdentityUsage::default();
+        for (key, entry) in self.cache.iter() {
+            let size = key.size() + entry.size();
+            actual_bytes += size;
+            identity_usage.add(key.identity_category(), size);
+        }
+        self.identity_usage = identity_usage;
+        if tracked_bytes != actual_bytes {
+            tracing::warn!(
+                "Query cache size drifted: tracked {tracked_bytes} bytes, but entries take \
+                 {actual_bytes} bytes"
+            );
+            log_cache_size_drift(tracked_bytes.abs_diff(actual_bytes));
+            self.size = actual_bytes;
+            // The corrected size may be over the limit.
+            self.enforce_size_limit();
+        }
+        RecomputedCacheSize {
+            tracked_bytes,
+            actual_bytes,
+        }
+    }
+
     /// Pop records until the cache is under the given size.
     fn enforce_size_limit(&mut self) {
         while self.size > self.size_limit {
@@ -1012,14 +1330,54 @@ impl Inner {
                 .pop_lru()
                 .expect("Cache is too large without any items?");
             self.size -= popped_key.size() + popped_entry.size();
-            if let CacheEntry::Ready(r) = popped_entry {
-                let system_time: SystemTime = r.token.ts().into();
-                if let Ok(t) = system_time.elapsed() {
-                    query_cache_log_eviction(t);
-                }
+            self.identity_usage.remove(
+                popped_key.identity_category(),
+                popped_key.size() + popped_entry.size(),
+            );
+            match popped_entry {
+                CacheEntry::Ready(r) => {
+                    let system_time: SystemTime = r.token.ts().into();
+                    if let Ok(t) = system_time.elapsed() {
+                        query_cache_log_eviction(t);
+                    }
+                },
+                ref entry @ CacheEntry::Waiting { .. } => self.forget_waiting(entry),
             }
         }
-        log_cache_size(self.size)
+        self.log_metrics()
+    }
+
+    /// Untrack an entry that was popped from the cache.
+    fn forget_entry(&mut self, key: &StoredCacheKey, entry: &CacheEntry) {
+        let size = key.size() + entry.size();
+        self.size -= size;
+        self.identity_usage.remove(key.identity_category(), size);
+        self.forget_waiting(entry);
+    }
+
+    fn forget_waiting(&mut self, entry: &CacheEntry) {
+        match entry {
+            CacheEntry::Waiting { id, started, .. } => {
+                self.waiting_started.remove(&(*started, *id));
+            },
+            CacheEntry::Ready(_) => (),
+        }
+    }
+
+    fn log_metrics(&self) {
+        log_cache_size(self.size);
+    }
+
+    // A Waiting entry that stays around far longer than the cache timeout
+    // points at an executor that never broadcast its result. `now` comes from
+    // the caller's runtime clock.
+    fn log_oldest_waiting_entry_age(&self, now: tokio::time::Instant) {
+        let oldest_waiting_age = self
+            .waiting_started
+            .first()
+            .map(|(started, _)| now.saturating_duration_since(*started))
+            .unwrap_or_default();
+        log_oldest_waiting_entry_age(oldest_waiting_age);
     }
 }
 
@@ -1045,3 +1403,199 @@ enum CacheOp<'a> {
         context: ExecutionContext,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common::{
+        bootstrap_model::tables::TABLES_TABLE,
+        components::{
+            CanonicalizedComponentFunctionPath,
+            ComponentPath,
+            PublicFunctionPath,
+        },
+        identity::InertIdentity,
+        log_lines::LogLines,
+        query::{
+            Order,
+            Query,
+        },
+        query_journal::QueryJournal,
+        runtime::UnixTimestamp,
+        types::{
+            AllowedVisibility,
+            Timestamp,
+        },
+    };
+    use database::{
+        test_helpers::DbFixtures,
+        ResolvedQuery,
+        TableModel,
+        Token,
+    };
+    use runtime::testing::TestRuntime;
+    use smallvec::smallvec;
+    use sync_types::{
+        types::SerializedArgs,
+        CanonicalizedUdfPath,
+    };
+    use udf::{
+        SyscallTrace,
+        UdfOutcome,
+    };
+    use value::{
+        ConvexValue,
+        JsonPackedValue,
+        TableNamespace,
+    };
+
+    use super::{
+        replaces_cached_result,
+        CacheEntry,
+        CacheResult,
+        QueryCache,
+        QueryCacheTenantId,
+        RecomputedCacheSize,
+        StoredCacheKey,
+        TokenRefreshes,
+    };
+
+    fn stored_key(udf_path: &str) -> anyhow::Result<StoredCacheKey> {
+        Ok(StoredCacheKey {
+            tenant_id: QueryCacheTenantId(0),
+            path: PublicFunctionPath::RootExport(udf_path.parse::<CanonicalizedUdfPath>()?.into()),
+            args: SerializedArgs::from_args(vec![])?,
+            identity: None,
+            journal: QueryJournal::new(),
+            allowed_visibility: AllowedVisibility::PublicOnly,
+            max_cache_age: None,
+        })
+    }
+
+    fn cache_result(udf_path: &str, token: Token) -> anyhow::Result<CacheResult> {
+        let outcome = UdfOutcome {
+            path: CanonicalizedComponentFunctionPath {
+                component: ComponentPath::root(),
+                udf_path: udf_path.parse()?,
+            },
+            arguments: SerializedArgs::from_args(vec![])?,
+            identity: InertIdentity::System,
+            observed_identity: false,
+            rng_seed: [0; 32],
+            observed_rng: false,
+            unix_timestamp: UnixTimestamp::from_millis(0),
+            observed_time: false,
+            log_lines: LogLines::default(),
+            journal: QueryJournal::new(),
+            audit_log_lines: Default::default(),
+            result: Ok(JsonPackedValue::pack(ConvexValue::Null)),
+            syscall_trace: SyscallTrace::default(),
+            udf_server_version: None,
+            memory_in_mb: 0,
+            user_execution_time: None,
+        };
+        Ok(CacheResult {
+            outcome: Arc::new(outcome),
+            original_ts: token.ts(),
+            token,
+        })
+    }
+
+    #[test]
+    fn test_put_ready_tie_break() {
+        let ts = Timestamp::must;
+        // A later `original_ts` always wins, whatever the token timestamps.
+        assert!(replaces_cached_result((ts(1), ts(5)), (ts(2), ts(2))));
+        assert!(!replaces_cached_result((ts(2), ts(2)), (ts(1), ts(5))));
+        // At equal `original_ts`, the token valid for longer wins.
+        assert!(replaces_cached_result((ts(1), ts(3)), (ts(1), ts(4))));
+        assert!(!replaces_cached_result((ts(1), ts(4)), (ts(1), ts(3))));
+        // Exact ties keep the existing entry.
+        assert!(!replaces_cached_result((ts(1), ts(3)), (ts(1), ts(3))));
+    }
+
+    #[test]
+    fn test_recompute_size_corrects_drift() -> anyhow::Result<()> {
+        let cache = QueryCache::new(1 << 20);
+        let mut inner = cache.inner.lock();
+        let now = tokio::time::Instant::now();
+        inner.put_waiting(stored_key("messages:list")?, now, Timestamp::must(1));
+        inner.put_waiting(stored_key("messages:get")?, now, Timestamp::must(1));
+        let actual_bytes = inner.size;
+        assert!(actual_bytes > 0);
+        assert_eq!(
+            inner.recompute_size(),
+            RecomputedCacheSize {
+                tracked_bytes: actual_bytes,
+                actual_bytes,
+            }
+        );
+
+        inner.size += 1000;
+        assert_eq!(
+            inner.recompute_size(),
+            RecomputedCacheSize {
+                tracked_bytes: actual_bytes + 1000,
+                actual_bytes,
+            }
+        );
+        assert_eq!(inner.size, actual_bytes);
+        Ok(())
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_exported_entries_import_into_fresh_cache(rt: TestRuntime) -> anyhow::Result<()> {
+        let DbFixtures { db, .. } = DbFixtures::new_with_model(&rt).await?;
+        let tenant_id = QueryCacheTenantId(0);
+
+        // `messages:list` read `_tables`, which creating a table below writes
+        // to. `messages:count` read nothing, so it stays valid.
+        let mut tx = db.begin_system().await?;
+        let mut query = ResolvedQuery::new(
+            &mut tx,
+            TableNamespace::Global,
+            Query::full_table_scan(TABLES_TABLE.clone(), Order::Asc),
+        )?;
+        while query.next(&mut tx, None).await?.is_some() {}
+        let invalidated_token = tx.into_token()?;
+        let valid_token = db.begin_system().await?.into_token()?;
+        let exporting = QueryCache::new(1 << 20);
+        exporting.put_ready(
+            smallvec![stored_key("messages:list")?],
+            cache_result("messages:list", invalidated_token)?,
+        );
+        exporting.put_ready(
+            smallvec![stored_key("messages:count")?],
+            cache_result("messages:count", valid_token)?,
+        );
+        let entries = exporting.export_entries(tenant_id);
+        assert_eq!(entries.len(), 2);
+
+        let mut tx = db.begin_system().await?;
+        TableModel::new(&mut tx)
+            .insert_table_metadata(TableNamespace::root_component(), &"messages".parse()?)
+            .await?;
+        db.commit_with_write_source(tx, "test").await?;
+
+        let ts = *db.now_ts_for_reads();
+        let token_refreshes = TokenRefreshes::default();
+        let importing = QueryCache::new(1 << 20);
+        let imported = importing
+            .import_entries(tenant_id, entries, async |key, result| {
+                let refreshed = token_refreshes
+                    .refresh(&db, key, result.original_ts, result.token.clone(), ts)
+                    .await?;
+                Ok(refreshed.ok().map(|token| CacheResult { token, ..result }))
+            })
+            .await;
+        assert_eq!(imported, 1);
+        let inner = importing.inner.lock();
+        assert!(matches!(
+            inner.cache.peek(&stored_key("messages:count")?),
+            Some(CacheEntry::Ready(result)) if result.token.ts() == ts
+        ));
+        assert!(inner.cache.peek(&stored_key("messages:list")?).is_none());
+        Ok(())
+    }
+}
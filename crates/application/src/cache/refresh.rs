@@ -0,0 +1,113 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+};
+
+use common::{
+    runtime::Runtime,
+    types::Timestamp,
+};
+use database::{
+    Database,
+    Token,
+};
+use futures::{
+    future::{
+        BoxFuture,
+        Shared,
+    },
+    FutureExt,
+};
+use parking_lot::Mutex;
+
+use super::StoredCacheKey;
+
+/// Everyone validating the ready entry for a `StoredCacheKey` at some
+/// `original_ts` holds the same token, so `(key, original_ts, ts)` identifies
+/// a `(token, ts)` refresh.
+type RefreshKey = (StoredCacheKey, Timestamp, Timestamp);
+
+/// `None` if the shared `refresh_token` call failed.
+type RefreshFuture = Shared<BoxFuture<'static, Option<Result<Token, Option<Timestamp>>>>>;
+
+/// Deduplicates `Database::refresh_token` calls from concurrent
+/// `CacheManager::validate_cache_result`s, so a hot query with many waiters
+/// refreshes its cached token once per timestamp rather than once per waiter.
+#[derive(Clone, Default)]
+pub(super) struct TokenRefreshes {
+    in_flight: Arc<Mutex<HashMap<RefreshKey, RefreshFuture>>>,
+}
+
+impl TokenRefreshes {
+    /// Refreshes `token`, which belongs to the ready entry for `key` at
+    /// `original_ts`, to `ts`, joining a refresh already in flight for the same
+    /// entry and `ts` if there is one. The lock is only held to look up or
+    /// register the refresh, never while it runs.
+    ///
+    /// If the shared call fails, each caller retries with its own call, so one
+    /// failure isn't reported to every waiter.
+    pub(super) async fn refresh<RT: Runtime>(
+        &self,
+        database: &Database<RT>,
+        key: &StoredCacheKey,
+        original_ts: Timestamp,
+        token: Token,
+        ts: Timestamp,
+    ) -> anyhow::Result<Result<Token, Option<Timestamp>>> {
+        let refresh_key = (key.clone(), original_ts, ts);
+        let refresh = self
+            .in_flight
+            .lock()
+            .entry(refresh_key.clone())
+            .or_insert_with(|| {
+                let database = database.clone();
+                let token = token.clone();
+                async move {
+                    match database.refresh_token(token, ts).await {
+                        Ok(result) => Some(result),
+                        Err(e) => {
+                            tracing::debug!("Shared token refresh to {ts} failed: {e:#}");
+                            None
+                        },
+                    }
+                }
+                .boxed()
+                .shared()
+            })
+            .clone();
+        let result = {
+            let _guard = InFlightGuard {
+                in_flight: &self.in_flight,
+                key: refresh_key,
+                refresh: refresh.clone(),
+            };
+            refresh.await
+        };
+        match result {
+            Some(result) => Ok(result),
+            None => database.refresh_token(token, ts).await,
+        }
+    }
+}
+
+/// Unregisters a refresh once a caller is done with it, including when the
+/// caller is canceled, so later validations start a new one. Callers that
+/// already joined keep their clone of the future.
+struct InFlightGuard<'a> {
+    in_flight: &'a Mutex<HashMap<RefreshKey, RefreshFuture>>,
+    key: RefreshKey,
+    refresh: RefreshFuture,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock();
+        // Another refresh may have been registered for the key since.
+        if in_flight
+            .get(&self.key)
+            .is_some_and(|refresh| refresh.ptr_eq(&self.refresh))
+        {
+            in_flight.remove(&self.key);
+        }
+    }
+}
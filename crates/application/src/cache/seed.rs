@@ -0,0 +1,295 @@
+use std::{
+    collections::BTreeSet,
+    sync::Arc,
+    time::Duration,
+};
+
+use common::{
+    components::{
+        CanonicalizedComponentFunctionPath,
+        ComponentId,
+        ExportPath,
+        PublicFunctionPath,
+        ResolvedComponentFunctionPath,
+    },
+    identity::{
+        IdentityCacheKey,
+        InertIdentity,
+    },
+    query_journal::QueryJournal,
+    types::{
+        AllowedVisibility,
+        Timestamp,
+    },
+};
+use database::{
+    SerializedToken,
+    Token,
+};
+use pb::{
+    convex_query_journal::QueryJournal as QueryJournalProto,
+    outcome::UdfOutcome as UdfOutcomeProto,
+};
+use prost::Message;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value as JsonValue;
+use sync_types::{
+    types::SerializedArgs,
+    UserIdentityAttributes,
+};
+use udf::UdfOutcome;
+use value::base64;
+
+use super::{
+    CacheResult,
+    QueryCacheTenantId,
+    StoredCacheKey,
+};
+
+/// A ready query cache entry, as exported by
+/// [`super::CacheManager::export_entries`] so another backend for the same
+/// deployment can load it with [`super::CacheManager::import_entries`].
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializedCacheEntry {
+    path: SerializedCachePath,
+    args: String,
+    identity: Option<SerializedIdentityCacheKey>,
+    /// URL-safe base64 `QueryJournal` proto.
+    journal: String,
+    allowed_visibility: SerializedAllowedVisibility,
+    max_cache_age: Option<Duration>,
+
+    /// URL-safe base64 `UdfOutcome` proto, which doesn't include the path,
+    /// arguments, identity and server version the outcome is logged with.
+    outcome: String,
+    outcome_component: String,
+    outcome_udf_path: String,
+    outcome_arguments: String,
+    outcome_identity: String,
+    udf_server_version: Option<String>,
+
+    original_ts: u64,
+    token: SerializedToken,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum SerializedCachePath {
+    #[serde(rename_all = "camelCase")]
+    RootExport { udf_path: String },
+    #[serde(rename_all = "camelCase")]
+    Component { component: String, udf_path: String },
+    #[serde(rename_all = "camelCase")]
+    ResolvedComponent {
+        component_id: Option<String>,
+        component_path: String,
+        udf_path: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum SerializedIdentityCacheKey {
+    #[serde(rename_all = "camelCase")]
+    DeploymentAdmin {
+        deployment_name: String,
+        allowed_ops: BTreeSet<String>,
+    },
+    System,
+    #[serde(rename_all = "camelCase")]
+    Unknown {
+        error_message: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    User {
+        attributes: JsonValue,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+enum SerializedAllowedVisibility {
+    PublicOnly,
+    All,
+}
+
+impl SerializedCacheEntry {
+    pub(super) fn new(
+        StoredCacheKey {
+            tenant_id: _,
+            path,
+            args,
+            identity,
+            journal,
+            allowed_visibility,
+            max_cache_age,
+        }: StoredCacheKey,
+        CacheResult {
+            outcome,
+            original_ts,
+            token,
+        }: CacheResult,
+    ) -> anyhow::Result<Self> {
+        let path = match path {
+            PublicFunctionPath::RootExport(path) => SerializedCachePath::RootExport {
+                udf_path: String::from(path),
+            },
+            PublicFunctionPath::Component(path) => SerializedCachePath::Component {
+                component: String::from(path.component),
+                udf_path: path.udf_path.to_string(),
+            },
+            PublicFunctionPath::ResolvedComponent(path) => SerializedCachePath::ResolvedComponent {
+                component_id: path.component.serialize_to_string(),
+                component_path: String::from(path.component_path),
+                udf_path: path.udf_path.to_string(),
+            },
+        };
+        let identity = identity
+            .map(|identity| {
+                anyhow::Ok(match identity {
+                    IdentityCacheKey::DeploymentAdmin {
+                        deployment_name,
+                        allowed_ops,
+                    } => SerializedIdentityCacheKey::DeploymentAdmin {
+                        deployment_name,
+                        allowed_ops,
+                    },
+                    IdentityCacheKey::System => SerializedIdentityCacheKey::System,
+                    IdentityCacheKey::Unknown(error_message) => {
+                        SerializedIdentityCacheKey::Unknown { error_message }
+                    },
+                    IdentityCacheKey::User(attributes) => SerializedIdentityCacheKey::User {
+                        attributes: Arc::unwrap_or_clone(attributes).try_into()?,
+                    },
+                })
+            })
+            .transpose()?;
+        let allowed_visibility = match allowed_visibility {
+            AllowedVisibility::PublicOnly => SerializedAllowedVisibility::PublicOnly,
+            AllowedVisibility::All => SerializedAllowedVisibility::All,
+        };
+        let outcome = Arc::unwrap_or_clone(outcome);
+        let outcome_component = String::from(outcome.path.component.clone());
+        let outcome_udf_path = outcome.path.udf_path.to_string();
+        let outcome_arguments = outcome.arguments.get().to_string();
+        let outcome_identity = outcome.identity.to_string();
+        let udf_server_version = outcome.udf_server_version.as_ref().map(|v| v.to_string());
+        let outcome = UdfOutcomeProto::try_from(outcome)?;
+        Ok(Self {
+            path,
+            args: args.get().to_string(),
+            identity,
+            journal: base64::encode_urlsafe(&QueryJournalProto::from(journal).encode_to_vec()),
+            allowed_visibility,
+            max_cache_age,
+            outcome: base64::encode_urlsafe(&outcome.encode_to_vec()),
+            outcome_component,
+            outcome_udf_path,
+            outcome_arguments,
+            outcome_identity,
+            udf_server_version,
+            original_ts: original_ts.into(),
+            token: token.serialize()?,
+        })
+    }
+
+    /// Parse the entry as a cache entry for `tenant_id`. The result's token is
+    /// as of when it was exported, so it must be validated before it's used.
+    pub(super) fn into_cache_entry(
+        self,
+        tenant_id: QueryCacheTenantId,
+    ) -> anyhow::Result<(StoredCacheKey, CacheResult)> {
+        let Self {
+            path,
+            args,
+            identity,
+            journal,
+            allowed_visibility,
+            max_cache_age,
+            outcome,
+            outcome_component,
+            outcome_udf_path,
+            outcome_arguments,
+            outcome_identity,
+            udf_server_version,
+            original_ts,
+            token,
+        } = self;
+        let path = match path {
+            SerializedCachePath::RootExport { udf_path } => {
+                PublicFunctionPath::RootExport(udf_path.parse::<ExportPath>()?)
+            },
+            SerializedCachePath::Component {
+                component,
+                udf_path,
+            } => PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: component.parse()?,
+                udf_path: udf_path.parse()?,
+            }),
+            SerializedCachePath::ResolvedComponent {
+                component_id,
+                component_path,
+                udf_path,
+            } => PublicFunctionPath::ResolvedComponent(ResolvedComponentFunctionPath {
+                component: ComponentId::deserialize_from_string(component_id.as_deref())?,
+                udf_path: udf_path.parse()?,
+                component_path: component_path.parse()?,
+            }),
+        };
+        let identity = identity
+            .map(|identity| {
+                anyhow::Ok(match identity {
+                    SerializedIdentityCacheKey::DeploymentAdmin {
+                        deployment_name,
+                        allowed_ops,
+                    } => IdentityCacheKey::DeploymentAdmin {
+                        deployment_name,
+                        allowed_ops,
+                    },
+                    SerializedIdentityCacheKey::System => IdentityCacheKey::System,
+                    SerializedIdentityCacheKey::Unknown { error_message } => {
+                        IdentityCacheKey::Unknown(error_message)
+                    },
+                    SerializedIdentityCacheKey::User { attributes } => IdentityCacheKey::User(
+                        Arc::new(UserIdentityAttributes::try_from(attributes)?),
+                    ),
+                })
+            })
+            .transpose()?;
+        let allowed_visibility = match allowed_visibility {
+            SerializedAllowedVisibility::PublicOnly => AllowedVisibility::PublicOnly,
+            SerializedAllowedVisibility::All => AllowedVisibility::All,
+        };
+        let journal: QueryJournal =
+            QueryJournalProto::decode(&*base64::decode_urlsafe(&journal)?)?.try_into()?;
+        let key = StoredCacheKey {
+            tenant_id,
+            path,
+            args: SerializedArgs::from_slice(args.as_bytes())?,
+            identity,
+            journal,
+            allowed_visibility,
+            max_cache_age,
+        };
+        let outcome = UdfOutcome::from_proto_with_path(
+            UdfOutcomeProto::decode(&*base64::decode_urlsafe(&outcome)?)?,
+            CanonicalizedComponentFunctionPath {
+                component: outcome_component.parse()?,
+                udf_path: outcome_udf_path.parse()?,
+            },
+            SerializedArgs::from_slice(outcome_arguments.as_bytes())?,
+            outcome_identity.parse::<InertIdentity>()?,
+            udf_server_version.map(|v| v.parse()).transpose()?,
+        )?;
+        let result = CacheResult {
+            outcome: Arc::new(outcome),
+            original_ts: Timestamp::try_from(original_ts)?,
+            token: Token::deserialize(&token)?,
+        };
+        Ok((key, result))
+    }
+}
@@ -0,0 +1,206 @@
+use std::sync::atomic::{
+    AtomicI64,
+    AtomicU8,
+    AtomicUsize,
+    Ordering,
+};
+
+use common::identity::IdentityCacheKey;
+
+/// A snapshot of the query cache's contents and recent effectiveness, as
+/// returned by [`super::CacheManager::stats`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CacheStats {
+    /// Number of entries in the cache.
+    pub num_entries: usize,
+    /// Entries holding a cached result.
+    pub num_ready: usize,
+    /// Entries for queries that are still running, which other requests can
+    /// wait on.
+    pub num_waiting: usize,
+    /// Approximate size of the cache's entries in memory.
+    pub size_bytes: usize,
+    /// Number of reads the hit rate covers. Lower than the window size until
+    /// that many reads have happened.
+    pub recent_gets: usize,
+    /// Fraction of `recent_gets` served from the cache, or `None` if there
+    /// haven't been any.
+    pub recent_hit_rate: Option<f64>,
+}
+
+/// Who a cached query result can be served to, by its cache key's identity.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum IdentityCategory {
+    /// The query didn't read `ctx.auth`, so its result is shared by every
+    /// identity.
+    Public,
+    System,
+    Admin,
+    User,
+    Unknown,
+}
+
+impl IdentityCategory {
+    pub const ALL: [IdentityCategory; 5] = [
+        IdentityCategory::Public,
+        IdentityCategory::System,
+        IdentityCategory::Admin,
+        IdentityCategory::User,
+        IdentityCategory::Unknown,
+    ];
+
+    pub(super) fn of(identity: Option<&IdentityCacheKey>) -> Self {
+        match identity {
+            None => IdentityCategory::Public,
+            Some(IdentityCacheKey::System) => IdentityCategory::System,
+            Some(IdentityCacheKey::DeploymentAdmin { .. }) => IdentityCategory::Admin,
+            Some(IdentityCacheKey::User(_)) => IdentityCategory::User,
+            Some(IdentityCacheKey::Unknown(_)) => IdentityCategory::Unknown,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The query cache's contents and recent effectiveness for one
+/// [`IdentityCategory`], as returned by
+/// [`super::CacheManager::stats_by_identity`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IdentityCategoryStats {
+    /// Number of entries in the cache, ready or waiting.
+    pub num_entries: usize,
+    /// Approximate size of the entries in memory.
+    pub size_bytes: usize,
+    /// Number of recent reads that were served by or cached under this
+    /// category.
+    pub recent_gets: usize,
+    /// Fraction of `recent_gets` served from the cache, or `None` if there
+    /// haven't been any.
+    pub recent_hit_rate: Option<f64>,
+}
+
+/// Entries and bytes in the cache per [`IdentityCategory`], adjusted
+/// alongside `Inner::size` so reading them only takes the lock briefly.
+#[derive(Clone, Copy, Default)]
+pub(super) struct IdentityUsage {
+    entries: [usize; IdentityCategory::ALL.len()],
+    bytes: [usize; IdentityCategory::ALL.len()],
+}
+
+impl IdentityUsage {
+    pub(super) fn add(&mut self, category: IdentityCategory, bytes: usize) {
+        self.entries[category.index()] += 1;
+        self.bytes[category.index()] += bytes;
+    }
+
+    pub(super) fn remove(&mut self, category: IdentityCategory, bytes: usize) {
+        let i = category.index();
+        self.entries[i] = self.entries[i].saturating_sub(1);
+        self.bytes[i] = self.bytes[i].saturating_sub(bytes);
+    }
+
+    /// An entry in `category` changed size without being added or removed.
+    pub(super) fn resize(
+        &mut self,
+        category: IdentityCategory,
+        old_bytes: usize,
+        new_bytes: usize,
+    ) {
+        let i = category.index();
+        self.bytes[i] = (self.bytes[i] + new_bytes).saturating_sub(old_bytes);
+    }
+
+    /// Returns the number of entries and their size in bytes.
+    pub(super) fn get(&self, category: IdentityCategory) -> (usize, usize) {
+        (self.entries[category.index()], self.bytes[category.index()])
+    }
+}
+
+/// A [`HitRateWindow`] for each [`IdentityCategory`].
+pub(super) struct IdentityHitRates([HitRateWindow; IdentityCategory::ALL.len()]);
+
+impl IdentityHitRates {
+    pub(super) fn new(size: usize) -> Self {
+        Self(std::array::from_fn(|_| HitRateWindow::new(size)))
+    }
+
+    pub(super) fn get(&self, category: IdentityCategory) -> &HitRateWindow {
+        &self.0[category.index()]
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u8)]
+enum GetOutcome {
+    Empty = 0,
+    Miss = 1,
+    Hit = 2,
+}
+
+impl GetOutcome {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => GetOutcome::Empty,
+            1 => GetOutcome::Miss,
+            2 => GetOutcome::Hit,
+            // Slots are only ever written with a `GetOutcome`.
+            _ => unreachable!("invalid GetOutcome {value}"),
+        }
+    }
+}
+
+/// Hits and misses of the last `slots.len()` cache reads. Recording a read
+/// overwrites the oldest slot and adjusts running counts, so neither recording
+/// nor reading the rate takes a lock. Concurrent reads can leave the counts
+/// briefly off by a few, which is fine for reporting.
+pub(super) struct HitRateWindow {
+    slots: Box<[AtomicU8]>,
+    next: AtomicUsize,
+    gets: AtomicI64,
+    hits: AtomicI64,
+}
+
+impl HitRateWindow {
+    pub(super) fn new(size: usize) -> Self {
+        Self {
+            slots: (0..size.max(1))
+                .map(|_| AtomicU8::new(GetOutcome::Empty as u8))
+                .collect(),
+            next: AtomicUsize::new(0),
+            gets: AtomicI64::new(0),
+            hits: AtomicI64::new(0),
+        }
+    }
+
+    pub(super) fn record(&self, is_cache_hit: bool) {
+        let outcome = if is_cache_hit {
+            GetOutcome::Hit
+        } else {
+            GetOutcome::Miss
+        };
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.slots.len();
+        let evicted = GetOutcome::from_u8(self.slots[i].swap(outcome as u8, Ordering::Relaxed));
+        match evicted {
+            GetOutcome::Empty => {
+                self.gets.fetch_add(1, Ordering::Relaxed);
+            },
+            GetOutcome::Hit => {
+                self.hits.fetch_sub(1, Ordering::Relaxed);
+            },
+            GetOutcome::Miss => (),
+        }
+        if is_cache_hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the number of reads in the window and the fraction that hit.
+    pub(super) fn rate(&self) -> (usize, Option<f64>) {
+        let gets = self.gets.load(Ordering::Relaxed).max(0);
+        let hits = self.hits.load(Ordering::Relaxed).clamp(0, gets);
+        let rate = (gets > 0).then(|| hits as f64 / gets as f64);
+        (gets as usize, rate)
+    }
+}
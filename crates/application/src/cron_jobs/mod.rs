@@ -61,15 +61,20 @@ use keybroker::Identity;
 use model::{
     backend_state::BackendStateModel,
     cron_jobs::{
-        next_ts::compute_next_ts,
+        next_ts::{
+            compute_next_ts,
+            scheduled_from_ts,
+        },
         stream_cron_jobs_to_run,
         types::{
             CronJob,
+            CronJobLog,
             CronJobLogLines,
             CronJobResult,
             CronJobState,
             CronJobStatus,
             CronNextRun,
+            CronOverlapPolicy,
         },
         CronModel,
     },
@@ -108,11 +113,25 @@ const CRON_LOG_MAX_LOG_LINE_LENGTH: usize = 1000;
 // refactored later.
 pub struct CronJobExecutor<RT: Runtime> {
     context: CronJobContext<RT>,
+    /// Jobs whose scheduled run is in progress.
     running_job_ids: HashSet<ResolvedDocumentId>,
+    /// Detached runs in progress, which don't hold up their job's schedule.
+    num_detached_runs: usize,
     /// Some if there's at least one pending job. May be in the past!
     next_job_ready_time: Option<Timestamp>,
-    job_finished_tx: mpsc::Sender<ResolvedDocumentId>,
-    job_finished_rx: mpsc::Receiver<ResolvedDocumentId>,
+    job_finished_tx: mpsc::Sender<(ResolvedDocumentId, CronRunKind)>,
+    job_finished_rx: mpsc::Receiver<(ResolvedDocumentId, CronRunKind)>,
+}
+
+/// How a run of a cron job relates to the job's schedule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CronRunKind {
+    /// The job's scheduled run. It tracks its progress in the job's state and
+    /// moves the job on to its next run when it finishes.
+    Scheduled,
+    /// A run that neither reads nor updates the job's state, so it can run
+    /// alongside other runs of the job. Its result is still logged.
+    Detached,
 }
 
 #[derive(Clone)]
@@ -135,14 +154,9 @@ impl<RT: Runtime> CronJobExecutor<RT> {
         let (job_finished_tx, job_finished_rx) =
             mpsc::channel(*SCHEDULED_JOB_EXECUTION_PARALLELISM);
         let mut executor = Self {
-            context: CronJobContext {
-                rt,
-                deployment_name,
-                database,
-                runner,
-                function_log,
-            },
+            context: CronJobContext::new(rt, deployment_name, database, runner, function_log),
             running_job_ids: HashSet::new(),
+            num_detached_runs: 0,
             next_job_ready_time: None,
             job_finished_tx,
             job_finished_rx,
@@ -173,7 +187,7 @@ impl<RT: Runtime> CronJobExecutor<RT> {
 
         self.next_job_ready_time = if is_backend_stopped {
             None
-        } else if self.running_job_ids.len() == *SCHEDULED_JOB_EXECUTION_PARALLELISM {
+        } else if self.num_running() == *SCHEDULED_JOB_EXECUTION_PARALLELISM {
             self.next_job_ready_time
         } else {
             self.query_and_start_jobs(&mut tx).await?
@@ -201,10 +215,15 @@ impl<RT: Runtime> CronJobExecutor<RT> {
             .database
             .subscribe_and_wait_for_invalidation(token);
         select_biased! {
-            job_id = self.job_finished_rx.recv().fuse() => {
-                if let Some(job_id) = job_id {
+            finished = self.job_finished_rx.recv().fuse() => {
+                if let Some((job_id, kind)) = finished {
                     self.context.rt.pause_client().wait(CRON_JOB_EXECUTED).await;
-                    self.running_job_ids.remove(&job_id);
+                    match kind {
+                        CronRunKind::Scheduled => {
+                            self.running_job_ids.remove(&job_id);
+                        },
+                        CronRunKind::Detached => self.num_detached_runs -= 1,
+                    }
                 } else {
                     anyhow::bail!("Job results channel closed, this is unexpected!");
                 }
@@ -217,6 +236,10 @@ impl<RT: Runtime> CronJobExecutor<RT> {
         Ok(())
     }
 
+    fn num_running(&self) -> usize {
+        self.running_job_ids.len() + self.num_detached_runs
+    }
+
     async fn query_and_start_jobs(
         &mut self,
         tx: &mut Transaction<RT>,
@@ -233,9 +256,23 @@ impl<RT: Runtime> CronJobExecutor<RT> {
             // caught up, we can sleep until the timestamp. If we're behind and
             // at our concurrency limit, we can use the timestamp to log how far
             // behind we get.
-            if next_ts > now || self.running_job_ids.len() == *SCHEDULED_JOB_EXECUTION_PARALLELISM {
+            if next_ts > now || self.num_running() == *SCHEDULED_JOB_EXECUTION_PARALLELISM {
                 return Ok(Some(next_ts));
             }
+            let kind = match job.cron_spec.overlap_policy {
+                CronOverlapPolicy::Skip | CronOverlapPolicy::Queue => CronRunKind::Scheduled,
+                CronOverlapPolicy::Allow => {
+                    if !self.context.advance_schedule(&job).await? {
+                        // The job changed since we read it, so pick it up again
+                        // on the next pass.
+                        continue;
+                    }
+                    CronRunKind::Detached
+                },
+            };
+            self.context
+                .function_log
+                .log_cron_job_lag(&job.name, now - next_ts);
             let sentry_hub = sentry::Hub::with(|hub| sentry::Hub::new_from_top(hub));
             let context = self.context.clone();
             let tx = self.job_finished_tx.clone();
@@ -247,23 +284,56 @@ impl<RT: Runtime> CronJobExecutor<RT> {
                         _ = tx.closed().fuse() => {
                             tracing::error!("Cron job receiver closed");
                         },
-                        result = context.execute_job(job).fuse() => {
-                            let _ = tx.send(result).await;
+                        result = context.execute_job(job, kind).fuse() => {
+                            let _ = tx.send((result, kind)).await;
                         },
                     }
                 }
                 .bind_hub(sentry_hub),
             );
-            self.running_job_ids.insert(job_id);
+            match kind {
+                CronRunKind::Scheduled => {
+                    self.running_job_ids.insert(job_id);
+                },
+                CronRunKind::Detached => self.num_detached_runs += 1,
+            }
         }
         Ok(None)
     }
 }
 
 impl<RT: Runtime> CronJobContext<RT> {
+    pub fn new(
+        rt: RT,
+        deployment_name: String,
+        database: Database<RT>,
+        runner: Arc<ApplicationFunctionRunner<RT>>,
+        function_log: FunctionExecutionLog<RT>,
+    ) -> Self {
+        Self {
+            rt,
+            deployment_name,
+            database,
+            runner,
+            function_log,
+        }
+    }
+
+    /// Runs `job` once right away, outside of its schedule, and returns the
+    /// log of the run. The job's next scheduled run is left as is.
+    pub async fn run_now(&self, mut job: CronJob) -> anyhow::Result<Option<CronJobLog>> {
+        // The run is logged at the time it was requested rather than at a
+        // scheduled time.
+        job.next_ts = self.rt.generate_timestamp()?;
+        let (component, name, ts) = (job.component, job.name.clone(), job.next_ts);
+        self.execute_job(job, CronRunKind::Detached).await;
+        let mut tx = self.database.begin(Identity::Unknown(None)).await?;
+        CronModel::new(&mut tx, component).job_log(&name, ts).await
+    }
+
     // This handles re-running the cron job on transient errors. It
     // guarantees that the job was successfully run or the job state changed.
-    pub async fn execute_job(&self, job: CronJob) -> ResolvedDocumentId {
+    pub async fn execute_job(&self, job: CronJob, kind: CronRunKind) -> ResolvedDocumentId {
         let mut function_backoff = Backoff::new(INITIAL_BACKOFF, MAX_BACKOFF);
         loop {
             let mutation_retry_count = function_backoff.failures() as usize;
@@ -280,7 +350,7 @@ impl<RT: Runtime> CronJobContext<RT> {
                 ]
             });
             let result = self
-                .run_function(job.clone(), mutation_retry_count)
+                .run_function(job.clone(), kind, mutation_retry_count)
                 .in_span(root)
                 .await;
             match result {
@@ -308,11 +378,12 @@ impl<RT: Runtime> CronJobContext<RT> {
     async fn run_function(
         &self,
         job: CronJob,
+        kind: CronRunKind,
         mutation_retry_count: usize,
     ) -> anyhow::Result<ResolvedDocumentId> {
         let usage_tracker = FunctionUsageTracker::new();
         let Some(mut tx) = self
-            .new_transaction_for_job_state(&job, usage_tracker.clone())
+            .transaction_for_run(&job, kind, usage_tracker.clone())
             .await?
         else {
             // Continue without running function since the job state has changed
@@ -341,10 +412,10 @@ impl<RT: Runtime> CronJobContext<RT> {
         let job_id = job.id;
         match udf_type {
             UdfType::Mutation => {
-                self.handle_mutation(tx, job, usage_tracker, mutation_retry_count)
+                self.handle_mutation(tx, job, kind, usage_tracker, mutation_retry_count)
                     .await?
             },
-            UdfType::Action => self.handle_action(tx, job, usage_tracker).await?,
+            UdfType::Action => self.handle_action(tx, job, kind, usage_tracker).await?,
             udf_type => {
                 anyhow::bail!(
                     "Cron trying to execute {} which is a {} function. This should have been \
@@ -418,6 +489,7 @@ impl<RT: Runtime> CronJobContext<RT> {
         &self,
         mut tx: Transaction<RT>,
         job: CronJob,
+        kind: CronRunKind,
         usage_tracker: FunctionUsageTracker,
         mutation_retry_count: usize,
     ) -> anyhow::Result<()> {
@@ -474,6 +546,7 @@ impl<RT: Runtime> CronJobContext<RT> {
                         identity.clone(),
                         &mut tx,
                         &job,
+                        kind,
                         UdfType::Mutation,
                         context.clone(),
                         Some(mutation_retry_count),
@@ -569,7 +642,7 @@ impl<RT: Runtime> CronJobContext<RT> {
             // transaction it executed in. We should remove the job in a new
             // transaction.
             let Some(mut tx) = self
-                .new_transaction_for_job_state(&job, usage_tracker.clone())
+                .transaction_for_run(&job, kind, usage_tracker.clone())
                 .await?
             else {
                 // Continue without updating since the job state has changed
@@ -589,6 +662,7 @@ impl<RT: Runtime> CronJobContext<RT> {
                 identity,
                 &mut tx,
                 &job,
+                kind,
                 UdfType::Mutation,
                 context.clone(),
                 Some(mutation_retry_count),
@@ -620,6 +694,7 @@ impl<RT: Runtime> CronJobContext<RT> {
         &self,
         mut tx: Transaction<RT>,
         job: CronJob,
+        kind: CronRunKind,
         usage_tracker: FunctionUsageTracker,
     ) -> anyhow::Result<()> {
         let component = tx
@@ -629,7 +704,13 @@ impl<RT: Runtime> CronJobContext<RT> {
         let identity = tx.identity().clone();
         let (_, component_path) = self.get_job_component(&mut tx, job.id).await?;
         let caller = FunctionCaller::Cron;
-        match job.state {
+        // The job's state belongs to its scheduled run, so a detached run
+        // always starts from scratch.
+        let state = match kind {
+            CronRunKind::Scheduled => job.state.clone(),
+            CronRunKind::Detached => CronJobState::Pending,
+        };
+        match state {
             CronJobState::Pending => {
                 // Create a new request & execution ID
                 let request_id = RequestId::new();
@@ -641,16 +722,21 @@ impl<RT: Runtime> CronJobContext<RT> {
 
                 // Set state to in progress
                 let mut updated_job = job.clone();
-                updated_job.state = CronJobState::InProgress {
-                    request_id: context.request_id.clone(),
-                    execution_id: context.execution_id,
-                };
-                CronModel::new(&mut tx, component)
-                    .update_job_state(updated_job.cron_next_run())
-                    .await?;
-                self.database
-                    .commit_with_write_source(tx, "cron_in_progress")
-                    .await?;
+                match kind {
+                    CronRunKind::Scheduled => {
+                        updated_job.state = CronJobState::InProgress {
+                            request_id: context.request_id.clone(),
+                            execution_id: context.execution_id,
+                        };
+                        CronModel::new(&mut tx, component)
+                            .update_job_state(updated_job.cron_next_run())
+                            .await?;
+                        self.database
+                            .commit_with_write_source(tx, "cron_in_progress")
+                            .await?;
+                    },
+                    CronRunKind::Detached => drop(tx),
+                }
 
                 // Execute the action
                 let path = CanonicalizedComponentFunctionPath {
@@ -690,6 +776,7 @@ impl<RT: Runtime> CronJobContext<RT> {
                     .complete_action_run(
                         identity.clone(),
                         &updated_job,
+                        kind,
                         status.clone(),
                         truncated_log_lines.clone(),
                         execution_time_f64,
@@ -740,6 +827,7 @@ impl<RT: Runtime> CronJobContext<RT> {
                     identity.clone(),
                     &mut tx,
                     &job,
+                    kind,
                     UdfType::Action,
                     context.clone(),
                     None,
@@ -772,6 +860,75 @@ impl<RT: Runtime> CronJobContext<RT> {
         Ok(())
     }
 
+    // Creates a new transaction for a run of `job`. Scheduled runs require the
+    // job's state to still match `job`, while detached runs only require the
+    // job to still exist.
+    async fn transaction_for_run(
+        &self,
+        job: &CronJob,
+        kind: CronRunKind,
+        usage_tracker: FunctionUsageTracker,
+    ) -> anyhow::Result<Option<Transaction<RT>>> {
+        match kind {
+            CronRunKind::Scheduled => self.new_transaction_for_job_state(job, usage_tracker).await,
+            CronRunKind::Detached => {
+                let mut tx = self
+                    .database
+                    .begin_with_usage(Identity::Unknown(None), usage_tracker)
+                    .await?;
+                let exists = CronModel::new(&mut tx, job.component)
+                    .get(job.id)
+                    .await?
+                    .is_some();
+                Ok(exists.then_some(tx))
+            },
+        }
+    }
+
+    // Moves a job with the `Allow` overlap policy on to its next run before
+    // starting a detached run of it. Returns false if the job has changed.
+    async fn advance_schedule(&self, job: &CronJob) -> anyhow::Result<bool> {
+        let Some(mut tx) = self
+            .new_transaction_for_job_state(job, FunctionUsageTracker::new())
+            .await?
+        else {
+            return Ok(false);
+        };
+        let now = self.rt.generate_timestamp()?;
+        let prev_ts = scheduled_from_ts(&job.cron_spec, job.next_ts, now);
+        let mut next_ts = compute_next_ts(
+            &job.cron_spec,
+            job.id.developer_id,
+            Some(prev_ts),
+            now,
+            &mut self.rt.rng(),
+        )?;
+        // Runs that are already in the past were missed, e.g. because the
+        // executor was at its parallelism limit.
+        while next_ts < now {
+            next_ts = compute_next_ts(
+                &job.cron_spec,
+                job.id.developer_id,
+                Some(next_ts),
+                now,
+                &mut self.rt.rng(),
+            )?;
+        }
+        let next_run = CronNextRun {
+            cron_job_id: job.id.developer_id,
+            state: CronJobState::Pending,
+            prev_ts: Some(prev_ts),
+            next_ts,
+        };
+        CronModel::new(&mut tx, job.component)
+            .update_job_state(next_run)
+            .await?;
+        self.database
+            .commit_with_write_source(tx, "cron_advance_schedule")
+            .await?;
+        Ok(true)
+    }
+
     // Creates a new transaction and verifies the job state matches the given one.
     async fn new_transaction_for_job_state(
         &self,
@@ -795,6 +952,7 @@ impl<RT: Runtime> CronJobContext<RT> {
         &self,
         identity: InertIdentity,
         expected_state: &CronJob,
+        kind: CronRunKind,
         status: CronJobStatus,
         log_lines: CronJobLogLines,
         execution_time: f64,
@@ -802,7 +960,7 @@ impl<RT: Runtime> CronJobContext<RT> {
         context: ExecutionContext,
     ) -> anyhow::Result<()> {
         let Some(mut tx) = self
-            .new_transaction_for_job_state(expected_state, usage_tracker)
+            .transaction_for_run(expected_state, kind, usage_tracker)
             .await?
         else {
             // Continue without updating since the job state has changed
@@ -820,6 +978,7 @@ impl<RT: Runtime> CronJobContext<RT> {
             identity,
             &mut tx,
             expected_state,
+            kind,
             UdfType::Action,
             context,
             None,
@@ -836,20 +995,56 @@ impl<RT: Runtime> CronJobContext<RT> {
         identity: InertIdentity,
         tx: &mut Transaction<RT>,
         job: &CronJob,
+        kind: CronRunKind,
         udf_type: UdfType,
         context: ExecutionContext,
         mutation_retry_count: Option<usize>,
     ) -> anyhow::Result<()> {
+        match kind {
+            CronRunKind::Scheduled => {},
+            // The job's schedule doesn't wait on detached runs.
+            CronRunKind::Detached => return Ok(()),
+        }
         let now = self.rt.generate_timestamp()?;
-        let prev_ts = job.next_ts;
-        let mut next_ts = compute_next_ts(&job.cron_spec, Some(prev_ts), now, &mut self.rt.rng())?;
+        let prev_ts = scheduled_from_ts(&job.cron_spec, job.next_ts, now);
+        let mut next_ts = compute_next_ts(
+            &job.cron_spec,
+            job.id.developer_id,
+            Some(prev_ts),
+            now,
+            &mut self.rt.rng(),
+        )?;
         let mut num_skipped = 0;
         let first_skipped_ts = next_ts;
         let (component, component_path) = self.get_job_component(tx, job.id).await?;
         let mut model = CronModel::new(tx, component);
         while next_ts < now {
             num_skipped += 1;
-            next_ts = compute_next_ts(&job.cron_spec, Some(next_ts), now, &mut self.rt.rng())?;
+            next_ts = compute_next_ts(
+                &job.cron_spec,
+                job.id.developer_id,
+                Some(next_ts),
+                now,
+                &mut self.rt.rng(),
+            )?;
+        }
+        let run_queued = match job.cron_spec.overlap_policy {
+            // Check whether the run after this one came due while it was going.
+            CronOverlapPolicy::Queue => {
+                compute_next_ts(
+                    &job.cron_spec,
+                    job.id.developer_id,
+                    Some(prev_ts),
+                    job.next_ts,
+                    &mut self.rt.rng(),
+                )? <= now
+            },
+            CronOverlapPolicy::Skip | CronOverlapPolicy::Allow => false,
+        };
+        if run_queued {
+            // Start the queued run right away instead of skipping it.
+            next_ts = now;
+            num_skipped = num_skipped.saturating_sub(1);
         }
         if num_skipped > 0 {
             let job_id = job.id.developer_id;
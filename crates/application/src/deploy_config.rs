@@ -3,6 +3,7 @@ use std::{
         BTreeMap,
         BTreeSet,
     },
+    sync::Arc,
     time::{
         Duration,
         Instant,
@@ -21,6 +22,7 @@ use common::{
         },
     },
     components::{
+        CanonicalizedComponentModulePath,
         ComponentDefinitionPath,
         ComponentId,
         ComponentName,
@@ -46,6 +48,7 @@ use database::{
     IndexModel,
     OccRetryStats,
     Token,
+    Transaction,
     WriteSource,
     MAX_OCC_FAILURES,
     SCHEMAS_TABLE,
@@ -101,10 +104,14 @@ use model::{
     },
     environment_variables::EnvironmentVariablesModel,
     external_packages::types::ExternalDepsPackageId,
-    modules::module_versions::{
-        AnalyzedModule,
-        ModuleSource,
-        SourceMap,
+    modules::{
+        module_versions::{
+            AnalyzedModule,
+            ModuleSource,
+            SourceMap,
+        },
+        ModuleModel,
+        MODULES_TABLE,
     },
     source_packages::{
         types::{
@@ -136,6 +143,7 @@ use value::{
     DeveloperDocumentId,
     ResolvedDocumentId,
     TableNamespace,
+    TabletId,
 };
 
 use crate::{
@@ -822,6 +830,31 @@ impl<RT: Runtime> Application<RT> {
                 }
             })?;
 
+        // Module versions are content-addressed, but a rollback can reinstate
+        // a path we've already cached, so always reload pushed modules. Start
+        // loading them right away so the first requests after the push don't
+        // wait on storage.
+        let mut tx = self.begin(identity).await?;
+        let mut pushed_metadata = Vec::new();
+        for (modules_table, module_path) in
+            pushed_modules(&mut tx, &downloaded_source_packages).await?
+        {
+            self.module_cache.invalidate(modules_table, &module_path);
+            let component = ComponentId::from(tx.table_mapping().tablet_namespace(modules_table)?);
+            let path = CanonicalizedComponentModulePath {
+                component,
+                module_path,
+            };
+            if let Some(metadata) = ModuleModel::new(&mut tx).get_metadata(path).await? {
+                pushed_metadata.push(Arc::unwrap_or_clone(metadata));
+            }
+        }
+        // The push has committed, so a failed prefetch only means the modules
+        // are loaded on first use instead.
+        if let Err(e) = self.module_cache.prefetch(&mut tx, pushed_metadata).await {
+            tracing::warn!("Failed to prefetch pushed modules: {e:?}");
+        }
+
         Ok((diff, ts))
     }
 
@@ -981,6 +1014,45 @@ impl<RT: Runtime> InitializerEvaluator for ApplicationInitializerEvaluator<'_, R
     }
 }
 
+/// The `_modules` table and path of every module pushed in `packages`, for
+/// each component instantiating the definition the module was pushed for.
+async fn pushed_modules<RT: Runtime>(
+    tx: &mut Transaction<RT>,
+    packages: &BTreeMap<ComponentDefinitionPath, BTreeMap<CanonicalizedModulePath, ModuleConfig>>,
+) -> anyhow::Result<Vec<(TabletId, CanonicalizedModulePath)>> {
+    let mut components_model = BootstrapComponentsModel::new(tx);
+    let definitions = components_model.load_all_definitions().await?;
+    let components = components_model.load_all_components().await?;
+    let mut pushed = Vec::new();
+    for (definition_path, package) in packages {
+        // The root component's modules are always in the global namespace,
+        // even for apps that were pushed before components existed.
+        let namespaces: Vec<TableNamespace> = if definition_path.is_root() {
+            vec![TableNamespace::Global]
+        } else {
+            let Some(definition) = definitions.get(definition_path) else {
+                continue;
+            };
+            components
+                .iter()
+                .filter(|component| component.definition_id == definition.id().developer_id())
+                .map(|component| ComponentId::Child(component.id().developer_id()).into())
+                .collect()
+        };
+        for namespace in namespaces {
+            let Some(modules_table) = tx
+                .table_mapping()
+                .namespace(namespace)
+                .id_if_exists(&MODULES_TABLE)
+            else {
+                continue;
+            };
+            pushed.extend(package.keys().map(|path| (modules_table, path.clone())));
+        }
+    }
+    Ok(pushed)
+}
+
 fn validate_env_var_declarations(
     evaluated_components: &BTreeMap<ComponentDefinitionPath, EvaluatedComponentDefinition>,
 ) -> anyhow::Result<()> {
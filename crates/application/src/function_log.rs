@@ -74,6 +74,7 @@ use udf::{
     HttpActionOutcome,
     HttpActionRequestHead,
     SyscallTrace,
+    SyscallTraceSummary,
     UdfOutcome,
 };
 use udf_metrics::{
@@ -199,6 +200,20 @@ impl FunctionExecution {
         }
     }
 
+    /// The component that the executed function belongs to.
+    pub fn component_path(&self) -> &ComponentPath {
+        match &self.params {
+            UdfParams::Function { identifier, .. } => &identifier.component,
+            UdfParams::Http { component, .. } => component,
+        }
+    }
+
+    /// Total syscalls this execution made and the `limit` syscalls it made
+    /// the most of and spent the most time in.
+    pub fn syscall_summary(&self, limit: usize) -> SyscallTraceSummary {
+        self.syscall_trace.summary(limit)
+    }
+
     fn event_source(
         &self,
         sub_function_path: Option<&CanonicalizedComponentFunctionPath>,
@@ -212,12 +227,7 @@ impl FunctionExecution {
             Some(path) => (path.component.clone(), path.udf_path.to_string()),
             None => {
                 let udf_id = self.params.identifier_str();
-                let component_path = match &self.params {
-                    UdfParams::Function { identifier, .. } => identifier.component.clone(),
-                    // TODO(ENG-7612): Support HTTP actions in components.
-                    UdfParams::Http { .. } => ComponentPath::root(),
-                };
-                (component_path, udf_id)
+                (self.component_path().clone(), udf_id)
             },
         };
 
@@ -376,6 +386,21 @@ pub enum FunctionExecutionPart {
     Progress(FunctionExecutionProgress),
 }
 
+impl FunctionExecutionPart {
+    /// The component that the function producing this part belongs to.
+    pub fn component_path(&self) -> &ComponentPath {
+        match self {
+            FunctionExecutionPart::Completion(c) => c.component_path(),
+            FunctionExecutionPart::Progress(c) => &c.event_source.component_path,
+        }
+    }
+
+    /// Whether this part passes a stream's component filter.
+    fn is_in_component(&self, component: Option<&ComponentPath>) -> bool {
+        component.is_none_or(|c| self.component_path() == c)
+    }
+}
+
 impl HeapSize for FunctionExecutionPart {
     fn heap_size(&self) -> usize {
         match self {
@@ -416,6 +441,8 @@ pub enum UdfParams {
     Http {
         result: Result<HttpActionStatusCode, JsError>,
         identifier: HttpActionRoute,
+        /// Component whose `http.js` handled the request.
+        component: ComponentPath,
     },
 }
 
@@ -423,7 +450,11 @@ impl HeapSize for UdfParams {
     fn heap_size(&self) -> usize {
         match self {
             UdfParams::Function { error, identifier } => error.heap_size() + identifier.heap_size(),
-            UdfParams::Http { result, identifier } => result.heap_size() + identifier.heap_size(),
+            UdfParams::Http {
+                result,
+                identifier,
+                component,
+            } => result.heap_size() + identifier.heap_size() + component.heap_size(),
         }
     }
 }
@@ -525,6 +556,7 @@ pub struct FunctionExecutionLog<RT: Runtime> {
     usage_tracking: UsageCounter,
     rt: RT,
     concurrency_stats_logger: Arc<Mutex<Box<dyn SpawnHandle>>>,
+    retention_worker: Arc<Mutex<Box<dyn SpawnHandle>>>,
 }
 
 impl<RT: Runtime> FunctionExecutionLog<RT> {
@@ -597,16 +629,36 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
             },
         )));
 
+        // Spawn a background task to prune entries older than the retention
+        // window.
+        let inner_for_retention = inner.clone();
+        let retention_worker =
+            Arc::new(Mutex::new(rt.spawn("function_log_retention", async move {
+                let retention = *knobs::UDF_EXECUTION_LOG_RETENTION;
+                if retention.is_zero() {
+                    return;
+                }
+                let runtime = inner_for_retention.lock().rt.clone();
+                loop {
+                    runtime.wait(*knobs::UDF_EXECUTION_LOG_PRUNE_INTERVAL).await;
+                    if let Err(mut e) = inner_for_retention.lock().prune_expired(retention) {
+                        report_error_sync(&mut e);
+                    }
+                }
+            })));
+
         Self {
             inner,
             rt,
             usage_tracking,
             concurrency_stats_logger,
+            retention_worker,
         }
     }
 
     pub fn shutdown(&self) {
         self.concurrency_stats_logger.lock().shutdown();
+        self.retention_worker.lock().shutdown();
     }
 
     pub async fn log_query(
@@ -1065,6 +1117,7 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
     pub async fn log_http_action(
         &self,
         outcome: HttpActionOutcome,
+        component: ComponentPath,
         result: Result<HttpActionStatusCode, JsError>,
         log_lines: LogLines,
         execution_time: Duration,
@@ -1075,6 +1128,7 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
     ) {
         self._log_http_action(
             outcome,
+            component,
             result,
             log_lines,
             execution_time,
@@ -1090,6 +1144,7 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
         &self,
         error: &anyhow::Error,
         http_request: HttpActionRequestHead,
+        component: ComponentPath,
         identity: InertIdentity,
         start: tokio::time::Instant,
         caller: FunctionCaller,
@@ -1110,6 +1165,7 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
         );
         self._log_http_action(
             outcome,
+            component,
             Err(js_err),
             log_lines,
             start.elapsed(),
@@ -1124,6 +1180,7 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
     async fn _log_http_action(
         &self,
         outcome: HttpActionOutcome,
+        component: ComponentPath,
         result: Result<HttpActionStatusCode, JsError>,
         log_lines: LogLines,
         execution_time: Duration,
@@ -1172,6 +1229,7 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
             params: UdfParams::Http {
                 result,
                 identifier: outcome.route.clone(),
+                component,
             },
             unix_timestamp: self.rt.unix_timestamp(),
             execution_timestamp: outcome.unix_timestamp,
@@ -1205,14 +1263,14 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
     pub fn log_http_action_progress(
         &self,
         identifier: HttpActionRoute,
+        component: ComponentPath,
         unix_timestamp: UnixTimestamp,
         context: ExecutionContext,
         log_lines: LogLines,
         module_environment: ModuleEnvironment,
     ) {
         let event_source = FunctionEventSource {
-            // TODO(ENG-7612): Support HTTP actions in components.
-            component_path: ComponentPath::root(),
+            component_path: component,
             udf_path: identifier.to_string(),
             udf_type: UdfType::HttpAction,
             module_environment,
@@ -1689,14 +1747,22 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
         (Some(summary), new_cursor)
     }
 
-    pub async fn stream(&self, cursor: CursorMs) -> (Vec<FunctionExecution>, CursorMs) {
+    /// Returns the executions logged after `cursor`, waiting for one if there
+    /// aren't any yet. If `component` is set, only executions of functions in
+    /// that component are returned.
+    pub async fn stream(
+        &self,
+        mut cursor: CursorMs,
+        component: Option<&ComponentPath>,
+    ) -> (Vec<FunctionExecution>, CursorMs) {
         loop {
             let rx = {
                 let mut inner = self.inner.lock();
                 let first_entry_ix = inner.log.partition_point(|(ts, _)| *ts <= cursor);
                 if first_entry_ix < inner.log.len() {
-                    let entries = (first_entry_ix..inner.log.len())
+                    let entries: Vec<_> = (first_entry_ix..inner.log.len())
                         .map(|i| &inner.log[i])
+                        .filter(|(_, entry)| entry.is_in_component(component))
                         .filter_map(|(_, entry)| match entry {
                             FunctionExecutionPart::Completion(completion) => {
                                 Some(completion.clone())
@@ -1705,6 +1771,11 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
                         })
                         .collect();
                     let (new_cursor, _) = inner.log.back().unwrap();
+                    // Keep waiting if everything new was in other components.
+                    if entries.is_empty() && component.is_some() {
+                        cursor = *new_cursor;
+                        continue;
+                    }
                     return (entries, *new_cursor);
                 }
                 let (tx, rx) = oneshot::channel();
@@ -1715,14 +1786,21 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
         }
     }
 
-    pub async fn stream_parts(&self, cursor: CursorMs) -> (Vec<FunctionExecutionPart>, CursorMs) {
+    /// Like [`Self::stream`], but also returns progress from functions that
+    /// are still running.
+    pub async fn stream_parts(
+        &self,
+        mut cursor: CursorMs,
+        component: Option<&ComponentPath>,
+    ) -> (Vec<FunctionExecutionPart>, CursorMs) {
         loop {
             let rx = {
                 let mut inner = self.inner.lock();
                 let first_entry_ix = inner.log.partition_point(|(ts, _)| *ts <= cursor);
                 if first_entry_ix < inner.log.len() {
-                    let entries = (first_entry_ix..inner.log.len())
+                    let entries: Vec<_> = (first_entry_ix..inner.log.len())
                         .map(|i| &inner.log[i])
+                        .filter(|(_, entry)| entry.is_in_component(component))
                         .map(|(_, entry)| match entry {
                             FunctionExecutionPart::Completion(c) => {
                                 let with_stripped_log_lines = match c.udf_type {
@@ -1741,6 +1819,10 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
                         })
                         .collect();
                     let (new_cursor, _) = inner.log.back().unwrap();
+                    if entries.is_empty() && component.is_some() {
+                        cursor = *new_cursor;
+                        continue;
+                    }
                     return (entries, *new_cursor);
                 }
                 let (tx, rx) = oneshot::channel();
@@ -1888,6 +1970,44 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
         Ok(timeseries)
     }
 
+    /// Percentiles of how late scheduled runs of each cron job started, keyed
+    /// by cron job name.
+    pub fn cron_job_lag_percentiles(
+        &self,
+        percentiles: Vec<Percentile>,
+        window: MetricsWindow,
+    ) -> anyhow::Result<BTreeMap<String, BTreeMap<Percentile, Timeseries>>> {
+        let metrics = {
+            let inner = self.inner.lock();
+            inner.metrics.clone()
+        };
+        let mut result = BTreeMap::new();
+        for metric_name in metrics.metric_names_for_type(MetricType::Histogram) {
+            let Some(job_name) = metric_name
+                .strip_prefix("cron_jobs:")
+                .and_then(|name| name.strip_suffix(":lag"))
+            else {
+                continue;
+            };
+            let buckets = metrics.query_histogram(&metric_name, window.start..window.end)?;
+            result.insert(
+                job_name.to_string(),
+                window.resample_histograms(&metrics, buckets, &percentiles)?,
+            );
+        }
+        Ok(result)
+    }
+
+    /// Log how late a scheduled run of the cron job `name` started.
+    pub fn log_cron_job_lag(&self, name: &str, lag: Duration) {
+        let now = self.rt.system_time();
+        let _ = self
+            .inner
+            .lock()
+            .metrics
+            .add_histogram(&cron_job_lag_metric(name), now, lag);
+    }
+
     /// Log the current number of outstanding functions as a gauge that tracks
     /// the maximum.
     pub fn log_outstanding_functions(
@@ -1992,6 +2112,23 @@ fn cache_hit_percentage(hits: Option<f64>, misses: Option<f64>) -> Option<f64> {
     }
 }
 
+/// Drops the entries at the front of `log` logged before `cutoff`, returning
+/// how many of them were completions.
+fn prune_entries_before(
+    log: &mut WithHeapSize<VecDeque<(CursorMs, FunctionExecutionPart)>>,
+    cutoff: CursorMs,
+) -> usize {
+    let mut num_completions = 0;
+    while let Some((ts, _)) = log.front()
+        && *ts < cutoff
+    {
+        if let Some((_, FunctionExecutionPart::Completion(_))) = log.pop_front() {
+            num_completions += 1;
+        }
+    }
+    num_completions
+}
+
 struct Inner<RT: Runtime> {
     rt: RT,
 
@@ -2175,6 +2312,19 @@ impl<RT: Runtime> Inner<RT> {
         Ok(())
     }
 
+    /// Drops the entries logged more than `retention` ago. Entries only leave
+    /// from the front of the log, so a log stream's cursor keeps its place; a
+    /// stream that falls behind the window just skips the expired entries.
+    fn prune_expired(&mut self, retention: Duration) -> anyhow::Result<()> {
+        let since_epoch = self
+            .rt
+            .system_time()
+            .duration_since(SystemTime::UNIX_EPOCH)?;
+        let cutoff: CursorMs = since_epoch.saturating_sub(retention).as_secs_f64() * 1e3;
+        self.num_execution_completions -= prune_entries_before(&mut self.log, cutoff);
+        Ok(())
+    }
+
     fn next_time(&self) -> anyhow::Result<CursorMs> {
         let since_epoch = self
             .rt
@@ -2293,6 +2443,10 @@ fn scheduled_job_next_ts_metric() -> &'static str {
     "scheduled_jobs:next_ts"
 }
 
+fn cron_job_lag_metric(name: &str) -> MetricName {
+    format!("cron_jobs:{name}:lag")
+}
+
 fn outstanding_functions_metric(
     env: &ModuleEnvironment,
     udf_type: &UdfType,
@@ -2402,6 +2556,14 @@ impl<'a, RT: Runtime> FunctionMetricsLog<'a, RT> {
         self.log.scheduled_job_lag(window)
     }
 
+    pub fn cron_job_lag_percentiles(
+        &self,
+        percentiles: Vec<Percentile>,
+        window: MetricsWindow,
+    ) -> anyhow::Result<BTreeMap<String, BTreeMap<Percentile, Timeseries>>> {
+        self.log.cron_job_lag_percentiles(percentiles, window)
+    }
+
     pub fn function_concurrency(
         &self,
         window: MetricsWindow,
@@ -2428,12 +2590,20 @@ impl<'a, RT: Runtime> FunctionEntriesLog<'a, RT> {
         Self { log }
     }
 
-    pub async fn stream(&self, cursor: CursorMs) -> (Vec<FunctionExecution>, CursorMs) {
-        self.log.stream(cursor).await
+    pub async fn stream(
+        &self,
+        cursor: CursorMs,
+        component: Option<&ComponentPath>,
+    ) -> (Vec<FunctionExecution>, CursorMs) {
+        self.log.stream(cursor, component).await
     }
 
-    pub async fn stream_parts(&self, cursor: CursorMs) -> (Vec<FunctionExecutionPart>, CursorMs) {
-        self.log.stream_parts(cursor).await
+    pub async fn stream_parts(
+        &self,
+        cursor: CursorMs,
+        component: Option<&ComponentPath>,
+    ) -> (Vec<FunctionExecutionPart>, CursorMs) {
+        self.log.stream_parts(cursor, component).await
     }
 
     pub fn latest_cursor(&self) -> CursorMs {
@@ -2450,3 +2620,100 @@ fn udf_metric_name(identifier: &UdfIdentifier) -> String {
         None => id,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use common::{
+        components::ComponentPath,
+        execution_context::{
+            ExecutionContext,
+            ExecutionId,
+            RequestMetadata,
+        },
+        log_lines::LogLines,
+        log_streaming::FunctionEventSource,
+        runtime::UnixTimestamp,
+        types::{
+            ModuleEnvironment,
+            UdfType,
+        },
+        RequestId,
+    };
+    use value::heap_size::WithHeapSize;
+
+    use super::{
+        prune_entries_before,
+        FunctionExecutionPart,
+        FunctionExecutionProgress,
+    };
+
+    fn progress(component_path: ComponentPath) -> FunctionExecutionPart {
+        FunctionExecutionPart::Progress(FunctionExecutionProgress {
+            log_lines: LogLines::default(),
+            event_source: FunctionEventSource {
+                context: ExecutionContext::new_from_parts(
+                    RequestId::new(),
+                    ExecutionId::new(),
+                    None,
+                    true,
+                    RequestMetadata::system(),
+                ),
+                component_path,
+                udf_path: "messages:send".to_string(),
+                udf_type: UdfType::Action,
+                module_environment: ModuleEnvironment::Isolate,
+                cached: None,
+                mutation_queue_length: None,
+                mutation_retry_count: None,
+            },
+            function_start_timestamp: UnixTimestamp::from_millis(0),
+        })
+    }
+
+    #[test]
+    fn test_filter_by_child_component() -> anyhow::Result<()> {
+        let child: ComponentPath = "chat".parse()?;
+        let parts = [progress(ComponentPath::root()), progress(child.clone())];
+
+        let in_child: Vec<_> = parts
+            .iter()
+            .filter(|part| part.is_in_component(Some(&child)))
+            .map(FunctionExecutionPart::component_path)
+            .collect();
+        assert_eq!(in_child, vec![&child]);
+        let in_root: Vec<_> = parts
+            .iter()
+            .filter(|part| part.is_in_component(Some(&ComponentPath::root())))
+            .map(FunctionExecutionPart::component_path)
+            .collect();
+        assert_eq!(in_root, vec![&ComponentPath::root()]);
+        assert!(parts.iter().all(|part| part.is_in_component(None)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_entries_before_cutoff() -> anyhow::Result<()> {
+        let mut log: WithHeapSize<VecDeque<_>> = VecDeque::from(vec![
+            (1000., progress(ComponentPath::root())),
+            (2000., progress(ComponentPath::root())),
+            (3000., progress(ComponentPath::root())),
+        ])
+        .into();
+
+        assert_eq!(prune_entries_before(&mut log, 2500.), 0);
+        let remaining: Vec<_> = log.iter().map(|(ts, _)| *ts).collect();
+        assert_eq!(remaining, vec![3000.]);
+
+        // A stream whose cursor fell behind the window resumes at the first
+        // entry that's left.
+        let cursor = 1000.;
+        assert_eq!(log.partition_point(|(ts, _)| *ts <= cursor), 0);
+
+        // Nothing is pruned once every entry is inside the window.
+        assert_eq!(prune_entries_before(&mut log, 3000.), 0);
+        assert_eq!(log.len(), 1);
+        Ok(())
+    }
+}
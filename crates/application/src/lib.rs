@@ -12,6 +12,7 @@ use std::{
         BTreeSet,
         HashSet,
     },
+    iter,
     ops::Bound,
     sync::Arc,
     time::{
@@ -133,6 +134,7 @@ use common::{
         env_var_total_size,
         env_var_total_size_limit_met,
         AllowedVisibility,
+        BackendState,
         ConvexOrigin,
         ConvexSite,
         DeploymentMetadata,
@@ -153,12 +155,16 @@ use common::{
     RequestContext,
     RequestId,
 };
-use cron_jobs::CronJobExecutor;
+use cron_jobs::{
+    CronJobContext,
+    CronJobExecutor,
+};
 use database::{
     BootstrapComponentsModel,
     Database,
     IndexModel,
     IndexWorker,
+    OccConflictHotspot,
     OccRetryStats,
     ResolvedQuery,
     SchemaModel,
@@ -234,6 +240,7 @@ use model::{
         ComponentsModel,
     },
     config::{
+        module_loader::ModuleLoader,
         types::{
             ConfigFile,
             ConfigMetadata,
@@ -242,6 +249,13 @@ use model::{
         },
         ConfigModel,
     },
+    cron_jobs::{
+        types::{
+            CronIdentifier,
+            CronJobLog,
+        },
+        CronModel,
+    },
     database_globals::{
         types::StorageTagInitializer,
         DatabaseGlobalsModel,
@@ -280,12 +294,14 @@ use model::{
     fivetran_import::FivetranImportModel,
     migrations::MigrationWorker,
     modules::{
+        hash_module_source,
         module_versions::{
             AnalyzedModule,
             Visibility,
         },
         types::ModuleMetadata,
         ModuleModel,
+        MODULES_TABLE,
     },
     scheduled_jobs::{
         args::ScheduledJobArgsTable,
@@ -294,8 +310,10 @@ use model::{
     },
     session_requests::types::SessionRequestIdentifier,
     snapshot_imports::types::{
+        ClearTablesAuditLog,
         ImportFormat,
         ImportMode,
+        ImportOptions,
         ImportRequestor,
     },
     source_packages::{
@@ -335,7 +353,7 @@ use search_index_workers::{
 };
 use semver::Version;
 use short_future::ShortBoxFuture;
-use snapshot_import::start_stored_import;
+use snapshot_import::start_stored_multipart_import;
 use storage::{
     BufferedUpload,
     ClientDrivenUploadPartToken,
@@ -408,6 +426,7 @@ use crate::{
     },
     log_visibility::LogVisibility,
     module_cache::ModuleCache,
+    readiness::ReadinessSubsystem,
     redaction::{
         RedactedJsError,
         RedactedLogLines,
@@ -434,6 +453,7 @@ pub mod log_streaming;
 pub mod log_visibility;
 mod metrics;
 mod module_cache;
+pub mod readiness;
 pub mod redaction;
 pub mod scheduled_jobs;
 mod schema_worker;
@@ -444,7 +464,13 @@ mod table_summary_worker;
 pub mod valid_identifier;
 mod worker_handles;
 
-pub use crate::cache::QueryCache;
+pub use crate::cache::{
+    CacheStats,
+    IdentityCategory,
+    IdentityCategoryStats,
+    QueryCache,
+    SerializedCacheEntry,
+};
 use crate::{
     metrics::{
         log_external_deps_package,
@@ -903,6 +929,9 @@ impl<RT: Runtime> Application<RT> {
             application_storage.snapshot_imports_storage.clone(),
             file_storage.clone(),
             usage_counter.clone(),
+            runner.clone(),
+            default_system_env_vars.keys().cloned().collect(),
+            None,
         );
         let snapshot_import_worker = Arc::new(Mutex::new(Some(
             runtime.spawn("snapshot_import_worker", snapshot_import_worker),
@@ -991,11 +1020,32 @@ impl<RT: Runtime> Application<RT> {
         self.runner.clone()
     }
 
+    /// Returns the subsystems that aren't reachable, for readiness probes.
+    pub async fn check_readiness(&self) -> Vec<(ReadinessSubsystem, anyhow::Error)> {
+        readiness::check_readiness(
+            &self.runtime,
+            &self.database,
+            &self.application_storage.search_storage,
+            &self.application_storage.modules_storage,
+        )
+        .await
+    }
+
     pub fn metrics_log(&self, identity: &Identity) -> anyhow::Result<FunctionMetricsLog<'_, RT>> {
         identity.require_operation(DeploymentOp::ViewMetrics)?;
         Ok(FunctionMetricsLog::new(&self.function_log))
     }
 
+    /// The `k` indexes with the most OCC conflicts since this backend started.
+    pub fn occ_conflict_hotspots(
+        &self,
+        identity: &Identity,
+        k: usize,
+    ) -> anyhow::Result<Vec<OccConflictHotspot>> {
+        identity.require_operation(DeploymentOp::ViewMetrics)?;
+        self.database.occ_conflict_hotspots(k)
+    }
+
     pub fn function_log(&self, identity: &Identity) -> anyhow::Result<FunctionEntriesLog<'_, RT>> {
         identity.require_operation(DeploymentOp::ViewLogs)?;
         Ok(FunctionEntriesLog::new(&self.function_log))
@@ -1608,6 +1658,12 @@ impl<RT: Runtime> Application<RT> {
         expiration_ts_ns: Option<u64>,
     ) -> anyhow::Result<DeveloperDocumentId> {
         identity.require_operation(DeploymentOp::CreateBackups)?;
+        let ExportFormat::Zip { include_config, .. } = &format;
+        if include_config.environment_variables {
+            // The export contains their values, so it needs the same access as
+            // reading them directly.
+            identity.require_operation(DeploymentOp::ViewEnvironmentVariables)?;
+        }
         if let Some(expiration_ts_ns) = expiration_ts_ns {
             let now = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -1654,9 +1710,9 @@ impl<RT: Runtime> Application<RT> {
         let component_id = component.serialize_to_string();
         let component_path = tx.must_component_path(component)?;
         let format_str = match &format {
-            ExportFormat::Zip { include_storage } if *include_storage => {
-                "zip_with_storage".to_string()
-            },
+            ExportFormat::Zip {
+                include_storage, ..
+            } if *include_storage => "zip_with_storage".to_string(),
             ExportFormat::Zip { .. } => "zip".to_string(),
         };
         self.commit_with_audit_log_events(
@@ -1740,6 +1796,24 @@ impl<RT: Runtime> Application<RT> {
         &self,
         tx: &mut Transaction<RT>,
         changes: Vec<EnvVarChange>,
+    ) -> anyhow::Result<Vec<DeploymentAuditLogEvent>> {
+        Self::apply_environment_variable_changes(
+            self.runner.clone(),
+            &self.system_env_var_names,
+            tx,
+            changes,
+        )
+        .await
+    }
+
+    /// Validates and applies `changes` in `tx`, returning the audit log events
+    /// for them. Also used by snapshot import to restore environment variables
+    /// when it finalizes the import.
+    pub(crate) async fn apply_environment_variable_changes(
+        runner: Arc<ApplicationFunctionRunner<RT>>,
+        system_env_var_names: &HashSet<EnvVarName>,
+        tx: &mut Transaction<RT>,
+        changes: Vec<EnvVarChange>,
     ) -> anyhow::Result<Vec<DeploymentAuditLogEvent>> {
         let app_def = BootstrapComponentsModel::new(tx)
             .load_definition_metadata(ComponentDefinitionId::Root)
@@ -1799,7 +1873,7 @@ impl<RT: Runtime> Application<RT> {
                             name: name.clone(),
                         });
                     }
-                    model.create(env_var, &self.system_env_var_names).await?;
+                    model.create(env_var, system_env_var_names).await?;
                 },
                 EnvVarChange::Unset(name) => {
                     if let Some(_existing) = model.delete(&name).await? {
@@ -1819,7 +1893,7 @@ impl<RT: Runtime> Application<RT> {
             env_var_total_size_limit_met(total_size),
         );
 
-        Self::reevaluate_existing_auth_config(self.runner().clone(), tx).await?;
+        Self::reevaluate_existing_auth_config(runner, tx).await?;
 
         Ok(audit_events)
     }
@@ -2094,6 +2168,36 @@ impl<RT: Runtime> Application<RT> {
         }
     }
 
+    /// Check that the source the module cache serves for the module at `path`
+    /// matches the source in storage. Returns `None` if there's no such
+    /// module.
+    pub async fn module_source_matches_storage(
+        &self,
+        identity: Identity,
+        path: CanonicalizedComponentModulePath,
+    ) -> anyhow::Result<Option<bool>> {
+        let mut tx = self.begin(identity).await?;
+        let component = path.component;
+        let Some((module_metadata, stored_source)) = self
+            .module_cache
+            .get_module_with_metadata_uncached(&mut tx, path)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let source_package = SourcePackageModel::new(&mut tx, component.into())
+            .get(module_metadata.source_package_id)
+            .await?;
+        let cached_source = self
+            .module_cache
+            .get_module_with_metadata(&module_metadata, &source_package)
+            .await?;
+        Ok(Some(
+            hash_module_source(&cached_source.source, cached_source.source_map.as_ref())
+                == hash_module_source(&stored_source.source, stored_source.source_map.as_ref()),
+        ))
+    }
+
     // This is only relevant to auth config set via `auth.config.js`.
     // Because legacy setups didn't use `auth.config.js` we do not
     // reset the auth config if `auth.config.js` is not present.
@@ -2159,13 +2263,28 @@ impl<RT: Runtime> Application<RT> {
         apply_config_args: ApplyConfigArgs,
     ) -> anyhow::Result<(ConfigMetadataAndSchema, OccRetryStats)> {
         let runner = self.runner.clone();
-        self.execute_with_audit_log_events_and_occ_retries_reporting_stats(
-            identity,
-            request_metadata,
-            "apply_config",
-            |tx| Self::_apply_config(runner.clone(), tx, apply_config_args.clone()).into(),
-        )
-        .await
+        let result = self
+            .execute_with_audit_log_events_and_occ_retries_reporting_stats(
+                identity,
+                request_metadata,
+                "apply_config",
+                |tx| Self::_apply_config(runner.clone(), tx, apply_config_args.clone()).into(),
+            )
+            .await?;
+        // Apps without components only have root modules.
+        if let Some(modules_table) = self
+            .database
+            .latest_snapshot()?
+            .table_mapping()
+            .namespace(TableNamespace::Global)
+            .id_if_exists(&MODULES_TABLE)
+        {
+            for module in &apply_config_args.modules {
+                self.module_cache
+                    .invalidate(modules_table, &module.path.clone().canonicalize());
+            }
+        }
+        Ok(result)
     }
 
     #[fastrace::trace]
@@ -2519,27 +2638,38 @@ impl<RT: Runtime> Application<RT> {
         format: ImportFormat,
         mode: ImportMode,
         component_path: ComponentPath,
+        options: ImportOptions,
         upload_token: ClientDrivenUploadToken,
         part_tokens: Vec<ClientDrivenUploadPartToken>,
+        additional_uploads: Vec<(ClientDrivenUploadToken, Vec<ClientDrivenUploadPartToken>)>,
     ) -> anyhow::Result<DeveloperDocumentId> {
         identity.require_operation(DeploymentOp::ImportBackups)?;
-        let object_key = self
-            .application_storage
-            .snapshot_imports_storage
-            .finish_client_driven_upload(upload_token, part_tokens)
-            .await?;
-        let fq_key = self
-            .application_storage
-            .snapshot_imports_storage
-            .fully_qualified_key(&object_key);
-        start_stored_import(
+        // Each upload is one file of the import. More than one means a ZIP
+        // export that was split into several files.
+        let mut fq_keys = vec![];
+        for (upload_token, part_tokens) in
+            iter::once((upload_token, part_tokens)).chain(additional_uploads)
+        {
+            let object_key = self
+                .application_storage
+                .snapshot_imports_storage
+                .finish_client_driven_upload(upload_token, part_tokens)
+                .await?;
+            fq_keys.push(
+                self.application_storage
+                    .snapshot_imports_storage
+                    .fully_qualified_key(&object_key),
+            );
+        }
+        start_stored_multipart_import(
             self,
             identity,
             format,
             mode,
             component_path,
-            fq_key,
+            fq_keys,
             ImportRequestor::SnapshotImport,
+            options,
         )
         .await
     }
@@ -2629,6 +2759,7 @@ impl<RT: Runtime> Application<RT> {
         request_metadata: RequestMetadata,
         table_names: Vec<(ComponentPath, TableName)>,
         requestor: ImportRequestor,
+        audit_log: ClearTablesAuditLog,
         usage: FunctionUsageTracker,
     ) -> anyhow::Result<u64> {
         clear_tables(
@@ -2637,6 +2768,7 @@ impl<RT: Runtime> Application<RT> {
             request_metadata,
             table_names,
             requestor,
+            audit_log,
             usage,
         )
         .await
@@ -2960,10 +3092,18 @@ impl<RT: Runtime> Application<RT> {
         Ok(())
     }
 
-    async fn bail_if_not_running(&self) -> anyhow::Result<()> {
-        let backend_state = BackendStateModel::new(&mut self.begin(Identity::Unknown(None)).await?)
+    /// Returns whether the deployment is running, paused, or disabled.
+    pub async fn backend_state(&self, identity: Identity) -> anyhow::Result<BackendState> {
+        let mut tx = self.begin(identity).await?;
+        let backend_state = BackendStateModel::new(&mut tx)
             .get_backend_state()
-            .await?;
+            .await?
+            .into_value();
+        Ok(backend_state)
+    }
+
+    async fn bail_if_not_running(&self) -> anyhow::Result<()> {
+        let backend_state = self.backend_state(Identity::Unknown(None)).await?;
         if backend_state.is_stopped() {
             anyhow::bail!(ErrorMetadata::bad_request(
                 "BackendIsNotRunning",
@@ -3293,6 +3433,62 @@ impl<RT: Runtime> Application<RT> {
         Ok((count, events))
     }
 
+    /// Runs the cron job `name` once right away and returns the log of the
+    /// run. The job's next scheduled run isn't affected.
+    pub async fn run_cron_job_now(
+        &self,
+        identity: Identity,
+        component: ComponentId,
+        name: CronIdentifier,
+    ) -> anyhow::Result<CronJobLog> {
+        let mut tx = self.begin(identity.clone()).await?;
+        let Some(job) = CronModel::new(&mut tx, component)
+            .get_by_name(&name)
+            .await?
+        else {
+            anyhow::bail!(ErrorMetadata::not_found(
+                "CronJobNotFound",
+                format!("Cron job '{name}' not found"),
+            ));
+        };
+        let path = CanonicalizedComponentFunctionPath {
+            component: tx.must_component_path(component)?,
+            udf_path: job.cron_spec.udf_path.clone(),
+        };
+        let udf_type = ModuleModel::new(&mut tx)
+            .get_analyzed_function(&path)
+            .await?
+            .map_err(|e| {
+                ErrorMetadata::bad_request(
+                    "CronFunctionNotFound",
+                    format!("Cron job '{name}' can't be run: {e}"),
+                )
+            })?
+            .udf_type;
+        let operation = match udf_type {
+            UdfType::Mutation => DeploymentOp::RunInternalMutations,
+            UdfType::Action => DeploymentOp::RunInternalActions,
+            UdfType::Query | UdfType::HttpAction => anyhow::bail!(
+                "Cron job '{name}' runs {udf_type} function {}",
+                job.cron_spec.udf_path
+            ),
+        };
+        identity.require_operation(operation)?;
+        drop(tx);
+
+        let context = CronJobContext::new(
+            self.runtime.clone(),
+            self.deployment_name(),
+            self.database.clone(),
+            self.runner.clone(),
+            self.function_log.clone(),
+        );
+        context
+            .run_now(job)
+            .await?
+            .with_context(|| format!("Log for run of cron job '{name}' not found"))
+    }
+
     /// Commit a transaction and send audit log events to the log manager if the
     /// transaction commits successfully.
     pub async fn commit_with_audit_log_events(
@@ -1,4 +1,9 @@
+use async_lru::async_lru::AsyncLruStats;
 use metrics::{
+    log_counter,
+    log_gauge,
+    register_convex_counter,
+    register_convex_gauge,
     register_convex_histogram,
     StatusTimer,
     STATUS_LABEL,
@@ -13,3 +18,44 @@ register_convex_histogram!(
 pub fn module_cache_get_module_timer() -> StatusTimer {
     StatusTimer::new(&MODULE_CACHE_GET_MODULE_SECONDS)
 }
+
+register_convex_counter!(
+    MODULE_CACHE_INVALIDATED_TOTAL,
+    "Number of module cache entries removed by explicit invalidation"
+);
+
+pub fn log_module_cache_invalidated(num_entries: usize) {
+    log_counter(&MODULE_CACHE_INVALIDATED_TOTAL, num_entries as u64);
+}
+
+register_convex_gauge!(
+    MODULE_CACHE_SIZE_BYTES,
+    "Total size of the module sources in the module cache"
+);
+register_convex_gauge!(
+    MODULE_CACHE_SIZE_FRACTION,
+    "Module cache size as a fraction of MODULE_CACHE_MAX_SIZE_BYTES"
+);
+register_convex_gauge!(
+    MODULE_CACHE_NUM_ENTRIES,
+    "Number of modules in the module cache"
+);
+register_convex_counter!(
+    MODULE_CACHE_EVICTIONS_TOTAL,
+    "Number of modules evicted from the module cache to stay within its size limit"
+);
+
+pub fn log_module_cache_eviction() {
+    log_counter(&MODULE_CACHE_EVICTIONS_TOTAL, 1);
+}
+
+pub fn log_module_cache_stats(stats: &AsyncLruStats) {
+    log_gauge(&MODULE_CACHE_SIZE_BYTES, stats.size as f64);
+    if stats.max_size > 0 {
+        log_gauge(
+            &MODULE_CACHE_SIZE_FRACTION,
+            stats.size as f64 / stats.max_size as f64,
+        );
+    }
+    log_gauge(&MODULE_CACHE_NUM_ENTRIES, stats.num_entries as f64);
+}
@@ -1,55 +1,226 @@
-use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::Duration,
+};
 
-use async_lru::async_lru::AsyncLru;
+use anyhow::Context;
+use async_lru::async_lru::{
+    AsyncLru,
+    AsyncLruStats,
+};
 use async_trait::async_trait;
 use common::{
+    components::CanonicalizedComponentModulePath,
     document::ParsedDocument,
     knobs::{
         MODULE_CACHE_MAX_CONCURRENCY,
         MODULE_CACHE_MAX_SIZE_BYTES,
     },
-    runtime::Runtime,
+    runtime::{
+        Runtime,
+        SpawnHandle,
+    },
+};
+use database::Transaction;
+use futures::{
+    stream,
+    StreamExt,
 };
 use model::{
-    config::module_loader::ModuleLoader,
+    config::{
+        module_loader::ModuleLoader,
+        types::ModuleConfig,
+    },
     modules::{
         hash_module_source,
         module_versions::FullModuleSource,
         types::ModuleMetadata,
+        ModuleModel,
     },
     source_packages::{
         types::SourcePackage,
         upload_download::download_package,
+        SourcePackageModel,
     },
 };
 use storage::Storage;
 use sync_types::CanonicalizedModulePath;
-use value::sha256::Sha256Digest;
+use value::{
+    sha256::Sha256Digest,
+    TableNamespace,
+    TabletId,
+};
 
 mod metrics;
 
+/// How often the cache's size gauges are logged.
+const MODULE_CACHE_STATS_INTERVAL: Duration = Duration::from_secs(10);
+
 #[derive(Clone)]
 pub struct ModuleCache<RT: Runtime> {
+    rt: RT,
     modules_storage: Arc<dyn Storage>,
 
-    cache: AsyncLru<RT, (CanonicalizedModulePath, Sha256Digest), FullModuleSource, Sha256Digest>,
+    cache: AsyncLru<RT, ModuleCacheKey, FullModuleSource, Sha256Digest>,
+    _stats_logger: Arc<Box<dyn SpawnHandle>>,
+}
+
+/// Module paths are relative to their component, so cache entries are also
+/// keyed by the `_modules` table of the component the module belongs to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ModuleCacheKey {
+    modules_table: TabletId,
+    path: CanonicalizedModulePath,
+    sha256: Sha256Digest,
+}
+
+impl ModuleCacheKey {
+    fn is_version_of(&self, modules_table: TabletId, path: &CanonicalizedModulePath) -> bool {
+        self.modules_table == modules_table && &self.path == path
+    }
 }
 
 impl<RT: Runtime> ModuleCache<RT> {
     pub async fn new(rt: RT, modules_storage: Arc<dyn Storage>) -> Self {
         let cache = AsyncLru::new(
-            rt,
+            rt.clone(),
             *MODULE_CACHE_MAX_SIZE_BYTES,
             *MODULE_CACHE_MAX_CONCURRENCY,
             200,
             "module_cache",
-        );
+        )
+        .with_eviction_listener(metrics::log_module_cache_eviction);
+        let stats_logger = rt.spawn("module_cache_stats", {
+            let rt = rt.clone();
+            let cache = cache.clone();
+            async move {
+                loop {
+                    metrics::log_module_cache_stats(&cache.stats());
+                    rt.wait(MODULE_CACHE_STATS_INTERVAL).await;
+                }
+            }
+        });
 
         Self {
+            rt,
             modules_storage,
             cache,
+            _stats_logger: Arc::new(stats_logger),
         }
     }
+
+    /// Start loading `modules` into the cache in the background, e.g. right
+    /// after a push, so the first requests to run them don't wait on storage.
+    /// Returns once the modules' source packages have been looked up; at most
+    /// [MODULE_CACHE_MAX_CONCURRENCY] fetches run at a time.
+    pub async fn prefetch(
+        &self,
+        tx: &mut Transaction<RT>,
+        modules: Vec<ParsedDocument<ModuleMetadata>>,
+    ) -> anyhow::Result<()> {
+        let mut modules_by_namespace: BTreeMap<TableNamespace, Vec<_>> = BTreeMap::new();
+        for module in modules {
+            let namespace = tx.table_mapping().tablet_namespace(module.id().tablet_id)?;
+            modules_by_namespace
+                .entry(namespace)
+                .or_default()
+                .push(module);
+        }
+        let mut to_fetch = Vec::new();
+        for (namespace, modules) in modules_by_namespace {
+            let source_packages = SourcePackageModel::new(tx, namespace)
+                .get_many(modules.iter().map(|module| module.source_package_id))
+                .await?;
+            for module in modules {
+                let source_package = source_packages
+                    .get(&module.source_package_id)
+                    .with_context(|| format!("Missing source package for {:?}", module.path))?
+                    .clone();
+                to_fetch.push((module, source_package));
+            }
+        }
+
+        let module_cache = self.clone();
+        self.rt
+            .spawn_background("module_cache_prefetch", async move {
+                let module_cache = &module_cache;
+                let mut results = stream::iter(to_fetch)
+                    .map(|(module, source_package)| async move {
+                        module_cache
+                            .get_module_with_metadata(&module, &source_package)
+                            .await
+                    })
+                    .buffer_unordered(*MODULE_CACHE_MAX_CONCURRENCY);
+                while let Some(result) = results.next().await {
+                    if let Err(e) = result {
+                        tracing::warn!("Failed to prefetch module: {e:?}");
+                    }
+                }
+            });
+        Ok(())
+    }
+
+    /// Load the module at `path` and its metadata, reading the source straight
+    /// from its source package in storage without reading or populating the
+    /// cache. Meant for checking that the bytes in storage match what the
+    /// cache serves. As with [ModuleLoader::get_module], the module is looked
+    /// up through `tx`, so the read is recorded.
+    #[fastrace::trace]
+    pub async fn get_module_with_metadata_uncached(
+        &self,
+        tx: &mut Transaction<RT>,
+        path: CanonicalizedComponentModulePath,
+    ) -> anyhow::Result<Option<(Arc<ParsedDocument<ModuleMetadata>>, Arc<FullModuleSource>)>> {
+        let component = path.component;
+        let Some(module_metadata) = ModuleModel::new(tx).get_metadata(path).await? else {
+            return Ok(None);
+        };
+        let source_package = SourcePackageModel::new(tx, component.into())
+            .get(module_metadata.source_package_id)
+            .await?;
+        let package = download_package(self.modules_storage.clone(), &source_package).await?;
+        let source =
+            module_source_from_package(package, &module_metadata.path, &module_metadata.sha256)
+                .with_context(|| format!("Reading source package {}", source_package.id()))?;
+        Ok(Some((module_metadata, source)))
+    }
+
+    /// Current size and cumulative hit, miss and eviction counts of the cache.
+    pub fn stats(&self) -> AsyncLruStats {
+        self.cache.stats()
+    }
+
+    /// Drop all cached versions of the module at `module_path` in the
+    /// component whose `_modules` table is `modules_table`, so the next load
+    /// re-fetches its source from storage. Called after a deploy so a rollback
+    /// can never be served source that was cached before it.
+    pub fn invalidate(&self, modules_table: TabletId, module_path: &CanonicalizedModulePath) {
+        let num_removed = self
+            .cache
+            .remove_if(|key| key.is_version_of(modules_table, module_path));
+        metrics::log_module_cache_invalidated(num_removed);
+    }
+}
+
+/// Take the module at `path` out of a downloaded source package, checking that
+/// it's the version with hash `sha256`.
+fn module_source_from_package(
+    mut package: BTreeMap<CanonicalizedModulePath, ModuleConfig>,
+    path: &CanonicalizedModulePath,
+    sha256: &Sha256Digest,
+) -> anyhow::Result<Arc<FullModuleSource>> {
+    let module_config = package
+        .remove(path)
+        .with_context(|| format!("Module {path:?} missing from source package"))?;
+    anyhow::ensure!(
+        hash_module_source(&module_config.source, module_config.source_map.as_ref()) == *sha256,
+        "Module {path:?} in storage doesn't match its metadata's hash"
+    );
+    Ok(Arc::new(FullModuleSource {
+        source: module_config.source,
+        source_map: module_config.source_map,
+    }))
 }
 
 #[async_trait]
@@ -62,7 +233,12 @@ impl<RT: Runtime> ModuleLoader<RT> for ModuleCache<RT> {
     ) -> anyhow::Result<Arc<FullModuleSource>> {
         let timer = metrics::module_cache_get_module_timer();
 
-        let key = (module_metadata.path.clone(), module_metadata.sha256.clone());
+        let modules_table = module_metadata.id().tablet_id;
+        let key = ModuleCacheKey {
+            modules_table,
+            path: module_metadata.path.clone(),
+            sha256: module_metadata.sha256.clone(),
+        };
         let result = self
             .cache
             .get_and_prepopulate(&key, || {
@@ -74,13 +250,14 @@ impl<RT: Runtime> ModuleLoader<RT> for ModuleCache<RT> {
                         .into_iter()
                         .map(|(module_path, module_config)| {
                             (
-                                (
-                                    module_path,
-                                    hash_module_source(
+                                ModuleCacheKey {
+                                    modules_table,
+                                    path: module_path,
+                                    sha256: hash_module_source(
                                         &module_config.source,
                                         module_config.source_map.as_ref(),
                                     ),
-                                ),
+                                },
                                 Arc::new(FullModuleSource {
                                     source: module_config.source,
                                     source_map: module_config.source_map,
@@ -99,3 +276,202 @@ impl<RT: Runtime> ModuleLoader<RT> for ModuleCache<RT> {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::Arc,
+    };
+
+    use anyhow::Context;
+    use common::{
+        components::{
+            CanonicalizedComponentModulePath,
+            ComponentId,
+        },
+        types::ModuleEnvironment,
+    };
+    use database::{
+        test_helpers::DbFixtures,
+        Database,
+    };
+    use model::{
+        config::{
+            module_loader::ModuleLoader,
+            types::ModuleConfig,
+        },
+        modules::{
+            hash_module_source,
+            module_versions::AnalyzedModule,
+            ModuleModel,
+        },
+        source_packages::{
+            types::SourcePackage,
+            upload_download::upload_package,
+            SourcePackageModel,
+        },
+    };
+    use runtime::testing::TestRuntime;
+    use storage::{
+        LocalDirStorage,
+        Storage,
+    };
+    use sync_types::CanonicalizedModulePath;
+    use value::{
+        sha256::Sha256Digest,
+        InternalId,
+        TabletId,
+    };
+
+    use super::{
+        module_source_from_package,
+        ModuleCache,
+        ModuleCacheKey,
+    };
+
+    fn key(modules_table: TabletId, path: &str, source: &[u8]) -> ModuleCacheKey {
+        ModuleCacheKey {
+            modules_table,
+            path: path.parse().unwrap(),
+            sha256: Sha256Digest::hash(source),
+        }
+    }
+
+    #[test]
+    fn test_invalidation_is_scoped_to_component() {
+        let app = TabletId(InternalId([1; 16]));
+        let component = TabletId(InternalId([2; 16]));
+        let path: CanonicalizedModulePath = "messages.js".parse().unwrap();
+
+        // Every cached version of the path in the targeted component is evicted.
+        assert!(key(app, "messages.js", b"v1").is_version_of(app, &path));
+        assert!(key(app, "messages.js", b"v2").is_version_of(app, &path));
+        // The same path in another component and other paths are kept.
+        assert!(!key(component, "messages.js", b"v1").is_version_of(app, &path));
+        assert!(!key(app, "users.js", b"v1").is_version_of(app, &path));
+    }
+
+    fn package(modules: &[(&str, &str)]) -> BTreeMap<CanonicalizedModulePath, ModuleConfig> {
+        modules
+            .iter()
+            .map(|(path, source)| {
+                let config = ModuleConfig {
+                    path: path.parse().unwrap(),
+                    source: (*source).into(),
+                    source_map: None,
+                    environment: ModuleEnvironment::Isolate,
+                };
+                (path.parse().unwrap(), config)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_module_source_from_package() -> anyhow::Result<()> {
+        let path: CanonicalizedModulePath = "messages.js".parse()?;
+        let source = "export const list = 1;";
+        let sha256 = hash_module_source(&source.into(), None);
+        let modules = [
+            ("messages.js", source),
+            ("users.js", "export const get = 1;"),
+        ];
+
+        let full_source = module_source_from_package(package(&modules), &path, &sha256)?;
+        assert_eq!(
+            hash_module_source(&full_source.source, full_source.source_map.as_ref()),
+            sha256
+        );
+
+        // The module isn't in the package.
+        let err = module_source_from_package(package(&modules[1..]), &path, &sha256).unwrap_err();
+        assert!(
+            err.to_string().contains("missing from source package"),
+            "{err}"
+        );
+
+        // Storage has a different version of the module than the metadata.
+        let modules = [("messages.js", "export const list = 2;")];
+        let err = module_source_from_package(package(&modules), &path, &sha256).unwrap_err();
+        assert!(err.to_string().contains("doesn't match"), "{err}");
+        Ok(())
+    }
+
+    /// Pushes `source` as the only module, `messages.js`, in a new source
+    /// package.
+    async fn deploy(
+        db: &Database<TestRuntime>,
+        storage: Arc<dyn Storage>,
+        source: &str,
+    ) -> anyhow::Result<()> {
+        let path: CanonicalizedModulePath = "messages.js".parse()?;
+        let module = ModuleConfig {
+            path: path.clone().into(),
+            source: source.into(),
+            source_map: None,
+            environment: ModuleEnvironment::Isolate,
+        };
+        let (storage_key, sha256, package_size) =
+            upload_package(BTreeMap::from([(path.clone(), &module)]), storage, None).await?;
+        let mut tx = db.begin_system().await?;
+        let source_package_id = SourcePackageModel::new(&mut tx, ComponentId::Root.into())
+            .put(SourcePackage {
+                storage_key,
+                sha256,
+                external_deps_package_id: None,
+                package_size,
+                node_version: None,
+            })
+            .await?;
+        let analyze_result = AnalyzedModule {
+            functions: Default::default(),
+            http_routes: None,
+            cron_specs: None,
+            source_index: None,
+            reuse_context: false,
+        };
+        ModuleModel::new(&mut tx)
+            .apply(
+                ComponentId::Root,
+                vec![module],
+                Some(source_package_id),
+                BTreeMap::from([(path, analyze_result)]),
+            )
+            .await?;
+        db.commit_with_write_source(tx, "test").await?;
+        Ok(())
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_fetch_after_deploy_returns_new_source(rt: TestRuntime) -> anyhow::Result<()> {
+        let DbFixtures { db, .. } = DbFixtures::new_with_model(&rt).await?;
+        let storage: Arc<dyn Storage> = Arc::new(LocalDirStorage::new(rt.clone())?);
+        let module_cache = ModuleCache::new(rt, storage.clone()).await;
+        let path = CanonicalizedComponentModulePath {
+            component: ComponentId::Root,
+            module_path: "messages.js".parse()?,
+        };
+
+        // The last deploy rolls back to source that's already been cached.
+        for source in [
+            "export const version = 1;",
+            "export const version = 2;",
+            "export const version = 1;",
+        ] {
+            deploy(&db, storage.clone(), source).await?;
+            let mut tx = db.begin_system().await?;
+            // Invalidate the module the way a push does once it commits.
+            let metadata = ModuleModel::new(&mut tx)
+                .get_metadata(path.clone())
+                .await?
+                .context("Deployed module is missing")?;
+            module_cache.invalidate(metadata.id().tablet_id, &metadata.path);
+            let module = module_cache
+                .get_module(&mut tx, path.clone())
+                .await?
+                .context("Deployed module is missing")?;
+            assert_eq!(&*module.source, source);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+//! Checks that the backend can reach the services it depends on, for
+//! readiness probes.
+
+use std::{
+    future::Future,
+    sync::Arc,
+};
+
+use common::{
+    knobs::READINESS_CHECK_TIMEOUT,
+    runtime::Runtime,
+    types::ObjectKey,
+};
+use database::{
+    Database,
+    DatabaseSnapshot,
+};
+use futures::{
+    future,
+    select_biased,
+    FutureExt,
+};
+use storage::Storage;
+
+/// A service the backend needs to be reachable to serve requests.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReadinessSubsystem {
+    Persistence,
+    SearchStorage,
+    ModulesStorage,
+}
+
+impl ReadinessSubsystem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadinessSubsystem::Persistence => "persistence",
+            ReadinessSubsystem::SearchStorage => "search storage",
+            ReadinessSubsystem::ModulesStorage => "modules storage",
+        }
+    }
+}
+
+/// Object looked up to check that a storage is reachable. It doesn't need to
+/// exist: a missing object still takes a round-trip.
+const PROBE_OBJECT_KEY: &str = "readiness-probe";
+
+/// Runs a cheap round-trip against each subsystem concurrently and returns
+/// the ones that failed or took longer than [READINESS_CHECK_TIMEOUT].
+pub async fn check_readiness<RT: Runtime>(
+    runtime: &RT,
+    database: &Database<RT>,
+    search_storage: &Arc<dyn Storage>,
+    modules_storage: &Arc<dyn Storage>,
+) -> Vec<(ReadinessSubsystem, anyhow::Error)> {
+    let persistence = async {
+        let _tx = database.begin_system().await?;
+        let snapshot = database.latest_database_snapshot()?;
+        DatabaseSnapshot::<RT>::max_ts(snapshot.persistence_reader.as_ref()).await?;
+        anyhow::Ok(())
+    };
+    let (persistence, search_storage, modules_storage) = future::join3(
+        with_timeout(runtime, persistence),
+        with_timeout(runtime, stat_probe_object(search_storage)),
+        with_timeout(runtime, stat_probe_object(modules_storage)),
+    )
+    .await;
+    [
+        (ReadinessSubsystem::Persistence, persistence),
+        (ReadinessSubsystem::SearchStorage, search_storage),
+        (ReadinessSubsystem::ModulesStorage, modules_storage),
+    ]
+    .into_iter()
+    .filter_map(|(subsystem, result)| result.err().map(|e| (subsystem, e)))
+    .collect()
+}
+
+async fn stat_probe_object(storage: &Arc<dyn Storage>) -> anyhow::Result<()> {
+    storage
+        .get_object_attributes(&ObjectKey::try_from(PROBE_OBJECT_KEY)?)
+        .await?;
+    Ok(())
+}
+
+async fn with_timeout<RT: Runtime>(
+    runtime: &RT,
+    check: impl Future<Output = anyhow::Result<()>>,
+) -> anyhow::Result<()> {
+    let timeout = *READINESS_CHECK_TIMEOUT;
+    select_biased! {
+        result = check.fuse() => result,
+        _ = runtime.wait(timeout).fuse() => {
+            anyhow::bail!("didn't respond within {timeout:?}")
+        },
+    }
+}
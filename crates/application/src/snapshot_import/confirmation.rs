@@ -1,6 +1,10 @@
-use std::collections::{
-    BTreeMap,
-    BTreeSet,
+use std::{
+    collections::{
+        btree_map::Entry,
+        BTreeMap,
+        BTreeSet,
+    },
+    time::Duration,
 };
 
 use anyhow::Context;
@@ -23,6 +27,7 @@ use model::{
         FILE_STORAGE_VIRTUAL_TABLE,
     },
     snapshot_imports::types::{
+        ImportFormat,
         ImportMode,
         ImportTableCheckpoint,
         SnapshotImport,
@@ -30,55 +35,117 @@ use model::{
 };
 
 use crate::snapshot_import::{
+    ensure_no_table_name_case_collisions,
+    ensure_per_table_modes_in_import,
     import_error::ImportError,
     table_change::{
         render_table_changes,
         TableChange,
     },
+    throughput::describe_estimate,
     SnapshotImportExecutor,
 };
 
+/// What the user is shown before confirming an import.
+pub struct ImportConfirmationInfo {
+    pub info_message: String,
+    pub require_manual_confirmation: bool,
+    /// Approximately how long the import will take, from the number of rows
+    /// to write and the throughput of recent imports.
+    pub estimated_duration: Duration,
+    pub new_checkpoints: Vec<ImportTableCheckpoint>,
+}
+
 /// Parse the uploaded import file, compare it to existing data, and return
 /// a message to display about the import before it begins.
 pub async fn info_message_for_import<RT: Runtime>(
     executor: &SnapshotImportExecutor<RT>,
     snapshot_import: ParsedDocument<SnapshotImport>,
-) -> anyhow::Result<(String, bool, Vec<ImportTableCheckpoint>)> {
+) -> anyhow::Result<ImportConfirmationInfo> {
     executor.fail_if_too_old(&snapshot_import)?;
     let mut message_lines = Vec::new();
-    let (content_confirmation_messages, require_manual_confirmation, new_checkpoints) =
+    let (content_confirmation_messages, csv_warnings, require_manual_confirmation, new_checkpoints) =
         messages_to_confirm_replace(executor, snapshot_import).await?;
     message_lines.extend(content_confirmation_messages);
     // Consider adding confirmation messages about bandwidth usage.
     if !message_lines.is_empty() {
         message_lines.insert(0, "Import change summary:".to_string())
     }
+    message_lines.extend(csv_warnings);
+    let num_rows_to_write = new_checkpoints
+        .iter()
+        .map(|checkpoint| checkpoint.total_num_rows_to_write.max(0) as u64)
+        .sum();
+    let estimated_duration = executor.throughput.estimate(num_rows_to_write);
+    message_lines.push(format!(
+        "Estimated import time (approximate): {}",
+        describe_estimate(estimated_duration)
+    ));
     message_lines.push(
         "Once the import has started, it will run in the background.\nInterrupting `npx convex \
          import` will not cancel it."
             .to_string(),
     );
-    Ok((
-        message_lines.join("\n"),
+    Ok(ImportConfirmationInfo {
+        info_message: message_lines.join("\n"),
         require_manual_confirmation,
+        estimated_duration,
         new_checkpoints,
-    ))
+    })
+}
+
+/// Fails a RequireEmpty import if any table it writes to already has
+/// documents, naming every such table at once.
+pub fn ensure_tables_empty(nonempty_tables: &[&(ComponentPath, TableName)]) -> anyhow::Result<()> {
+    match nonempty_tables {
+        [] => Ok(()),
+        [(_, table_name)] => anyhow::bail!(ImportError::TableExists(table_name.clone())),
+        [..] => anyhow::bail!(ImportError::TablesExist(
+            nonempty_tables
+                .iter()
+                .map(|(component_path, table_name)| {
+                    format!("{table_name}{}", component_path.in_component_str())
+                })
+                .collect()
+        )),
+    }
 }
 
 async fn messages_to_confirm_replace<RT: Runtime>(
     executor: &SnapshotImportExecutor<RT>,
     snapshot_import: ParsedDocument<SnapshotImport>,
-) -> anyhow::Result<(Vec<String>, bool, Vec<ImportTableCheckpoint>)> {
+) -> anyhow::Result<(Vec<String>, Vec<String>, bool, Vec<ImportTableCheckpoint>)> {
     let mode = snapshot_import.mode;
+    let is_csv = match snapshot_import.format {
+        ImportFormat::Csv(_) => true,
+        ImportFormat::JsonLines(_)
+        | ImportFormat::JsonLinesGzip(_)
+        | ImportFormat::JsonArray(_)
+        | ImportFormat::Zip => false,
+    };
     let (_, import) = executor.parse_import(snapshot_import.id()).await?;
     // Find all tables being written to.
     let mut count_by_table: BTreeMap<(ComponentPath, TableName), u64> = BTreeMap::new();
     let mut tables_missing_id_field: BTreeSet<(ComponentPath, TableName)> = BTreeSet::new();
+    let mut csv_warnings = Vec::new();
     for (component_path, table_name, mut objects) in import.documents {
         let mut lineno = 0u64;
         let component_table = (component_path, table_name);
+        // For CSV imports, whether each column has had a number and a
+        // non-empty string.
+        let mut csv_column_kinds: BTreeMap<String, (bool, bool)> = BTreeMap::new();
         while let Some(exported_value) = objects.try_next().await? {
             lineno += 1;
+            if is_csv && let Some(exported_object) = exported_value.as_object() {
+                for (column, value) in exported_object {
+                    let is_number = value.is_number();
+                    let is_string = value.as_str().is_some_and(|s| !s.is_empty());
+                    let (has_number, has_string) =
+                        csv_column_kinds.entry(column.clone()).or_default();
+                    *has_number |= is_number;
+                    *has_string |= is_string;
+                }
+            }
             if component_table.1 == TABLES_TABLE {
                 let exported_object = exported_value
                     .as_object()
@@ -89,12 +156,19 @@ async fn messages_to_confirm_replace<RT: Runtime>(
                     .with_context(|| {
                     ImportError::InvalidConvexValue(lineno, anyhow::anyhow!("table requires name"))
                 })?;
-                let entry_table_name = entry_table_name
+                let entry_table_name: TableName = entry_table_name
                     .parse()
                     .map_err(|e| ImportError::InvalidName(entry_table_name.to_string(), e))?;
-                count_by_table
-                    .entry((component_table.0.clone(), entry_table_name))
-                    .or_default();
+                let entry = (component_table.0.clone(), entry_table_name);
+                // Tables left out of the import aren't written.
+                if snapshot_import
+                    .options
+                    .include_tables
+                    .as_ref()
+                    .is_none_or(|include_tables| include_tables.contains(&entry))
+                {
+                    count_by_table.entry(entry).or_default();
+                }
             }
             if !tables_missing_id_field.contains(&component_table)
                 && exported_value.get(&*ID_FIELD).is_none()
@@ -102,13 +176,35 @@ async fn messages_to_confirm_replace<RT: Runtime>(
                 tables_missing_id_field.insert(component_table.clone());
             }
         }
+        for (column, (has_number, has_string)) in csv_column_kinds {
+            if has_number && has_string {
+                csv_warnings.push(format!(
+                    "Warning: column {column:?} in table {:?} has both numbers and strings. If it \
+                     should only contain strings (e.g. IDs or ZIP codes with leading zeros), pass \
+                     it in csvStringColumns.",
+                    component_table.1.to_string(),
+                ));
+            }
+        }
         *count_by_table.entry(component_table.clone()).or_default() += lineno;
     }
 
+    // Catch collisions here too, so they're reported before the user confirms.
+    ensure_no_table_name_case_collisions(
+        count_by_table
+            .keys()
+            .map(|(component_path, table_name)| (component_path, table_name)),
+    )?;
+    ensure_per_table_modes_in_import(
+        count_by_table.keys().map(|(_, table_name)| table_name),
+        &snapshot_import.options.per_table_mode,
+    )?;
+
     let db_snapshot = executor.database.latest_snapshot()?;
 
     // Add to count_by_table all tables that are being replaced that don't appear in
     // the import.
+    let mut cleared_by_replace_all = BTreeSet::new();
     if mode == ImportMode::ReplaceAll {
         let component_paths = db_snapshot.component_ids_to_paths();
         let table_mapping = db_snapshot.table_mapping();
@@ -119,15 +215,28 @@ async fn messages_to_confirm_replace<RT: Runtime>(
             if !table_mapping.is_active(tablet_id) {
                 continue;
             }
-            count_by_table
-                .entry((component_path.clone(), table_name.clone()))
-                .or_default();
+            if let Entry::Vacant(v) =
+                count_by_table.entry((component_path.clone(), table_name.clone()))
+            {
+                cleared_by_replace_all.insert(v.key().clone());
+                v.insert(0);
+            }
         }
     }
 
     let mut table_changes = BTreeMap::new();
+    // Tables that RequireEmpty would write to but already have documents,
+    // reported together once all tables are checked.
+    let mut nonempty_tables = Vec::new();
     for (component_and_table, count_importing) in count_by_table.iter() {
         let (component_path, table_name) = component_and_table;
+        // Per-table overrides only apply to tables in the import. Other tables
+        // are cleared by ReplaceAll regardless.
+        let table_mode = if cleared_by_replace_all.contains(component_and_table) {
+            mode
+        } else {
+            snapshot_import.options.mode_for_table(mode, table_name)
+        };
         let existing_num_values = db_snapshot
             .component_registry
             .component_path_to_ids(component_path, &mut TransactionReadSet::new())?
@@ -143,14 +252,15 @@ async fn messages_to_confirm_replace<RT: Runtime>(
             .transpose()?
             .unwrap_or(0);
         if !table_name.is_system() {
-            let to_delete = match mode {
+            let to_delete = match table_mode {
                 ImportMode::Replace | ImportMode::ReplaceAll => {
                     // Overwriting nonempty user table.
                     existing_num_values
                 },
                 ImportMode::Append => 0,
                 ImportMode::RequireEmpty if existing_num_values > 0 => {
-                    anyhow::bail!(ImportError::TableExists(table_name.clone()))
+                    nonempty_tables.push(component_and_table);
+                    0
                 },
                 ImportMode::RequireEmpty => 0,
             };
@@ -162,18 +272,20 @@ async fn messages_to_confirm_replace<RT: Runtime>(
                     existing: existing_num_values,
                     unit: "",
                     is_missing_id_field: tables_missing_id_field.contains(component_and_table),
+                    mode: table_mode,
                 },
             );
         }
         if table_name == &FILE_STORAGE_VIRTUAL_TABLE {
-            let to_delete = match mode {
+            let to_delete = match table_mode {
                 ImportMode::Replace | ImportMode::ReplaceAll => {
                     // Overwriting nonempty file storage.
                     existing_num_values
                 },
                 ImportMode::Append => 0,
                 ImportMode::RequireEmpty if existing_num_values > 0 => {
-                    anyhow::bail!(ImportError::TableExists(table_name.clone()))
+                    nonempty_tables.push(component_and_table);
+                    0
                 },
                 ImportMode::RequireEmpty => 0,
             };
@@ -185,10 +297,12 @@ async fn messages_to_confirm_replace<RT: Runtime>(
                     existing: existing_num_values,
                     unit: " files",
                     is_missing_id_field: tables_missing_id_field.contains(component_and_table),
+                    mode: table_mode,
                 },
             );
         }
     }
+    ensure_tables_empty(&nonempty_tables)?;
     let mut require_manual_confirmation = false;
     let mut new_checkpoints = Vec::new();
 
@@ -200,6 +314,7 @@ async fn messages_to_confirm_replace<RT: Runtime>(
             existing,
             unit: _,
             is_missing_id_field,
+            mode: _,
         },
     ) in table_changes.iter()
     {
@@ -228,5 +343,10 @@ async fn messages_to_confirm_replace<RT: Runtime>(
         }
         message_lines.extend(render_table_changes(table_changes.collect()));
     }
-    Ok((message_lines, require_manual_confirmation, new_checkpoints))
+    Ok((
+        message_lines,
+        csv_warnings,
+        require_manual_confirmation,
+        new_checkpoints,
+    ))
 }
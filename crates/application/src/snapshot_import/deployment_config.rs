@@ -0,0 +1,454 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+        HashSet,
+    },
+    sync::Arc,
+};
+
+use common::{
+    bootstrap_model::{
+        components::ComponentType,
+        index::{
+            database_index::DatabaseIndexSpec,
+            text_index::TextIndexSpec,
+            vector_index::VectorIndexSpec,
+            IndexMetadata,
+            TabletIndexMetadata,
+        },
+    },
+    components::{
+        ComponentId,
+        ComponentPath,
+        Resource,
+    },
+    runtime::Runtime,
+    schemas::{
+        json::DatabaseSchemaJson,
+        DatabaseSchema,
+    },
+    types::{
+        EnvVarName,
+        EnvironmentVariable,
+        IndexDescriptor,
+        TableName,
+        TabletIndexName,
+    },
+};
+use database::{
+    BootstrapComponentsModel,
+    Database,
+    IndexModel,
+    SchemaModel,
+    Transaction,
+};
+use errors::ErrorMetadata;
+use keybroker::Identity;
+use model::{
+    cron_jobs::{
+        types::{
+            CronIdentifier,
+            CronSpec,
+        },
+        CronModel,
+    },
+    deployment_audit_log::{
+        developer_index_config::{
+            DeveloperIndexConfig,
+            DeveloperIndexSpec,
+            SerializedNamedDeveloperIndexConfig,
+        },
+        types::DeploymentAuditLogEvent,
+    },
+    exports::deployment_config::{
+        DeploymentConfigManifest,
+        DeploymentConfigSections,
+        SerializedBundledCron,
+        SerializedBundledEnvironmentVariable,
+        SerializedBundledIndex,
+        COMPONENT_ARGS_FILE,
+        CRONS_FILE,
+        DEPLOYMENT_CONFIG_VERSION,
+        ENVIRONMENT_VARIABLES_FILE,
+        INDEXES_FILE,
+        MANIFEST_FILE,
+        SCHEMA_FILE,
+    },
+    snapshot_imports::types::ComponentArgsPolicy,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+use usage_tracking::FunctionUsageTracker;
+use value::{
+    identifier::Identifier,
+    ConvexValue,
+    TabletId,
+};
+
+use crate::{
+    application_function_runner::ApplicationFunctionRunner,
+    snapshot_import::{
+        backfill_and_enable_indexes_on_table,
+        prepare_component::prepare_component_for_import,
+    },
+    Application,
+    EnvVarChange,
+};
+
+/// An index from the bundle, to be added to an imported table.
+#[derive(Clone, Debug)]
+pub struct BundledIndex {
+    pub descriptor: IndexDescriptor,
+    pub spec: DeveloperIndexSpec,
+}
+
+/// The deployment configuration found in a ZIP import, laid out as described
+/// in [`model::exports::deployment_config`].
+#[derive(Default)]
+pub struct DeploymentConfigBundle {
+    manifest: Option<DeploymentConfigManifest>,
+    schemas: BTreeMap<ComponentPath, DatabaseSchema>,
+    pub indexes: BTreeMap<(ComponentPath, TableName), Vec<BundledIndex>>,
+    pub crons: BTreeMap<ComponentPath, BTreeMap<CronIdentifier, CronSpec>>,
+    pub environment_variables: Vec<EnvironmentVariable>,
+    component_args: BTreeMap<ComponentPath, BTreeMap<Identifier, ConvexValue>>,
+}
+
+fn invalid_deployment_config(msg: String) -> anyhow::Error {
+    anyhow::anyhow!(ErrorMetadata::bad_request("InvalidDeploymentConfig", msg))
+}
+
+fn parse_jsonl<T: DeserializeOwned>(file_name: &str, contents: &[u8]) -> anyhow::Result<Vec<T>> {
+    contents
+        .split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_slice(line)
+                .map_err(|e| invalid_deployment_config(format!("{file_name} line {}: {e}", i + 1)))
+        })
+        .collect()
+}
+
+impl DeploymentConfigBundle {
+    /// Whether the ZIP had no deployment configuration files.
+    pub fn is_empty(&self) -> bool {
+        self.manifest.is_none()
+            && self.schemas.is_empty()
+            && self.indexes.is_empty()
+            && self.crons.is_empty()
+            && self.environment_variables.is_empty()
+            && self.component_args.is_empty()
+    }
+
+    /// Adds a file from a `_deployment/` directory of the ZIP.
+    pub fn add_file(
+        &mut self,
+        component_path: ComponentPath,
+        file_name: &str,
+        contents: &[u8],
+    ) -> anyhow::Result<()> {
+        match file_name {
+            MANIFEST_FILE => {
+                if component_path.is_root() {
+                    self.manifest =
+                        Some(serde_json::from_slice(contents).map_err(|e| {
+                            invalid_deployment_config(format!("{MANIFEST_FILE}: {e}"))
+                        })?);
+                }
+            },
+            SCHEMA_FILE => {
+                let schema: DatabaseSchemaJson = serde_json::from_slice(contents)
+                    .map_err(|e| invalid_deployment_config(format!("{SCHEMA_FILE}: {e}")))?;
+                self.schemas.insert(component_path, schema.try_into()?);
+            },
+            INDEXES_FILE => {
+                for SerializedBundledIndex {
+                    table,
+                    index: SerializedNamedDeveloperIndexConfig { name, index_config },
+                } in parse_jsonl(file_name, contents)?
+                {
+                    let config = DeveloperIndexConfig::try_from(index_config)?;
+                    // Staged indexes aren't enabled by a push either.
+                    if config.is_staged() {
+                        continue;
+                    }
+                    self.indexes
+                        .entry((component_path.clone(), table.parse()?))
+                        .or_default()
+                        .push(BundledIndex {
+                            descriptor: IndexDescriptor::new(name)?,
+                            spec: config.into_spec(),
+                        });
+                }
+            },
+            CRONS_FILE => {
+                let crons = self.crons.entry(component_path).or_default();
+                for SerializedBundledCron { name, spec } in parse_jsonl(file_name, contents)? {
+                    crons.insert(name.parse()?, spec.try_into()?);
+                }
+            },
+            ENVIRONMENT_VARIABLES_FILE => {
+                if component_path.is_root() {
+                    for SerializedBundledEnvironmentVariable { name, value } in
+                        parse_jsonl(file_name, contents)?
+                    {
+                        self.environment_variables
+                            .push(EnvironmentVariable::new(name.parse()?, value.parse()?));
+                    }
+                }
+            },
+            COMPONENT_ARGS_FILE => {
+                if !component_path.is_root() {
+                    let args: BTreeMap<String, JsonValue> = serde_json::from_slice(contents)
+                        .map_err(|e| {
+                            invalid_deployment_config(format!("{COMPONENT_ARGS_FILE}: {e}"))
+                        })?;
+                    let args = args
+                        .into_iter()
+                        .map(|(name, value)| anyhow::Ok((name.parse()?, value.try_into()?)))
+                        .collect::<anyhow::Result<_>>()?;
+                    self.component_args.insert(component_path, args);
+                }
+            },
+            _ => tracing::info!("Skipping unknown deployment config file {file_name}"),
+        }
+        Ok(())
+    }
+
+    /// Checks that the bundle was exported with every section that's being
+    /// restored.
+    fn validate(&self, sections: DeploymentConfigSections) -> anyhow::Result<()> {
+        let Some(manifest) = &self.manifest else {
+            return Err(invalid_deployment_config(
+                "The import doesn't contain a deployment configuration manifest".to_string(),
+            ));
+        };
+        if manifest.version != DEPLOYMENT_CONFIG_VERSION {
+            return Err(invalid_deployment_config(format!(
+                "Unsupported deployment configuration version {}",
+                manifest.version
+            )));
+        }
+        if !manifest.sections.contains(&sections) {
+            return Err(invalid_deployment_config(format!(
+                "Can't restore {sections:?} from an export that only contains {:?}",
+                manifest.sections
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Fails if a child component the bundle recorded arguments for exists in this
+/// deployment with different arguments. Components that don't exist yet are
+/// created by the import, so there's nothing to compare them to.
+///
+/// This runs before anything is written, including components created by the
+/// import itself.
+pub async fn check_component_args<RT: Runtime>(
+    database: &Database<RT>,
+    identity: &Identity,
+    bundle: &DeploymentConfigBundle,
+    policy: ComponentArgsPolicy,
+) -> anyhow::Result<()> {
+    match policy {
+        ComponentArgsPolicy::Check => {},
+        ComponentArgsPolicy::Ignore => return Ok(()),
+    }
+    let mut tx = database.begin(identity.clone()).await?;
+    for (component_path, exported_args) in &bundle.component_args {
+        let Some(metadata) = BootstrapComponentsModel::new(&mut tx).resolve_path(component_path)?
+        else {
+            continue;
+        };
+        let args = match &metadata.component_type {
+            ComponentType::App => continue,
+            ComponentType::ChildComponent { args, .. } => args,
+        };
+        let args: BTreeMap<_, _> = args
+            .iter()
+            .filter_map(|(name, resource)| match resource {
+                Resource::Value(value) => Some((name.clone(), value.clone())),
+                Resource::Function(_) | Resource::ResolvedSystemUdf(_) => None,
+            })
+            .collect();
+        let mismatched: BTreeSet<_> = exported_args
+            .keys()
+            .chain(args.keys())
+            .filter(|name| exported_args.get(*name) != args.get(*name))
+            .collect();
+        if !mismatched.is_empty() {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "ComponentArgsMismatch",
+                format!(
+                    "Component {component_path} was exported with different arguments than it has \
+                     in this deployment ({}). Import with componentArgs=ignore to import anyway.",
+                    mismatched
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the bundle can restore `sections` and restores schemas, which
+/// must be in place before any documents are imported. A restored schema is
+/// submitted as pending, so the imported documents are checked against it, and
+/// it's activated by the next push once the schema worker has validated it.
+pub async fn restore_deployment_config_before_data<RT: Runtime>(
+    database: &Database<RT>,
+    identity: &Identity,
+    bundle: &DeploymentConfigBundle,
+    sections: DeploymentConfigSections,
+) -> anyhow::Result<()> {
+    if sections.is_empty() {
+        return Ok(());
+    }
+    bundle.validate(sections)?;
+    if sections.schema {
+        for (component_path, schema) in &bundle.schemas {
+            let component_id = prepare_component_for_import(database, component_path).await?;
+            database
+                .execute_with_overloaded_retries(
+                    identity.clone(),
+                    FunctionUsageTracker::new(),
+                    "snapshot_import_restore_schema",
+                    |tx| {
+                        async {
+                            SchemaModel::new(tx, component_id.into())
+                                .submit_pending(schema.clone())
+                                .await?;
+                            Ok(())
+                        }
+                        .into()
+                    },
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Environment variables from the bundle. They're restored in the transaction
+/// that finalizes the import, with the same validation, limits and audit log
+/// events as any other environment variable update, so they only take effect
+/// if the import does.
+pub struct EnvironmentVariablesRestore<'a, RT: Runtime> {
+    pub runner: &'a Arc<ApplicationFunctionRunner<RT>>,
+    pub system_env_var_names: &'a HashSet<EnvVarName>,
+    pub environment_variables: Vec<EnvironmentVariable>,
+}
+
+impl<RT: Runtime> EnvironmentVariablesRestore<'_, RT> {
+    /// Sets the environment variables in `tx`, overwriting any existing ones
+    /// with the same names, and returns the audit log events for them.
+    pub async fn apply(
+        &self,
+        tx: &mut Transaction<RT>,
+    ) -> anyhow::Result<Vec<DeploymentAuditLogEvent>> {
+        Application::apply_environment_variable_changes(
+            self.runner.clone(),
+            self.system_env_var_names,
+            tx,
+            self.environment_variables
+                .iter()
+                .cloned()
+                .map(EnvVarChange::Set)
+                .collect(),
+        )
+        .await
+    }
+}
+
+/// Adds bundled indexes to a table that's being imported, and waits for them
+/// to backfill. This runs before the table's documents are written, so the
+/// backfill is quick.
+pub async fn restore_indexes_on_table<RT: Runtime>(
+    database: &Database<RT>,
+    identity: &Identity,
+    tablet_id: TabletId,
+    indexes: &[BundledIndex],
+) -> anyhow::Result<()> {
+    database
+        .execute_with_overloaded_retries(
+            identity.clone(),
+            FunctionUsageTracker::new(),
+            "snapshot_import_restore_indexes",
+            |tx| {
+                async {
+                    let begin_ts = *tx.begin_timestamp();
+                    let indexes = indexes
+                        .iter()
+                        .map(|BundledIndex { descriptor, spec }| {
+                            let name = TabletIndexName::new(tablet_id, descriptor.clone())?;
+                            let metadata: TabletIndexMetadata = match spec.clone() {
+                                DeveloperIndexSpec::Database(DatabaseIndexSpec { fields }) => {
+                                    IndexMetadata::new_backfilling(begin_ts, name, fields)
+                                },
+                                DeveloperIndexSpec::Search(TextIndexSpec {
+                                    search_field,
+                                    filter_fields,
+                                }) => IndexMetadata::new_backfilling_text_index(
+                                    name,
+                                    search_field,
+                                    filter_fields,
+                                ),
+                                DeveloperIndexSpec::Vector(VectorIndexSpec {
+                                    dimensions,
+                                    vector_field,
+                                    filter_fields,
+                                }) => IndexMetadata::new_backfilling_vector_index(
+                                    name,
+                                    vector_field,
+                                    dimensions,
+                                    filter_fields,
+                                ),
+                            };
+                            anyhow::Ok(metadata)
+                        })
+                        .collect::<anyhow::Result<_>>()?;
+                    IndexModel::new(tx)
+                        .add_backfilling_indexes_to_table(tablet_id, indexes)
+                        .await?;
+                    Ok(())
+                }
+                .into()
+            },
+        )
+        .await?;
+    backfill_and_enable_indexes_on_table(database, identity, tablet_id, None).await
+}
+
+/// Restores cron jobs once the import's documents are in place, so a cron
+/// can't run against a partially imported deployment.
+pub async fn restore_crons<RT: Runtime>(
+    database: &Database<RT>,
+    identity: &Identity,
+    crons: BTreeMap<ComponentPath, BTreeMap<CronIdentifier, CronSpec>>,
+) -> anyhow::Result<()> {
+    for (component_path, crons) in crons {
+        let component_id: ComponentId =
+            prepare_component_for_import(database, &component_path).await?;
+        database
+            .execute_with_overloaded_retries(
+                identity.clone(),
+                FunctionUsageTracker::new(),
+                "snapshot_import_restore_crons",
+                |tx| {
+                    async {
+                        CronModel::new(tx, component_id).restore(&crons).await?;
+                        Ok(())
+                    }
+                    .into()
+                },
+            )
+            .await?;
+    }
+    Ok(())
+}
@@ -1,6 +1,12 @@
-use std::sync::LazyLock;
+use std::{
+    sync::LazyLock,
+    time::Duration,
+};
 
-use common::knobs::TRANSACTION_MAX_USER_WRITE_SIZE_BYTES;
+use common::{
+    document::MAX_USER_SIZE,
+    knobs::TRANSACTION_MAX_USER_WRITE_SIZE_BYTES,
+};
 use errors::{
     ErrorMetadata,
     ErrorMetadataAnyhowExt,
@@ -22,12 +28,21 @@ pub enum ImportError {
     )]
     TableExists(TableName),
 
+    #[error(
+        "Tables {} already exist. Please choose new table names or use replace/append modes.",
+        .0.join(", ")
+    )]
+    TablesExist(Vec<String>),
+
     #[error("{0:?} isn't a valid table name: {1}")]
     InvalidName(String, anyhow::Error),
 
     #[error("Import wasn't valid UTF8: {0}")]
     NotUtf8(std::io::Error),
 
+    #[error("Import wasn't a valid gzip file: {0}")]
+    InvalidGzip(std::io::Error),
+
     #[error("UTF-8 BOM is not supported. Please save your file without BOM.")]
     Utf8BomNotSupported,
 
@@ -46,6 +61,9 @@ pub enum ImportError {
     #[error("Failed to parse CSV row {0}: {1}")]
     CsvInvalidRow(u64, csv_async::Error),
 
+    #[error("String column {0:?} isn't in the CSV header")]
+    CsvStringColumnNotInHeader(String),
+
     #[error("CSV row {0} doesn't have all of the fields in the header")]
     CsvRowMissingFields(u64),
 
@@ -55,14 +73,67 @@ pub enum ImportError {
     #[error("Row {0} wasn't a valid Convex value: {1}")]
     InvalidConvexValue(u64, anyhow::Error),
 
+    #[error(
+        "Row {row_number} doesn't match the schema{}: expected {expected}.\n{message}",
+        .field.as_ref().map(|field| format!(" at field `{field}`")).unwrap_or_default()
+    )]
+    SchemaMismatch {
+        row_number: u64,
+        field: Option<String>,
+        expected: String,
+        message: String,
+    },
+
     #[error("Row {0} wasn't an object")]
     NotAnObject(u64),
 
+    #[error("Row {0} is missing the `_id` field, which this import requires")]
+    MissingIdField(u64),
+
+    #[error(
+        "Row {row_number} is too large to import ({} > maximum {}). Its largest field is \
+         `{largest_field}` ({}). Consider storing large values in file storage and keeping their \
+         `_storage` ids in the document instead",
+        .size.format_size(BINARY),
+        MAX_USER_SIZE.format_size(BINARY),
+        .largest_field_size.format_size(BINARY)
+    )]
+    DocumentTooLarge {
+        row_number: u64,
+        size: usize,
+        largest_field: String,
+        largest_field_size: usize,
+    },
+
     #[error("Not a JSON array")]
     NotJsonArray,
 
+    #[error("JSON pointer {0:?} doesn't match any value in the file")]
+    JsonPointerNotFound(String),
+
+    #[error("JSON pointer {0:?} doesn't point to an array")]
+    JsonPointerNotArray(String),
+
+    #[error("Row {0} doesn't have a number or string `{1}` field to order the import by")]
+    InvalidOrderByValue(u64, String),
+
+    #[error(
+        "Row {0} is out of order by `{1}`. Only JSON array imports can be sorted during import, \
+         so other formats must already be sorted by this field"
+    )]
+    NotSortedByField(u64, String),
+
     #[error("Not valid JSON: {0}")]
     NotJson(serde_json::Error),
+
+    #[error("The transform function failed on row {0}: {1}")]
+    TransformFailed(u64, String),
+
+    #[error("The transform function took longer than {1:?} on row {0}")]
+    TransformTimedOut(u64, Duration),
+
+    #[error("The transform function didn't return an object for row {0}")]
+    TransformNotAnObject(u64),
 }
 
 impl ImportError {
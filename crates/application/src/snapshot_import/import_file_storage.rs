@@ -60,6 +60,9 @@ use crate::snapshot_import::{
     progress::{
         add_checkpoint_message,
         best_effort_update_progress_message,
+        ImportProgressSink,
+        ImportTablePhase,
+        TableProgress,
     },
 };
 
@@ -76,6 +79,7 @@ pub async fn import_storage_table<RT: Runtime>(
     num_to_skip: u64,
     requestor: ImportRequestor,
     table_mapping_for_schema: &TableMapping,
+    progress_sink: Option<&dyn ImportProgressSink>,
 ) -> anyhow::Result<()> {
     let snapshot = database.latest_snapshot()?;
     let virtual_table_number = snapshot.table_mapping().tablet_number(table_id.tablet_id)?;
@@ -134,6 +138,13 @@ pub async fn import_storage_table<RT: Runtime>(
         );
     }
     let total_num_files = storage_metadata.len();
+    let progress = TableProgress::new(
+        progress_sink,
+        component_path,
+        &FILE_STORAGE_VIRTUAL_TABLE,
+        Some(total_num_files as u64),
+    );
+    progress.report(ImportTablePhase::Started, num_to_skip);
     let mut num_files = 0;
     for (id, file_chunks) in storage_files {
         // The or_default means a storage file with a valid id will be imported
@@ -143,8 +154,19 @@ pub async fn import_storage_table<RT: Runtime>(
             storage_metadata.remove(&id).unwrap_or_default();
         let mut entry = file_storage
             .transactional_file_storage
-            .upload_file(content_length, content_type, file_chunks(), expected_sha256)
+            .upload_file(
+                content_length,
+                content_type,
+                file_chunks(),
+                expected_sha256.clone(),
+            )
             .await?;
+        if let Some(expected_sha256) = &expected_sha256 {
+            file_storage
+                .transactional_file_storage
+                .verify_stored_file(&entry, expected_sha256)
+                .await?;
+        }
         if let Some(storage_id) = storage_id {
             entry.storage_id = storage_id;
         }
@@ -222,7 +244,9 @@ pub async fn import_storage_table<RT: Runtime>(
             )
             .await;
         }
+        progress.report(ImportTablePhase::Writing, num_files);
     }
+    progress.report(ImportTablePhase::Finished, num_files);
     if let Some(import_id) = import_id {
         add_checkpoint_message(
             database,
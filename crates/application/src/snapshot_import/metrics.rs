@@ -42,6 +42,14 @@ pub fn log_snapshot_import_failed(e: &anyhow::Error) {
     );
 }
 
+register_convex_histogram!(
+    SNAPSHOT_IMPORT_TABLES_CREATED,
+    "Number of new tables created by a snapshot import",
+);
+pub fn log_snapshot_import_tables_created(num_tables: usize) {
+    log_distribution(&SNAPSHOT_IMPORT_TABLES_CREATED, num_tables as f64);
+}
+
 register_convex_counter!(
     SNAPSHOT_IMPORT_LEGACY_GENERATED_SCHEMA_TOTAL,
     "Number of times snapshot import encountered a non-uniform generated_schema.jsonl"
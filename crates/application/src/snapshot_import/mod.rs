@@ -8,6 +8,9 @@
 //!     [ImportDocumentStream] for each table (found in
 //!     [ParsedImport::documents]). This works because zip files are seekable.
 //!   - Other import formats resolve to just one table.
+//!   - A ZIP export split into several files goes through
+//!     [parse_multipart_import] instead, which joins each table's streams
+//!     across the parts.
 //!   - At the same time, we save a copy of the schemas from the database.
 //! - [import_objects] copies data from the [ParsedImport] into the database,
 //!   writing into hidden tables (except in [ImportMode::Append]).
@@ -31,6 +34,9 @@ use std::{
         BTreeSet,
         HashSet,
     },
+    future::Future,
+    iter,
+    mem,
     pin::Pin,
     sync::Arc,
     time::Duration,
@@ -52,6 +58,7 @@ use common::{
         CreationTime,
         ParsedDocument,
         ID_FIELD,
+        MAX_USER_SIZE,
     },
     errors::report_error,
     execution_context::{
@@ -60,6 +67,14 @@ use common::{
     },
     knobs::{
         MAX_IMPORT_AGE,
+        SNAPSHOT_IMPORT_ACTIVATION_BATCH_SIZE,
+        SNAPSHOT_IMPORT_BATCH_MAX_NUM_DOCUMENTS,
+        SNAPSHOT_IMPORT_BATCH_MAX_SIZE_BYTES,
+        SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER,
+        SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER_MIN_ROWS,
+        SNAPSHOT_IMPORT_MAX_PARTS,
+        SNAPSHOT_IMPORT_MAX_TABLES_CREATED,
+        SNAPSHOT_IMPORT_TABLE_PARALLELISM,
         TRANSACTION_MAX_NUM_USER_WRITES,
         TRANSACTION_MAX_USER_WRITE_SIZE_BYTES,
     },
@@ -67,7 +82,9 @@ use common::{
         assert_send,
         Runtime,
     },
+    schemas::SchemaEnforcementError,
     types::{
+        EnvVarName,
         FullyQualifiedObjectKey,
         MemberId,
         TableName,
@@ -76,13 +93,16 @@ use common::{
     RequestId,
 };
 use database::{
+    unauthorized_error,
     BootstrapComponentsModel,
     Database,
     ImportFacingModel,
     IndexModel,
+    IndexState,
     SchemaModel,
     TableModel,
     Transaction,
+    UpsertOutcome,
 };
 use errors::{
     ErrorMetadata,
@@ -90,11 +110,13 @@ use errors::{
 };
 use file_storage::FileStorage;
 use futures::{
+    select_biased,
     stream::{
         self,
         BoxStream,
         Peekable,
     },
+    FutureExt,
     Stream,
     StreamExt,
     TryStreamExt,
@@ -114,11 +136,15 @@ use model::{
     },
     snapshot_imports::{
         types::{
+            ClearTablesAuditLog,
             ImportFormat,
             ImportMode,
+            ImportOptions,
             ImportRequestor,
             ImportState,
+            PartitionBy,
             SnapshotImport,
+            UnknownFieldsPolicy,
         },
         SnapshotImportModel,
     },
@@ -145,7 +171,9 @@ use value::{
     id_v6::DeveloperDocumentId,
     ConvexObject,
     ConvexValue,
+    FieldName,
     IdentifierFieldName,
+    Namespace,
     ResolvedDocumentId,
     Size,
     TableMapping,
@@ -156,17 +184,32 @@ use value::{
 };
 
 use crate::{
+    application_function_runner::ApplicationFunctionRunner,
     snapshot_import::{
         audit_log::make_audit_log_event,
-        confirmation::info_message_for_import,
+        confirmation::{
+            info_message_for_import,
+            ImportConfirmationInfo,
+        },
+        deployment_config::{
+            check_component_args,
+            restore_crons,
+            restore_deployment_config_before_data,
+            restore_indexes_on_table,
+            EnvironmentVariablesRestore,
+        },
         import_error::{
             wrap_import_err,
             ImportError,
         },
         import_file_storage::import_storage_table,
-        metrics::log_snapshot_import_age,
+        metrics::{
+            log_snapshot_import_age,
+            log_snapshot_import_tables_created,
+        },
         parse::{
             parse_import_file,
+            parse_multipart_import,
             ImportDocumentStream,
             ImportStorageFileStream,
             ParsedImport,
@@ -175,18 +218,25 @@ use crate::{
         progress::{
             add_checkpoint_message,
             best_effort_update_progress_message,
+            total_rows_to_write,
+            TableProgress,
         },
         schema_constraints::{
+            fields_allowed_by_schemas,
             schemas_for_import,
             ImportSchemaConstraints,
             SchemasForImport,
         },
+        throughput::ImportThroughput,
+        transform::ImportTransform,
+        verify_references::verify_references,
     },
     Application,
 };
 
 mod audit_log;
 mod confirmation;
+mod deployment_config;
 mod import_error;
 mod import_file_storage;
 mod metrics;
@@ -195,8 +245,18 @@ mod prepare_component;
 mod progress;
 mod schema_constraints;
 mod table_change;
+#[cfg(test)]
+mod tests;
+mod throughput;
+mod transform;
+mod verify_references;
 mod worker;
 
+pub use progress::{
+    ImportProgressEvent,
+    ImportProgressSink,
+    ImportTablePhase,
+};
 pub use worker::SnapshotImportWorker;
 
 // NB: This is a bandaid. In general, we want to retry forever on system
@@ -205,6 +265,8 @@ pub use worker::SnapshotImportWorker;
 // while we are in the process, use this as a bandaid to limit the damage. Once
 // nontransient system errors are fixed, we can remove this.
 const SNAPSHOT_IMPORT_MAX_SYSTEM_FAILURES: u32 = 5;
+const ACTIVATION_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const ACTIVATION_MAX_BACKOFF: Duration = Duration::from_secs(60);
 
 struct SnapshotImportExecutor<RT: Runtime> {
     runtime: RT,
@@ -212,7 +274,11 @@ struct SnapshotImportExecutor<RT: Runtime> {
     snapshot_imports_storage: Arc<dyn Storage>,
     file_storage: FileStorage<RT>,
     usage_tracking: UsageCounter,
+    runner: Arc<ApplicationFunctionRunner<RT>>,
+    system_env_var_names: HashSet<EnvVarName>,
     backoff: Backoff,
+    throughput: ImportThroughput,
+    progress_sink: Option<Arc<dyn ImportProgressSink>>,
 }
 
 impl<RT: Runtime> SnapshotImportExecutor<RT> {
@@ -224,7 +290,12 @@ impl<RT: Runtime> SnapshotImportExecutor<RT> {
         tracing::info!("Marking snapshot import as WaitingForConfirmation");
         let import_id = snapshot_import.id();
         match info_message_for_import(self, snapshot_import).await {
-            Ok((info_message, require_manual_confirmation, new_checkpoints)) => {
+            Ok(ImportConfirmationInfo {
+                info_message,
+                require_manual_confirmation,
+                estimated_duration,
+                new_checkpoints,
+            }) => {
                 self.database
                     .execute_with_overloaded_retries(
                         Identity::system(),
@@ -238,6 +309,7 @@ impl<RT: Runtime> SnapshotImportExecutor<RT> {
                                         import_id,
                                         info_message.clone(),
                                         require_manual_confirmation,
+                                        Some(estimated_duration),
                                         new_checkpoints.clone(),
                                     )
                                     .await?;
@@ -366,9 +438,51 @@ impl<RT: Runtime> SnapshotImportExecutor<RT> {
         snapshot_import: ParsedDocument<SnapshotImport>,
     ) -> anyhow::Result<(Timestamp, u64)> {
         self.fail_if_too_old(&snapshot_import)?;
-        let (initial_schemas, import) = self.parse_import(snapshot_import.id()).await?;
+        let start = self.runtime.monotonic_now();
+        let (mut initial_schemas, mut import) = self.parse_import(snapshot_import.id()).await?;
+        check_component_args(
+            &self.database,
+            &Identity::system(),
+            &import.deployment_config,
+            snapshot_import.options.component_args,
+        )
+        .await?;
+        let restore_config = snapshot_import.options.restore_config;
+        if restore_config.schema || restore_config.environment_variables {
+            restore_deployment_config_before_data(
+                &self.database,
+                &Identity::system(),
+                &import.deployment_config,
+                restore_config,
+            )
+            .await?;
+            // Re-read the schemas so the imported documents are checked
+            // against a restored schema.
+            let mut tx = self.database.begin(Identity::system()).await?;
+            initial_schemas = schemas_for_import(&mut tx).await?;
+        }
+        let restored_crons = mem::take(&mut import.deployment_config.crons);
+        let restored_environment_variables =
+            restore_config
+                .environment_variables
+                .then(|| EnvironmentVariablesRestore {
+                    runner: &self.runner,
+                    system_env_var_names: &self.system_env_var_names,
+                    environment_variables: mem::take(
+                        &mut import.deployment_config.environment_variables,
+                    ),
+                });
 
         let usage = FunctionUsageTracker::new();
+        let transform = snapshot_import.options.transform.clone().map(|udf_path| {
+            ImportTransform::new(
+                &self.runtime,
+                &self.database,
+                &self.runner,
+                snapshot_import.component_path.clone(),
+                udf_path,
+            )
+        });
 
         let (imported_tables, total_documents_imported) = import_objects(
             &self.database,
@@ -376,10 +490,13 @@ impl<RT: Runtime> SnapshotImportExecutor<RT> {
             Identity::system(),
             &initial_schemas,
             snapshot_import.mode,
+            &snapshot_import.options,
             import,
             usage.clone(),
             Some(snapshot_import.id()),
             snapshot_import.requestor.clone(),
+            transform.as_ref(),
+            self.progress_sink.as_deref(),
         )
         .await?;
 
@@ -396,13 +513,22 @@ impl<RT: Runtime> SnapshotImportExecutor<RT> {
             },
         })?
         .context("error getting export object attributes from S3")?;
+        let mut import_size = object_attributes.size;
+        for key in &snapshot_import.additional_object_keys {
+            import_size += self
+                .snapshot_imports_storage
+                .get_fq_object_attributes(key)
+                .await?
+                .context("error getting export object attributes from S3")?
+                .size;
+        }
 
         // Charge file bandwidth for the download of the snapshot from imports storage
         usage
             .track_storage_egress(
                 ComponentPath::root(),
                 snapshot_import.requestor.usage_tag().to_string(),
-                object_attributes.size,
+                import_size,
             )
             .await;
 
@@ -417,14 +543,23 @@ impl<RT: Runtime> SnapshotImportExecutor<RT> {
             initial_schemas,
             snapshot_import.mode,
             imported_tables,
-            AuditLogInfo::SnapshotImport {
+            Some(AuditLogInfo::SnapshotImport {
                 import_format: snapshot_import.format,
-            },
+            }),
             Some(id),
             snapshot_import.requestor.clone(),
             usage.clone(),
+            restored_environment_variables,
+            *SNAPSHOT_IMPORT_ACTIVATION_BATCH_SIZE,
         )
         .await?;
+        if restore_config.crons {
+            restore_crons(&self.database, &Identity::system(), restored_crons).await?;
+        }
+        self.throughput.record(
+            total_documents_imported,
+            self.runtime.monotonic_now() - start,
+        );
 
         // Track usage for snapshot imports
         let tag = snapshot_import.requestor.usage_tag().to_string();
@@ -452,8 +587,10 @@ impl<RT: Runtime> SnapshotImportExecutor<RT> {
     ) -> anyhow::Result<(SchemasForImport, ParsedImport)> {
         let SnapshotImport {
             object_key,
+            additional_object_keys,
             format,
             component_path,
+            options,
             ..
         } = {
             let mut tx = self.database.begin(Identity::system()).await?;
@@ -468,13 +605,31 @@ impl<RT: Runtime> SnapshotImportExecutor<RT> {
             Ok(key) => key,
             Err(key) => self.snapshot_imports_storage.fully_qualified_key(&key),
         };
-        let import = parse_import_file(
-            format.clone(),
-            component_path.clone(),
-            self.snapshot_imports_storage.clone(),
-            fq_key,
-        )
-        .await?;
+        let mut import = if additional_object_keys.is_empty() {
+            parse_import_file(
+                format.clone(),
+                component_path.clone(),
+                self.snapshot_imports_storage.clone(),
+                fq_key,
+                options.json_pointer.as_deref(),
+                options.order_by.as_ref(),
+                options.partition_by.as_ref(),
+                &options.csv_string_columns,
+            )
+            .await?
+        } else {
+            parse_multipart_import(
+                component_path.clone(),
+                self.snapshot_imports_storage.clone(),
+                iter::once(fq_key).chain(additional_object_keys).collect(),
+                options.order_by.as_ref(),
+                &options.csv_string_columns,
+            )
+            .await?
+        };
+        if let Some(include_tables) = &options.include_tables {
+            retain_included_tables(&mut import.documents, include_tables)?;
+        }
 
         let component_id = prepare_component_for_import(&self.database, &component_path).await?;
         // Remapping could be more extensive here, it's just relatively simple to handle
@@ -484,14 +639,25 @@ impl<RT: Runtime> SnapshotImportExecutor<RT> {
         let mut tx = self.database.begin(Identity::system()).await?;
         let initial_schemas = schemas_for_import(&mut tx).await?;
         let import = match format {
-            ImportFormat::Csv(table_name) => {
-                remap_empty_string_by_schema(
-                    TableNamespace::from(component_id),
-                    table_name,
-                    &mut tx,
-                    import,
-                )
-                .await?
+            ImportFormat::Csv(_) => {
+                // A partitioned CSV import has a stream for each destination
+                // table, each remapped by its own table's schema.
+                let table_names: BTreeSet<_> = import
+                    .documents
+                    .iter()
+                    .map(|(_, table_name, _)| table_name.clone())
+                    .collect();
+                let mut import = import;
+                for table_name in table_names {
+                    import = remap_empty_string_by_schema(
+                        TableNamespace::from(component_id),
+                        table_name,
+                        &mut tx,
+                        import,
+                    )
+                    .await?;
+                }
+                import
             },
             _ => import,
         };
@@ -508,8 +674,73 @@ pub async fn start_stored_import<RT: Runtime>(
     component_path: ComponentPath,
     fq_object_key: FullyQualifiedObjectKey,
     requestor: ImportRequestor,
+    options: ImportOptions,
+) -> anyhow::Result<DeveloperDocumentId> {
+    start_stored_multipart_import(
+        application,
+        identity,
+        format,
+        mode,
+        component_path,
+        vec![fq_object_key],
+        requestor,
+        options,
+    )
+    .await
+}
+
+/// Starts an import of a ZIP export that was split into several files, given
+/// in order. The parts are imported as a single archive and finalized together.
+/// See [parse_multipart_import].
+pub async fn start_stored_multipart_import<RT: Runtime>(
+    application: &Application<RT>,
+    identity: Identity,
+    format: ImportFormat,
+    mode: ImportMode,
+    component_path: ComponentPath,
+    fq_object_keys: Vec<FullyQualifiedObjectKey>,
+    requestor: ImportRequestor,
+    options: ImportOptions,
 ) -> anyhow::Result<DeveloperDocumentId> {
     identity.require_operation(DeploymentOp::ImportBackups)?;
+    let mut fq_object_keys = fq_object_keys.into_iter();
+    let fq_object_key = fq_object_keys.next().context(ErrorMetadata::bad_request(
+        "InvalidMultipartImport",
+        "An import needs at least one file",
+    ))?;
+    let additional_object_keys: Vec<_> = fq_object_keys.collect();
+    if !additional_object_keys.is_empty() {
+        anyhow::ensure!(
+            matches!(format, ImportFormat::Zip),
+            ErrorMetadata::bad_request(
+                "InvalidMultipartImport",
+                "Only ZIP imports can be split into multiple files",
+            )
+        );
+        anyhow::ensure!(
+            options.partition_by.is_none(),
+            ErrorMetadata::bad_request(
+                "InvalidMultipartImport",
+                "partition_by is only supported for single-table imports",
+            )
+        );
+        let num_parts = additional_object_keys.len() + 1;
+        anyhow::ensure!(
+            num_parts <= *SNAPSHOT_IMPORT_MAX_PARTS,
+            ErrorMetadata::bad_request(
+                "InvalidMultipartImport",
+                format!(
+                    "An import can be split into at most {} files, but this one has {num_parts}",
+                    *SNAPSHOT_IMPORT_MAX_PARTS
+                ),
+            )
+        );
+    }
+    if options.restore_config.environment_variables {
+        identity.require_operation(DeploymentOp::WriteEnvironmentVariables)?;
+    }
+    // Reject invalid options before the import is queued.
+    validate_import_options(&format, mode, &options)?;
     let (_, id, _) = application
         .database
         .execute_with_overloaded_retries(
@@ -518,6 +749,9 @@ pub async fn start_stored_import<RT: Runtime>(
             "snapshot_import_store_uploaded",
             |tx| {
                 async {
+                    if let Some(partition_by) = &options.partition_by {
+                        validate_partition_tables(tx, &component_path, partition_by)?;
+                    }
                     let mut model = SnapshotImportModel::new(tx);
                     model
                         .start_import(
@@ -525,7 +759,9 @@ pub async fn start_stored_import<RT: Runtime>(
                             mode,
                             component_path.clone(),
                             fq_object_key.clone(),
+                            additional_object_keys.clone(),
                             requestor.clone(),
+                            options.clone(),
                         )
                         .await
                 }
@@ -588,10 +824,34 @@ pub async fn cancel_import<RT: Runtime>(
     Ok(())
 }
 
+/// Runs one phase of [do_import], failing with an `ImportTimedOut` error if
+/// `deadline` passes before it completes.
+async fn with_import_deadline<RT: Runtime, T>(
+    rt: &RT,
+    deadline: Option<tokio::time::Instant>,
+    phase: &'static str,
+    fut: impl Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    let Some(deadline) = deadline else {
+        return fut.await;
+    };
+    let remaining = deadline.saturating_duration_since(rt.monotonic_now());
+    select_biased! {
+        result = fut.fuse() => result,
+        _ = rt.wait(remaining) => {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "ImportTimedOut",
+                format!("Import did not finish before its deadline (timed out while {phase})"),
+            ))
+        },
+    }
+}
+
 async fn wait_for_import_worker<RT: Runtime>(
     application: &Application<RT>,
     identity: Identity,
     import_id: DeveloperDocumentId,
+    deadline: Option<tokio::time::Instant>,
 ) -> anyhow::Result<ParsedDocument<SnapshotImport>> {
     let snapshot_import = loop {
         let mut tx = application.begin(identity.clone()).await?;
@@ -608,9 +868,13 @@ async fn wait_for_import_worker<RT: Runtime>(
         match &snapshot_import.state {
             ImportState::Uploaded | ImportState::InProgress { .. } => {
                 let token = tx.into_token()?;
-                application
-                    .subscribe_and_wait_for_invalidation(token)
-                    .await?;
+                with_import_deadline(
+                    &application.runtime,
+                    deadline,
+                    "waiting for the import worker",
+                    application.subscribe_and_wait_for_invalidation(token),
+                )
+                .await?;
             },
             ImportState::WaitingForConfirmation { .. }
             | ImportState::Completed { .. }
@@ -622,22 +886,38 @@ async fn wait_for_import_worker<RT: Runtime>(
     Ok(snapshot_import)
 }
 
+/// Uploads, confirms, and performs an import, waiting for it to complete.
+///
+/// If `deadline` is set, it bounds the whole operation: each phase only gets
+/// the time remaining from the overall budget, and an import that times out
+/// after it has been started is canceled.
 pub async fn do_import<RT: Runtime>(
     application: &Application<RT>,
     identity: Identity,
     format: ImportFormat,
     mode: ImportMode,
     component_path: ComponentPath,
+    options: ImportOptions,
     body_stream: BoxStream<'_, anyhow::Result<Bytes>>,
+    deadline: Option<Duration>,
 ) -> anyhow::Result<u64> {
-    let object_key = application.upload_snapshot_import(body_stream).await?;
-    do_import_from_object_key(
+    let deadline = deadline.map(|deadline| application.runtime.monotonic_now() + deadline);
+    let object_key = with_import_deadline(
+        &application.runtime,
+        deadline,
+        "uploading",
+        application.upload_snapshot_import(body_stream),
+    )
+    .await?;
+    _do_import_from_object_key(
         application,
         identity,
         format,
         mode,
         component_path,
+        options,
         object_key,
+        deadline,
     )
     .await
 }
@@ -648,7 +928,33 @@ pub async fn do_import_from_object_key<RT: Runtime>(
     format: ImportFormat,
     mode: ImportMode,
     component_path: ComponentPath,
+    options: ImportOptions,
+    export_object_key: FullyQualifiedObjectKey,
+    deadline: Option<Duration>,
+) -> anyhow::Result<u64> {
+    let deadline = deadline.map(|deadline| application.runtime.monotonic_now() + deadline);
+    _do_import_from_object_key(
+        application,
+        identity,
+        format,
+        mode,
+        component_path,
+        options,
+        export_object_key,
+        deadline,
+    )
+    .await
+}
+
+async fn _do_import_from_object_key<RT: Runtime>(
+    application: &Application<RT>,
+    identity: Identity,
+    format: ImportFormat,
+    mode: ImportMode,
+    component_path: ComponentPath,
+    options: ImportOptions,
     export_object_key: FullyQualifiedObjectKey,
+    deadline: Option<tokio::time::Instant>,
 ) -> anyhow::Result<u64> {
     let import_id = start_stored_import(
         application,
@@ -658,10 +964,32 @@ pub async fn do_import_from_object_key<RT: Runtime>(
         component_path,
         export_object_key,
         ImportRequestor::SnapshotImport,
+        options,
     )
     .await?;
 
-    let snapshot_import = wait_for_import_worker(application, identity.clone(), import_id).await?;
+    let result = wait_for_started_import(application, identity.clone(), import_id, deadline).await;
+    if let Err(e) = &result
+        && e.short_msg() == "ImportTimedOut"
+    {
+        // Don't leave the worker importing data nobody is waiting for.
+        if let Err(mut cancel_err) = cancel_import(application, identity, import_id).await {
+            cancel_err =
+                cancel_err.context(format!("Failed to cancel timed out import {import_id}"));
+            report_error(&mut cancel_err).await;
+        }
+    }
+    result
+}
+
+async fn wait_for_started_import<RT: Runtime>(
+    application: &Application<RT>,
+    identity: Identity,
+    import_id: DeveloperDocumentId,
+    deadline: Option<tokio::time::Instant>,
+) -> anyhow::Result<u64> {
+    let snapshot_import =
+        wait_for_import_worker(application, identity.clone(), import_id, deadline).await?;
     match &snapshot_import.state {
         ImportState::Uploaded | ImportState::InProgress { .. } | ImportState::Completed { .. } => {
             anyhow::bail!("should be WaitingForConfirmation, is {snapshot_import:?}")
@@ -672,9 +1000,16 @@ pub async fn do_import_from_object_key<RT: Runtime>(
         },
     }
 
-    perform_import(application, identity.clone(), import_id).await?;
+    with_import_deadline(
+        &application.runtime,
+        deadline,
+        "confirming",
+        perform_import(application, identity.clone(), import_id),
+    )
+    .await?;
 
-    let snapshot_import = wait_for_import_worker(application, identity.clone(), import_id).await?;
+    let snapshot_import =
+        wait_for_import_worker(application, identity.clone(), import_id, deadline).await?;
     match &snapshot_import.state {
         ImportState::Uploaded
         | ImportState::WaitingForConfirmation { .. }
@@ -701,8 +1036,19 @@ pub async fn clear_tables<RT: Runtime>(
     request_metadata: RequestMetadata,
     table_names: Vec<(ComponentPath, TableName)>,
     requestor: ImportRequestor,
+    audit_log: ClearTablesAuditLog,
     usage: FunctionUsageTracker,
 ) -> anyhow::Result<u64> {
+    let audit_log_info = match audit_log {
+        ClearTablesAuditLog::Record => Some(AuditLogInfo::ClearTables),
+        ClearTablesAuditLog::Skip => {
+            anyhow::ensure!(
+                identity.is_admin() || identity.is_system(),
+                unauthorized_error("clear_tables_skip_audit")
+            );
+            None
+        },
+    };
     let (initial_schemas, original_table_mapping) = {
         let mut tx = application.begin(identity.clone()).await?;
         (
@@ -746,10 +1092,12 @@ pub async fn clear_tables<RT: Runtime>(
         initial_schemas,
         ImportMode::Replace,
         table_mapping,
-        AuditLogInfo::ClearTables,
+        audit_log_info,
         None,
         requestor,
         usage.clone(),
+        None,
+        *SNAPSHOT_IMPORT_ACTIVATION_BATCH_SIZE,
     )
     .await?;
     Ok(documents_deleted)
@@ -764,10 +1112,13 @@ async fn import_objects<RT: Runtime>(
     identity: Identity,
     initial_schemas: &SchemasForImport,
     mode: ImportMode,
-    import: ParsedImport,
+    options: &ImportOptions,
+    mut import: ParsedImport,
     usage: FunctionUsageTracker,
     import_id: Option<ResolvedDocumentId>,
     requestor: ImportRequestor,
+    transform: Option<&ImportTransform<'_, RT>>,
+    progress_sink: Option<&dyn ImportProgressSink>,
 ) -> anyhow::Result<(TableMapping, u64)> {
     let mut generated_schemas: BTreeMap<_, _> = import
         .generated_schemas
@@ -776,7 +1127,6 @@ async fn import_objects<RT: Runtime>(
             ((component_path, table_name), generated_schema)
         })
         .collect();
-    let mut total_num_documents = 0;
 
     // In ReplaceAll mode, we want to delete all unaffected user tables
     // If there's a schema, then we want to clear it instead.
@@ -801,9 +1151,40 @@ async fn import_objects<RT: Runtime>(
         .try_collect()
         .await?;
 
+    ensure_no_table_name_case_collisions(
+        tables
+            .iter()
+            .map(|(component_path, _, table_name, _)| (component_path, table_name)),
+    )?;
     let (tables_tables, mut tables) = tables
         .into_iter()
         .partition::<Vec<_>, _>(|(_, _, table_name, _)| *table_name == TABLES_TABLE);
+    apply_table_order(&mut tables, &options.table_order)?;
+    ensure_per_table_modes_in_import(
+        tables.iter().map(|(_, _, table_name, _)| table_name),
+        &options.per_table_mode,
+    )?;
+    let batch_limits = ImportBatchLimits::new(options)?;
+    if options.diff_import {
+        for (_, _, table_name, _) in &tables {
+            // Storage files aren't diffed.
+            if *table_name == FILE_STORAGE_TABLE {
+                continue;
+            }
+            match options.mode_for_table(mode, table_name) {
+                ImportMode::Append => {},
+                table_mode @ (ImportMode::Replace
+                | ImportMode::ReplaceAll
+                | ImportMode::RequireEmpty) => anyhow::bail!(ErrorMetadata::bad_request(
+                    "InvalidDiffImport",
+                    format!(
+                        "A diff import only applies to tables imported in append mode, but \
+                         \"{table_name}\" is imported in {table_mode} mode"
+                    ),
+                )),
+            }
+        }
+    }
 
     database
         .runtime()
@@ -820,24 +1201,41 @@ async fn import_objects<RT: Runtime>(
         &mut tables,
         original_table_mapping,
         initial_schemas,
+        options.include_tables.as_ref(),
     )
     .await?;
 
+    let mut restored_indexes = BTreeMap::new();
+    if options.restore_config.indexes {
+        for ((component_path, table_name), indexes) in
+            mem::take(&mut import.deployment_config.indexes)
+        {
+            let component_id = prepare_component_for_import(database, &component_path).await?;
+            restored_indexes.insert((component_id, table_name), indexes);
+        }
+    }
+
     // Now prepare all imported tables using the requested table numbers.
     // This creates empty hidden tables or resumes from a checkpoint.
     let mut table_mapping_in_import = TableMapping::new();
     let mut tablet_id_to_num_to_skip: BTreeMap<TabletId, u64> = BTreeMap::new();
+    let mut num_tables_created = 0;
     for (&(component_id, ref table_name), &table_number) in &table_name_to_number {
         let (table_id, num_to_skip) = prepare_table_for_import(
             database,
             &identity,
             mode,
+            options,
             component_id,
             table_name,
             table_number,
             import_id,
+            &mut num_tables_created,
         )
         .await?;
+        if let Some(indexes) = restored_indexes.get(&(component_id, table_name.clone())) {
+            restore_indexes_on_table(database, &identity, table_id.tablet_id, indexes).await?;
+        }
         table_mapping_in_import.insert(
             table_id.tablet_id,
             component_id.into(),
@@ -849,6 +1247,8 @@ async fn import_objects<RT: Runtime>(
             .is_none());
     }
 
+    log_snapshot_import_tables_created(num_tables_created);
+
     let table_mapping_for_schema = {
         let mut mapping = TableMapping::new();
         for (tablet_id, namespace, table_number, table_name) in original_table_mapping.iter() {
@@ -881,29 +1281,83 @@ async fn import_objects<RT: Runtime>(
             .push((id, stream));
     }
 
+    // Each table is written to its own hidden table, so tables can be imported
+    // independently. Tables named in `table_order` and `_storage` tables are
+    // imported one at a time first, and the rest concurrently.
+    let identity = &identity;
+    let table_mapping_for_schema = &table_mapping_for_schema;
+    let mut sequential_imports = vec![];
+    let mut parallel_imports = vec![];
     for (component_path, component_id, table_name, document_stream) in tables {
-        let generated_schema =
-            generated_schemas.get_mut(&(&component_path, &table_name) as &dyn TupleKey<_, _>);
+        let mut generated_schema =
+            generated_schemas.remove(&(&component_path, &table_name) as &dyn TupleKey<_, _>);
+        let allowed_fields = match options.unknown_fields {
+            UnknownFieldsPolicy::Error => None,
+            UnknownFieldsPolicy::Drop => {
+                fields_allowed_by_schemas(initial_schemas, component_id.into(), &table_name)
+            },
+        };
         let table_id = table_mapping_in_import
             .namespace(component_id.into())
             .id(&table_name)?;
-        total_num_documents += import_single_table(
+        let num_to_skip = *tablet_id_to_num_to_skip
+            .get(&table_id.tablet_id)
+            .context("missing entry in tablet_id_to_num_to_skip")?;
+        let is_storage_table = table_name == FILE_STORAGE_TABLE;
+        let storage_files = if is_storage_table {
+            storage_files_by_component
+                .remove(&component_path)
+                .unwrap_or_default()
+        } else {
+            vec![]
+        };
+        let is_ordered = options.table_order.contains(&table_name);
+        let usage = usage.clone();
+        let requestor = requestor.clone();
+        let table_import = async move {
+            import_single_table(
+                database,
+                file_storage,
+                identity,
+                &component_path,
+                &table_name,
+                document_stream,
+                storage_files,
+                generated_schema.as_mut(),
+                table_mapping_for_schema,
+                table_id,
+                num_to_skip,
+                usage,
+                import_id,
+                requestor,
+                batch_limits,
+                options.require_id_field,
+                options.diff_import,
+                allowed_fields.as_ref(),
+                transform,
+                progress_sink,
+            )
+            .await
+        };
+        if is_storage_table || is_ordered {
+            sequential_imports.push(table_import);
+        } else {
+            parallel_imports.push(table_import);
+        }
+    }
+    let total_num_documents = run_table_imports(
+        sequential_imports,
+        parallel_imports,
+        *SNAPSHOT_IMPORT_TABLE_PARALLELISM,
+    )
+    .await?;
+
+    if options.defer_reference_checks {
+        verify_references(
             database,
-            file_storage,
-            &identity,
-            &component_path,
-            &table_name,
-            document_stream,
-            &mut storage_files_by_component,
-            generated_schema,
-            &table_mapping_for_schema,
-            table_id,
-            *tablet_id_to_num_to_skip
-                .get(&table_id.tablet_id)
-                .context("missing entry in tablet_id_to_num_to_skip")?,
-            usage.clone(),
-            import_id,
-            requestor.clone(),
+            initial_schemas,
+            &table_mapping_in_import,
+            table_mapping_for_schema,
         )
         .await?;
     }
@@ -911,6 +1365,361 @@ async fn import_objects<RT: Runtime>(
     Ok((table_mapping_in_import, total_num_documents))
 }
 
+/// Drops the tables that aren't in `include_tables` from a parsed import.
+/// `_tables` is kept, since it assigns the numbers of the included tables.
+///
+/// Every table is its own document stream, so skipping one doesn't affect how
+/// the others are parsed.
+fn retain_included_tables<T>(
+    documents: &mut Vec<(ComponentPath, TableName, T)>,
+    include_tables: &BTreeSet<(ComponentPath, TableName)>,
+) -> anyhow::Result<()> {
+    for (component_path, table_name) in include_tables {
+        if !documents
+            .iter()
+            .any(|(path, name, _)| path == component_path && name == table_name)
+        {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidIncludeTables",
+                format!(
+                    "Table \"{table_name}\"{} is in include_tables but not in the import",
+                    component_path.in_component_str()
+                ),
+            ));
+        }
+    }
+    documents.retain(|(component_path, table_name, _)| {
+        *table_name == TABLES_TABLE
+            || include_tables.contains(&(component_path, table_name) as &dyn TupleKey<_, _>)
+    });
+    Ok(())
+}
+
+/// Moves the tables named in `table_order` to the front of `tables`, in the
+/// requested order. Tables that aren't named keep their relative order.
+///
+/// Every document stream is read lazily, so reordering a zip import (where each
+/// table is its own entry) is free. Single-table formats only have one stream,
+/// so the only valid order is one naming that table.
+fn apply_table_order<T>(
+    tables: &mut [(ComponentPath, ComponentId, TableName, T)],
+    table_order: &[TableName],
+) -> anyhow::Result<()> {
+    if table_order.is_empty() {
+        return Ok(());
+    }
+    let mut positions = BTreeMap::new();
+    for (position, table_name) in table_order.iter().enumerate() {
+        // `_storage` is renamed to its system table before import.
+        let table_name = if *table_name == FILE_STORAGE_VIRTUAL_TABLE {
+            FILE_STORAGE_TABLE.clone()
+        } else {
+            table_name.clone()
+        };
+        if positions.insert(table_name.clone(), position).is_some() {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidTableOrder",
+                format!("Table \"{table_name}\" appears more than once in the table order"),
+            ));
+        }
+        if !tables.iter().any(|(_, _, name, _)| *name == table_name) {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidTableOrder",
+                format!("Table \"{table_name}\" is in the table order but not in the import"),
+            ));
+        }
+    }
+    // `sort_by_key` is stable, so unlisted tables stay in stream order.
+    tables.sort_by_key(|(_, _, table_name, _)| {
+        positions.get(table_name).copied().unwrap_or(usize::MAX)
+    });
+    Ok(())
+}
+
+/// A per-table mode for a table that isn't in the import would silently do
+/// nothing, which is almost certainly a typo, so reject it. Per-table modes
+/// name tables as they appear in the import, so file storage is `_storage`
+/// even once it's been renamed to its system table.
+fn ensure_per_table_modes_in_import<'a>(
+    table_names: impl IntoIterator<Item = &'a TableName>,
+    per_table_mode: &BTreeMap<TableName, ImportMode>,
+) -> anyhow::Result<()> {
+    let imported: BTreeSet<TableName> = table_names
+        .into_iter()
+        .map(|table_name| {
+            if *table_name == FILE_STORAGE_TABLE {
+                FILE_STORAGE_VIRTUAL_TABLE.clone()
+            } else {
+                table_name.clone()
+            }
+        })
+        .collect();
+    for table_name in per_table_mode.keys() {
+        if !imported.contains(table_name) {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidPerTableMode",
+                format!("Table \"{table_name}\" has a mode but is not in the import"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Table names are case-sensitive, so an import containing both `Users` and
+/// `users` would create two tables. That's almost always a mistake in how the
+/// import was produced, so fail instead of guessing which was meant.
+fn ensure_no_table_name_case_collisions<'a>(
+    tables: impl IntoIterator<Item = (&'a ComponentPath, &'a TableName)>,
+) -> anyhow::Result<()> {
+    let mut by_lowercase_name: BTreeMap<(&ComponentPath, String), &TableName> = BTreeMap::new();
+    for (component_path, table_name) in tables {
+        let key = (component_path, table_name.to_lowercase());
+        match by_lowercase_name.entry(key) {
+            Entry::Vacant(v) => {
+                v.insert(table_name);
+            },
+            Entry::Occupied(o) => {
+                if *o.get() != table_name {
+                    anyhow::bail!(ErrorMetadata::bad_request(
+                        "TableNameCaseCollision",
+                        format!(
+                            "Import contains tables `{}` and `{table_name}`{}, whose names differ \
+                             only by case",
+                            o.get(),
+                            component_path.in_component_str(),
+                        ),
+                    ));
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
+fn validate_import_options(
+    format: &ImportFormat,
+    mode: ImportMode,
+    options: &ImportOptions,
+) -> anyhow::Result<()> {
+    ImportBatchLimits::new(options)?;
+    for (table_name, &table_mode) in &options.per_table_mode {
+        match format {
+            ImportFormat::Csv(imported_table)
+            | ImportFormat::JsonLines(imported_table)
+            | ImportFormat::JsonLinesGzip(imported_table)
+            | ImportFormat::JsonArray(imported_table) => {
+                let is_partition_table =
+                    options.partition_by.as_ref().is_some_and(|partition_by| {
+                        partition_by.tables.values().any(|t| t == table_name)
+                    });
+                if table_name != imported_table && !is_partition_table {
+                    anyhow::bail!(ErrorMetadata::bad_request(
+                        "InvalidPerTableMode",
+                        format!("Table \"{table_name}\" has a mode but is not in the import"),
+                    ));
+                }
+            },
+            ImportFormat::Zip => {},
+        }
+        match table_mode {
+            ImportMode::Append | ImportMode::Replace => {},
+            // ReplaceAll also clears tables that aren't in the import, so it
+            // only makes sense for the import as a whole.
+            ImportMode::ReplaceAll => anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidPerTableMode",
+                format!(
+                    "Table \"{table_name}\" can't use ReplaceAll, which applies to every table"
+                ),
+            )),
+            // A ReplaceAll import deletes every existing table, so requiring
+            // one of them to be empty contradicts the import's mode.
+            ImportMode::RequireEmpty => {
+                if mode == ImportMode::ReplaceAll {
+                    anyhow::bail!(ErrorMetadata::bad_request(
+                        "InvalidPerTableMode",
+                        format!(
+                            "Table \"{table_name}\" can't use RequireEmpty in a ReplaceAll import"
+                        ),
+                    ));
+                }
+            },
+        }
+    }
+    if let Some(json_pointer) = &options.json_pointer {
+        if !matches!(format, ImportFormat::JsonArray(_)) {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidJsonPointer",
+                "A JSON pointer can only be used with JSON array imports",
+            ));
+        }
+        if !json_pointer.is_empty() && !json_pointer.starts_with('/') {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidJsonPointer",
+                format!("JSON pointer {json_pointer:?} must be empty or start with \"/\""),
+            ));
+        }
+    }
+    if !options.csv_string_columns.is_empty() {
+        match format {
+            ImportFormat::Csv(_) => {},
+            ImportFormat::JsonLines(_)
+            | ImportFormat::JsonLinesGzip(_)
+            | ImportFormat::JsonArray(_)
+            | ImportFormat::Zip => anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidCsvStringColumns",
+                "String columns can only be set for CSV imports",
+            )),
+        }
+    }
+    if !options.restore_config.is_empty() {
+        match format {
+            ImportFormat::Zip => {},
+            ImportFormat::Csv(_)
+            | ImportFormat::JsonLines(_)
+            | ImportFormat::JsonLinesGzip(_)
+            | ImportFormat::JsonArray(_) => {
+                anyhow::bail!(ErrorMetadata::bad_request(
+                    "InvalidDeploymentConfig",
+                    "Deployment configuration can only be restored from a ZIP import",
+                ))
+            },
+        }
+    }
+    if options.include_tables.is_some() {
+        match format {
+            ImportFormat::Zip => {},
+            ImportFormat::Csv(_)
+            | ImportFormat::JsonLines(_)
+            | ImportFormat::JsonLinesGzip(_)
+            | ImportFormat::JsonArray(_) => anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidIncludeTables",
+                "include_tables can only be used with ZIP imports",
+            )),
+        }
+        // ReplaceAll would delete every table that isn't selected.
+        if mode == ImportMode::ReplaceAll {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidIncludeTables",
+                "include_tables can't be used with ReplaceAll imports",
+            ));
+        }
+    }
+    if let Some(partition_by) = &options.partition_by {
+        match format {
+            ImportFormat::Csv(_)
+            | ImportFormat::JsonLines(_)
+            | ImportFormat::JsonLinesGzip(_)
+            | ImportFormat::JsonArray(_) => {},
+            ImportFormat::Zip => anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidPartitionBy",
+                "partition_by can only be used with single-table imports",
+            )),
+        }
+        if partition_by.tables.is_empty() {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidPartitionBy",
+                "partition_by must route at least one value to a table",
+            ));
+        }
+        if let Some(table_name) = partition_by.tables.values().find(|t| t.is_system()) {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidPartitionBy",
+                format!("Can't partition documents into system table \"{table_name}\""),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Partitioned imports only write to destination tables that already exist,
+/// so a mistyped table name fails the import instead of creating a new table.
+fn validate_partition_tables<RT: Runtime>(
+    tx: &mut Transaction<RT>,
+    component_path: &ComponentPath,
+    partition_by: &PartitionBy,
+) -> anyhow::Result<()> {
+    let Some((_, component_id)) =
+        BootstrapComponentsModel::new(tx).component_path_to_ids(component_path)?
+    else {
+        anyhow::bail!(ErrorMetadata::bad_request(
+            "InvalidPartitionBy",
+            format!("Component \"{component_path}\" doesn't exist, so neither do its tables"),
+        ));
+    };
+    let namespace = TableNamespace::from(component_id);
+    for table_name in partition_by.tables.values() {
+        if !TableModel::new(tx).table_exists(namespace, table_name) {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidPartitionBy",
+                format!("Partition table \"{table_name}\" does not exist"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Thresholds at which buffered documents are flushed into a transaction
+/// while importing a table.
+#[derive(Clone, Copy, Debug)]
+struct ImportBatchLimits {
+    max_size_bytes: usize,
+    max_num_documents: usize,
+}
+
+impl ImportBatchLimits {
+    fn new(options: &ImportOptions) -> anyhow::Result<Self> {
+        let max_size_bytes = Self::resolve(
+            "batch size",
+            options.batch_max_size_bytes,
+            *SNAPSHOT_IMPORT_BATCH_MAX_SIZE_BYTES,
+            *TRANSACTION_MAX_USER_WRITE_SIZE_BYTES,
+        )?;
+        let max_num_documents = Self::resolve(
+            "batch document count",
+            options.batch_max_num_documents,
+            *SNAPSHOT_IMPORT_BATCH_MAX_NUM_DOCUMENTS,
+            *TRANSACTION_MAX_NUM_USER_WRITES,
+        )?;
+        Ok(Self {
+            max_size_bytes,
+            max_num_documents,
+        })
+    }
+
+    /// Defaults to the deployment's knob, which is already kept under the
+    /// transaction limit.
+    fn resolve(
+        name: &str,
+        requested: Option<usize>,
+        default: usize,
+        limit: usize,
+    ) -> anyhow::Result<usize> {
+        let Some(requested) = requested else {
+            return Ok(default);
+        };
+        if requested == 0 || requested > limit {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidImportBatchSize",
+                format!("Import {name} must be between 1 and {limit}, got {requested}"),
+            ));
+        }
+        Ok(requested)
+    }
+
+    /// Whether `num_documents` documents of total size `size_bytes` should be
+    /// flushed before buffering another document of size `next_size_bytes`.
+    fn should_flush(
+        &self,
+        num_documents: usize,
+        size_bytes: usize,
+        next_size_bytes: usize,
+    ) -> bool {
+        num_documents > 0
+            && (num_documents + 1 > self.max_num_documents
+                || size_bytes + next_size_bytes > self.max_size_bytes)
+    }
+}
+
 struct TableMappingForImport {
     table_mapping_in_import: TableMapping,
     to_delete: BTreeMap<TabletId, (TableNamespace, TableNumber, TableName)>,
@@ -951,10 +1760,13 @@ async fn finalize_import<RT: Runtime>(
     initial_schemas: SchemasForImport,
     mode: ImportMode,
     imported_tables: TableMapping,
-    audit_log_info: AuditLogInfo,
+    // `None` skips the deployment audit log entry.
+    audit_log_info: Option<AuditLogInfo>,
     import_id: Option<ResolvedDocumentId>,
     requestor: ImportRequestor,
     usage: FunctionUsageTracker,
+    restored_environment_variables: Option<EnvironmentVariablesRestore<'_, RT>>,
+    activation_batch_size: usize,
 ) -> anyhow::Result<(Timestamp, u64)> {
     // Ensure that schemas will be valid after the tables are activated.
     // TODO: we should be checking that `initial_schemas` matches the schemas at
@@ -964,7 +1776,24 @@ async fn finalize_import<RT: Runtime>(
     // If we inserted into an existing table, we're done because the table is
     // now populated and active.
     // If we inserted into an Hidden table, make it Active.
-    let (ts, documents_deleted, _occ_stats) = database
+    //
+    // Tables are activated in batches of `activation_batch_size`, so imports
+    // with many tables don't hit the transaction limits. The first transaction
+    // does everything that can fail: it checks the import state, validates the
+    // schema constraints, deletes every table the import replaces and
+    // activates the first batch. If it fails, the deployment is left as it was
+    // before the import. Once it commits, the import can't be rolled back, so
+    // the remaining batches are retried until they're activated. Activation
+    // skips tables that are already active, so retrying a batch is safe.
+    let tablet_ids: Vec<TabletId> = imported_tables
+        .iter()
+        .map(|(tablet_id, ..)| tablet_id)
+        .collect();
+    let mut activation_batches = tablet_ids.chunks(activation_batch_size);
+    let first_batch = activation_batches.next().unwrap_or_default();
+    let later_batches_identity = identity.clone();
+    let later_batches_usage = usage.clone();
+    let (mut ts, mut documents_deleted, _occ_stats) = database
         .execute_with_overloaded_retries(identity, usage, "snapshot_import_finalize", |tx| {
             async {
                 if let Some(import_id) = import_id {
@@ -1019,8 +1848,9 @@ async fn finalize_import<RT: Runtime>(
                 };
 
                 let audit_log_event = match &audit_log_info {
-                    AuditLogInfo::ClearTables => DeploymentAuditLogEvent::ClearTables,
-                    AuditLogInfo::SnapshotImport { import_format } => {
+                    None => None,
+                    Some(AuditLogInfo::ClearTables) => Some(DeploymentAuditLogEvent::ClearTables),
+                    Some(AuditLogInfo::SnapshotImport { import_format }) => Some(
                         make_audit_log_event(
                             tx,
                             &table_mapping_for_import,
@@ -1028,8 +1858,8 @@ async fn finalize_import<RT: Runtime>(
                             import_format.clone(),
                             requestor.clone(),
                         )
-                        .await?
-                    },
+                        .await?,
+                    ),
                 };
 
                 let mut documents_deleted = 0;
@@ -1050,26 +1880,32 @@ async fn finalize_import<RT: Runtime>(
                         .await?;
                 }
                 schema_constraints.validate(tx).await?;
-                let mut table_model = TableModel::new(tx);
-                documents_deleted += assert_send(table_model.activate_tables(
-                    table_mapping_for_import.table_mapping_in_import.iter().map(
-                        |(tablet_id, namespace, _table_number, table_name)| {
-                            tracing::info!(
-                                "finalize_import({import_id:?}) Activating table {table_name} in \
-                                 namespace {namespace:?}"
-                            );
-                            tablet_id
-                        },
-                    ),
+                let mut audit_log_events: Vec<_> = audit_log_event.into_iter().collect();
+                if let Some(restored_environment_variables) = &restored_environment_variables {
+                    audit_log_events.extend(restored_environment_variables.apply(tx).await?);
+                }
+                // Deleting every replaced table before activating any batch
+                // means no table number is ever used twice.
+                documents_deleted += assert_send(
+                    TableModel::new(tx).delete_tables_replaced_by(tablet_ids.iter().copied()),
+                )
+                .await?;
+                documents_deleted += assert_send(activate_imported_tables(
+                    tx,
+                    &imported_tables,
+                    first_batch,
+                    import_id,
                 ))
                 .await?;
-                DeploymentAuditLogModel::new(tx)
-                    .insert_with_member_override(
-                        vec![audit_log_event.clone()],
-                        member_id_override,
-                        &request_metadata,
-                    )
-                    .await?;
+                if !audit_log_events.is_empty() {
+                    DeploymentAuditLogModel::new(tx)
+                        .insert_with_member_override(
+                            audit_log_events,
+                            member_id_override,
+                            &request_metadata,
+                        )
+                        .await?;
+                }
 
                 Ok(documents_deleted)
             }
@@ -1077,9 +1913,70 @@ async fn finalize_import<RT: Runtime>(
         })
         .await?;
 
+    let mut backoff = Backoff::new(ACTIVATION_INITIAL_BACKOFF, ACTIVATION_MAX_BACKOFF);
+    for batch in activation_batches {
+        loop {
+            let result = database
+                .execute_with_overloaded_retries(
+                    later_batches_identity.clone(),
+                    later_batches_usage.clone(),
+                    "snapshot_import_finalize_activate_tables",
+                    |tx| {
+                        async {
+                            assert_send(activate_imported_tables(
+                                tx,
+                                &imported_tables,
+                                batch,
+                                import_id,
+                            ))
+                            .await
+                        }
+                        .into()
+                    },
+                )
+                .await;
+            match result {
+                Ok((batch_ts, batch_documents_deleted, _occ_stats)) => {
+                    ts = batch_ts;
+                    documents_deleted += batch_documents_deleted;
+                    backoff.reset();
+                    break;
+                },
+                Err(e) => {
+                    report_error(&mut e.context(format!(
+                        "finalize_import({import_id:?}) failed to activate a batch of tables"
+                    )))
+                    .await;
+                    let delay = backoff.fail(&mut database.runtime().rng());
+                    database.runtime().wait(delay).await;
+                },
+            }
+        }
+    }
+
     Ok((ts, documents_deleted))
 }
 
+/// Activates a batch of the import's hidden tables. Tables that are already
+/// active are skipped, so a batch can be re-run after a failure.
+async fn activate_imported_tables<RT: Runtime>(
+    tx: &mut Transaction<RT>,
+    imported_tables: &TableMapping,
+    tablet_ids: &[TabletId],
+    import_id: Option<ResolvedDocumentId>,
+) -> anyhow::Result<u64> {
+    for &tablet_id in tablet_ids {
+        tracing::info!(
+            "finalize_import({import_id:?}) Activating table {} in namespace {:?}",
+            imported_tables.tablet_name(tablet_id)?,
+            imported_tables.tablet_namespace(tablet_id)?,
+        );
+    }
+    TableModel::new(tx)
+        .activate_tables(tablet_ids.iter().copied())
+        .await
+}
+
 /// Assign table numbers. There are numerous constraints:
 /// - table numbers must not conflict after the import is finalized
 /// - table numbers encoded in _id fields should match their tables
@@ -1102,6 +1999,7 @@ async fn assign_table_numbers<RT: Runtime>(
     )>,
     original_table_mapping: &TableMapping,
     initial_schemas: &SchemasForImport,
+    include_tables: Option<&BTreeSet<(ComponentPath, TableName)>>,
 ) -> anyhow::Result<BTreeMap<(ComponentId, TableName), Option<TableNumber>>> {
     let mut table_name_to_number: BTreeMap<(ComponentId, TableName), Option<TableNumber>> =
         BTreeMap::new(); // None here means that we'll pick any number
@@ -1129,19 +2027,17 @@ async fn assign_table_numbers<RT: Runtime>(
     // untouched snapshot export this will assign every table a proper number.
     for (component_path, component_id, _, objects) in tables_tables {
         let mut stream = parse_tables_table(objects);
+        let mut entries = TablesTableEntries::default();
         while let Some((table_name, table_number)) = stream.try_next().await? {
-            anyhow::ensure!(
-                table_name_to_number
-                    .insert((component_id, table_name.clone()), Some(table_number))
-                    .is_none(),
-                ErrorMetadata::bad_request(
-                    "DuplicateTableName",
-                    format!(
-                        "`_tables` contains duplicate entries for `{table_name}`{}",
-                        component_path.in_component_str()
-                    )
-                )
-            );
+            // Tables left out of the import don't get a number, or they'd be
+            // created empty.
+            if let Some(include_tables) = include_tables
+                && !include_tables.contains(&(&component_path, &table_name) as &dyn TupleKey<_, _>)
+            {
+                continue;
+            }
+            entries.add(&component_path, &table_name, table_number)?;
+            table_name_to_number.insert((component_id, table_name.clone()), Some(table_number));
             assign_number(&component_path, component_id, table_name, table_number)?;
         }
     }
@@ -1234,6 +2130,48 @@ async fn assign_table_numbers<RT: Runtime>(
     Ok(table_name_to_number)
 }
 
+/// The entries of one component's `_tables`, which must list each table name
+/// and table number at most once.
+#[derive(Default)]
+struct TablesTableEntries {
+    names_by_number: BTreeMap<TableNumber, TableName>,
+    names: BTreeSet<TableName>,
+}
+
+impl TablesTableEntries {
+    fn add(
+        &mut self,
+        component_path: &ComponentPath,
+        table_name: &TableName,
+        table_number: TableNumber,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.names.insert(table_name.clone()),
+            ErrorMetadata::bad_request(
+                "DuplicateTableDefinition",
+                format!(
+                    "`_tables` contains duplicate entries for `{table_name}`{}",
+                    component_path.in_component_str()
+                )
+            )
+        );
+        if let Some(existing_name) = self
+            .names_by_number
+            .insert(table_number, table_name.clone())
+        {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "DuplicateTableDefinition",
+                format!(
+                    "`_tables` assigns number {table_number} to both `{existing_name}` and \
+                     `{table_name}`{}",
+                    component_path.in_component_str()
+                )
+            ));
+        }
+        Ok(())
+    }
+}
+
 fn parse_tables_table(
     objects: impl Stream<Item = anyhow::Result<JsonValue>> + Unpin,
 ) -> impl Stream<Item = anyhow::Result<(TableName, TableNumber)>> + Unpin {
@@ -1277,10 +2215,7 @@ async fn import_single_table<RT: Runtime>(
     component_path: &ComponentPath,
     table_name: &TableName,
     mut objects: Peekable<ImportDocumentStream>,
-    storage_files_by_component: &mut BTreeMap<
-        ComponentPath,
-        Vec<(DeveloperDocumentId, ImportStorageFileStream)>,
-    >,
+    storage_files: Vec<(DeveloperDocumentId, ImportStorageFileStream)>,
     mut generated_schema: Option<&mut GeneratedSchema<ProdConfig>>,
     table_mapping_for_schema: &TableMapping,
     table_id: TabletIdAndTableNumber,
@@ -1288,6 +2223,12 @@ async fn import_single_table<RT: Runtime>(
     usage: FunctionUsageTracker,
     import_id: Option<ResolvedDocumentId>,
     requestor: ImportRequestor,
+    batch_limits: ImportBatchLimits,
+    require_id_field: bool,
+    diff_import: bool,
+    allowed_fields: Option<&BTreeSet<FieldName>>,
+    transform: Option<&ImportTransform<'_, RT>>,
+    progress_sink: Option<&dyn ImportProgressSink>,
 ) -> anyhow::Result<u64> {
     if let Some(import_id) = import_id {
         best_effort_update_progress_message(
@@ -1309,9 +2250,6 @@ async fn import_single_table<RT: Runtime>(
     anyhow::ensure!(*table_name != TABLES_TABLE);
 
     if *table_name == FILE_STORAGE_TABLE {
-        let storage_files = storage_files_by_component
-            .remove(component_path)
-            .unwrap_or_default();
         import_storage_table(
             database,
             file_storage,
@@ -1325,33 +2263,65 @@ async fn import_single_table<RT: Runtime>(
             num_to_skip,
             requestor,
             table_mapping_for_schema,
+            progress_sink,
         )
         .await?;
         return Ok(0);
     }
 
-    let mut num_objects = 0;
+    let total_rows = match (progress_sink, import_id) {
+        (Some(_), Some(import_id)) => {
+            total_rows_to_write(database, identity, import_id, component_path, table_name).await?
+        },
+        _ => None,
+    };
+    let progress = TableProgress::new(progress_sink, component_path, table_name, total_rows);
+    progress.report(ImportTablePhase::Started, num_to_skip);
+
+    let mut rows = ImportRowCounter::new(num_to_skip);
+    let mut num_fields_dropped: u64 = 0;
+    let mut diff_counts = diff_import.then(DiffImportCounts::default);
+    // Creation times generated by earlier batches, which later batches must
+    // come after so the table keeps the file's order.
+    let mut creation_time_floor = None;
 
     let mut objects_to_insert = vec![];
     let mut objects_to_insert_size = 0;
     while let Some(exported_value) = objects.try_next().await? {
-        if num_objects < num_to_skip {
-            num_objects += 1;
+        let Some(row_number) = rows.next_row() else {
             continue;
-        }
-        let row_number = num_objects + 1;
+        };
         let convex_value =
             GeneratedSchema::<ProdConfig>::apply(generated_schema.as_deref_mut(), exported_value)
                 .map_err(|e| ImportError::InvalidConvexValue(row_number, e))?;
         let ConvexValue::Object(convex_object) = convex_value else {
             anyhow::bail!(ImportError::NotAnObject(row_number));
         };
-        objects_to_insert_size += convex_object.size();
-        objects_to_insert.push(convex_object);
-
-        if objects_to_insert_size > *TRANSACTION_MAX_USER_WRITE_SIZE_BYTES / 2
-            || objects_to_insert.len() > *TRANSACTION_MAX_NUM_USER_WRITES / 2
-        {
+        let convex_object = match transform {
+            Some(transform) => transform.apply(identity, row_number, convex_object).await?,
+            None => convex_object,
+        };
+        if require_id_field && convex_object.get(&*ID_FIELD).is_none() {
+            anyhow::bail!(ImportError::MissingIdField(row_number));
+        }
+        let convex_object = match allowed_fields {
+            Some(allowed_fields) => {
+                let num_fields = convex_object.len();
+                let convex_object = convex_object
+                    .filter_fields(|field| field.is_system() || allowed_fields.contains(field));
+                num_fields_dropped += (num_fields - convex_object.len()) as u64;
+                convex_object
+            },
+            None => convex_object,
+        };
+        check_document_size(row_number, &convex_object)?;
+        if batch_limits.should_flush(
+            objects_to_insert.len(),
+            objects_to_insert_size,
+            convex_object.size(),
+        ) {
+            // The batch holds the rows before this one.
+            let num_rows_written = row_number - 1;
             insert_import_objects(
                 database,
                 identity,
@@ -1360,8 +2330,13 @@ async fn import_single_table<RT: Runtime>(
                 table_id,
                 table_mapping_for_schema,
                 usage.clone(),
+                &mut creation_time_floor,
+                diff_counts.as_mut(),
+                import_id,
+                num_rows_written,
             )
             .await?;
+            progress.report(ImportTablePhase::Writing, num_rows_written);
             objects_to_insert = Vec::new();
             objects_to_insert_size = 0;
             if let Some(import_id) = import_id {
@@ -1371,18 +2346,20 @@ async fn import_single_table<RT: Runtime>(
                     import_id,
                     format!(
                         "Importing \"{table_name}\" ({} documents)",
-                        num_objects.separate_with_commas()
+                        num_rows_written.separate_with_commas()
                     ),
                     component_path,
                     table_name,
-                    num_objects as i64,
+                    num_rows_written as i64,
                 )
                 .await;
             }
         }
-        num_objects += 1;
+        objects_to_insert_size += convex_object.size();
+        objects_to_insert.push(convex_object);
     }
 
+    let num_objects = rows.num_rows();
     insert_import_objects(
         database,
         identity,
@@ -1391,18 +2368,43 @@ async fn import_single_table<RT: Runtime>(
         table_id,
         table_mapping_for_schema,
         usage,
+        &mut creation_time_floor,
+        diff_counts.as_mut(),
+        import_id,
+        num_objects,
     )
     .await?;
+    progress.report(ImportTablePhase::Finished, num_objects);
 
     if let Some(import_id) = import_id {
+        let mut details = vec![format!("{} documents", num_objects.separate_with_commas())];
+        if let Some(DiffImportCounts {
+            inserted,
+            updated,
+            unchanged,
+        }) = diff_counts
+        {
+            details.push(format!(
+                "{} inserted, {} updated, {} unchanged",
+                inserted.separate_with_commas(),
+                updated.separate_with_commas(),
+                unchanged.separate_with_commas()
+            ));
+        }
+        if num_fields_dropped > 0 {
+            details.push(format!(
+                "dropped {} fields not in the schema",
+                num_fields_dropped.separate_with_commas()
+            ));
+        }
         add_checkpoint_message(
             database,
             identity,
             import_id,
             format!(
-                "Imported \"{table_name}\"{} ({} documents)",
+                "Imported \"{table_name}\"{} ({})",
                 component_path.in_component_str(),
-                num_objects.separate_with_commas()
+                details.join(", ")
             ),
             component_path,
             table_name,
@@ -1414,6 +2416,74 @@ async fn import_single_table<RT: Runtime>(
     Ok(num_objects)
 }
 
+/// Runs the table imports in `sequential` one at a time, in order, and then the
+/// ones in `parallel` up to `parallelism` at a time. Returns how many documents
+/// they imported.
+async fn run_table_imports<F: Future<Output = anyhow::Result<u64>>>(
+    sequential: Vec<F>,
+    parallel: Vec<F>,
+    parallelism: usize,
+) -> anyhow::Result<u64> {
+    let mut total_num_documents = 0;
+    for table_import in sequential {
+        total_num_documents += table_import.await?;
+    }
+    let mut parallel = stream::iter(parallel).buffer_unordered(parallelism);
+    while let Some(num_documents) = parallel.try_next().await? {
+        total_num_documents += num_documents;
+    }
+    Ok(total_num_documents)
+}
+
+/// Counts a table's rows as they're read from the import file, skipping the
+/// rows an earlier attempt at the import already wrote.
+struct ImportRowCounter {
+    num_to_skip: u64,
+    num_rows: u64,
+}
+
+impl ImportRowCounter {
+    fn new(num_to_skip: u64) -> Self {
+        Self {
+            num_to_skip,
+            num_rows: 0,
+        }
+    }
+
+    /// Counts the next row, returning its row number if it still needs to be
+    /// written.
+    fn next_row(&mut self) -> Option<u64> {
+        self.num_rows += 1;
+        (self.num_rows > self.num_to_skip).then_some(self.num_rows)
+    }
+
+    /// How many rows have been counted, including skipped ones. Once they're
+    /// all written, this is the table's checkpoint.
+    fn num_rows(&self) -> u64 {
+        self.num_rows
+    }
+}
+
+/// Fails with an error naming the largest field if `object` is too large to
+/// be stored as a single document, rather than the generic error from the
+/// insert.
+fn check_document_size(row_number: u64, object: &ConvexObject) -> anyhow::Result<()> {
+    let size = object.size();
+    if size <= MAX_USER_SIZE {
+        return Ok(());
+    }
+    let (largest_field, largest_value) = object
+        .iter()
+        .max_by_key(|(_, value)| value.size())
+        .context("Oversized document has no fields")?;
+    anyhow::bail!(ImportError::DocumentTooLarge {
+        row_number,
+        size,
+        largest_field: largest_field.to_string(),
+        largest_field_size: largest_value.size(),
+    })
+}
+
 async fn insert_import_objects<RT: Runtime>(
     database: &Database<RT>,
     identity: &Identity,
@@ -1422,10 +2492,21 @@ async fn insert_import_objects<RT: Runtime>(
     table_id: TabletIdAndTableNumber,
     table_mapping_for_schema: &TableMapping,
     usage: FunctionUsageTracker,
+    creation_time_floor: &mut Option<CreationTime>,
+    // When set, documents are synced into the table with
+    // `ImportFacingModel::upsert_if_changed` and the outcomes are counted here.
+    diff_counts: Option<&mut DiffImportCounts>,
+    import_id: Option<ResolvedDocumentId>,
+    // How many of the table's rows will have been imported once these objects
+    // are written, including rows skipped when resuming.
+    num_rows_written: u64,
 ) -> anyhow::Result<()> {
     if objects_to_insert.is_empty() {
         return Ok(());
     }
+    let floor = *creation_time_floor;
+    let diff_import = diff_counts.is_some();
+    let first_row_number = num_rows_written - objects_to_insert.len() as u64 + 1;
     let object_ids: Vec<_> = objects_to_insert
         .iter()
         .filter_map(|object| object.get(&*ID_FIELD))
@@ -1437,40 +2518,118 @@ async fn insert_import_objects<RT: Runtime>(
             format!("Objects in table \"{table_name}\" have duplicate _id fields")
         ));
     }
-    database
+    let (_, (next_creation_time, batch_diff_counts), _) = database
         .execute_with_overloaded_and_ratelimited_retries(
             identity.clone(),
             usage,
             "snapshot_import_insert_objects",
             |tx| {
                 async {
-                    for object_to_insert in objects_to_insert.clone() {
-                        ImportFacingModel::new(tx)
-                            .insert(
+                    if let Some(floor) = floor {
+                        ImportFacingModel::new(tx).generate_creation_times_after(floor)?;
+                    }
+                    let mut batch_diff_counts = DiffImportCounts::default();
+                    for (row_number, object_to_insert) in
+                        (first_row_number..).zip(objects_to_insert.clone())
+                    {
+                        let mut model = ImportFacingModel::new(tx);
+                        if !diff_import {
+                            model
+                                .insert(
+                                    table_id,
+                                    table_name,
+                                    object_to_insert,
+                                    table_mapping_for_schema,
+                                )
+                                .await
+                                .map_err(|e| with_schema_mismatch_row(row_number, e))?;
+                            continue;
+                        }
+                        let outcome = model
+                            .upsert_if_changed(
                                 table_id,
                                 table_name,
                                 object_to_insert,
                                 table_mapping_for_schema,
                             )
+                            .await
+                            .map_err(|e| with_schema_mismatch_row(row_number, e))?;
+                        batch_diff_counts.record(outcome);
+                    }
+                    if let Some(import_id) = import_id {
+                        SnapshotImportModel::new(tx)
+                            .checkpoint_rows_written(
+                                import_id,
+                                table_id.tablet_id,
+                                num_rows_written as i64,
+                            )
                             .await?;
                     }
-                    Ok(())
+                    Ok((
+                        ImportFacingModel::new(tx).next_creation_time(),
+                        batch_diff_counts,
+                    ))
                 }
                 .into()
             },
         )
         .await?;
+    *creation_time_floor = Some(next_creation_time);
+    if let Some(diff_counts) = diff_counts {
+        diff_counts.inserted += batch_diff_counts.inserted;
+        diff_counts.updated += batch_diff_counts.updated;
+        diff_counts.unchanged += batch_diff_counts.unchanged;
+    }
     Ok(())
 }
 
+/// Reports a document that doesn't match the table's schema with its row
+/// number and the field that failed. Other errors are returned unchanged.
+fn with_schema_mismatch_row(row_number: u64, e: anyhow::Error) -> anyhow::Error {
+    let Some(SchemaEnforcementError::Document {
+        validation_error, ..
+    }) = e.downcast_ref::<SchemaEnforcementError>()
+    else {
+        return e;
+    };
+    let (field, expected) = validation_error.failed_field();
+    anyhow::anyhow!(ImportError::SchemaMismatch {
+        row_number,
+        field: field.map(|path| path.trim_start_matches('.').to_string()),
+        expected,
+        message: validation_error.to_string(),
+    })
+}
+
+/// How the documents of a diff import compared with the table's existing
+/// documents.
+#[derive(Clone, Copy, Debug, Default)]
+struct DiffImportCounts {
+    inserted: u64,
+    updated: u64,
+    unchanged: u64,
+}
+
+impl DiffImportCounts {
+    fn record(&mut self, outcome: UpsertOutcome) {
+        match outcome {
+            UpsertOutcome::Inserted => self.inserted += 1,
+            UpsertOutcome::Updated => self.updated += 1,
+            UpsertOutcome::Unchanged => self.unchanged += 1,
+        }
+    }
+}
+
 async fn prepare_table_for_import<RT: Runtime>(
     database: &Database<RT>,
     identity: &Identity,
     mode: ImportMode,
+    options: &ImportOptions,
     component_id: ComponentId,
     table_name: &TableName,
     table_number: Option<TableNumber>,
     import_id: Option<ResolvedDocumentId>,
+    num_tables_created: &mut usize,
 ) -> anyhow::Result<(TabletIdAndTableNumber, u64)> {
     anyhow::ensure!(
         table_name == &FILE_STORAGE_TABLE || !table_name.is_system(),
@@ -1484,6 +2643,7 @@ async fn prepare_table_for_import<RT: Runtime>(
     } else {
         table_name
     };
+    let mode = options.mode_for_table(mode, display_table_name);
     let mut tx = database.begin(identity.clone()).await?;
     let component_path = BootstrapComponentsModel::new(&mut tx)
         .get_component_path(component_id)
@@ -1508,9 +2668,21 @@ async fn prepare_table_for_import<RT: Runtime>(
                 anyhow::bail!("can't resume append import");
             }
             let existing_table_number = tx.table_mapping().tablet_number(tablet_id)?;
-            let num_to_skip = TableModel::new(&mut tx)
-                .must_count_tablet(tablet_id)
-                .await?;
+            let num_to_skip = if table_name == &FILE_STORAGE_TABLE {
+                // Each file is stored in its own transaction, so every row in
+                // the table is a file that was already imported.
+                TableModel::new(&mut tx)
+                    .must_count_tablet(tablet_id)
+                    .await?
+            } else {
+                // User tables checkpoint how many rows they've written in the
+                // same transaction as the rows.
+                let import_id = import_id.context("checkpoint without an import")?;
+                let num_rows_written = SnapshotImportModel::new(&mut tx)
+                    .rows_written(import_id, tablet_id)
+                    .await?;
+                u64::try_from(num_rows_written)?
+            };
             (
                 Some(TabletIdAndTableNumber {
                     tablet_id,
@@ -1540,6 +2712,23 @@ async fn prepare_table_for_import<RT: Runtime>(
             (tablet_id, 0)
         },
     };
+    // Tables being replaced already exist, so only tables that are new to
+    // the component count towards the cap.
+    if insert_into_existing_table_id.is_none()
+        && !TableModel::new(&mut tx).table_exists(component_id.into(), table_name)
+    {
+        *num_tables_created += 1;
+        anyhow::ensure!(
+            *num_tables_created <= *SNAPSHOT_IMPORT_MAX_TABLES_CREATED,
+            ErrorMetadata::bad_request(
+                "TooManyTablesCreated",
+                format!(
+                    "Import would create more than {} new tables",
+                    *SNAPSHOT_IMPORT_MAX_TABLES_CREATED
+                )
+            )
+        );
+    }
     drop(tx);
     let table_id = if let Some(insert_into_existing_table_id) = insert_into_existing_table_id {
         insert_into_existing_table_id
@@ -1614,31 +2803,99 @@ async fn create_empty_table<RT: Runtime>(
             },
         )
         .await?;
-    backfill_and_enable_indexes_on_table(database, identity, table_id.tablet_id).await?;
+    let progress = import_id.map(|import_id| (import_id, component_path, display_table_name));
+    backfill_and_enable_indexes_on_table(database, identity, table_id.tablet_id, progress).await?;
     Ok(table_id)
 }
 
 /// Waits for all indexes on a table to be backfilled, which may take a while
-/// for large tables. After the indexes are backfilled, enable them.
+/// for large tables. After the indexes are backfilled, enable them, pausing
+/// [`SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER`] between each one if it's set and
+/// the table has at least [`SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER_MIN_ROWS`]
+/// rows.
+///
+/// If `progress` names an import, its progress message reports how many of the
+/// table's indexes are ready while waiting.
 async fn backfill_and_enable_indexes_on_table<RT: Runtime>(
     database: &Database<RT>,
     identity: &Identity,
     tablet_id: TabletId,
+    progress: Option<(ResolvedDocumentId, &ComponentPath, &TableName)>,
 ) -> anyhow::Result<()> {
+    let mut last_reported_backfilling = None;
     loop {
         let mut tx = database.begin(identity.clone()).await?;
-        let still_backfilling = IndexModel::new(&mut tx)
-            .all_indexes_on_table(tablet_id)
-            .await?
-            .into_iter()
-            .any(|index| index.config.is_backfilling());
-        if !still_backfilling {
+        let index_states = IndexModel::new(&mut tx)
+            .tablet_index_states(tablet_id)
+            .await?;
+        let num_backfilling = index_states
+            .iter()
+            .filter(|(_, state)| *state == IndexState::Backfilling)
+            .count();
+        if num_backfilling == 0 {
             break;
         }
+        if let Some((import_id, component_path, display_table_name)) = progress
+            && last_reported_backfilling != Some(num_backfilling)
+        {
+            best_effort_update_progress_message(
+                database,
+                identity,
+                import_id,
+                format!(
+                    "Backfilling indexes on \"{display_table_name}\" ({}/{} ready)",
+                    index_states.len() - num_backfilling,
+                    index_states.len(),
+                ),
+                component_path,
+                display_table_name,
+                0,
+            )
+            .await;
+            last_reported_backfilling = Some(num_backfilling);
+        }
         let token = tx.into_token()?;
         database.subscribe_and_wait_for_invalidation(token).await?;
     }
     // Enable the indexes now that they are backfilled.
+    let stagger = *SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER;
+    if stagger.is_zero() {
+        return enable_backfilled_indexes_on_table(database, identity, tablet_id, None).await;
+    }
+    let index_ids: Vec<_> = {
+        let mut tx = database.begin(identity.clone()).await?;
+        // Treat a table whose count isn't known yet as large.
+        let is_small = TableModel::new(&mut tx)
+            .count_tablet(tablet_id)
+            .await?
+            .is_some_and(|count| count < *SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER_MIN_ROWS);
+        if is_small {
+            return enable_backfilled_indexes_on_table(database, identity, tablet_id, None).await;
+        }
+        IndexModel::new(&mut tx)
+            .all_indexes_on_table(tablet_id)
+            .await?
+            .into_iter()
+            .filter(|index| !index.config.is_enabled())
+            .map(|index| index.id())
+            .collect()
+    };
+    for (i, index_id) in index_ids.into_iter().enumerate() {
+        if i > 0 {
+            database.runtime().wait(stagger).await;
+        }
+        enable_backfilled_indexes_on_table(database, identity, tablet_id, Some(index_id)).await?;
+    }
+    Ok(())
+}
+
+/// Enables the backfilled indexes on a table, or just `only_index` if given.
+async fn enable_backfilled_indexes_on_table<RT: Runtime>(
+    database: &Database<RT>,
+    identity: &Identity,
+    tablet_id: TabletId,
+    only_index: Option<ResolvedDocumentId>,
+) -> anyhow::Result<()> {
     database
         .execute_with_overloaded_retries(
             identity.clone(),
@@ -1649,7 +2906,9 @@ async fn backfill_and_enable_indexes_on_table<RT: Runtime>(
                     let mut index_model = IndexModel::new(tx);
                     let mut backfilled_indexes = vec![];
                     for index in index_model.all_indexes_on_table(tablet_id).await? {
-                        if !index.config.is_enabled() {
+                        if !index.config.is_enabled()
+                            && only_index.is_none_or(|id| id == index.id())
+                        {
                             backfilled_indexes.push(index);
                         }
                     }
@@ -1705,6 +2964,9 @@ async fn remap_empty_string_by_schema<RT: Runtime>(
             .documents
             .into_iter()
             .map(move |(component, table, stream)| {
+                if table != table_name {
+                    return (component, table, stream);
+                }
                 let optional_fields = optional_fields.clone();
                 (
                     component,
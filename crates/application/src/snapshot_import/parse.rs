@@ -1,14 +1,32 @@
 use std::{
-    collections::BTreeMap,
+    cmp::Ordering,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    future,
     io,
+    iter,
+    mem,
+    pin::Pin,
     str::FromStr,
     sync::{
+        atomic::{
+            AtomicU64,
+            Ordering as AtomicOrdering,
+        },
         Arc,
         LazyLock,
     },
+    task::{
+        ready,
+        Context as TaskContext,
+        Poll,
+    },
 };
 
 use anyhow::Context;
+use async_compression::tokio::bufread::GzipDecoder;
 use bytes::Bytes;
 use common::{
     bootstrap_model::tables::TABLES_TABLE,
@@ -16,7 +34,13 @@ use common::{
         ComponentName,
         ComponentPath,
     },
-    knobs::TRANSACTION_MAX_USER_WRITE_SIZE_BYTES,
+    knobs::{
+        SNAPSHOT_IMPORT_MAX_COMPONENT_DEPTH,
+        SNAPSHOT_IMPORT_ZIP_MAX_BUFFERED_ENTRY_BYTES,
+        SNAPSHOT_IMPORT_ZIP_MAX_ENTRIES,
+        SNAPSHOT_IMPORT_ZIP_MAX_UNCOMPRESSED_BYTES,
+        TRANSACTION_MAX_USER_WRITE_SIZE_BYTES,
+    },
     types::{
         FieldName,
         FullyQualifiedObjectKey,
@@ -38,8 +62,12 @@ use futures_async_stream::{
     try_stream_block,
 };
 use model::{
+    exports::deployment_config::DEPLOYMENT_CONFIG_DIR,
     file_storage::FILE_STORAGE_VIRTUAL_TABLE,
-    snapshot_imports::types::ImportFormat,
+    snapshot_imports::types::{
+        ImportFormat,
+        PartitionBy,
+    },
 };
 use regex::Regex;
 use serde_json::{
@@ -61,17 +89,24 @@ use storage::{
 };
 use storage_zip_reader::StorageZipArchive;
 use tokio::io::{
+    AsyncBufRead,
     AsyncBufReadExt as _,
     AsyncRead,
+    AsyncReadExt as _,
     BufReader,
+    ReadBuf,
+};
+use tokio_util::{
+    either::Either,
+    io::ReaderStream,
 };
-use tokio_util::io::ReaderStream;
 use value::{
     id_v6::DeveloperDocumentId,
     TableName,
 };
 
 use crate::snapshot_import::{
+    deployment_config::DeploymentConfigBundle,
     import_error::ImportError,
     metrics::log_snapshot_import_found_legacy_generated_schema,
 };
@@ -86,6 +121,8 @@ pub struct ParsedImport {
         'static,
         anyhow::Result<(ComponentPath, DeveloperDocumentId, ImportStorageFileStream)>,
     >,
+    /// Deployment configuration bundled in a ZIP import, if any.
+    pub deployment_config: DeploymentConfigBundle,
 }
 
 impl ParsedImport {
@@ -98,6 +135,7 @@ impl ParsedImport {
             generated_schemas: vec![],
             documents: vec![(component_path, table_name, documents)],
             storage_files: stream::empty().boxed(),
+            deployment_config: DeploymentConfigBundle::default(),
         }
     }
 }
@@ -108,12 +146,162 @@ static GENERATED_SCHEMA_PATTERN: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^(.*/)?([^/]+)/generated_schema\.jsonl$").unwrap());
 static DOCUMENTS_PATTERN: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^(.*/)?([^/]+)/documents\.jsonl$").unwrap());
+static DEPLOYMENT_CONFIG_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"^(.*/)?{DEPLOYMENT_CONFIG_DIR}/([^/]+)$")).unwrap());
 // _storage/(ID) with optional ignored prefix and extension like
 // snapshot/_storage/(ID).png
 static STORAGE_FILE_PATTERN: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(.*/)?_storage/([^/.]+)(?:\.[^/]+)?$").unwrap());
 
-fn map_zip_io_error(e: io::Error) -> anyhow::Error {
+/// Rejects zip entry names that are absolute or have `..` segments, before
+/// the patterns above get a chance to read components or tables out of them.
+/// Exports never write such names.
+pub fn validate_zip_entry_name(name: &str) -> anyhow::Result<()> {
+    let invalid = |reason: &str| {
+        anyhow::anyhow!(ErrorMetadata::bad_request(
+            "InvalidZip",
+            format!("invalid zip entry {name:?}: {reason}"),
+        ))
+    };
+    if name.starts_with(['/', '\\']) {
+        return Err(invalid("absolute paths aren't allowed"));
+    }
+    if name.split(['/', '\\']).any(|segment| segment == "..") {
+        return Err(invalid("'..' path segments aren't allowed"));
+    }
+    Ok(())
+}
+
+/// Fails a ZIP import whose central directory lists more than `max_entries`
+/// entries.
+pub fn check_zip_entry_count(num_entries: usize, max_entries: usize) -> anyhow::Result<()> {
+    if num_entries > max_entries {
+        anyhow::bail!(ErrorMetadata::bad_request(
+            "InvalidZip",
+            format!("zip file has {num_entries} entries, more than the maximum of {max_entries}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Counts the bytes decompressed from all of a ZIP import's entries, failing
+/// reads once the total passes `limit`, which is
+/// [SNAPSHOT_IMPORT_ZIP_MAX_UNCOMPRESSED_BYTES] for imports. The sizes in the
+/// central directory aren't trusted.
+#[derive(Clone)]
+pub struct ZipUncompressedBudget {
+    bytes_read: Arc<AtomicU64>,
+    limit: u64,
+}
+
+impl ZipUncompressedBudget {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            bytes_read: Arc::new(AtomicU64::new(0)),
+            limit,
+        }
+    }
+
+    /// Wraps an entry that's streamed, like documents or storage files.
+    pub fn wrap<R: AsyncRead + Unpin>(&self, reader: R) -> BudgetedZipEntryReader<R> {
+        BudgetedZipEntryReader {
+            reader,
+            budget: self.clone(),
+            entry_bytes_read: 0,
+            max_entry_bytes: None,
+        }
+    }
+
+    /// Wraps an entry that's read into memory whole, which also fails once
+    /// the entry passes `max_entry_bytes`.
+    pub fn wrap_buffered<R: AsyncRead + Unpin>(
+        &self,
+        reader: R,
+        max_entry_bytes: u64,
+    ) -> BudgetedZipEntryReader<R> {
+        BudgetedZipEntryReader {
+            max_entry_bytes: Some(max_entry_bytes),
+            ..self.wrap(reader)
+        }
+    }
+}
+
+pub struct BudgetedZipEntryReader<R> {
+    reader: R,
+    budget: ZipUncompressedBudget,
+    entry_bytes_read: u64,
+    max_entry_bytes: Option<u64>,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for BudgetedZipEntryReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled_before = buf.filled().len();
+        ready!(Pin::new(&mut self.reader).poll_read(cx, buf))?;
+        let read = (buf.filled().len() - filled_before) as u64;
+        let total = self
+            .budget
+            .bytes_read
+            .fetch_add(read, AtomicOrdering::Relaxed)
+            + read;
+        if total > self.budget.limit {
+            return Poll::Ready(Err(io::Error::other(ErrorMetadata::bad_request(
+                "InvalidZip",
+                format!(
+                    "zip file decompresses to more than the maximum of {} bytes",
+                    self.budget.limit
+                ),
+            ))));
+        }
+        self.entry_bytes_read += read;
+        if let Some(max_entry_bytes) = self.max_entry_bytes
+            && self.entry_bytes_read > max_entry_bytes
+        {
+            return Poll::Ready(Err(zip_entry_too_large(max_entry_bytes)));
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn zip_entry_too_large(max_bytes: u64) -> io::Error {
+    io::Error::other(ErrorMetadata::bad_request(
+        "InvalidZip",
+        format!("zip file has an entry or line larger than the maximum of {max_bytes} bytes"),
+    ))
+}
+
+/// Reads a line into `line` like `read_until`, but fails rather than
+/// buffering a line longer than `max_line_bytes`, counting its newline.
+async fn read_line_limited(
+    reader: &mut (impl AsyncBufRead + Unpin),
+    line: &mut Vec<u8>,
+    max_line_bytes: u64,
+) -> io::Result<usize> {
+    let n = (&mut *reader)
+        .take(max_line_bytes + 1)
+        .read_until(b'\n', line)
+        .await?;
+    if n as u64 > max_line_bytes {
+        return Err(zip_entry_too_large(max_line_bytes));
+    }
+    Ok(n)
+}
+
+/// The error from passing one of a ZIP import's limits while reading it, like
+/// a [BudgetedZipEntryReader] running out of budget, if `e` is one.
+fn zip_budget_error(e: &io::Error) -> Option<anyhow::Error> {
+    e.get_ref()
+        .and_then(|inner| inner.downcast_ref::<ErrorMetadata>())
+        .map(|error_metadata| anyhow::anyhow!(error_metadata.clone()))
+}
+
+pub fn map_zip_io_error(e: io::Error) -> anyhow::Error {
+    if let Some(e) = zip_budget_error(&e) {
+        return e;
+    }
     if e.kind() == io::ErrorKind::InvalidData {
         // Content errors become InvalidData errors
         anyhow::Error::from(e).context(ErrorMetadata::bad_request("InvalidZip", "invalid zip file"))
@@ -123,6 +311,34 @@ fn map_zip_io_error(e: io::Error) -> anyhow::Error {
     }
 }
 
+fn map_gzip_io_error(e: io::Error) -> anyhow::Error {
+    if let Some(e) = zip_budget_error(&e) {
+        return e;
+    }
+    let is_decompression_error = matches!(
+        e.kind(),
+        io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof
+    ) || e
+        .get_ref()
+        .is_some_and(|inner| inner.is::<flate2::DecompressError>());
+    if is_decompression_error {
+        ImportError::InvalidGzip(e).into()
+    } else {
+        // Errors reading the compressed data from storage
+        e.into()
+    }
+}
+
+/// The first bytes of every gzip member.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+pub fn gzip_decoder<R: AsyncBufRead>(reader: R) -> GzipDecoder<R> {
+    let mut decoder = GzipDecoder::new(reader);
+    // Large exports are often compressed in chunks that are concatenated.
+    decoder.multiple_members(true);
+    decoder
+}
+
 fn map_csv_error(e: csv_async::Error) -> anyhow::Error {
     let pos_line = |pos: &Option<csv_async::Position>| pos.as_ref().map_or(0, |pos| pos.line());
     match e.kind() {
@@ -145,11 +361,245 @@ fn map_csv_error(e: csv_async::Error) -> anyhow::Error {
 
 /// Parse the imported file, returning separate streams for each table or
 /// storage file.
+///
+/// With `order_by`, a JSON array import is sorted by that field. Other formats
+/// are streamed, so their user tables fail to import if they aren't already
+/// sorted by it.
+///
+/// With `partition_by`, a single-table import is split into a stream for each
+/// destination table. Each stream parses the file separately and keeps only
+/// its own documents, so a partitioned file is read once per destination.
 pub async fn parse_import_file(
     format: ImportFormat,
     component_path: ComponentPath,
     storage: Arc<dyn Storage>,
     fq_object_key: FullyQualifiedObjectKey,
+    json_pointer: Option<&str>,
+    order_by: Option<&FieldName>,
+    partition_by: Option<&PartitionBy>,
+    csv_string_columns: &BTreeSet<FieldName>,
+) -> anyhow::Result<ParsedImport> {
+    let Some(partition_by) = partition_by else {
+        return parse_import_file_unpartitioned(
+            format,
+            component_path,
+            storage,
+            fq_object_key,
+            json_pointer,
+            order_by,
+            csv_string_columns,
+        )
+        .await;
+    };
+    let default_table = match &format {
+        ImportFormat::Csv(table_name)
+        | ImportFormat::JsonLines(table_name)
+        | ImportFormat::JsonLinesGzip(table_name)
+        | ImportFormat::JsonArray(table_name) => table_name.clone(),
+        ImportFormat::Zip => {
+            anyhow::bail!("partition_by is only supported for single-table imports")
+        },
+    };
+    let destinations: BTreeSet<TableName> = partition_by
+        .tables
+        .values()
+        .cloned()
+        .chain([default_table.clone()])
+        .collect();
+    let documents = destinations
+        .into_iter()
+        .map(|table_name| {
+            let format = format.clone();
+            let import_component_path = component_path.clone();
+            let storage = storage.clone();
+            let fq_object_key = fq_object_key.clone();
+            let json_pointer = json_pointer.map(str::to_owned);
+            let order_by = order_by.cloned();
+            let partition_by = partition_by.clone();
+            let csv_string_columns = csv_string_columns.clone();
+            let default_table = default_table.clone();
+            let destination = table_name.clone();
+            let documents = stream::once(async move {
+                let import = parse_import_file_unpartitioned(
+                    format,
+                    import_component_path,
+                    storage,
+                    fq_object_key,
+                    json_pointer.as_deref(),
+                    order_by.as_ref(),
+                    &csv_string_columns,
+                )
+                .await?;
+                let (_, _, documents) = import
+                    .documents
+                    .into_iter()
+                    .next()
+                    .context("Single-table import has no documents stream")?;
+                anyhow::Ok(documents)
+            })
+            .try_flatten()
+            .try_filter(move |document| {
+                let table_name = partition_table(&partition_by, document).unwrap_or(&default_table);
+                future::ready(*table_name == destination)
+            })
+            .boxed();
+            (component_path.clone(), table_name, documents)
+        })
+        .collect();
+    Ok(ParsedImport {
+        generated_schemas: vec![],
+        documents,
+        storage_files: stream::empty().boxed(),
+        deployment_config: DeploymentConfigBundle::default(),
+    })
+}
+
+/// The table `document` is routed to, or `None` if it goes to the import's
+/// own table.
+fn partition_table<'a>(
+    partition_by: &'a PartitionBy,
+    document: &JsonValue,
+) -> Option<&'a TableName> {
+    let key = match document.get(&*partition_by.field)? {
+        JsonValue::String(s) => s.clone(),
+        value => value.to_string(),
+    };
+    partition_by.tables.get(&key)
+}
+
+async fn parse_import_file_unpartitioned(
+    format: ImportFormat,
+    component_path: ComponentPath,
+    storage: Arc<dyn Storage>,
+    fq_object_key: FullyQualifiedObjectKey,
+    json_pointer: Option<&str>,
+    order_by: Option<&FieldName>,
+    csv_string_columns: &BTreeSet<FieldName>,
+) -> anyhow::Result<ParsedImport> {
+    let is_json_array = matches!(format, ImportFormat::JsonArray(_));
+    let mut import = parse_import_file_unordered(
+        format,
+        component_path,
+        storage,
+        fq_object_key,
+        json_pointer,
+        if is_json_array { order_by } else { None },
+        csv_string_columns,
+    )
+    .await?;
+    if let Some(field) = order_by
+        && !is_json_array
+    {
+        ensure_import_sorted_by(&mut import, field);
+    }
+    Ok(import)
+}
+
+/// Fails each user table's documents stream if it isn't sorted by `field`.
+fn ensure_import_sorted_by(import: &mut ParsedImport, field: &FieldName) {
+    for (_, table_name, documents) in &mut import.documents {
+        // `_tables` and `_storage` are written in their own order.
+        if table_name.is_system() {
+            continue;
+        }
+        let unordered = mem::replace(documents, stream::empty().boxed());
+        *documents = ensure_sorted_by(unordered, field.clone()).boxed();
+    }
+}
+
+/// Parses the parts of a ZIP import that was split into several files as if
+/// they were one archive.
+///
+/// A table's documents are read from every part that has them, in part order.
+/// `_tables` and the deployment configuration are only read from the first
+/// part, and a table's generated schema from the first part that has one.
+pub async fn parse_multipart_import(
+    component_path: ComponentPath,
+    storage: Arc<dyn Storage>,
+    fq_object_keys: Vec<FullyQualifiedObjectKey>,
+    order_by: Option<&FieldName>,
+    csv_string_columns: &BTreeSet<FieldName>,
+) -> anyhow::Result<ParsedImport> {
+    let mut import = ParsedImport {
+        generated_schemas: vec![],
+        documents: vec![],
+        storage_files: stream::empty().boxed(),
+        deployment_config: DeploymentConfigBundle::default(),
+    };
+    let mut storage_files = vec![];
+    for (i, fq_object_key) in fq_object_keys.into_iter().enumerate() {
+        let part_number = i + 1;
+        let part = parse_import_file_unordered(
+            ImportFormat::Zip,
+            component_path.clone(),
+            storage.clone(),
+            fq_object_key,
+            None,
+            None,
+            csv_string_columns,
+        )
+        .await?;
+        if part_number == 1 {
+            import.deployment_config = part.deployment_config;
+        } else if !part.deployment_config.is_empty() {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidMultipartImport",
+                format!(
+                    "Part {part_number} of the import contains a deployment configuration, which \
+                     must be in the first part"
+                ),
+            ));
+        }
+        for (component_path, table_name, generated_schema) in part.generated_schemas {
+            if !import
+                .generated_schemas
+                .iter()
+                .any(|(c, t, _)| *c == component_path && *t == table_name)
+            {
+                import
+                    .generated_schemas
+                    .push((component_path, table_name, generated_schema));
+            }
+        }
+        for (component_path, table_name, documents) in part.documents {
+            if part_number > 1 && table_name == TABLES_TABLE {
+                tracing::info!(
+                    "Skipping {component_path}:{table_name} in part {part_number} of a multi-part \
+                     import"
+                );
+                continue;
+            }
+            match import
+                .documents
+                .iter_mut()
+                .find(|(c, t, _)| *c == component_path && *t == table_name)
+            {
+                Some((_, _, earlier_documents)) => {
+                    let earlier = mem::replace(earlier_documents, stream::empty().boxed());
+                    *earlier_documents = earlier.chain(documents).boxed();
+                },
+                None => import
+                    .documents
+                    .push((component_path, table_name, documents)),
+            }
+        }
+        storage_files.push(part.storage_files);
+    }
+    import.storage_files = stream::iter(storage_files).flatten().boxed();
+    if let Some(field) = order_by {
+        ensure_import_sorted_by(&mut import, field);
+    }
+    Ok(import)
+}
+
+async fn parse_import_file_unordered(
+    format: ImportFormat,
+    component_path: ComponentPath,
+    storage: Arc<dyn Storage>,
+    fq_object_key: FullyQualifiedObjectKey,
+    json_pointer: Option<&str>,
+    json_array_order_by: Option<&FieldName>,
+    csv_string_columns: &BTreeSet<FieldName>,
 ) -> anyhow::Result<ParsedImport> {
     let stream_body = || async {
         storage
@@ -161,7 +611,7 @@ pub async fn parse_import_file(
         ImportFormat::Csv(table_name) => Ok(ParsedImport::single_table(
             component_path,
             table_name,
-            parse_csv_import(stream_body().await?).boxed(),
+            parse_csv_import(stream_body().await?, csv_string_columns.clone()).boxed(),
         )),
         ImportFormat::JsonLines(table_name) => {
             let mut reader = stream_body().await?.into_reader();
@@ -191,6 +641,18 @@ pub async fn parse_import_file(
                 .boxed(),
             ))
         },
+        ImportFormat::JsonLinesGzip(table_name) => {
+            let reader = gzip_decoder(stream_body().await?.into_tokio_reader());
+            Ok(ParsedImport::single_table(
+                component_path,
+                table_name,
+                parse_jsonl_gzip(
+                    BufReader::new(reader),
+                    *SNAPSHOT_IMPORT_ZIP_MAX_BUFFERED_ENTRY_BYTES,
+                )
+                .boxed(),
+            ))
+        },
         ImportFormat::JsonArray(table_name) => {
             let reader = stream_body().await?;
             let mut buf = Vec::new();
@@ -201,15 +663,33 @@ pub async fn parse_import_file(
             if buf.len() > *TRANSACTION_MAX_USER_WRITE_SIZE_BYTES {
                 anyhow::bail!(ImportError::JsonArrayTooLarge(buf.len()));
             }
-            let v: serde_json::Value = {
+            let mut v: serde_json::Value = {
                 // Check for UTF-8 BOM and reject it
                 if buf.starts_with(&[0xEF, 0xBB, 0xBF]) {
                     anyhow::bail!(ImportError::Utf8BomNotSupported);
                 }
                 serde_json::from_slice(&buf).map_err(ImportError::NotJson)?
             };
-            let JsonValue::Array(array) = v else {
-                anyhow::bail!(ImportError::NotJsonArray)
+            let array = match json_pointer {
+                Some(json_pointer) => {
+                    let target = v.pointer_mut(json_pointer).ok_or_else(|| {
+                        ImportError::JsonPointerNotFound(json_pointer.to_string())
+                    })?;
+                    let JsonValue::Array(array) = target.take() else {
+                        anyhow::bail!(ImportError::JsonPointerNotArray(json_pointer.to_string()))
+                    };
+                    array
+                },
+                None => {
+                    let JsonValue::Array(array) = v else {
+                        anyhow::bail!(ImportError::NotJsonArray)
+                    };
+                    array
+                },
+            };
+            let array = match json_array_order_by {
+                Some(field) => sort_by_field(array, field)?,
+                None => array,
             };
             Ok(ParsedImport::single_table(
                 component_path,
@@ -220,10 +700,18 @@ pub async fn parse_import_file(
         ImportFormat::Zip => {
             let base_component_path = component_path;
             let zip_reader = StorageZipArchive::open_fq(storage, fq_object_key).await?;
+            check_zip_entry_count(
+                zip_reader.entries().count(),
+                *SNAPSHOT_IMPORT_ZIP_MAX_ENTRIES,
+            )?;
+            let budget = ZipUncompressedBudget::new(*SNAPSHOT_IMPORT_ZIP_MAX_UNCOMPRESSED_BYTES);
+            let max_buffered_entry_bytes = *SNAPSHOT_IMPORT_ZIP_MAX_BUFFERED_ENTRY_BYTES;
 
             let mut generated_schemas = vec![];
             let mut documents = vec![];
+            let mut deployment_config = DeploymentConfigBundle::default();
             for entry in zip_reader.entries() {
+                validate_zip_entry_name(&entry.name)?;
                 if let Some((component_path, table_name)) =
                     parse_documents_jsonl_table_name(&entry.name, &base_component_path)?
                 {
@@ -234,26 +722,46 @@ pub async fn parse_import_file(
                         tracing::info!("Skipping system table entry {}", entry.name);
                         continue;
                     }
-                    let entry_reader = zip_reader.read_entry(entry.clone());
+                    let entry_reader = budget.wrap(zip_reader.read_entry(entry.clone()));
                     tracing::info!(
                         "importing zip file containing table {component_path}:{table_name}"
                     );
                     documents.push((
                         component_path,
                         table_name,
-                        parse_documents_jsonl(entry_reader).boxed(),
+                        parse_documents_jsonl(entry_reader, max_buffered_entry_bytes).boxed(),
                     ));
                 } else if let Some((component_path, table_name)) = parse_table_filename(
                     &entry.name,
                     &base_component_path,
                     &GENERATED_SCHEMA_PATTERN,
                 )? {
-                    let entry_reader = zip_reader.read_entry(entry.clone());
+                    let entry_reader = budget.wrap_buffered(
+                        zip_reader.read_entry(entry.clone()),
+                        max_buffered_entry_bytes,
+                    );
                     tracing::info!("importing zip file containing generated_schema {table_name}");
                     let generated_schema =
                         parse_generated_schema(&entry.name, entry_reader).await?;
 
                     generated_schemas.push((component_path, table_name, generated_schema));
+                } else if let Some(captures) = DEPLOYMENT_CONFIG_PATTERN.captures(&entry.name) {
+                    let prefix = captures.get(1).map_or("", |c| c.as_str());
+                    let component_path = parse_component_path(prefix, &base_component_path)?;
+                    let file_name = captures
+                        .get(2)
+                        .expect("regex has two capture groups")
+                        .as_str();
+                    let mut contents = vec![];
+                    budget
+                        .wrap_buffered(
+                            zip_reader.read_entry(entry.clone()),
+                            max_buffered_entry_bytes,
+                        )
+                        .read_to_end(&mut contents)
+                        .await
+                        .map_err(map_zip_io_error)?;
+                    deployment_config.add_file(component_path, file_name, &contents)?;
                 }
             }
             let storage_files = try_stream_block!({
@@ -264,9 +772,10 @@ pub async fn parse_import_file(
                     {
                         let zip_reader = zip_reader.clone();
                         let entry = entry.clone();
+                        let budget = budget.clone();
                         let entry_reader_stream = Box::new(move || {
-                            ReaderStream::new(zip_reader.read_entry(entry))
-                                .map_err(anyhow::Error::from)
+                            ReaderStream::new(budget.wrap(zip_reader.read_entry(entry)))
+                                .map_err(map_zip_io_error)
                                 .boxed()
                         }) as Box<_>;
                         yield (component_path, storage_id, entry_reader_stream);
@@ -277,13 +786,17 @@ pub async fn parse_import_file(
                 generated_schemas,
                 documents,
                 storage_files: storage_files.boxed(),
+                deployment_config,
             })
         },
     }
 }
 
 #[try_stream(ok = JsonValue, error = anyhow::Error)]
-async fn parse_csv_import(reader: storage::StorageGetStream) {
+pub async fn parse_csv_import(
+    reader: storage::StorageGetStream,
+    string_columns: BTreeSet<FieldName>,
+) {
     let mut reader = csv_async::AsyncReader::from_reader(reader.into_reader());
     if !reader.has_headers() {
         // TODO: this will never happen.
@@ -301,6 +814,22 @@ async fn parse_csv_import(reader: storage::StorageGetStream) {
             })
             .collect::<anyhow::Result<Vec<_>>>()?
     };
+    if let Some(column) = string_columns
+        .iter()
+        .find(|column| !field_names.contains(column))
+    {
+        anyhow::bail!(ImportError::CsvStringColumnNotInHeader(column.to_string()));
+    }
+    let column_types: Vec<_> = field_names
+        .iter()
+        .map(|field_name| {
+            if string_columns.contains(field_name) {
+                CsvColumnType::String
+            } else {
+                CsvColumnType::Inferred
+            }
+        })
+        .collect();
     let mut lineno = 0;
     let mut rows = reader.records();
     while let Some(row_r) = rows.next().await {
@@ -308,7 +837,13 @@ async fn parse_csv_import(reader: storage::StorageGetStream) {
         let parsed_row = row_r
             .map_err(map_csv_error)?
             .iter()
-            .map(parse_csv_cell)
+            .zip(
+                column_types
+                    .iter()
+                    .copied()
+                    .chain(iter::repeat(CsvColumnType::Inferred)),
+            )
+            .map(|(cell, column_type)| parse_csv_cell(cell, column_type))
             .collect::<Vec<JsonValue>>();
         let mut obj = BTreeMap::new();
         if field_names.len() != parsed_row.len() {
@@ -321,12 +856,101 @@ async fn parse_csv_import(reader: storage::StorageGetStream) {
     }
 }
 
+/// The value of an import's `order_by` field in one document. Numbers sort
+/// before strings, as they do for Convex values.
+#[derive(Debug)]
+enum OrderByKey {
+    Number(f64),
+    String(String),
+}
+
+impl OrderByKey {
+    fn new(lineno: u64, field: &FieldName, document: &JsonValue) -> anyhow::Result<Self> {
+        let key = match document.get(&**field) {
+            Some(JsonValue::Number(n)) => n.as_f64().map(Self::Number),
+            Some(JsonValue::String(s)) => Some(Self::String(s.clone())),
+            _ => None,
+        };
+        key.with_context(|| ImportError::InvalidOrderByValue(lineno, field.to_string()))
+    }
+}
+
+impl Ord for OrderByKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::String(a), Self::String(b)) => a.cmp(b),
+            (Self::Number(_), Self::String(_)) => Ordering::Less,
+            (Self::String(_), Self::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+impl PartialOrd for OrderByKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for OrderByKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrderByKey {}
+
+/// Stably sorts the documents of a JSON array import, which is already held in
+/// memory in full, by `field`.
+fn sort_by_field(array: Vec<JsonValue>, field: &FieldName) -> anyhow::Result<Vec<JsonValue>> {
+    let mut keyed = array
+        .into_iter()
+        .enumerate()
+        .map(|(i, document)| {
+            let key = OrderByKey::new(i as u64 + 1, field, &document)?;
+            anyhow::Ok((key, document))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(keyed.into_iter().map(|(_, document)| document).collect())
+}
+
+/// Passes `documents` through unchanged, failing on the first document that
+/// sorts before the previous one by `field`.
+#[try_stream(ok = JsonValue, error = anyhow::Error)]
+async fn ensure_sorted_by(mut documents: ImportDocumentStream, field: FieldName) {
+    let mut previous: Option<OrderByKey> = None;
+    let mut lineno = 0;
+    while let Some(document) = documents.try_next().await? {
+        lineno += 1;
+        let key = OrderByKey::new(lineno, &field, &document)?;
+        if let Some(previous) = &previous
+            && key < *previous
+        {
+            anyhow::bail!(ImportError::NotSortedByField(lineno, field.to_string()));
+        }
+        previous = Some(key);
+        yield document;
+    }
+}
+
 pub fn parse_component_path(
     mut filename: &str,
     base_component_path: &ComponentPath,
 ) -> anyhow::Result<ComponentPath> {
+    let full_filename = filename;
+    let base_depth = base_component_path.iter().count();
     let mut component_names = Vec::new();
     while let Some(captures) = COMPONENT_NAME_PATTERN.captures(filename) {
+        if base_depth + component_names.len() >= *SNAPSHOT_IMPORT_MAX_COMPONENT_DEPTH {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "ComponentTooDeep",
+                format!(
+                    "{full_filename} is nested more than {} components deep",
+                    *SNAPSHOT_IMPORT_MAX_COMPONENT_DEPTH
+                ),
+            ));
+        }
         filename = captures.get(1).map_or("", |c| c.as_str());
         let component_name_str = captures
             .get(2)
@@ -400,32 +1024,76 @@ fn parse_storage_filename(
     }
 }
 
-fn parse_documents_jsonl_table_name(
+pub fn parse_documents_jsonl_table_name(
     filename: &str,
     base_component_path: &ComponentPath,
 ) -> anyhow::Result<Option<(ComponentPath, TableName)>> {
     parse_table_filename(filename, base_component_path, &DOCUMENTS_PATTERN)
 }
 
+/// Parses a `documents.jsonl` entry, failing on any line longer than
+/// `max_line_bytes` rather than reading it into memory.
 #[try_stream(ok = JsonValue, error = anyhow::Error)]
-async fn parse_documents_jsonl(reader: impl AsyncRead + Unpin) {
-    let mut line = String::new();
+pub async fn parse_documents_jsonl(reader: impl AsyncRead + Unpin, max_line_bytes: u64) {
+    let mut line = Vec::new();
     let mut lineno = 1;
     let mut reader = BufReader::new(reader);
-    while reader
-        .read_line(&mut line)
+    // Entries may be gzipped themselves, e.g. a `.jsonl.gz` export that was
+    // zipped up as `documents.jsonl`.
+    let is_gzip = reader
+        .fill_buf()
         .await
         .map_err(map_zip_io_error)?
+        .starts_with(&GZIP_MAGIC);
+    let (mut reader, map_io_error): (_, fn(io::Error) -> anyhow::Error) = if is_gzip {
+        (
+            Either::Left(BufReader::new(gzip_decoder(reader))),
+            map_gzip_io_error,
+        )
+    } else {
+        (Either::Right(reader), map_zip_io_error)
+    };
+    while read_line_limited(&mut reader, &mut line, max_line_bytes)
+        .await
+        .map_err(map_io_error)?
         > 0
     {
         let v: serde_json::Value =
-            serde_json::from_str(&line).map_err(|e| ImportError::JsonInvalidRow(lineno, e))?;
+            serde_json::from_slice(&line).map_err(|e| ImportError::JsonInvalidRow(lineno, e))?;
+        yield v;
+        line.clear();
+        lineno += 1;
+    }
+}
+
+/// Parses decompressed JSON lines, failing on any line longer than
+/// `max_line_bytes` rather than reading it into memory. Lines are read as
+/// bytes so that errors reading `reader` are always decompression or storage
+/// errors.
+#[try_stream(ok = JsonValue, error = anyhow::Error)]
+pub async fn parse_jsonl_gzip(mut reader: impl AsyncBufRead + Unpin, max_line_bytes: u64) {
+    let mut line = Vec::new();
+    let mut lineno = 1;
+    while read_line_limited(&mut reader, &mut line, max_line_bytes)
+        .await
+        .map_err(map_gzip_io_error)?
+        > 0
+    {
+        if lineno == 1 && line.starts_with(&[0xEF, 0xBB, 0xBF]) {
+            anyhow::bail!(ImportError::Utf8BomNotSupported);
+        }
+        let v: serde_json::Value =
+            serde_json::from_slice(&line).map_err(|e| ImportError::JsonInvalidRow(lineno, e))?;
         yield v;
         line.clear();
         lineno += 1;
     }
 }
 
+fn map_generated_schema_io_error(e: io::Error) -> anyhow::Error {
+    zip_budget_error(&e).unwrap_or_else(|| ImportError::NotUtf8(e).into())
+}
+
 async fn parse_generated_schema<T: ShapeConfig>(
     filename: &str,
     entry_reader: impl tokio::io::AsyncRead + Unpin,
@@ -436,7 +1104,7 @@ async fn parse_generated_schema<T: ShapeConfig>(
     entry_reader
         .read_line(&mut line)
         .await
-        .map_err(ImportError::NotUtf8)?;
+        .map_err(map_generated_schema_io_error)?;
     let inferred_type_json: serde_json::Value =
         serde_json::from_str(&line).map_err(|e| ImportError::JsonInvalidRow(lineno, e))?;
     if inferred_type_json.as_str() == Some("uniform") {
@@ -461,7 +1129,7 @@ async fn parse_generated_schema<T: ShapeConfig>(
     while entry_reader
         .read_line(&mut line)
         .await
-        .map_err(ImportError::NotUtf8)?
+        .map_err(map_generated_schema_io_error)?
         > 0
     {
         let mut v: serde_json::Value =
@@ -494,10 +1162,24 @@ async fn parse_generated_schema<T: ShapeConfig>(
     Ok(generated_schema)
 }
 
+/// How the cells of a CSV column are parsed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CsvColumnType {
+    /// Cells that parse as floats are numbers, and the rest are strings.
+    Inferred,
+    /// Every cell is a string.
+    String,
+}
+
 // For now, we only parse out floats and strings in CSV files.
-pub fn parse_csv_cell(s: &str) -> JsonValue {
-    if let Ok(r) = s.parse::<f64>() {
-        return json!(r);
+pub fn parse_csv_cell(s: &str, column_type: CsvColumnType) -> JsonValue {
+    match column_type {
+        CsvColumnType::Inferred => {
+            if let Ok(r) = s.parse::<f64>() {
+                return json!(r);
+            }
+            json!(s)
+        },
+        CsvColumnType::String => json!(s),
     }
-    json!(s)
 }
@@ -11,6 +11,87 @@ use model::snapshot_imports::SnapshotImportModel;
 use usage_tracking::FunctionUsageTracker;
 use value::ResolvedDocumentId;
 
+/// Which part of importing a table an [`ImportProgressEvent`] reports.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ImportTablePhase {
+    /// The table is about to be written.
+    Started,
+    /// A batch of the table's rows was written.
+    Writing,
+    /// All of the table's rows were written.
+    Finished,
+}
+
+/// A machine-readable progress update for one table in an import.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImportProgressEvent {
+    pub component_path: ComponentPath,
+    pub table_name: TableName,
+    /// Rows written so far, including any written before the import resumed.
+    pub rows_written: u64,
+    /// Rows in the table, if known.
+    pub total_rows: Option<u64>,
+    pub phase: ImportTablePhase,
+}
+
+/// Receives structured progress updates as an import writes its tables. These
+/// are in addition to the progress messages shown in the dashboard.
+pub trait ImportProgressSink: Send + Sync {
+    fn on_progress(&self, event: ImportProgressEvent);
+}
+
+/// Sends a table's progress updates to an optional [`ImportProgressSink`].
+pub struct TableProgress<'a> {
+    sink: Option<&'a dyn ImportProgressSink>,
+    component_path: &'a ComponentPath,
+    table_name: &'a TableName,
+    total_rows: Option<u64>,
+}
+
+impl<'a> TableProgress<'a> {
+    pub fn new(
+        sink: Option<&'a dyn ImportProgressSink>,
+        component_path: &'a ComponentPath,
+        table_name: &'a TableName,
+        total_rows: Option<u64>,
+    ) -> Self {
+        Self {
+            sink,
+            component_path,
+            table_name,
+            total_rows,
+        }
+    }
+
+    pub fn report(&self, phase: ImportTablePhase, rows_written: u64) {
+        if let Some(sink) = self.sink {
+            sink.on_progress(ImportProgressEvent {
+                component_path: self.component_path.clone(),
+                table_name: self.table_name.clone(),
+                rows_written,
+                total_rows: self.total_rows,
+                phase,
+            });
+        }
+    }
+}
+
+/// The number of rows the import will write to a table, as counted before
+/// the import was confirmed.
+pub async fn total_rows_to_write<RT: Runtime>(
+    database: &Database<RT>,
+    identity: &Identity,
+    import_id: ResolvedDocumentId,
+    component_path: &ComponentPath,
+    display_table_name: &TableName,
+) -> anyhow::Result<Option<u64>> {
+    let mut tx = database.begin(identity.clone()).await?;
+    let checkpoint = SnapshotImportModel::new(&mut tx)
+        .get_table_checkpoint(import_id, component_path, display_table_name)
+        .await?;
+    Ok(checkpoint.and_then(|checkpoint| u64::try_from(checkpoint.total_num_rows_to_write).ok()))
+}
+
 pub async fn best_effort_update_progress_message<RT: Runtime>(
     database: &Database<RT>,
     identity: &Identity,
@@ -6,7 +6,10 @@ use std::{
 use common::{
     bootstrap_model::schema::SchemaState,
     runtime::Runtime,
-    schemas::DatabaseSchema,
+    schemas::{
+        DatabaseSchema,
+        DocumentSchema,
+    },
     types::TableName,
 };
 use database::{
@@ -17,6 +20,7 @@ use database::{
 };
 use errors::ErrorMetadata;
 use value::{
+    FieldName,
     ResolvedDocumentId,
     TableMapping,
     TableNamespace,
@@ -144,6 +148,41 @@ pub type SchemasForImport = Vec<(
     (ResolvedDocumentId, Arc<DatabaseSchema>),
 )>;
 
+/// The top-level fields that documents in `table_name` may have under every
+/// enforced schema in `namespace`, or `None` if no schema restricts them.
+pub fn fields_allowed_by_schemas(
+    initial_schemas: &SchemasForImport,
+    namespace: TableNamespace,
+    table_name: &TableName,
+) -> Option<BTreeSet<FieldName>> {
+    let mut allowed: Option<BTreeSet<FieldName>> = None;
+    for (_, _, (_, schema)) in initial_schemas
+        .iter()
+        .filter(|(schema_namespace, ..)| *schema_namespace == namespace)
+    {
+        if !schema.schema_validation {
+            continue;
+        }
+        let Some(table_schema) = schema.tables.get(table_name) else {
+            continue;
+        };
+        let validators = match &table_schema.document_type {
+            None | Some(DocumentSchema::Any) => continue,
+            Some(DocumentSchema::Union(validators)) => validators,
+        };
+        // A field is allowed if any member of the union defines it.
+        let fields: BTreeSet<FieldName> = validators
+            .iter()
+            .flat_map(|validator| validator.0.keys().cloned().map(FieldName::from))
+            .collect();
+        allowed = Some(match allowed {
+            Some(allowed) => allowed.intersection(&fields).cloned().collect(),
+            None => fields,
+        });
+    }
+    allowed
+}
+
 /// Documents in an imported table should match the schema.
 /// ImportFacingModel::insert checks that new documents match the schema,
 /// but SchemaWorker does not check new schemas against existing documents in
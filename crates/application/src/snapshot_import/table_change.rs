@@ -1,6 +1,8 @@
 use std::collections::BTreeMap;
 
 use common::components::ComponentPath;
+use itertools::Itertools;
+use model::snapshot_imports::types::ImportMode;
 use thousands::Separable;
 use value::TableName;
 
@@ -10,6 +12,8 @@ pub struct TableChange {
     pub existing: u64,
     pub unit: &'static str,
     pub is_missing_id_field: bool,
+    /// The mode this table is imported with, after per-table overrides.
+    pub mode: ImportMode,
 }
 
 pub fn render_table_changes(
@@ -23,12 +27,18 @@ pub fn render_table_changes(
     big      | 100,000 | 100,000 of 100,000 documents |
     messages | 20      | 21 of 21 documents           |
             */
+    // If tables are imported with different modes, a `mode` column is added
+    // after the table name.
+    let show_mode = !table_changes.values().map(|change| change.mode).all_equal();
     let mut message_lines = Vec::new();
-    let mut parts = vec![(
+    let mut parts = vec![vec![
         "table".to_string(),
         "create".to_string(),
         "delete".to_string(),
-    )];
+    ]];
+    if show_mode {
+        parts[0].insert(1, "mode".to_string());
+    }
     for (
         (_, table_name),
         TableChange {
@@ -37,10 +47,11 @@ pub fn render_table_changes(
             existing,
             unit,
             is_missing_id_field: _,
+            mode,
         },
     ) in table_changes
     {
-        parts.push((
+        let mut row = vec![
             table_name.to_string(),
             added.separate_with_commas(),
             format!(
@@ -49,35 +60,33 @@ pub fn render_table_changes(
                 existing.separate_with_commas(),
                 unit
             ),
-        ));
+        ];
+        if show_mode {
+            row.insert(1, mode.to_string());
+        }
+        parts.push(row);
     }
-    let part_lengths = (
-        parts
-            .iter()
-            .map(|p| p.0.len())
-            .max()
-            .expect("should be nonempty"),
-        parts
-            .iter()
-            .map(|p| p.1.len())
-            .max()
-            .expect("should be nonempty"),
-        parts
-            .iter()
-            .map(|p| p.2.len())
-            .max()
-            .expect("should be nonempty"),
-    );
-    for (i, part) in parts.into_iter().enumerate() {
-        message_lines.push(format!(
-            "{:3$} | {:4$} | {:5$} |",
-            part.0, part.1, part.2, part_lengths.0, part_lengths.1, part_lengths.2
-        ));
+    let part_lengths: Vec<usize> = (0..parts[0].len())
+        .map(|column| {
+            parts
+                .iter()
+                .map(|row| row[column].len())
+                .max()
+                .expect("should be nonempty")
+        })
+        .collect();
+    for (i, row) in parts.into_iter().enumerate() {
+        message_lines.push(
+            row.iter()
+                .zip(&part_lengths)
+                .map(|(part, &len)| format!("{part:len$} |"))
+                .join(" "),
+        );
         if i == 0 {
             message_lines.push(format!(
                 "{:-<1$}",
                 "",
-                part_lengths.0 + 3 + part_lengths.1 + 3 + part_lengths.2 + 2
+                part_lengths.iter().sum::<usize>() + 3 * part_lengths.len() - 1
             ));
         }
     }
@@ -0,0 +1,971 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    collections::BTreeSet,
+    future,
+    io::Write,
+    rc::Rc,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    task::Poll,
+    time::Duration,
+};
+
+use async_zip::{
+    tokio::write::ZipFileWriter,
+    Compression,
+    ZipEntryBuilder,
+};
+use bytes::Bytes;
+use common::{
+    components::{
+        ComponentId,
+        ComponentPath,
+    },
+    errors::JsError,
+    execution_context::RequestMetadata,
+    knobs::SNAPSHOT_IMPORT_MAX_COMPONENT_DEPTH,
+    object_validator,
+    schemas::{
+        validator::{
+            FieldValidator,
+            Validator,
+        },
+        SchemaEnforcementError,
+    },
+    virtual_system_mapping::VirtualSystemMapping,
+};
+use database::{
+    test_helpers::DbFixtures,
+    TableModel,
+};
+use errors::ErrorMetadataAnyhowExt;
+use flate2::{
+    write::GzEncoder,
+    Compression,
+};
+use futures::{
+    executor::block_on,
+    future::poll_fn,
+    stream,
+    StreamExt,
+    TryStreamExt,
+};
+use keybroker::Identity;
+use model::snapshot_imports::types::{
+    ImportFormat,
+    ImportMode,
+    ImportOptions,
+    ImportRequestor,
+};
+use runtime::testing::TestRuntime;
+use serde_json::{
+    json,
+    Value as JsonValue,
+};
+use storage::{
+    LocalDirStorage,
+    Storage,
+    StorageGetStream,
+    Upload,
+};
+use tokio::io::{
+    AsyncReadExt as _,
+    BufReader,
+};
+use usage_tracking::FunctionUsageTracker;
+use value::{
+    obj,
+    ConvexValue,
+    FieldName,
+    JsonPackedValue,
+    TableMapping,
+    TableName,
+    TableNamespace,
+    TableNumber,
+    TabletIdAndTableNumber,
+};
+
+use crate::snapshot_import::{
+    confirmation::ensure_tables_empty,
+    create_empty_table,
+    ensure_no_table_name_case_collisions,
+    finalize_import,
+    import_error::{
+        wrap_import_err,
+        ImportError,
+    },
+    parse::{
+        check_zip_entry_count,
+        gzip_decoder,
+        map_zip_io_error,
+        parse_component_path,
+        parse_csv_cell,
+        parse_csv_import,
+        parse_documents_jsonl,
+        parse_documents_jsonl_table_name,
+        parse_import_file,
+        parse_jsonl_gzip,
+        validate_zip_entry_name,
+        CsvColumnType,
+        ZipUncompressedBudget,
+    },
+    progress::{
+        ImportProgressEvent,
+        ImportProgressSink,
+        ImportTablePhase,
+        TableProgress,
+    },
+    retain_included_tables,
+    run_table_imports,
+    schema_constraints::schemas_for_import,
+    transform::transformed_object,
+    with_import_deadline,
+    with_schema_mismatch_row,
+    ImportBatchLimits,
+    ImportRowCounter,
+    TablesTableEntries,
+};
+
+#[test]
+fn test_tables_table_rejects_duplicate_names() -> anyhow::Result<()> {
+    let component_path = ComponentPath::root();
+    let mut entries = TablesTableEntries::default();
+    entries.add(
+        &component_path,
+        &"users".parse()?,
+        TableNumber::try_from(10001)?,
+    )?;
+    entries.add(
+        &component_path,
+        &"messages".parse()?,
+        TableNumber::try_from(10002)?,
+    )?;
+    let err = entries
+        .add(
+            &component_path,
+            &"users".parse()?,
+            TableNumber::try_from(10003)?,
+        )
+        .unwrap_err();
+    assert_eq!(err.short_msg(), "DuplicateTableDefinition");
+    assert!(err.msg().contains("`users`"), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn test_tables_table_rejects_duplicate_numbers() -> anyhow::Result<()> {
+    let component_path = ComponentPath::root();
+    let mut entries = TablesTableEntries::default();
+    entries.add(
+        &component_path,
+        &"users".parse()?,
+        TableNumber::try_from(10001)?,
+    )?;
+    let err = entries
+        .add(
+            &component_path,
+            &"messages".parse()?,
+            TableNumber::try_from(10001)?,
+        )
+        .unwrap_err();
+    assert_eq!(err.short_msg(), "DuplicateTableDefinition");
+    assert!(err.msg().contains("`users` and `messages`"), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn test_table_names_differing_by_case_collide() -> anyhow::Result<()> {
+    let root = ComponentPath::root();
+    let component: ComponentPath = "chat".parse()?;
+    let users: TableName = "users".parse()?;
+    let capitalized_users: TableName = "Users".parse()?;
+    let messages: TableName = "messages".parse()?;
+
+    let err = ensure_no_table_name_case_collisions([
+        (&root, &users),
+        (&root, &messages),
+        (&root, &capitalized_users),
+    ])
+    .unwrap_err();
+    assert_eq!(err.short_msg(), "TableNameCaseCollision");
+    assert!(err.msg().contains("`users` and `Users`"), "{err:?}");
+
+    // The same name can appear more than once, e.g. in `_tables` and as its
+    // own table, and tables in different components don't collide.
+    ensure_no_table_name_case_collisions([
+        (&root, &users),
+        (&root, &users),
+        (&component, &capitalized_users),
+    ])?;
+    Ok(())
+}
+
+#[test]
+fn test_component_path_too_deep() -> anyhow::Result<()> {
+    let max_depth = *SNAPSHOT_IMPORT_MAX_COMPONENT_DEPTH;
+    let at_limit = "_components/chat/".repeat(max_depth);
+    let component_path = parse_component_path(&at_limit, &ComponentPath::root())?;
+    assert_eq!(component_path.iter().count(), max_depth);
+
+    let too_deep = "_components/chat/".repeat(max_depth + 1);
+    let err = parse_component_path(&too_deep, &ComponentPath::root()).unwrap_err();
+    assert_eq!(err.short_msg(), "ComponentTooDeep");
+
+    // The component the import targets counts towards the depth.
+    let base: ComponentPath = "chat".parse()?;
+    let err = parse_component_path(&at_limit, &base).unwrap_err();
+    assert_eq!(err.short_msg(), "ComponentTooDeep");
+    Ok(())
+}
+
+#[test]
+fn test_transform_result_is_imported() -> anyhow::Result<()> {
+    let transformed = obj!("name" => "alice", "age" => 30.0)?;
+    let result = Ok(JsonPackedValue::pack(ConvexValue::Object(
+        transformed.clone(),
+    )));
+    assert_eq!(transformed_object(3, result)?, transformed);
+    Ok(())
+}
+
+#[test]
+fn test_transform_error_names_row() -> anyhow::Result<()> {
+    let result = Err(JsError::from_message("bad document".to_string()));
+    let err = wrap_import_err(transformed_object(7, result).unwrap_err());
+    assert_eq!(err.short_msg(), "TransformFailed");
+    assert!(err.msg().contains("row 7"), "{err:?}");
+    assert!(err.msg().contains("bad document"), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn test_transform_must_return_object() -> anyhow::Result<()> {
+    for value in [ConvexValue::Null, ConvexValue::from(true)] {
+        let result = Ok(JsonPackedValue::pack(value));
+        let err = wrap_import_err(transformed_object(2, result).unwrap_err());
+        assert_eq!(err.short_msg(), "TransformNotAnObject");
+        assert!(err.msg().contains("row 2"), "{err:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_resumed_import_writes_each_row_once() {
+    // The first attempt is killed partway through a table of 10 rows, after
+    // committing 8 of them along with their checkpoint and while reading the
+    // 9th.
+    let mut first_attempt = ImportRowCounter::new(0);
+    let mut written: Vec<u64> = (0..8).filter_map(|_| first_attempt.next_row()).collect();
+    let checkpoint = first_attempt.num_rows();
+    assert_eq!(first_attempt.next_row(), Some(9));
+
+    // The retry reads the whole file again and skips what was checkpointed.
+    let mut retry = ImportRowCounter::new(checkpoint);
+    written.extend((0..10).filter_map(|_| retry.next_row()));
+    assert_eq!(written, (1..=10).collect::<Vec<_>>());
+    assert_eq!(retry.num_rows(), 10);
+}
+
+async fn yield_now() {
+    let mut yielded = false;
+    poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await
+}
+
+#[test]
+fn test_table_imports_run_in_parallel() -> anyhow::Result<()> {
+    let running = Rc::new(Cell::new(0));
+    let max_running = Rc::new(Cell::new(0));
+    // Each table and how many other tables were being imported when it started.
+    let started = Rc::new(RefCell::new(vec![]));
+    let import_table = |table: u64| {
+        let (running, max_running, started) =
+            (running.clone(), max_running.clone(), started.clone());
+        async move {
+            started.borrow_mut().push((table, running.get()));
+            running.set(running.get() + 1);
+            max_running.set(max_running.get().max(running.get()));
+            for _ in 0..3 {
+                yield_now().await;
+            }
+            running.set(running.get() - 1);
+            anyhow::Ok(table * 100)
+        }
+    };
+    // Two tables in the import's table order, then ten others.
+    let sequential = (0..2).map(import_table).collect();
+    let parallel = (2..12).map(import_table).collect();
+
+    let total_num_documents = block_on(run_table_imports(sequential, parallel, 4))?;
+    assert_eq!(total_num_documents, (0..12).map(|table| table * 100).sum());
+    assert_eq!(max_running.get(), 4);
+    let started = started.borrow();
+    assert_eq!(started[..2], [(0, 0), (1, 0)]);
+    let mut parallel_tables: Vec<_> = started[2..].iter().map(|(table, _)| *table).collect();
+    parallel_tables.sort();
+    assert_eq!(parallel_tables, (2..12).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn test_table_import_error_fails_import() {
+    let import_table = |table: u64| async move {
+        anyhow::ensure!(table != 5, "table {table} failed");
+        Ok(table)
+    };
+    let parallel = (0..10).map(import_table).collect();
+    let err = block_on(run_table_imports(vec![], parallel, 4)).unwrap_err();
+    assert_eq!(err.to_string(), "table 5 failed");
+}
+
+fn gzip(data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+fn parse_gzipped_jsonl(data: &[u8]) -> anyhow::Result<Vec<JsonValue>> {
+    block_on(parse_jsonl_gzip(BufReader::new(gzip_decoder(data)), 1024).try_collect())
+}
+
+#[test]
+fn test_gzipped_jsonl_round_trips() -> anyhow::Result<()> {
+    let jsonl = "{\"a\": 1}\n{\"b\": \"two\"}\n{\"c\": [3]}";
+    let documents = parse_gzipped_jsonl(&gzip(jsonl.as_bytes())?)?;
+    assert_eq!(
+        documents,
+        vec![json!({"a": 1}), json!({"b": "two"}), json!({"c": [3]})]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_gzipped_jsonl_with_multiple_members() -> anyhow::Result<()> {
+    let mut data = gzip(b"{\"a\": 1}\n")?;
+    data.extend(gzip(b"{\"a\": 2}\n")?);
+    let documents = parse_gzipped_jsonl(&data)?;
+    assert_eq!(documents, vec![json!({"a": 1}), json!({"a": 2})]);
+    Ok(())
+}
+
+#[test]
+fn test_truncated_gzipped_jsonl_is_invalid_gzip() -> anyhow::Result<()> {
+    let data = gzip(b"{\"a\": 1}\n{\"a\": 2}\n")?;
+    let err = wrap_import_err(parse_gzipped_jsonl(&data[..data.len() - 4]).unwrap_err());
+    assert_eq!(err.short_msg(), "InvalidGzip");
+    Ok(())
+}
+
+#[test]
+fn test_gzipped_jsonl_line_longer_than_cap_is_rejected() -> anyhow::Result<()> {
+    // Each line is 9 bytes including its newline.
+    let data = gzip(b"{\"a\": 1}\n{\"a\": 2}\n")?;
+    let documents: Vec<JsonValue> =
+        block_on(parse_jsonl_gzip(BufReader::new(gzip_decoder(&data[..])), 9).try_collect())?;
+    assert_eq!(documents, vec![json!({"a": 1}), json!({"a": 2})]);
+    let result: anyhow::Result<Vec<JsonValue>> =
+        block_on(parse_jsonl_gzip(BufReader::new(gzip_decoder(&data[..])), 8).try_collect());
+    let err = wrap_import_err(result.unwrap_err());
+    assert_eq!(err.short_msg(), "InvalidZip");
+    Ok(())
+}
+
+#[test]
+fn test_zip_documents_entry_may_be_gzipped() -> anyhow::Result<()> {
+    let jsonl = b"{\"a\": 1}\n{\"a\": 2}\n";
+    let plain: Vec<JsonValue> = block_on(parse_documents_jsonl(&jsonl[..], 1024).try_collect())?;
+    let data = gzip(jsonl)?;
+    let gzipped: Vec<JsonValue> = block_on(parse_documents_jsonl(&data[..], 1024).try_collect())?;
+    assert_eq!(plain, vec![json!({"a": 1}), json!({"a": 2})]);
+    assert_eq!(gzipped, plain);
+    Ok(())
+}
+
+#[derive(Default)]
+struct CollectingSink {
+    events: Mutex<Vec<ImportProgressEvent>>,
+}
+
+impl ImportProgressSink for CollectingSink {
+    fn on_progress(&self, event: ImportProgressEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+/// Reports progress for a table the way `import_single_table` does, flushing
+/// every `batch_size` rows.
+fn write_table_with_progress(
+    sink: &dyn ImportProgressSink,
+    table_name: &TableName,
+    total_rows: u64,
+    num_to_skip: u64,
+    batch_size: u64,
+) {
+    let component_path = ComponentPath::root();
+    let progress = TableProgress::new(Some(sink), &component_path, table_name, Some(total_rows));
+    progress.report(ImportTablePhase::Started, num_to_skip);
+    let mut rows = ImportRowCounter::new(num_to_skip);
+    let mut batch_len = 0;
+    for _ in 0..total_rows {
+        let Some(row_number) = rows.next_row() else {
+            continue;
+        };
+        if batch_len == batch_size {
+            progress.report(ImportTablePhase::Writing, row_number - 1);
+            batch_len = 0;
+        }
+        batch_len += 1;
+    }
+    progress.report(ImportTablePhase::Finished, rows.num_rows());
+}
+
+#[test]
+fn test_progress_sink_receives_monotonic_events_per_table() -> anyhow::Result<()> {
+    let sink = CollectingSink::default();
+    let users: TableName = "users".parse()?;
+    let messages: TableName = "messages".parse()?;
+    write_table_with_progress(&sink, &users, 10, 0, 3);
+    // `messages` resumes after 4 rows were written before a restart.
+    write_table_with_progress(&sink, &messages, 7, 4, 2);
+
+    let events = sink.events.into_inner().unwrap();
+    for (table_name, total_rows, num_to_skip) in [(&users, 10, 0), (&messages, 7, 4)] {
+        let table_events: Vec<_> = events
+            .iter()
+            .filter(|event| event.table_name == *table_name)
+            .collect();
+        assert!(table_events.len() > 2, "{table_events:?}");
+        assert_eq!(
+            table_events.first().map(|e| (e.phase, e.rows_written)),
+            Some((ImportTablePhase::Started, num_to_skip))
+        );
+        assert_eq!(
+            table_events.last().map(|e| (e.phase, e.rows_written)),
+            Some((ImportTablePhase::Finished, total_rows))
+        );
+        assert!(table_events[1..table_events.len() - 1]
+            .iter()
+            .all(|e| e.phase == ImportTablePhase::Writing));
+        assert!(
+            table_events
+                .windows(2)
+                .all(|pair| pair[0].rows_written <= pair[1].rows_written),
+            "{table_events:?}"
+        );
+        assert!(table_events
+            .iter()
+            .all(|e| e.total_rows == Some(total_rows) && e.component_path.is_root()));
+    }
+    Ok(())
+}
+
+fn parse_csv(csv: impl Into<Bytes>, string_columns: &[&str]) -> anyhow::Result<Vec<JsonValue>> {
+    let csv = csv.into();
+    let reader = StorageGetStream {
+        content_length: csv.len() as i64,
+        stream: stream::once(async move { Ok(csv) }).boxed(),
+    };
+    let string_columns = string_columns
+        .iter()
+        .map(|column| column.parse())
+        .collect::<anyhow::Result<BTreeSet<FieldName>>>()?;
+    block_on(parse_csv_import(reader, string_columns).try_collect())
+}
+
+#[test]
+fn test_csv_cell_leading_zeros() {
+    assert_eq!(
+        parse_csv_cell("01234", CsvColumnType::Inferred),
+        json!(1234.0)
+    );
+    assert_eq!(
+        parse_csv_cell("01234", CsvColumnType::String),
+        json!("01234")
+    );
+    assert_eq!(parse_csv_cell("1e3", CsvColumnType::String), json!("1e3"));
+}
+
+#[test]
+fn test_csv_string_columns_keep_leading_zeros() -> anyhow::Result<()> {
+    let csv = "zip,count\n01234,007\n98765,12\n";
+    assert_eq!(
+        parse_csv(csv, &["zip"])?,
+        vec![
+            json!({"zip": "01234", "count": 7.0}),
+            json!({"zip": "98765", "count": 12.0}),
+        ]
+    );
+    assert_eq!(
+        parse_csv(csv, &[])?,
+        vec![
+            json!({"zip": 1234.0, "count": 7.0}),
+            json!({"zip": 98765.0, "count": 12.0}),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_csv_string_column_must_be_in_header() -> anyhow::Result<()> {
+    let err = wrap_import_err(parse_csv("zip,count\n01234,7\n", &["phone"]).unwrap_err());
+    assert_eq!(err.short_msg(), "CsvStringColumnNotInHeader");
+    Ok(())
+}
+
+#[test]
+fn test_schema_mismatch_reports_row_field_and_expected_validator() -> anyhow::Result<()> {
+    let validator = Validator::Object(object_validator!(
+        "name" => FieldValidator::required_field_type(Validator::String),
+        "count" => FieldValidator {
+            validator: Validator::Float64,
+            optional: true,
+        },
+    ));
+    let table_mapping = TableMapping::new();
+    let check = |object| {
+        validator.check_value(
+            &ConvexValue::Object(object),
+            &table_mapping.namespace(TableNamespace::Global),
+            &VirtualSystemMapping::default(),
+        )
+    };
+    assert!(check(obj!("name" => "crate")?).is_ok());
+    assert!(check(obj!("name" => "crate", "count" => 12.0)?).is_ok());
+
+    // A CSV cell that isn't a number is imported as a string.
+    let validation_error = check(obj!("name" => "crate", "count" => "12 apples")?).unwrap_err();
+    let schema_error = SchemaEnforcementError::Document {
+        validation_error,
+        table_name: "orders".parse()?,
+    };
+    // Schema enforcement keeps the structured error beneath its metadata.
+    let error = anyhow::Error::new(schema_error.clone()).context(schema_error.to_error_metadata());
+
+    let error = with_schema_mismatch_row(7, error);
+    let Some(ImportError::SchemaMismatch {
+        row_number,
+        field,
+        expected,
+        ..
+    }) = error.downcast_ref::<ImportError>()
+    else {
+        panic!("Expected a SchemaMismatch error, got {error:#}");
+    };
+    assert_eq!(*row_number, 7);
+    assert_eq!(field.as_deref(), Some("count"));
+    assert_eq!(expected, "v.float64()");
+    assert!(error
+        .to_string()
+        .starts_with("Row 7 doesn't match the schema at field `count`: expected v.float64()."));
+    Ok(())
+}
+
+#[test]
+fn test_schema_mismatch_leaves_other_errors_unchanged() {
+    let error = with_schema_mismatch_row(7, anyhow::anyhow!("Transaction conflict"));
+    assert!(error.downcast_ref::<ImportError>().is_none());
+    assert_eq!(error.to_string(), "Transaction conflict");
+}
+
+#[test]
+fn test_csv_export_round_trips_through_import() -> anyhow::Result<()> {
+    let exported = json!({
+        "text": "hello, \"world\"\nagain",
+        "number": 1.5,
+        "integer": 42.0,
+        "leadingZeros": "01234",
+        "exponent": "1e3",
+        "flag": true,
+        "flagText": "false",
+        "missing": null,
+    });
+    let JsonValue::Object(exported) = exported else {
+        anyhow::bail!("not an object");
+    };
+    let (header, row): (Vec<_>, Vec<_>) = exported
+        .into_iter()
+        .map(|(field, value)| (field, exports::csv_cell(value)))
+        .unzip();
+    let mut csv = Vec::new();
+    block_on(async {
+        let mut writer = csv_async::AsyncWriter::from_writer(&mut csv);
+        writer.write_record(&header).await?;
+        writer.write_record(&row).await?;
+        writer.flush().await?;
+        anyhow::Ok(())
+    })?;
+
+    assert_eq!(
+        parse_csv(csv.clone(), &[])?,
+        vec![json!({
+            "text": "hello, \"world\"\nagain",
+            "number": 1.5,
+            "integer": 42.0,
+            // Strings that parse as numbers import as numbers...
+            "leadingZeros": 1234.0,
+            "exponent": 1000.0,
+            // ...and CSV import has no booleans or nulls.
+            "flag": "true",
+            "flagText": "false",
+            "missing": "",
+        })]
+    );
+    // ...unless their columns are imported as strings.
+    let imported = parse_csv(csv, &["leadingZeros", "exponent"])?;
+    assert_eq!(imported[0]["leadingZeros"], json!("01234"));
+    assert_eq!(imported[0]["exponent"], json!("1e3"));
+    Ok(())
+}
+
+#[test]
+fn test_require_empty_names_every_nonempty_table() -> anyhow::Result<()> {
+    let messages = (ComponentPath::root(), "messages".parse()?);
+    let users = (ComponentPath::root(), "users".parse()?);
+    let waitlist_users = ("waitlist".parse()?, "users".parse()?);
+    ensure_tables_empty(&[])?;
+
+    let err = wrap_import_err(ensure_tables_empty(&[&messages]).unwrap_err());
+    assert_eq!(err.short_msg(), "TableExists");
+
+    let err = wrap_import_err(ensure_tables_empty(&[&messages, &users]).unwrap_err());
+    assert_eq!(err.short_msg(), "TablesExist");
+    assert!(
+        err.msg().contains("Tables messages, users already exist"),
+        "{}",
+        err.msg()
+    );
+
+    let err = wrap_import_err(ensure_tables_empty(&[&users, &waitlist_users]).unwrap_err());
+    assert!(
+        err.msg()
+            .contains("Tables users, users in 'waitlist' already exist"),
+        "{}",
+        err.msg()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_zip_entry_names_with_traversal_are_rejected() {
+    for name in [
+        "../users/documents.jsonl",
+        "/users/documents.jsonl",
+        "\\users\\documents.jsonl",
+        "_components/../users/documents.jsonl",
+        "_components/waitlist/../users/documents.jsonl",
+        "../_components/waitlist/users/documents.jsonl",
+        "users\\..\\messages/documents.jsonl",
+        "_storage/../../etc/passwd",
+        "users/..",
+    ] {
+        let err = wrap_import_err(validate_zip_entry_name(name).unwrap_err());
+        assert_eq!(err.short_msg(), "InvalidZip", "{name}");
+    }
+}
+
+#[test]
+fn test_zip_entry_names_from_exports_are_accepted() -> anyhow::Result<()> {
+    for name in [
+        "README.md",
+        "users/documents.jsonl",
+        "users/generated_schema.jsonl",
+        "_components/waitlist/users/documents.jsonl",
+        "_storage/kg2ah5c4zjaw1p4wnmz8ntndsh6t2wkx.png",
+        "users/..documents.jsonl",
+        "...users/documents.jsonl",
+    ] {
+        validate_zip_entry_name(name)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_zip_entry_name_traversal_would_parse_into_another_component() -> anyhow::Result<()> {
+    // Without validation, the patterns would quietly file this under the
+    // root's `users` table rather than anything in `waitlist`.
+    let name = "_components/waitlist/../users/documents.jsonl";
+    let parsed = parse_documents_jsonl_table_name(name, &ComponentPath::root())?;
+    assert_eq!(
+        parsed,
+        Some((ComponentPath::root(), "users".parse::<TableName>()?))
+    );
+    assert!(validate_zip_entry_name(name).is_err());
+    Ok(())
+}
+
+fn read_entry(mut reader: impl tokio::io::AsyncRead + Unpin) -> anyhow::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    block_on(reader.read_to_end(&mut contents)).map_err(map_zip_io_error)?;
+    Ok(contents)
+}
+
+#[test]
+fn test_zip_bomb_entry_fails_once_over_budget() {
+    // An entry that would decompress forever.
+    let budget = ZipUncompressedBudget::new(1 << 20);
+    let err = wrap_import_err(read_entry(budget.wrap(tokio::io::repeat(b' '))).unwrap_err());
+    assert_eq!(err.short_msg(), "InvalidZip");
+    assert!(err.msg().contains("1048576 bytes"), "{}", err.msg());
+}
+
+#[test]
+fn test_zip_budget_is_shared_across_entries() -> anyhow::Result<()> {
+    let budget = ZipUncompressedBudget::new(10);
+    assert_eq!(read_entry(budget.wrap(&b"abcdef"[..]))?, b"abcdef");
+    let err = wrap_import_err(read_entry(budget.wrap(&b"ghijkl"[..])).unwrap_err());
+    assert_eq!(err.short_msg(), "InvalidZip");
+    Ok(())
+}
+
+#[test]
+fn test_zip_with_too_many_entries_is_rejected() -> anyhow::Result<()> {
+    check_zip_entry_count(3, 3)?;
+    let err = wrap_import_err(check_zip_entry_count(4, 3).unwrap_err());
+    assert_eq!(err.short_msg(), "InvalidZip");
+    assert!(err.msg().contains("4 entries"), "{}", err.msg());
+    Ok(())
+}
+
+#[test]
+fn test_oversize_buffered_zip_entry_is_rejected() -> anyhow::Result<()> {
+    let budget = ZipUncompressedBudget::new(1 << 20);
+    assert_eq!(read_entry(budget.wrap_buffered(&b"abcd"[..], 4))?, b"abcd");
+    let err = wrap_import_err(read_entry(budget.wrap_buffered(&b"abcde"[..], 4)).unwrap_err());
+    assert_eq!(err.short_msg(), "InvalidZip");
+    // Streamed entries are only limited by the budget.
+    assert_eq!(read_entry(budget.wrap(&b"abcde"[..]))?, b"abcde");
+    Ok(())
+}
+
+#[test]
+fn test_zip_documents_line_longer_than_cap_is_rejected() -> anyhow::Result<()> {
+    // Each line is 9 bytes including its newline.
+    let jsonl = b"{\"a\": 1}\n{\"a\": 2}\n";
+    let documents: Vec<JsonValue> = block_on(parse_documents_jsonl(&jsonl[..], 9).try_collect())?;
+    assert_eq!(documents, vec![json!({"a": 1}), json!({"a": 2})]);
+    let result: anyhow::Result<Vec<JsonValue>> =
+        block_on(parse_documents_jsonl(&jsonl[..], 8).try_collect());
+    let err = wrap_import_err(result.unwrap_err());
+    assert_eq!(err.short_msg(), "InvalidZip");
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_finalize_import_activates_tables_across_batches(
+    rt: TestRuntime,
+) -> anyhow::Result<()> {
+    let DbFixtures { db, .. } = DbFixtures::new_with_model(&rt).await?;
+    let identity = Identity::system();
+    let namespace = TableNamespace::root_component();
+    let existing_table: TableName = "users".parse()?;
+    let mut tx = db.begin_system().await?;
+    TableModel::new(&mut tx)
+        .insert_table_metadata(namespace, &existing_table)
+        .await?;
+    db.commit_with_write_source(tx, "test").await?;
+
+    // Five tables with a batch size of two are activated in three batches, and
+    // the first batch replaces the existing `users` table.
+    let mut imported_tables = TableMapping::new();
+    for name in ["users", "messages", "channels", "reactions", "threads"] {
+        let table_name: TableName = name.parse()?;
+        let TabletIdAndTableNumber {
+            tablet_id,
+            table_number,
+        } = create_empty_table(
+            &db,
+            &identity,
+            ComponentId::Root,
+            &table_name,
+            None,
+            None,
+            &table_name,
+            &ComponentPath::root(),
+        )
+        .await?;
+        imported_tables.insert(tablet_id, namespace, table_number, table_name);
+    }
+    let mut tx = db.begin_system().await?;
+    let initial_schemas = schemas_for_import(&mut tx).await?;
+    let (_ts, documents_deleted) = finalize_import(
+        &db,
+        identity,
+        None,
+        RequestMetadata::system(),
+        initial_schemas,
+        ImportMode::Replace,
+        imported_tables.clone(),
+        None,
+        None,
+        ImportRequestor::SnapshotImport,
+        FunctionUsageTracker::new(),
+        None,
+        2,
+    )
+    .await?;
+    assert_eq!(documents_deleted, 0);
+
+    let mut tx = db.begin_system().await?;
+    let table_mapping = tx.table_mapping().namespace(namespace);
+    for (tablet_id, _namespace, table_number, table_name) in imported_tables.iter() {
+        assert_eq!(
+            table_mapping.id(table_name)?,
+            TabletIdAndTableNumber {
+                tablet_id,
+                table_number,
+            },
+        );
+    }
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_import_deadline_names_timed_out_phase(rt: TestRuntime) -> anyhow::Result<()> {
+    let deadline = Some(rt.monotonic_now() + Duration::from_secs(1));
+    // An import worker that never finishes.
+    let err = with_import_deadline(
+        &rt,
+        deadline,
+        "waiting for the import worker",
+        future::pending::<anyhow::Result<()>>(),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.short_msg(), "ImportTimedOut");
+    assert!(err.msg().contains("waiting for the import worker"), "{err}");
+
+    // Without a deadline, the phase runs to completion.
+    let result = with_import_deadline(&rt, None, "uploading", async { Ok(1) }).await?;
+    assert_eq!(result, 1);
+    Ok(())
+}
+
+/// Builds a ZIP import with a `documents.jsonl` entry for each table.
+async fn zip_import(tables: &[(&str, &[JsonValue])]) -> anyhow::Result<Bytes> {
+    let mut out = vec![];
+    let mut writer = ZipFileWriter::with_tokio(&mut out);
+    for (table_name, documents) in tables {
+        let mut jsonl = String::new();
+        for document in *documents {
+            jsonl.push_str(&document.to_string());
+            jsonl.push('\n');
+        }
+        let builder = ZipEntryBuilder::new(
+            format!("{table_name}/documents.jsonl").into(),
+            Compression::Deflate,
+        );
+        writer.write_entry_whole(builder, jsonl.as_bytes()).await?;
+    }
+    writer.close().await?;
+    Ok(out.into())
+}
+
+#[convex_macro::test_runtime]
+async fn test_import_single_table_from_zip(rt: TestRuntime) -> anyhow::Result<()> {
+    let storage: Arc<dyn Storage> = Arc::new(LocalDirStorage::new(rt)?);
+    let users = [json!({"name": "sarah"}), json!({"name": "lee"})];
+    let messages = [json!({"body": "hello"})];
+    let mut upload = storage.start_upload().await?;
+    upload
+        .write(zip_import(&[("users", &users), ("messages", &messages)]).await?)
+        .await?;
+    let object_key = storage.fully_qualified_key(&upload.complete().await?);
+
+    let mut import = parse_import_file(
+        ImportFormat::Zip,
+        ComponentPath::root(),
+        storage,
+        object_key,
+        None,
+        None,
+        None,
+        &BTreeSet::new(),
+    )
+    .await?;
+    let users_table: TableName = "users".parse()?;
+    let include_tables = BTreeSet::from([(ComponentPath::root(), users_table.clone())]);
+    retain_included_tables(&mut import.documents, &include_tables)?;
+
+    // Only `users` is left, and skipping `messages` doesn't affect its rows.
+    assert_eq!(import.documents.len(), 1);
+    let (component_path, table_name, documents) = import.documents.remove(0);
+    assert_eq!(component_path, ComponentPath::root());
+    assert_eq!(table_name, users_table);
+    let documents: Vec<JsonValue> = documents.try_collect().await?;
+    assert_eq!(documents, users);
+    Ok(())
+}
+
+#[test]
+fn test_include_tables_must_be_in_import() -> anyhow::Result<()> {
+    let mut documents = vec![(
+        ComponentPath::root(),
+        "users".parse::<TableName>()?,
+        stream::empty::<anyhow::Result<JsonValue>>().boxed(),
+    )];
+    let include_tables = BTreeSet::from([(ComponentPath::root(), "messages".parse()?)]);
+    let err = retain_included_tables(&mut documents, &include_tables).unwrap_err();
+    assert_eq!(err.short_msg(), "InvalidIncludeTables");
+    Ok(())
+}
+
+/// Counts the transactions `import_single_table` writes for documents of the
+/// given sizes.
+fn num_batches(limits: &ImportBatchLimits, document_sizes: &[usize]) -> usize {
+    let mut num_batches = 0;
+    let mut num_documents = 0;
+    let mut size_bytes = 0;
+    for &next_size_bytes in document_sizes {
+        if limits.should_flush(num_documents, size_bytes, next_size_bytes) {
+            num_batches += 1;
+            num_documents = 0;
+            size_bytes = 0;
+        }
+        num_documents += 1;
+        size_bytes += next_size_bytes;
+    }
+    if num_documents > 0 {
+        num_batches += 1;
+    }
+    num_batches
+}
+
+#[test]
+fn test_custom_import_batch_size_overrides_default() -> anyhow::Result<()> {
+    let document_sizes = [100; 10];
+    // The default thresholds fit every document in one transaction.
+    let limits = ImportBatchLimits::new(&ImportOptions::default())?;
+    assert_eq!(num_batches(&limits, &document_sizes), 1);
+
+    let limits = ImportBatchLimits::new(&ImportOptions {
+        batch_max_num_documents: Some(3),
+        ..Default::default()
+    })?;
+    assert_eq!(num_batches(&limits, &document_sizes), 4);
+
+    let limits = ImportBatchLimits::new(&ImportOptions {
+        batch_max_size_bytes: Some(250),
+        ..Default::default()
+    })?;
+    assert_eq!(num_batches(&limits, &document_sizes), 5);
+
+    let err = ImportBatchLimits::new(&ImportOptions {
+        batch_max_num_documents: Some(0),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert_eq!(err.short_msg(), "InvalidImportBatchSize");
+    Ok(())
+}
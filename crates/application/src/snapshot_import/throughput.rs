@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use common::knobs::SNAPSHOT_IMPORT_DEFAULT_DOCUMENTS_PER_SECOND;
+
+/// Weight given to the most recent import in the rolling average.
+const SMOOTHING: f64 = 0.3;
+
+/// A rolling average of how quickly this backend writes imported documents,
+/// used to estimate how long an import will take before it's confirmed.
+#[derive(Debug, Default)]
+pub struct ImportThroughput {
+    documents_per_second: Option<f64>,
+}
+
+impl ImportThroughput {
+    pub fn record(&mut self, num_documents: u64, elapsed: Duration) {
+        if num_documents == 0 || elapsed.is_zero() {
+            return;
+        }
+        let latest = num_documents as f64 / elapsed.as_secs_f64();
+        self.documents_per_second = Some(match self.documents_per_second {
+            Some(average) => SMOOTHING * latest + (1. - SMOOTHING) * average,
+            None => latest,
+        });
+    }
+
+    /// A coarse estimate of how long writing `num_documents` will take. Until
+    /// an import has completed on this backend, this assumes
+    /// [`SNAPSHOT_IMPORT_DEFAULT_DOCUMENTS_PER_SECOND`].
+    pub fn estimate(&self, num_documents: u64) -> Duration {
+        let documents_per_second = self
+            .documents_per_second
+            .unwrap_or(*SNAPSHOT_IMPORT_DEFAULT_DOCUMENTS_PER_SECOND as f64);
+        Duration::from_secs_f64(num_documents as f64 / documents_per_second)
+    }
+}
+
+/// Renders an estimated import duration for the confirmation message, e.g.
+/// "about 4 minutes".
+pub fn describe_estimate(estimate: Duration) -> String {
+    let minutes = estimate.as_secs().div_ceil(60);
+    match minutes {
+        0 | 1 => "less than a minute".to_string(),
+        2..120 => format!("about {minutes} minutes"),
+        _ => format!("about {} hours", minutes.div_ceil(60)),
+    }
+}
@@ -0,0 +1,115 @@
+use common::{
+    components::{
+        CanonicalizedComponentFunctionPath,
+        ComponentPath,
+    },
+    errors::JsError,
+    execution_context::RequestContext,
+    knobs::SNAPSHOT_IMPORT_TRANSFORM_TIMEOUT,
+    runtime::Runtime,
+    types::FunctionCaller,
+    RequestId,
+};
+use database::Database;
+use futures::{
+    select_biased,
+    FutureExt,
+};
+use keybroker::Identity;
+use sync_types::{
+    CanonicalizedUdfPath,
+    SerializedArgs,
+};
+use value::{
+    ConvexObject,
+    ConvexValue,
+    JsonPackedValue,
+};
+
+use crate::{
+    application_function_runner::ApplicationFunctionRunner,
+    snapshot_import::import_error::ImportError,
+};
+
+/// A query that each imported document is passed through before it's written.
+///
+/// The query is called with the document as its only argument, in its own
+/// read-only transaction, and the object it returns is imported instead.
+pub struct ImportTransform<'a, RT: Runtime> {
+    runtime: &'a RT,
+    database: &'a Database<RT>,
+    runner: &'a ApplicationFunctionRunner<RT>,
+    path: CanonicalizedComponentFunctionPath,
+}
+
+impl<'a, RT: Runtime> ImportTransform<'a, RT> {
+    pub fn new(
+        runtime: &'a RT,
+        database: &'a Database<RT>,
+        runner: &'a ApplicationFunctionRunner<RT>,
+        component: ComponentPath,
+        udf_path: CanonicalizedUdfPath,
+    ) -> Self {
+        Self {
+            runtime,
+            database,
+            runner,
+            path: CanonicalizedComponentFunctionPath {
+                component,
+                udf_path,
+            },
+        }
+    }
+
+    /// Runs the transform on the document at `row_number`, failing the import
+    /// if it throws, doesn't return an object, or takes longer than
+    /// [SNAPSHOT_IMPORT_TRANSFORM_TIMEOUT].
+    pub async fn apply(
+        &self,
+        identity: &Identity,
+        row_number: u64,
+        object: ConvexObject,
+    ) -> anyhow::Result<ConvexObject> {
+        let timeout = *SNAPSHOT_IMPORT_TRANSFORM_TIMEOUT;
+        let result = select_biased! {
+            result = self.call(identity, object).fuse() => result?,
+            _ = self.runtime.wait(timeout) => {
+                anyhow::bail!(ImportError::TransformTimedOut(row_number, timeout))
+            },
+        };
+        transformed_object(row_number, result)
+    }
+
+    async fn call(
+        &self,
+        identity: &Identity,
+        object: ConvexObject,
+    ) -> anyhow::Result<Result<JsonPackedValue, JsError>> {
+        let tx = self.database.begin(identity.clone()).await?;
+        let arguments = SerializedArgs::from_args(vec![object.into()])?;
+        let (result, _log_lines) = self
+            .runner
+            .run_query_without_caching(
+                RequestContext::new_for_system_request(RequestId::new()),
+                tx,
+                self.path.clone(),
+                arguments,
+                FunctionCaller::SnapshotImport,
+            )
+            .await?;
+        Ok(result)
+    }
+}
+
+/// The object to import for the document at `row_number`, given what the
+/// transform returned for it.
+pub fn transformed_object(
+    row_number: u64,
+    result: Result<JsonPackedValue, JsError>,
+) -> anyhow::Result<ConvexObject> {
+    let value = result.map_err(|e| ImportError::TransformFailed(row_number, e.message))?;
+    let ConvexValue::Object(object) = value.unpack()? else {
+        anyhow::bail!(ImportError::TransformNotAnObject(row_number));
+    };
+    Ok(object)
+}
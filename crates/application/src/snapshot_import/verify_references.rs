@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+
+use common::{
+    runtime::Runtime,
+    schemas::{
+        validator::Validator,
+        DocumentSchema,
+    },
+    types::TableName,
+};
+use database::{
+    Database,
+    IndexModel,
+};
+use errors::ErrorMetadata;
+use futures::{
+    pin_mut,
+    TryStreamExt,
+};
+use value::{
+    ConvexValue,
+    DeveloperDocumentId,
+    ResolvedDocumentId,
+    TableMapping,
+    TableNamespace,
+};
+
+use super::schema_constraints::SchemasForImport;
+
+/// Number of referenced documents to look up per transaction.
+const LOOKUP_BATCH_SIZE: usize = 1000;
+
+/// Dangling references to include in the error message.
+const MAX_REPORTED_DANGLING_REFERENCES: usize = 5;
+
+/// Checks that every `v.id()` reference in the imported tables points to a
+/// document that will exist once the import is finalized.
+///
+/// Schema enforcement during insertion only checks that an id names the right
+/// table, so tables that reference each other can be imported in any order.
+/// This pass runs once every table has been written, and fails the import if
+/// any reference is still dangling. References are found by walking documents
+/// alongside their table's schema, so tables without a schema aren't checked.
+pub async fn verify_references<RT: Runtime>(
+    database: &Database<RT>,
+    initial_schemas: &SchemasForImport,
+    table_mapping_in_import: &TableMapping,
+    table_mapping_for_schema: &TableMapping,
+) -> anyhow::Result<()> {
+    let ts = database.now_ts_for_reads();
+    let by_id_indexes = {
+        let mut tx = database.begin_system().await?;
+        IndexModel::new(&mut tx).by_id_indexes().await?
+    };
+
+    // Each referenced document, along with the first table referencing it.
+    let mut references: BTreeMap<(TableNamespace, DeveloperDocumentId), TableName> =
+        BTreeMap::new();
+    for (tablet_id, namespace, _, table_name) in table_mapping_in_import.iter() {
+        let validators: Vec<_> = initial_schemas
+            .iter()
+            .filter(|(schema_namespace, ..)| *schema_namespace == namespace)
+            .filter_map(|(_, _, (_, schema))| schema.tables.get(table_name))
+            .filter_map(|table_schema| match &table_schema.document_type {
+                None | Some(DocumentSchema::Any) => None,
+                Some(DocumentSchema::Union(validators)) => Some(validators.clone()),
+            })
+            .flatten()
+            .map(Validator::Object)
+            .filter(|validator| validator.foreign_keys().next().is_some())
+            .collect();
+        if validators.is_empty() {
+            continue;
+        }
+        let by_id = *by_id_indexes
+            .get(&tablet_id)
+            .ok_or_else(|| anyhow::anyhow!("Failed to find id index for table id {tablet_id}"))?;
+        let mapping = table_mapping_for_schema.namespace(namespace);
+        let stream = database
+            .table_iterator(ts, 1000)
+            .stream_documents_in_table(tablet_id, by_id, None);
+        pin_mut!(stream);
+        while let Some(doc) = stream.try_next().await? {
+            let value = ConvexValue::Object(doc.value.into_value().0);
+            let mut referenced_ids = vec![];
+            for validator in &validators {
+                collect_referenced_ids(validator, &value, &mut referenced_ids);
+            }
+            for (referenced_table, id) in referenced_ids {
+                // System tables (e.g. `_storage`) are addressed by virtual ids.
+                if referenced_table.is_system()
+                    || mapping
+                        .id_and_number_if_exists(&referenced_table)
+                        .is_some_and(|table| table.table_number != id.table())
+                {
+                    continue;
+                }
+                references
+                    .entry((namespace, id))
+                    .or_insert_with(|| table_name.clone());
+            }
+        }
+    }
+
+    let mut num_dangling = 0;
+    let mut reported = vec![];
+    let references: Vec<_> = references.into_iter().collect();
+    for batch in references.chunks(LOOKUP_BATCH_SIZE) {
+        let mut tx = database.begin_system().await?;
+        for ((namespace, id), referencing_table) in batch {
+            let exists = match table_mapping_for_schema
+                .namespace(*namespace)
+                .number_to_tablet()(id.table())
+            {
+                Ok(tablet_id) => tx
+                    .get(ResolvedDocumentId::new(tablet_id, *id))
+                    .await?
+                    .is_some(),
+                Err(_) => false,
+            };
+            if !exists {
+                num_dangling += 1;
+                if reported.len() < MAX_REPORTED_DANGLING_REFERENCES {
+                    reported.push(format!("{id} (referenced from '{referencing_table}')"));
+                }
+            }
+        }
+    }
+    anyhow::ensure!(
+        num_dangling == 0,
+        ErrorMetadata::bad_request(
+            "DanglingReferences",
+            format!(
+                "Import has {num_dangling} references to documents that don't exist, including: {}",
+                reported.join(", ")
+            ),
+        )
+    );
+    Ok(())
+}
+
+/// Appends the ids in `value` that `validator` expects to be `v.id()`s, along
+/// with the table each one should reference. Ids that don't decode are left to
+/// schema enforcement.
+fn collect_referenced_ids(
+    validator: &Validator,
+    value: &ConvexValue,
+    referenced_ids: &mut Vec<(TableName, DeveloperDocumentId)>,
+) {
+    match validator {
+        Validator::Id(table_name) => {
+            if let ConvexValue::String(s) = value
+                && let Ok(id) = DeveloperDocumentId::decode(s)
+            {
+                referenced_ids.push((table_name.clone(), id));
+            }
+        },
+        Validator::Array(item_validator) => {
+            if let ConvexValue::Array(items) = value {
+                for item in items {
+                    collect_referenced_ids(item_validator, item, referenced_ids);
+                }
+            }
+        },
+        Validator::Record(key_validator, value_validator) => {
+            if let ConvexValue::Object(object) = value {
+                for (key, value) in object.iter() {
+                    if let Validator::Id(table_name) = &**key_validator
+                        && let Ok(id) = DeveloperDocumentId::decode(key)
+                    {
+                        referenced_ids.push((table_name.clone(), id));
+                    }
+                    collect_referenced_ids(value_validator, value, referenced_ids);
+                }
+            }
+        },
+        Validator::Object(object_validator) => {
+            if let ConvexValue::Object(object) = value {
+                for (field_name, field_validator) in &object_validator.0 {
+                    if let Some(value) = object.get::<str>(field_name) {
+                        collect_referenced_ids(field_validator.validator(), value, referenced_ids);
+                    }
+                }
+            }
+        },
+        // Every option is walked; ids collected from an option that doesn't
+        // match are filtered out by their table number.
+        Validator::Union(options) => {
+            for option in options {
+                collect_referenced_ids(option, value, referenced_ids);
+            }
+        },
+        Validator::Null
+        | Validator::Float64
+        | Validator::Int64
+        | Validator::CommitTs
+        | Validator::Boolean
+        | Validator::String
+        | Validator::Bytes
+        | Validator::Literal(_)
+        | Validator::Any => {},
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     future::Future,
     sync::Arc,
     time::Duration,
@@ -8,6 +9,7 @@ use common::{
     backoff::Backoff,
     errors::report_error,
     runtime::Runtime,
+    types::EnvVarName,
 };
 use database::{
     Database,
@@ -23,12 +25,15 @@ use storage::Storage;
 use usage_tracking::UsageCounter;
 
 use crate::{
+    application_function_runner::ApplicationFunctionRunner,
     metrics::log_worker_starting,
     snapshot_import::{
         metrics::{
             log_snapshot_import_failed,
             snapshot_import_timer,
         },
+        throughput::ImportThroughput,
+        ImportProgressSink,
         SnapshotImportExecutor,
     },
 };
@@ -45,6 +50,9 @@ impl SnapshotImportWorker {
         snapshot_imports_storage: Arc<dyn Storage>,
         file_storage: FileStorage<RT>,
         usage_tracking: UsageCounter,
+        runner: Arc<ApplicationFunctionRunner<RT>>,
+        system_env_var_names: HashSet<EnvVarName>,
+        progress_sink: Option<Arc<dyn ImportProgressSink>>,
     ) -> impl Future<Output = ()> + Send {
         let mut worker = SnapshotImportExecutor {
             runtime,
@@ -52,7 +60,11 @@ impl SnapshotImportWorker {
             snapshot_imports_storage,
             file_storage,
             usage_tracking,
+            runner,
+            system_env_var_names,
             backoff: Backoff::new(INITIAL_BACKOFF, MAX_BACKOFF),
+            throughput: ImportThroughput::default(),
+            progress_sink,
         };
         async move {
             loop {
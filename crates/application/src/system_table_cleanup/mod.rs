@@ -63,7 +63,10 @@ use metrics::{
 };
 use model::{
     exports::ExportsModel,
-    modules::ModuleModel,
+    modules::{
+        types::ModuleMetadata,
+        ModuleModel,
+    },
     session_requests::SESSION_REQUESTS_TABLE,
     source_packages::{
         types::SourcePackageId,
@@ -478,13 +481,11 @@ impl<RT: Runtime> SystemTableCleanupWorker<RT> {
             .table_mapping()
             .namespaces_for_name(&SourcePackagesTable::TABLE_NAME)
         {
-            let mut source_package_ids: BTreeSet<SourcePackageId> = BTreeSet::new();
-            for module in ModuleModel::new(&mut tx)
-                .get_all_metadata(namespace.into())
-                .await?
-            {
-                source_package_ids.insert(module.source_package_id);
-            }
+            let modules = ModuleModel::new(&mut tx)
+                .get_all_metadata_including_deleted(namespace.into())
+                .await?;
+            let source_package_ids =
+                source_packages_in_use(modules.iter().map(|module| &***module));
             for source_package in tx
                 .query_system(namespace, &SystemIndex::<SourcePackagesTable>::by_id())?
                 .all()
@@ -512,6 +513,17 @@ impl<RT: Runtime> SystemTableCleanupWorker<RT> {
     }
 }
 
+/// The source packages `modules` point at. Deleted modules still count, since
+/// restoring one brings back the source package it was last pushed in.
+fn source_packages_in_use<'a>(
+    modules: impl IntoIterator<Item = &'a ModuleMetadata>,
+) -> BTreeSet<SourcePackageId> {
+    modules
+        .into_iter()
+        .map(|module| module.source_package_id)
+        .collect()
+}
+
 #[derive(Clone, Copy, Debug)]
 enum CreationTimeInterval {
     #[allow(dead_code)]
@@ -519,3 +531,63 @@ enum CreationTimeInterval {
     None,
     Before(CreationTime),
 }
+
+#[cfg(test)]
+mod tests {
+    use common::types::{
+        ModuleEnvironment,
+        Timestamp,
+    };
+    use model::{
+        modules::types::ModuleMetadata,
+        source_packages::types::SourcePackageId,
+    };
+    use value::{
+        id_v6::DeveloperDocumentId,
+        InternalId,
+        TableNumber,
+    };
+
+    use super::source_packages_in_use;
+
+    fn source_package_id(n: u8) -> anyhow::Result<SourcePackageId> {
+        Ok(DeveloperDocumentId::new(TableNumber::try_from(1)?, InternalId([n; 16])).into())
+    }
+
+    fn module(path: &str, source_package_id: SourcePackageId) -> anyhow::Result<ModuleMetadata> {
+        Ok(ModuleMetadata {
+            path: path.parse()?,
+            source_package_id,
+            environment: ModuleEnvironment::Isolate,
+            analyze_result: None,
+            sha256: [0; 32].into(),
+            deleted_ts: None,
+        })
+    }
+
+    #[test]
+    fn test_deleted_module_keeps_source_package_until_restored() -> anyhow::Result<()> {
+        let old_package = source_package_id(1)?;
+        let new_package = source_package_id(2)?;
+        let unused_package = source_package_id(3)?;
+        let mut removed = module("removed.js", old_package)?;
+        let kept = module("kept.js", new_package)?;
+
+        // A push removes `removed.js`, leaving a tombstone.
+        removed.deleted_ts = Some(Timestamp::try_from(1000i64)?);
+        let in_use = source_packages_in_use([&removed, &kept]);
+        let deleted: Vec<_> = [old_package, new_package, unused_package]
+            .into_iter()
+            .filter(|id| !in_use.contains(id))
+            .collect();
+        assert_eq!(deleted, vec![unused_package]);
+
+        // Restoring the module points it back at its source package, which
+        // cleanup kept.
+        removed.deleted_ts = None;
+        assert!(source_packages_in_use([&removed, &kept])
+            .iter()
+            .all(|id| !deleted.contains(id)));
+        Ok(())
+    }
+}
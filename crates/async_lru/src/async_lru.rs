@@ -7,7 +7,13 @@ use std::{
         HashMap,
     },
     fmt::Debug,
-    sync::Arc,
+    sync::{
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
+        Arc,
+    },
 };
 
 use anyhow::Context as _;
@@ -80,6 +86,9 @@ const PAUSE_DURING_GENERATE_VALUE_LABEL: &str = "generate_value";
 pub struct AsyncLru<RT: Runtime, Key, Value: ?Sized, FetchKey = Key> {
     runtime: RT,
     inner: Arc<Mutex<Inner<RT, Key, Value, FetchKey>>>,
+    // Counted outside of `inner` so that hits don't contend on its lock.
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
     label: &'static str,
     handle: Arc<Box<dyn SpawnHandle>>,
 }
@@ -89,6 +98,8 @@ impl<RT: Runtime, Key, Value: ?Sized, FetchKey> Clone for AsyncLru<RT, Key, Valu
         Self {
             runtime: self.runtime.clone(),
             inner: self.inner.clone(),
+            hits: self.hits.clone(),
+            misses: self.misses.clone(),
             label: self.label,
             handle: self.handle.clone(),
         }
@@ -108,10 +119,27 @@ impl<Value: SizedValue + ?Sized> SizedValue for CacheEntry<Value> {
     }
 }
 
+/// A point-in-time view of an [AsyncLru]'s size and cumulative counters.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AsyncLruStats {
+    /// Total size of the cached values, as measured by `SizedValue`.
+    pub size: u64,
+    pub max_size: u64,
+    pub num_entries: usize,
+    /// Gets served from a value already in the cache.
+    pub hits: u64,
+    /// Gets that had to generate the value or wait for it to be generated.
+    pub misses: u64,
+    /// Entries dropped to stay within `max_size`.
+    pub evictions: u64,
+}
+
 struct Inner<RT: Runtime, Key, Value: ?Sized, FetchKey> {
     cache: LruCache<Key, CacheEntry<Value>>,
     current_size: u64,
     max_size: u64,
+    evictions: u64,
+    on_evict: Option<fn()>,
     label: &'static str,
     tx: CoDelQueueSender<RT, BuildValueRequest<Key, Value, FetchKey>>,
     in_progress: HashMap<FetchKey, BroadcastReceiver<BuildValueResult<Key, Value>>>,
@@ -128,6 +156,8 @@ impl<RT: Runtime, Key, Value: ?Sized, FetchKey> Inner<RT, Key, Value, FetchKey>
             cache,
             current_size: 0,
             max_size,
+            evictions: 0,
+            on_evict: None,
             label,
             tx,
             in_progress: HashMap::new(),
@@ -252,11 +282,20 @@ impl<
         Self {
             runtime: rt.clone(),
             inner,
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
             label,
             handle: Arc::new(handle),
         }
     }
 
+    /// Call `on_evict` each time an entry is dropped to stay within
+    /// `max_size`, e.g. to count evictions in a cache-specific metric.
+    pub fn with_eviction_listener(self, on_evict: fn()) -> Self {
+        self.inner.lock().on_evict = Some(on_evict);
+        self
+    }
+
     fn drop_waiting(inner: &mut Inner<RT, Key, Value, FetchKey>, key: &FetchKey) {
         inner.in_progress.remove(key);
     }
@@ -301,6 +340,10 @@ impl<
             }
             async_lru_log_eviction(inner.label, added.elapsed());
             inner.current_size -= size;
+            inner.evictions += 1;
+            if let Some(on_evict) = inner.on_evict {
+                on_evict();
+            }
         }
     }
 
@@ -309,6 +352,40 @@ impl<
         inner.current_size
     }
 
+    pub fn stats(&self) -> AsyncLruStats {
+        let inner = self.inner.lock();
+        AsyncLruStats {
+            size: inner.current_size,
+            max_size: inner.max_size,
+            num_entries: inner.cache.len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: inner.evictions,
+        }
+    }
+
+    /// Remove every cached entry whose key matches `predicate`, returning the
+    /// number of entries removed.
+    ///
+    /// In-flight fetches are unaffected, so a value that is currently being
+    /// generated may still be inserted after this returns.
+    pub fn remove_if(&self, predicate: impl Fn(&Key) -> bool) -> usize {
+        let mut inner = self.inner.lock();
+        let keys: Vec<Key> = inner
+            .cache
+            .iter()
+            .filter(|(key, _)| predicate(key))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &keys {
+            if let Some(CacheEntry { size, .. }) = inner.cache.pop(key) {
+                inner.current_size -= size;
+            }
+        }
+        log_async_lru_size(inner.cache.len(), inner.current_size, self.label);
+        keys.len()
+    }
+
     /// Get `key`. If it is not present, run `value_generator` and cache every
     /// key/value pair it returns.
     ///
@@ -388,14 +465,17 @@ impl<
         match status {
             Status::Ready(value) => {
                 log_async_lru_cache_hit(self.label);
+                self.hits.fetch_add(1, Ordering::Relaxed);
                 Ok(value)
             },
             Status::Waiting(rx) => {
                 log_async_lru_cache_waiting(self.label);
+                self.misses.fetch_add(1, Ordering::Relaxed);
                 Ok(Self::wait_for_value(key, rx).await?)
             },
             Status::Kickoff(rx) => {
                 log_async_lru_cache_miss(self.label);
+                self.misses.fetch_add(1, Ordering::Relaxed);
                 let rx = rx?;
                 let timer = async_lru_compute_timer(self.label);
                 pause_client.wait(PAUSE_DURING_GENERATE_VALUE_LABEL).await;
@@ -25,18 +25,19 @@ use aws_sdk_s3::{
     primitives::ByteStream,
     types::{
         ChecksumAlgorithm,
+        ChecksumMode,
         CompletedMultipartUpload,
         CompletedPart,
-        ServerSideEncryption,
     },
     Client,
 };
 use aws_utils::{
     are_checksums_disabled,
     is_range_prefetch_disabled,
-    is_sse_disabled,
     must_s3_config_from_env,
     s3::S3Client,
+    s3_server_side_encryption,
+    S3ServerSideEncryption,
 };
 use bytes::Bytes;
 use common::{
@@ -71,6 +72,7 @@ use serde_json::{
 };
 use storage::{
     BufferedUpload,
+    ChecksumVerification,
     ClientDrivenUploadPartToken,
     ClientDrivenUploadToken,
     InvalidGetRangeError,
@@ -84,6 +86,7 @@ use storage::{
     UploadId,
     MAXIMUM_PARALLEL_UPLOADS,
 };
+use value::sha256::Sha256Digest;
 
 use crate::{
     metrics::sign_url_timer,
@@ -114,6 +117,7 @@ pub struct S3Storage<RT: Runtime> {
     // Prefix gets added as prefix to all keys.
     key_prefix: String,
     runtime: RT,
+    server_side_encryption: Option<S3ServerSideEncryption>,
 }
 
 impl<RT: Runtime> std::fmt::Debug for S3Storage<RT> {
@@ -138,6 +142,7 @@ impl<RT: Runtime> S3Storage<RT> {
             bucket,
             key_prefix,
             runtime,
+            server_side_encryption: s3_server_side_encryption()?,
         })
     }
 
@@ -152,6 +157,7 @@ impl<RT: Runtime> S3Storage<RT> {
             bucket,
             key_prefix,
             runtime,
+            server_side_encryption: s3_server_side_encryption()?,
         };
         Ok(storage)
     }
@@ -165,28 +171,6 @@ impl<RT: Runtime> S3Storage<RT> {
         S3Storage::new_with_prefix(bucket_name, key_prefix, runtime).await
     }
 
-    /// Helper method to configure multipart upload builder with optional AWS
-    /// headers for S3 compatibility with non-AWS services
-    fn configure_multipart_upload_builder(
-        &self,
-        mut upload_builder: CreateMultipartUploadFluentBuilder,
-    ) -> CreateMultipartUploadFluentBuilder {
-        // Add server-side encryption if not disabled for S3 compatibility
-        if !is_sse_disabled() {
-            upload_builder = upload_builder.server_side_encryption(ServerSideEncryption::Aes256);
-        }
-
-        // Add checksum algorithm if not disabled for S3 compatibility
-        if !are_checksums_disabled() {
-            // Because we're using multipart uploads, we're really specifying the part
-            // checksum algorithm here, so it needs to match what we use for
-            // each part.
-            upload_builder = upload_builder.checksum_algorithm(ChecksumAlgorithm::Crc32);
-        }
-
-        upload_builder
-    }
-
     async fn start_upload_with_key(&self, key: ObjectKey) -> anyhow::Result<S3Upload<RT>> {
         let s3_key = S3Key(self.key_prefix.clone() + &key);
         let upload_builder = self
@@ -195,7 +179,10 @@ impl<RT: Runtime> S3Storage<RT> {
             .bucket(self.bucket.clone())
             .key(&s3_key.0);
 
-        let upload_builder = self.configure_multipart_upload_builder(upload_builder);
+        let upload_builder = configure_multipart_upload_builder(
+            upload_builder,
+            self.server_side_encryption.as_ref(),
+        );
 
         let output = upload_builder
             .send()
@@ -217,6 +204,30 @@ impl<RT: Runtime> S3Storage<RT> {
     }
 }
 
+/// Helper method to configure multipart upload builder with optional AWS
+/// headers for S3 compatibility with non-AWS services
+fn configure_multipart_upload_builder(
+    mut upload_builder: CreateMultipartUploadFluentBuilder,
+    server_side_encryption: Option<&S3ServerSideEncryption>,
+) -> CreateMultipartUploadFluentBuilder {
+    // Add server-side encryption if not disabled for S3 compatibility
+    if let Some(sse) = server_side_encryption {
+        upload_builder = upload_builder
+            .server_side_encryption(sse.algorithm.clone())
+            .set_ssekms_key_id(sse.kms_key_id.clone());
+    }
+
+    // Add checksum algorithm if not disabled for S3 compatibility
+    if !are_checksums_disabled() {
+        // Because we're using multipart uploads, we're really specifying the part
+        // checksum algorithm here, so it needs to match what we use for
+        // each part.
+        upload_builder = upload_builder.checksum_algorithm(ChecksumAlgorithm::Crc32);
+    }
+
+    upload_builder
+}
+
 async fn s3_client() -> Result<Client, anyhow::Error> {
     static S3_CLIENT: tokio::sync::OnceCell<Client> = tokio::sync::OnceCell::const_new();
     let client = S3_CLIENT
@@ -306,7 +317,10 @@ impl<RT: Runtime> Storage for S3Storage<RT> {
             .bucket(self.bucket.clone())
             .key(&s3_key.0);
 
-        let upload_builder = self.configure_multipart_upload_builder(upload_builder);
+        let upload_builder = configure_multipart_upload_builder(
+            upload_builder,
+            self.server_side_encryption.as_ref(),
+        );
 
         let output = upload_builder
             .send()
@@ -523,6 +537,38 @@ impl<RT: Runtime> Storage for S3Storage<RT> {
         }
     }
 
+    async fn verify_checksum(
+        &self,
+        key: &ObjectKey,
+        expected: &Sha256Digest,
+    ) -> anyhow::Result<ChecksumVerification> {
+        let fq_key = self.fully_qualified_key(key);
+        let (bucket, s3_key) = fq_key
+            .as_str()
+            .split_once('/')
+            .with_context(|| format!("Invalid fully qualified S3 key {fq_key:?}"))?;
+        let head_attributes = self
+            .client
+            .head_object()
+            .bucket(bucket)
+            .key(s3_key)
+            .checksum_mode(ChecksumMode::Enabled)
+            .send()
+            .await?;
+        // A checksum of a multipart upload is a checksum of the part checksums
+        // (suffixed with the number of parts), so it can't be compared with a
+        // hash of the whole object.
+        // Our own uploads are checksummed with CRC32, so there's usually no
+        // stored SHA-256. Reading the object back to hash it would cost as
+        // much as downloading it, so it's left unverified.
+        match head_attributes.checksum_sha256() {
+            Some(checksum) if !checksum.contains('-') => Ok(ChecksumVerification::from_match(
+                Sha256Digest::from_base64(checksum)? == *expected,
+            )),
+            Some(_) | None => Ok(ChecksumVerification::Unverifiable),
+        }
+    }
+
     fn storage_type_proto(&self) -> pb::searchlight::StorageType {
         let prefix = self.key_prefix.clone();
         let bucket = self.bucket.clone();
@@ -865,4 +911,118 @@ pub fn s3_bucket_name(use_case: &StorageUseCase) -> anyhow::Result<String> {
     ))
 }
 
+#[cfg(test)]
+mod tests {
+    use aws_sdk_s3::{
+        config::{
+            BehaviorVersion,
+            Credentials,
+            Region,
+        },
+        types::ServerSideEncryption,
+        Client,
+        Config,
+    };
+    use aws_utils::S3ServerSideEncryption;
+    use tokio::{
+        io::{
+            AsyncReadExt,
+            AsyncWriteExt,
+        },
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    use super::configure_multipart_upload_builder;
+
+    /// Accepts one request, answers it as a `CreateMultipartUpload`, and
+    /// returns the request's lowercased head.
+    async fn mock_s3_endpoint() -> anyhow::Result<(Client, JoinHandle<anyhow::Result<String>>)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let config = Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .credentials_provider(Credentials::new("key", "secret", None, None, "test"))
+            .endpoint_url(format!("http://{}", listener.local_addr()?))
+            .force_path_style(true)
+            .build();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).await?;
+                anyhow::ensure!(n > 0, "Connection closed before the request was sent");
+                request.extend_from_slice(&buf[..n]);
+            }
+            let body = "<InitiateMultipartUploadResult><Bucket>bucket</Bucket><Key>key</\
+                        Key><UploadId>upload-id</UploadId></InitiateMultipartUploadResult>";
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/xml\r\nContent-Length: \
+                 {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await?;
+            Ok(String::from_utf8_lossy(&request).to_ascii_lowercase())
+        });
+        Ok((Client::from_conf(config), server))
+    }
+
+    async fn create_upload_headers(sse: Option<&S3ServerSideEncryption>) -> anyhow::Result<String> {
+        let (client, server) = mock_s3_endpoint().await?;
+        let builder = client.create_multipart_upload().bucket("bucket").key("key");
+        let output = configure_multipart_upload_builder(builder, sse)
+            .send()
+            .await?;
+        assert_eq!(output.upload_id(), Some("upload-id"));
+        server.await?
+    }
+
+    #[tokio::test]
+    async fn test_multipart_upload_requests_sse_kms_key() -> anyhow::Result<()> {
+        let sse = S3ServerSideEncryption {
+            algorithm: ServerSideEncryption::AwsKms,
+            kms_key_id: Some("my-key".to_string()),
+        };
+        let headers = create_upload_headers(Some(&sse)).await?;
+        assert!(
+            headers.contains("x-amz-server-side-encryption: aws:kms\r\n"),
+            "{headers}"
+        );
+        assert!(
+            headers.contains("x-amz-server-side-encryption-aws-kms-key-id: my-key\r\n"),
+            "{headers}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_multipart_upload_sse_kms_with_default_key() -> anyhow::Result<()> {
+        let sse = S3ServerSideEncryption {
+            algorithm: ServerSideEncryption::AwsKms,
+            kms_key_id: None,
+        };
+        let headers = create_upload_headers(Some(&sse)).await?;
+        assert!(
+            headers.contains("x-amz-server-side-encryption: aws:kms\r\n"),
+            "{headers}"
+        );
+        assert!(
+            !headers.contains("x-amz-server-side-encryption-aws-kms-key-id"),
+            "{headers}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_multipart_upload_without_sse() -> anyhow::Result<()> {
+        let headers = create_upload_headers(None).await?;
+        assert!(
+            !headers.contains("x-amz-server-side-encryption"),
+            "{headers}"
+        );
+        Ok(())
+    }
+}
+
 // Test below only works if you have AWS environment variables set
\ No newline at end of file
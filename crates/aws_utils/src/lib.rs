@@ -11,7 +11,10 @@ use aws_config::{
     ConfigLoader,
 };
 use aws_credential_types::provider::ProvideCredentials;
-use aws_sdk_s3::config::Builder as S3ConfigBuilder;
+use aws_sdk_s3::{
+    config::Builder as S3ConfigBuilder,
+    types::ServerSideEncryption,
+};
 use aws_types::region::Region;
 
 pub mod firehose;
@@ -22,8 +25,12 @@ static S3_ENDPOINT_URL: LazyLock<Option<String>> =
 
 static AWS_REGION: LazyLock<Option<String>> = LazyLock::new(|| env::var("AWS_REGION").ok());
 
+/// Use path-style bucket addressing (e.g. for MinIO) instead of
+/// virtual-hosted style. `S3_FORCE_PATH_STYLE` is accepted as an alias, to
+/// match `S3_ENDPOINT_URL`.
 static AWS_S3_FORCE_PATH_STYLE: LazyLock<bool> = LazyLock::new(|| {
     env::var("AWS_S3_FORCE_PATH_STYLE")
+        .or_else(|_| env::var("S3_FORCE_PATH_STYLE"))
         .ok()
         .and_then(|s| s.parse().ok())
         .unwrap_or_default()
@@ -36,6 +43,15 @@ static AWS_S3_DISABLE_SSE: LazyLock<bool> = LazyLock::new(|| {
         .unwrap_or_default()
 });
 
+/// Only uploads the backend makes itself request SSE. Presigned upload URLs
+/// can't, since clients would have to send matching headers, so objects
+/// uploaded through them get the bucket's default encryption. Set the bucket's
+/// default encryption to the same mode and key if every object must use it.
+static S3_SSE_MODE: LazyLock<Option<String>> = LazyLock::new(|| env::var("S3_SSE_MODE").ok());
+
+static S3_SSE_KMS_KEY_ID: LazyLock<Option<String>> =
+    LazyLock::new(|| env::var("S3_SSE_KMS_KEY_ID").ok());
+
 static AWS_S3_DISABLE_CHECKSUMS: LazyLock<bool> = LazyLock::new(|| {
     env::var("AWS_S3_DISABLE_CHECKSUMS")
         .ok()
@@ -51,8 +67,14 @@ static AWS_S3_DISABLE_RANGE_PREFETCH: LazyLock<bool> = LazyLock::new(|| {
 });
 
 /// Similar aws_config::from_env but returns an error if credentials or
-/// region is are not. It also doesn't spew out log lines every time
+/// region are not set. It also doesn't spew out log lines every time
 /// credentials are accessed.
+///
+/// Credentials aren't pinned at startup: the returned loader uses the default
+/// provider chain, so static `AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY` are
+/// used when set, and otherwise role, web identity, container or instance
+/// credentials are fetched and refreshed before they expire.
+/// [preflight_credentials] only checks that some source is available.
 pub async fn must_config_from_env() -> anyhow::Result<ConfigLoader> {
     let Some(region) = AWS_REGION.clone() else {
         anyhow::bail!("AWS_REGION env variable must be set");
@@ -138,6 +160,41 @@ pub fn is_sse_disabled() -> bool {
     *AWS_S3_DISABLE_SSE
 }
 
+/// Server-side encryption to request when uploading to S3.
+#[derive(Clone, Debug)]
+pub struct S3ServerSideEncryption {
+    pub algorithm: ServerSideEncryption,
+    /// KMS key to encrypt with in `aws:kms` mode. If unset, S3 uses the
+    /// default KMS key.
+    pub kms_key_id: Option<String>,
+}
+
+/// Returns the server-side encryption for S3 uploads, or None if
+/// `AWS_S3_DISABLE_SSE` is set. `S3_SSE_MODE` is `AES256` (the default) or
+/// `aws:kms`, and `S3_SSE_KMS_KEY_ID` picks the key in `aws:kms` mode.
+/// This doesn't apply to presigned upload URLs; see [S3_SSE_MODE].
+pub fn s3_server_side_encryption() -> anyhow::Result<Option<S3ServerSideEncryption>> {
+    if is_sse_disabled() {
+        return Ok(None);
+    }
+    let (algorithm, kms_key_id) = match S3_SSE_MODE.as_deref() {
+        None | Some("AES256") => {
+            if S3_SSE_KMS_KEY_ID.is_some() {
+                tracing::warn!("Ignoring S3_SSE_KMS_KEY_ID since S3_SSE_MODE isn't \"aws:kms\"");
+            }
+            (ServerSideEncryption::Aes256, None)
+        },
+        Some("aws:kms") => (ServerSideEncryption::AwsKms, S3_SSE_KMS_KEY_ID.clone()),
+        Some(mode) => {
+            anyhow::bail!("S3_SSE_MODE must be \"AES256\" or \"aws:kms\", got {mode:?}")
+        },
+    };
+    Ok(Some(S3ServerSideEncryption {
+        algorithm,
+        kms_key_id,
+    }))
+}
+
 /// Returns true if checksum headers should be disabled
 pub fn are_checksums_disabled() -> bool {
     *AWS_S3_DISABLE_CHECKSUMS
@@ -22,6 +22,7 @@ use value::{
 use crate::{
     components::ComponentId,
     types::FunctionCaller,
+    version::ClientVersion,
 };
 
 /// A client IP address extracted from HTTP headers, with max length
@@ -91,7 +92,6 @@ impl RequestMetadata {
             user_agent: None,
         }
     }
-
 }
 
 impl HeapSize for RequestMetadata {
@@ -126,7 +126,6 @@ impl RequestContext {
             request_metadata: RequestMetadata::system(),
         }
     }
-
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -145,6 +144,8 @@ pub struct ExecutionContext {
     is_root: bool,
     /// Metadata about the originating HTTP request (IP, user agent).
     pub request_metadata: RequestMetadata,
+    /// The client that called this function, if it was called by one.
+    pub client_version: Option<ClientVersion>,
 }
 
 impl ExecutionContext {
@@ -155,6 +156,7 @@ impl ExecutionContext {
             parent_scheduled_job: caller.parent_scheduled_job(),
             is_root: caller.is_root(),
             request_metadata: request_context.request_metadata,
+            client_version: caller.client_version(),
         }
     }
 
@@ -171,6 +173,7 @@ impl ExecutionContext {
             parent_scheduled_job,
             is_root,
             request_metadata,
+            client_version: None,
         }
     }
 
@@ -301,6 +304,7 @@ impl From<ExecutionContext> for pb::common::ExecutionContext {
             is_root: Some(value.is_root),
             client_ip: value.request_metadata.ip.map(|ip| ip.into_string()),
             client_user_agent: value.request_metadata.user_agent.map(|ua| ua.into_string()),
+            client_version: value.client_version.map(Into::into),
         }
     }
 }
@@ -325,6 +329,7 @@ impl TryFrom<pb::common::ExecutionContext> for ExecutionContext {
                 ip: value.client_ip.map(ClientIp::from),
                 user_agent: value.client_user_agent.map(ClientUserAgent::from),
             },
+            client_version: value.client_version.map(TryInto::try_into).transpose()?,
         })
     }
 }
@@ -343,3 +348,41 @@ impl From<ExecutionContext> for JsonValue {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ExecutionContext,
+        RequestContext,
+        RequestId,
+    };
+    use crate::{
+        types::FunctionCaller,
+        version::ClientVersion,
+    };
+
+    #[test]
+    fn test_client_version_round_trips_through_proto() -> anyhow::Result<()> {
+        let client_version: ClientVersion = "npm-1.5.0".parse()?;
+        let context = ExecutionContext::new(
+            RequestContext::new_for_system_request(RequestId::new()),
+            &FunctionCaller::SyncWorker(client_version.clone()),
+        );
+        assert_eq!(context.client_version, Some(client_version));
+        let proto = pb::common::ExecutionContext::from(context.clone());
+        assert_eq!(ExecutionContext::try_from(proto)?, context);
+        Ok(())
+    }
+
+    #[test]
+    fn test_callers_without_a_client_have_no_client_version() -> anyhow::Result<()> {
+        let context = ExecutionContext::new(
+            RequestContext::new_for_system_request(RequestId::new()),
+            &FunctionCaller::Cron,
+        );
+        assert_eq!(context.client_version, None);
+        let proto = pb::common::ExecutionContext::from(context.clone());
+        assert_eq!(ExecutionContext::try_from(proto)?, context);
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    str::FromStr,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        Arc,
+    },
+};
+
+use bytes::Bytes;
+use errors::ErrorMetadata;
+use futures::{
+    stream::BoxStream,
+    Stream,
+    TryStreamExt,
+};
+use futures_async_stream::try_stream;
+use humansize::{
+    FormatSize,
+    BINARY,
+};
+
+/// Request body size limits for paths under given prefixes, parsed from
+/// comma-separated `prefix=bytes` pairs like
+/// `/upload=104857600,/files/=1073741824`. A prefix matches whole path
+/// segments, so `/upload` applies to `/upload` and `/upload/avatar` but not
+/// `/uploads`. The longest prefix matching a path applies.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathBodyLimits {
+    by_prefix: Vec<(String, usize)>,
+}
+
+impl PathBodyLimits {
+    /// The limit for `path`, or `default` if no prefix matches it.
+    pub fn limit_for(&self, path: &str, default: usize) -> usize {
+        self.by_prefix
+            .iter()
+            .filter(|(prefix, _)| prefix_matches(prefix, path))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(default, |(_, limit)| *limit)
+    }
+
+    /// The largest limit any path can have.
+    pub fn max_limit(&self, default: usize) -> usize {
+        self.by_prefix
+            .iter()
+            .map(|(_, limit)| *limit)
+            .fold(default, usize::max)
+    }
+}
+
+impl FromStr for PathBodyLimits {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let by_prefix = s
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (prefix, limit) = pair
+                    .rsplit_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("Expected `prefix=bytes`, got {pair:?}"))?;
+                let prefix = prefix.trim();
+                anyhow::ensure!(
+                    prefix.starts_with('/'),
+                    "Path prefix {prefix:?} must start with `/`"
+                );
+                Ok((prefix.to_string(), limit.trim().parse()?))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { by_prefix })
+    }
+}
+
+fn prefix_matches(prefix: &str, path: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || prefix.ends_with('/') || rest.starts_with('/'))
+}
+
+/// A request body's size limit. Streaming the body through
+/// [RequestBodyLimit::limit_stream] records whether it passed the limit, so
+/// the response can still be a 413 when the body has no `Content-Length`.
+#[derive(Clone, Debug)]
+pub struct RequestBodyLimit {
+    limit: usize,
+    exceeded: Arc<AtomicBool>,
+}
+
+impl RequestBodyLimit {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            exceeded: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Fails a request whose `Content-Length` is over the limit, before its
+    /// body is read.
+    pub fn check_content_length(&self, content_length: Option<u64>) -> anyhow::Result<()> {
+        if content_length.is_some_and(|length| length > self.limit as u64) {
+            anyhow::bail!(request_body_too_large(self.limit));
+        }
+        Ok(())
+    }
+
+    /// Fails `body` once it passes the limit, which [Self::exceeded] reports
+    /// from then on.
+    pub fn limit_stream(
+        &self,
+        body: impl Stream<Item = anyhow::Result<Bytes>> + Send + Unpin + 'static,
+    ) -> BoxStream<'static, anyhow::Result<Bytes>> {
+        limit_stream(body, self.clone())
+    }
+
+    /// Whether a body streamed through [Self::limit_stream] passed the
+    /// limit.
+    pub fn exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+}
+
+#[try_stream(ok = Bytes, error = anyhow::Error, boxed)]
+async fn limit_stream<S: Stream<Item = anyhow::Result<Bytes>> + Send + Unpin + 'static>(
+    mut body: S,
+    limit: RequestBodyLimit,
+) {
+    let mut size = 0;
+    while let Some(chunk) = body.try_next().await? {
+        size += chunk.len();
+        if size > limit.limit {
+            limit.exceeded.store(true, Ordering::Relaxed);
+            anyhow::bail!(request_body_too_large(limit.limit));
+        }
+        yield chunk;
+    }
+}
+
+/// The error for a request whose body is larger than `limit` bytes.
+pub fn request_body_too_large(limit: usize) -> ErrorMetadata {
+    ErrorMetadata::bad_request(
+        "RequestBodyTooLarge",
+        format!(
+            "Request body is too large. Requests to this path support bodies up to {}.",
+            limit.format_size(BINARY)
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use errors::ErrorMetadataAnyhowExt;
+    use futures::{
+        executor::block_on,
+        stream,
+        TryStreamExt,
+    };
+
+    use super::{
+        PathBodyLimits,
+        RequestBodyLimit,
+    };
+
+    fn read_body(limit: &RequestBodyLimit, chunks: &[&'static str]) -> anyhow::Result<Vec<Bytes>> {
+        let body = stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| anyhow::Ok(Bytes::from_static(chunk.as_bytes())))
+                .collect::<Vec<_>>(),
+        );
+        block_on(limit.limit_stream(body).try_collect())
+    }
+
+    #[test]
+    fn test_body_under_limit_is_streamed() -> anyhow::Result<()> {
+        let limit = RequestBodyLimit::new(10);
+        assert_eq!(read_body(&limit, &["abc", "defg"])?, ["abc", "defg"]);
+        assert_eq!(read_body(&limit, &["abcde", "fghij"])?, ["abcde", "fghij"]);
+        assert!(!limit.exceeded());
+        Ok(())
+    }
+
+    #[test]
+    fn test_body_over_limit_fails_and_is_recorded() {
+        let limit = RequestBodyLimit::new(10);
+        let err = read_body(&limit, &["abcde", "fghij", "k"]).unwrap_err();
+        assert_eq!(err.short_msg(), "RequestBodyTooLarge");
+        assert!(limit.exceeded());
+        // Clones share whether the limit was exceeded.
+        let limit = RequestBodyLimit::new(3);
+        let handle = limit.clone();
+        assert!(read_body(&limit, &["abcd"]).is_err());
+        assert!(handle.exceeded());
+    }
+
+    #[test]
+    fn test_content_length_over_limit_is_rejected() -> anyhow::Result<()> {
+        let limit = RequestBodyLimit::new(10);
+        limit.check_content_length(None)?;
+        limit.check_content_length(Some(10))?;
+        let err = limit.check_content_length(Some(11)).unwrap_err();
+        assert_eq!(err.short_msg(), "RequestBodyTooLarge");
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit_for_matches_whole_segments() -> anyhow::Result<()> {
+        let limits: PathBodyLimits = "/upload=100,/files/=200".parse()?;
+        assert_eq!(limits.limit_for("/upload", 1), 100);
+        assert_eq!(limits.limit_for("/upload/avatar", 1), 100);
+        assert_eq!(limits.limit_for("/uploads", 1), 1);
+        assert_eq!(limits.limit_for("/upload-big", 1), 1);
+        assert_eq!(limits.limit_for("/files/a.png", 1), 200);
+        assert_eq!(limits.limit_for("/files/", 1), 200);
+        assert_eq!(limits.limit_for("/files", 1), 1);
+        assert_eq!(limits.limit_for("/", 1), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit_for_longest_prefix_wins() -> anyhow::Result<()> {
+        let limits: PathBodyLimits = "/=5,/api=50,/api/upload=500".parse()?;
+        assert_eq!(limits.limit_for("/other", 1), 5);
+        assert_eq!(limits.limit_for("/api/messages", 1), 50);
+        assert_eq!(limits.limit_for("/api/upload/big", 1), 500);
+        assert_eq!(limits.max_limit(1), 500);
+        assert_eq!(limits.max_limit(1000), 1000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!("".parse::<PathBodyLimits>()?, PathBodyLimits::default());
+        assert_eq!(
+            " /upload = 100 , /files/=200, ".parse::<PathBodyLimits>()?,
+            PathBodyLimits {
+                by_prefix: vec![("/upload".to_string(), 100), ("/files/".to_string(), 200)],
+            }
+        );
+        // Only the last `=` separates the limit.
+        assert_eq!(
+            "/a=b=3".parse::<PathBodyLimits>()?,
+            PathBodyLimits {
+                by_prefix: vec![("/a=b".to_string(), 3)],
+            }
+        );
+        for invalid in [
+            "/upload",
+            "upload=100",
+            "/upload=lots",
+            "/upload=-1",
+            "=100",
+        ] {
+            assert!(invalid.parse::<PathBodyLimits>().is_err(), "{invalid}");
+        }
+        Ok(())
+    }
+}
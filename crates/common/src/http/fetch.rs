@@ -3,6 +3,7 @@ use std::{
         BTreeMap,
         HashMap,
     },
+    error::Error,
     pin::Pin,
     sync::{
         atomic::{
@@ -32,6 +33,11 @@ use reqwest::{
 use tokio::select;
 
 use crate::http::{
+    host_filter::{
+        FetchForbidden,
+        FetchHostFilter,
+        FilteringResolver,
+    },
     HttpRequestStream,
     HttpResponseStream,
 };
@@ -45,6 +51,10 @@ pub trait FetchClient: Send + Sync {
 pub struct ProxiedFetchClient {
     http_client:
         LazyLock<reqwest::Client, Box<dyn FnOnce() -> reqwest::Client + Send + Sync + 'static>>,
+    host_filter: Arc<FetchHostFilter>,
+    /// Whether the client resolves hosts itself, so `host_filter` can check
+    /// the addresses it connects to. A proxy resolves hosts on its own.
+    checks_resolved_ips: bool,
 }
 
 // Share the underlying TlsConnector between ProxiedFetchClients
@@ -66,6 +76,16 @@ pub fn build_proxied_reqwest_client(
     client_id: String,
     redirect_policy: reqwest::redirect::Policy,
 ) -> reqwest::Client {
+    proxied_reqwest_client_builder(proxy_url, client_id, redirect_policy)
+        .build()
+        .expect("Failed to build reqwest client")
+}
+
+fn proxied_reqwest_client_builder(
+    proxy_url: Option<Url>,
+    client_id: String,
+    redirect_policy: reqwest::redirect::Policy,
+) -> reqwest::ClientBuilder {
     let mut builder = reqwest::Client::builder().redirect(redirect_policy);
     // It's okay to panic on these errors, as they indicate a serious programming
     // error -- building the reqwest client is expected to be infallible.
@@ -79,22 +99,35 @@ pub fn build_proxied_reqwest_client(
             );
         builder = builder.proxy(proxy);
     }
-    builder = builder
+    builder
         .user_agent("Convex/1.0")
-        .use_preconfigured_tls(TLS_CONNECTOR.clone());
-    builder.build().expect("Failed to build reqwest client")
+        .use_preconfigured_tls(TLS_CONNECTOR.clone())
 }
 
 impl ProxiedFetchClient {
+    /// Requests to hosts `host_filter` doesn't allow fail with a
+    /// `FetchForbidden` error. Without a proxy, the addresses domain names
+    /// resolve to are checked too.
     pub fn new(
         proxy_url: Option<Url>,
         client_id: String,
         redirect_policy: reqwest::redirect::Policy,
+        host_filter: FetchHostFilter,
     ) -> Self {
+        let host_filter = Arc::new(host_filter);
+        let checks_resolved_ips = proxy_url.is_none() && !host_filter.is_empty();
+        let resolver = checks_resolved_ips.then(|| FilteringResolver::new(host_filter.clone()));
         Self {
             http_client: LazyLock::new(Box::new(move || {
-                build_proxied_reqwest_client(proxy_url, client_id, redirect_policy)
+                let mut builder =
+                    proxied_reqwest_client_builder(proxy_url, client_id, redirect_policy);
+                if let Some(resolver) = resolver {
+                    builder = builder.dns_resolver(Arc::new(resolver));
+                }
+                builder.build().expect("Failed to build reqwest client")
             })),
+            host_filter,
+            checks_resolved_ips,
         }
     }
 }
@@ -102,6 +135,12 @@ impl ProxiedFetchClient {
 #[async_trait]
 impl FetchClient for ProxiedFetchClient {
     async fn fetch(&self, mut request: HttpRequestStream) -> anyhow::Result<HttpResponseStream> {
+        if let Err(e) = self
+            .host_filter
+            .check_url(&request.url, self.checks_resolved_ips)
+        {
+            anyhow::bail!(e.error_metadata());
+        }
         let mut request_builder = self
             .http_client
             .request(request.method, request.url.as_str());
@@ -122,7 +161,15 @@ impl FetchClient for ProxiedFetchClient {
         let raw_request = request_builder.build()?;
         let raw_response = select! {
             response = self.http_client.execute(raw_request) => {
-                response?
+                match response {
+                    Ok(response) => response,
+                    Err(e) => {
+                        if let Some(forbidden) = find_fetch_forbidden(&e) {
+                            anyhow::bail!(forbidden.error_metadata());
+                        }
+                        return Err(e.into());
+                    },
+                }
             },
             _ = &mut request.signal => {
                 // TODO: This should turn into a DOMException with name "AbortError"
@@ -151,6 +198,19 @@ impl FetchClient for ProxiedFetchClient {
     }
 }
 
+/// Finds the [FetchForbidden] a [FilteringResolver] failed with in the chain
+/// of errors the request failed with, if there is one.
+fn find_fetch_forbidden(error: &reqwest::Error) -> Option<&FetchForbidden> {
+    let mut source: Option<&(dyn Error + 'static)> = Some(error);
+    while let Some(error) = source {
+        if let Some(forbidden) = error.downcast_ref::<FetchForbidden>() {
+            return Some(forbidden);
+        }
+        source = error.source();
+    }
+    None
+}
+
 /// Wraps a request body stream into a reqwest [`Body`], counting the bytes that
 /// flow through it into `request_size`.
 ///
@@ -0,0 +1,484 @@
+use std::{
+    net::{
+        IpAddr,
+        SocketAddr,
+    },
+    str::FromStr,
+    sync::Arc,
+};
+
+use errors::ErrorMetadata;
+use reqwest::dns::{
+    Addrs,
+    Name,
+    Resolve,
+    Resolving,
+};
+use url::{
+    Host,
+    Url,
+};
+
+/// Hostnames and IP ranges `ProxiedFetchClient` may or may not send requests
+/// to, so self-hosted deployments can restrict `fetch` without running a
+/// proxy.
+///
+/// A host is forbidden if it matches a denied pattern. If any patterns are
+/// allowed, a host is also forbidden unless it matches one of them. A domain
+/// name matches an IP range if any address it resolves to is in the range, so
+/// a name is denied if any of its addresses is denied, and it's only allowed
+/// by IP range if all of its addresses are allowed.
+#[derive(Clone, Debug, Default)]
+pub struct FetchHostFilter {
+    allow: Vec<HostPattern>,
+    deny: Vec<HostPattern>,
+}
+
+/// A domain name, `*.` followed by a domain name to match its subdomains, an
+/// IP address, or a CIDR range like `10.0.0.0/8` or `fd00::/8`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HostPattern {
+    Domain {
+        name: String,
+        include_subdomains: bool,
+    },
+    Cidr(IpCidr),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+/// Returned when a request's host isn't allowed by a [FetchHostFilter].
+#[derive(thiserror::Error, Debug)]
+#[error("Request to {host} is not allowed by this deployment's fetch host filter")]
+pub struct FetchForbidden {
+    host: String,
+}
+
+impl FetchForbidden {
+    pub fn error_metadata(&self) -> ErrorMetadata {
+        ErrorMetadata::bad_request("FetchForbidden", self.to_string())
+    }
+}
+
+impl FetchHostFilter {
+    pub fn new(allow: Vec<HostPattern>, deny: Vec<HostPattern>) -> Self {
+        Self { allow, deny }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Checks the host of `url` before the request is sent. If
+    /// `checks_resolved_ips` is false, because a proxy resolves the host
+    /// rather than [FilteringResolver], IP ranges only apply to IP literals
+    /// and a domain name can only be allowed by name.
+    pub fn check_url(&self, url: &Url, checks_resolved_ips: bool) -> Result<(), FetchForbidden> {
+        let allowed = match url.host() {
+            None => true,
+            Some(Host::Ipv4(ip)) => self.allows_ip(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => self.allows_ip(IpAddr::V6(ip)),
+            Some(Host::Domain(domain)) => {
+                let domain = normalize_domain(domain);
+                // Addresses are checked once the name is resolved, so a name
+                // that isn't allowed by name may still be allowed by address.
+                !self.deny.iter().any(|p| p.matches_domain(&domain))
+                    && (self.allow.is_empty()
+                        || self.allow.iter().any(|p| p.matches_domain(&domain))
+                        || (checks_resolved_ips && self.allow.iter().any(HostPattern::is_cidr)))
+            },
+        };
+        if !allowed {
+            return Err(FetchForbidden {
+                host: url.host_str().unwrap_or_default().to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks an address `domain` resolved to.
+    fn check_resolved_ip(&self, domain: &str, ip: IpAddr) -> Result<(), FetchForbidden> {
+        let forbidden = || FetchForbidden {
+            host: domain.to_string(),
+        };
+        let ip = ip.to_canonical();
+        if self.deny.iter().any(|p| p.matches_ip(ip)) {
+            return Err(forbidden());
+        }
+        if !self.allow.is_empty() {
+            let domain = normalize_domain(domain);
+            if !self.allow.iter().any(|p| p.matches_domain(&domain))
+                && !self.allow.iter().any(|p| p.matches_ip(ip))
+            {
+                return Err(forbidden());
+            }
+        }
+        Ok(())
+    }
+
+    fn allows_ip(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        !self.deny.iter().any(|p| p.matches_ip(ip))
+            && (self.allow.is_empty() || self.allow.iter().any(|p| p.matches_ip(ip)))
+    }
+}
+
+fn normalize_domain(domain: &str) -> String {
+    domain.trim_end_matches('.').to_ascii_lowercase()
+}
+
+impl HostPattern {
+    fn is_cidr(&self) -> bool {
+        match self {
+            HostPattern::Domain { .. } => false,
+            HostPattern::Cidr(_) => true,
+        }
+    }
+
+    fn matches_domain(&self, domain: &str) -> bool {
+        match self {
+            HostPattern::Domain {
+                name,
+                include_subdomains: false,
+            } => domain == name,
+            HostPattern::Domain {
+                name,
+                include_subdomains: true,
+            } => domain
+                .strip_suffix(name.as_str())
+                .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+            HostPattern::Cidr(_) => false,
+        }
+    }
+
+    fn matches_ip(&self, ip: IpAddr) -> bool {
+        match self {
+            HostPattern::Domain { .. } => false,
+            HostPattern::Cidr(cidr) => cidr.contains(ip),
+        }
+    }
+}
+
+impl FromStr for HostPattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let s = s.trim();
+        if s.contains('/') {
+            return Ok(HostPattern::Cidr(s.parse()?));
+        }
+        if let Ok(addr) = s.parse::<IpAddr>() {
+            return Ok(HostPattern::Cidr(IpCidr {
+                addr,
+                prefix_len: max_prefix_len(addr),
+            }));
+        }
+        let (name, include_subdomains) = match s.strip_prefix("*.") {
+            Some(name) => (name, true),
+            None => (s, false),
+        };
+        let name = normalize_domain(name);
+        anyhow::ensure!(
+            !name.is_empty() && !name.contains(['*', ':', '[', ']']),
+            "Invalid host pattern {s:?}: expected a domain name, `*.` and a domain name, an IP \
+             address, or a CIDR range"
+        );
+        Ok(HostPattern::Domain {
+            name,
+            include_subdomains,
+        })
+    }
+}
+
+impl IpCidr {
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => prefix_matches(
+                u32::from(net).into(),
+                u32::from(ip).into(),
+                32,
+                self.prefix_len,
+            ),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(net), u128::from(ip), 128, self.prefix_len)
+            },
+            (IpAddr::V4(_), IpAddr::V6(_)) | (IpAddr::V6(_), IpAddr::V4(_)) => false,
+        }
+    }
+}
+
+fn max_prefix_len(addr: IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn prefix_matches(net: u128, ip: u128, bits: u8, prefix_len: u8) -> bool {
+    let host_bits = u32::from(bits - prefix_len);
+    net.checked_shr(host_bits).unwrap_or(0) == ip.checked_shr(host_bits).unwrap_or(0)
+}
+
+impl FromStr for IpCidr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (addr, prefix_len) = s
+            .split_once('/')
+            .ok_or_else(|| anyhow::anyhow!("Invalid CIDR range {s:?}: missing prefix length"))?;
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid CIDR range {s:?}: {e}"))?;
+        let prefix_len: u8 = prefix_len
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid CIDR range {s:?}: {e}"))?;
+        anyhow::ensure!(
+            prefix_len <= max_prefix_len(addr),
+            "Invalid CIDR range {s:?}: prefix length is longer than the address"
+        );
+        Ok(Self { addr, prefix_len })
+    }
+}
+
+/// Resolves domain names for `ProxiedFetchClient`, failing with
+/// [FetchForbidden] if any address is forbidden. Checking the addresses the
+/// connection actually uses means a name can't resolve to an allowed address
+/// when checked and a forbidden one when connected to.
+pub(crate) struct FilteringResolver {
+    filter: Arc<FetchHostFilter>,
+}
+
+impl FilteringResolver {
+    pub(crate) fn new(filter: Arc<FetchHostFilter>) -> Self {
+        Self { filter }
+    }
+}
+
+impl Resolve for FilteringResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let filter = self.filter.clone();
+        Box::pin(async move {
+            let domain = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((domain, 0)).await?.collect();
+            for addr in &addrs {
+                filter.check_resolved_ip(domain, addr.ip())?;
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use url::Url;
+
+    use super::{
+        FetchHostFilter,
+        HostPattern,
+        IpCidr,
+    };
+
+    fn filter(allow: &[&str], deny: &[&str]) -> anyhow::Result<FetchHostFilter> {
+        let parse = |patterns: &[&str]| {
+            patterns
+                .iter()
+                .map(|p| p.parse())
+                .collect::<anyhow::Result<Vec<HostPattern>>>()
+        };
+        Ok(FetchHostFilter::new(parse(allow)?, parse(deny)?))
+    }
+
+    fn allows_url(filter: &FetchHostFilter, url: &str, checks_resolved_ips: bool) -> bool {
+        filter
+            .check_url(&Url::parse(url).unwrap(), checks_resolved_ips)
+            .is_ok()
+    }
+
+    fn allows_resolved(filter: &FetchHostFilter, domain: &str, ip: &str) -> bool {
+        filter
+            .check_resolved_ip(domain, ip.parse::<IpAddr>().unwrap())
+            .is_ok()
+    }
+
+    #[test]
+    fn test_empty_filter_allows_everything() -> anyhow::Result<()> {
+        let filter = filter(&[], &[])?;
+        assert!(filter.is_empty());
+        assert!(allows_url(&filter, "https://example.com/", true));
+        assert!(allows_url(&filter, "http://127.0.0.1/", true));
+        assert!(allows_url(&filter, "http://[::1]/", true));
+        assert!(allows_resolved(&filter, "example.com", "10.0.0.1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipv4_denylist() -> anyhow::Result<()> {
+        let filter = filter(&[], &["10.0.0.0/8", "169.254.169.254"])?;
+        assert!(!allows_url(&filter, "http://10.1.2.3/", false));
+        assert!(!allows_url(&filter, "http://169.254.169.254/latest", false));
+        assert!(allows_url(&filter, "http://11.0.0.1/", false));
+        assert!(allows_url(&filter, "http://169.254.169.253/", false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipv4_allowlist() -> anyhow::Result<()> {
+        let filter = filter(&["203.0.113.0/24"], &[])?;
+        assert!(allows_url(&filter, "http://203.0.113.7/", false));
+        assert!(!allows_url(&filter, "http://203.0.114.7/", false));
+        assert!(!allows_url(&filter, "http://[2001:db8::1]/", false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipv6_denylist() -> anyhow::Result<()> {
+        let filter = filter(&[], &["fd00::/8", "::1"])?;
+        assert!(!allows_url(&filter, "http://[fd12:3456::1]/", false));
+        assert!(!allows_url(&filter, "http://[::1]:8080/", false));
+        assert!(allows_url(&filter, "http://[fe80::1]/", false));
+        assert!(allows_url(&filter, "http://[2001:db8::1]/", false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipv4_mapped_ipv6_matches_ipv4_ranges() -> anyhow::Result<()> {
+        let deny = filter(&[], &["10.0.0.0/8"])?;
+        assert!(!allows_url(&deny, "http://[::ffff:10.0.0.1]/", false));
+        assert!(!allows_resolved(&deny, "example.com", "::ffff:10.0.0.1"));
+        assert!(allows_url(&deny, "http://[::ffff:11.0.0.1]/", false));
+
+        let allow = filter(&["203.0.113.0/24"], &[])?;
+        assert!(allows_url(&allow, "http://[::ffff:203.0.113.7]/", false));
+        assert!(allows_resolved(&allow, "example.com", "::ffff:203.0.113.7"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_domain_denylist() -> anyhow::Result<()> {
+        let filter = filter(&[], &["metadata.internal", "*.corp.example.com"])?;
+        assert!(!allows_url(&filter, "http://metadata.internal/", true));
+        assert!(!allows_url(&filter, "http://METADATA.Internal./", true));
+        assert!(!allows_url(&filter, "https://db.corp.example.com/", true));
+        assert!(!allows_url(&filter, "https://a.b.corp.example.com/", true));
+        // `*.` only matches subdomains, and only at a label boundary.
+        assert!(allows_url(&filter, "https://corp.example.com/", true));
+        assert!(allows_url(&filter, "https://notcorp.example.com/", true));
+        assert!(allows_url(&filter, "http://sub.metadata.internal/", true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_domain_allowlist() -> anyhow::Result<()> {
+        let filter = filter(&["api.example.com", "*.cdn.example.com"], &[])?;
+        assert!(allows_url(&filter, "https://api.example.com/v1", true));
+        assert!(allows_url(&filter, "https://img.cdn.example.com/", true));
+        assert!(!allows_url(&filter, "https://example.com/", true));
+        assert!(!allows_url(&filter, "https://cdn.example.com/", true));
+        assert!(!allows_url(&filter, "http://203.0.113.7/", true));
+        // A name allowed by name may resolve to any address that isn't denied.
+        assert!(allows_resolved(&filter, "api.example.com", "203.0.113.7"));
+        assert!(!allows_resolved(&filter, "example.com", "203.0.113.7"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_domain_allowed_by_resolved_ips() -> anyhow::Result<()> {
+        let filter = filter(&["203.0.113.0/24"], &[])?;
+        // Names are let through to be checked once resolved, unless a proxy
+        // resolves them.
+        assert!(allows_url(&filter, "https://example.com/", true));
+        assert!(!allows_url(&filter, "https://example.com/", false));
+        assert!(allows_resolved(&filter, "example.com", "203.0.113.7"));
+        assert!(!allows_resolved(&filter, "example.com", "198.51.100.7"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_domain_denied_by_resolved_ips() -> anyhow::Result<()> {
+        let filter = filter(&["api.example.com"], &["127.0.0.0/8", "fd00::/8"])?;
+        assert!(allows_url(&filter, "https://api.example.com/", true));
+        // Denied addresses win over names that are allowed.
+        assert!(!allows_resolved(&filter, "api.example.com", "127.0.0.1"));
+        assert!(!allows_resolved(
+            &filter,
+            "api.example.com",
+            "::ffff:127.0.0.1"
+        ));
+        assert!(!allows_resolved(&filter, "api.example.com", "fd00::1"));
+        assert!(allows_resolved(&filter, "api.example.com", "93.184.216.34"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_forbidden_error_names_host() -> anyhow::Result<()> {
+        let filter = filter(&[], &["10.0.0.0/8"])?;
+        let err = filter
+            .check_url(&Url::parse("http://10.0.0.1/")?, true)
+            .unwrap_err();
+        assert_eq!(err.error_metadata().short_msg, "FetchForbidden");
+        assert!(err.to_string().contains("10.0.0.1"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_host_patterns() -> anyhow::Result<()> {
+        assert_eq!(
+            "*.Example.COM.".parse::<HostPattern>()?,
+            HostPattern::Domain {
+                name: "example.com".to_string(),
+                include_subdomains: true,
+            }
+        );
+        assert_eq!(
+            " api.example.com ".parse::<HostPattern>()?,
+            HostPattern::Domain {
+                name: "api.example.com".to_string(),
+                include_subdomains: false,
+            }
+        );
+        assert_eq!(
+            "10.0.0.1".parse::<HostPattern>()?,
+            HostPattern::Cidr("10.0.0.1/32".parse()?)
+        );
+        assert_eq!(
+            "::1".parse::<HostPattern>()?,
+            HostPattern::Cidr("::1/128".parse()?)
+        );
+        for invalid in [
+            "",
+            "*",
+            "*.",
+            "a.*.example.com",
+            "[::1]",
+            "10.0.0.0/33",
+            "fd00::/129",
+        ] {
+            assert!(invalid.parse::<HostPattern>().is_err(), "{invalid}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_ip_cidr() -> anyhow::Result<()> {
+        assert!("10.0.0.0/0".parse::<IpCidr>()?.contains("1.2.3.4".parse()?));
+        assert!("::/0".parse::<IpCidr>()?.contains("2001:db8::1".parse()?));
+        assert!(!"::/0".parse::<IpCidr>()?.contains("1.2.3.4".parse()?));
+        for invalid in [
+            "10.0.0.0",
+            "10.0.0.0/",
+            "10.0.0/8",
+            "example.com/8",
+            "10.0.0.0/x",
+        ] {
+            assert!(invalid.parse::<IpCidr>().is_err(), "{invalid}");
+        }
+        Ok(())
+    }
+}
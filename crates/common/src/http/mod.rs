@@ -130,9 +130,11 @@ use crate::{
     RequestMetadata,
 };
 
+pub mod body_limit;
 pub mod extract;
 pub mod fetch;
 pub mod fork_of_axum_serve;
+pub mod host_filter;
 pub mod websocket;
 
 const MAX_HTTP2_STREAMS: u32 = 1024;
@@ -212,8 +214,7 @@ impl From<HttpRequest> for HttpRequestStream {
     }
 }
 
-impl HttpRequestStream {
-}
+impl HttpRequestStream {}
 
 impl HeapSize for HttpRequest {
     fn heap_size(&self) -> usize {
@@ -479,6 +480,17 @@ impl IntoResponse for HttpResponseError {
     }
 }
 
+impl HttpResponseError {
+    /// Like converting `err`, but responds with `status_code` rather than the
+    /// status its `ErrorMetadata` maps to, for statuses like 413 Payload Too
+    /// Large that no `ErrorCode` maps to.
+    pub fn with_status_code(err: anyhow::Error, status_code: StatusCode) -> Self {
+        let mut error = Self::from(err);
+        error.http_error.status_code = status_code;
+        error
+    }
+}
+
 impl From<anyhow::Error> for HttpResponseError {
     fn from(err: anyhow::Error) -> HttpResponseError {
         let http_error = HttpError {
@@ -652,7 +664,6 @@ impl ConvexHttpService {
             .await
         }
     }
-
 }
 
 /// Serves an HTTP server using the given service.
@@ -27,7 +27,10 @@ use std::{
 
 use cmd_util::env::env_config;
 
-use crate::fastrace_helpers::SamplingConfig;
+use crate::{
+    fastrace_helpers::SamplingConfig,
+    http::body_limit::PathBodyLimits,
+};
 
 /// This exists solely to allow knobs to have separate defaults for local
 /// execution and prod (running in Nomad). Don't export this outside of
@@ -72,10 +75,24 @@ pub static UDF_CACHE_MAX_SIZE: LazyLock<usize> =
 pub static SHARED_UDF_CACHE_MAX_SIZE: LazyLock<usize> =
     LazyLock::new(|| env_config("SHARED_UDF_CACHE_MAX_SIZE", 1024 * 1048576));
 
+/// Number of recent UDF cache reads that the reported cache hit rate covers.
+pub static UDF_CACHE_HIT_RATE_WINDOW: LazyLock<usize> =
+    LazyLock::new(|| env_config("UDF_CACHE_HIT_RATE_WINDOW", 1000));
+
 /// How many UDF execution logs to keep in memory.
 pub static MAX_UDF_EXECUTION: LazyLock<usize> =
     LazyLock::new(|| env_config("MAX_UDF_EXECUTION", 1000));
 
+/// How long UDF execution logs are kept in memory, on top of the
+/// [`MAX_UDF_EXECUTION`] limit. Zero keeps logs until that limit evicts them.
+pub static UDF_EXECUTION_LOG_RETENTION: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_secs(env_config("UDF_EXECUTION_LOG_RETENTION_SECS", 0)));
+
+/// How often UDF execution logs older than [`UDF_EXECUTION_LOG_RETENTION`] are
+/// pruned.
+pub static UDF_EXECUTION_LOG_PRUNE_INTERVAL: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_secs(env_config("UDF_EXECUTION_LOG_PRUNE_INTERVAL_SECS", 60)));
+
 /// What is the metrics aggregation window for UDF metrics?
 pub static UDF_METRICS_BUCKET_WIDTH: LazyLock<Duration> =
     LazyLock::new(|| Duration::from_secs(env_config("UDF_METRICS_BUCKET_WIDTH_SECS", 60)));
@@ -498,6 +515,21 @@ pub static SCHEDULED_JOB_RETENTION: LazyLock<Duration> = LazyLock::new(|| {
     ))
 });
 
+/// How long each check behind the backend's `/readyz` endpoint can take before
+/// the subsystem is reported as not ready.
+pub static READINESS_CHECK_TIMEOUT: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_secs(env_config("READINESS_CHECK_TIMEOUT_SECS", 5)));
+
+/// How long a module removed by a push is kept so it can be restored with
+/// `ModuleModel::restore`. Deleted modules older than this are removed for good
+/// on the next push.
+pub static MODULE_DELETION_RETENTION: LazyLock<Duration> = LazyLock::new(|| {
+    Duration::from_secs(env_config(
+        "MODULE_DELETION_RETENTION_SECS",
+        60 * 60 * 24 * 7, // 1 week
+    ))
+});
+
 /// Maximum number of scheduled jobs to garbage collect in a single transaction
 pub static SCHEDULED_JOB_GARBAGE_COLLECTION_BATCH_SIZE: LazyLock<usize> =
     LazyLock::new(|| env_config("SCHEDULED_JOB_GARBAGE_COLLECTION_BATCH_SIZE", 1000));
@@ -518,6 +550,11 @@ pub static SCHEDULED_JOB_GARBAGE_COLLECTION_DELAY: LazyLock<Duration> =
 pub static CRON_SPLAY_SECONDS: LazyLock<u64> =
     LazyLock::new(|| env_config("CRON_SPLAY_SECONDS", 60));
 
+/// Number of the most recent logs kept per cron job. Older logs are deleted
+/// whenever a new run is logged. Values below 1 are treated as 1.
+pub static CRON_JOB_LOGS_PER_JOB: LazyLock<usize> =
+    LazyLock::new(|| env_config("CRON_JOB_LOGS_PER_JOB", 5));
+
 /// Maximum number of syscalls that can run in a batch together when
 /// awaited in parallel. Higher values improve latency, while lower ones
 /// protect one isolate from hogging database connections.
@@ -544,6 +581,17 @@ pub static TRANSACTION_MAX_READ_SIZE_BYTES: LazyLock<usize> = LazyLock::new(|| {
     env_config("TRANSACTION_MAX_READ_SIZE_BYTES", 1 << 24) // 16 MiB
 });
 
+/// Highest number of rows a transaction can opt into reading by overriding
+/// its read limits. Never lower than `TRANSACTION_MAX_READ_SIZE_ROWS`.
+pub static TRANSACTION_ABSOLUTE_MAX_READ_SIZE_ROWS: LazyLock<usize> =
+    LazyLock::new(|| env_config("TRANSACTION_ABSOLUTE_MAX_READ_SIZE_ROWS", 128000));
+
+/// Highest number of bytes a transaction can opt into reading by overriding
+/// its read limits. Never lower than `TRANSACTION_MAX_READ_SIZE_BYTES`.
+pub static TRANSACTION_ABSOLUTE_MAX_READ_SIZE_BYTES: LazyLock<usize> = LazyLock::new(|| {
+    env_config("TRANSACTION_ABSOLUTE_MAX_READ_SIZE_BYTES", 1 << 26) // 64 MiB
+});
+
 /// Maximum size in bytes of the in-memory index cache carried across
 /// transactions. When a write pushes the cache past this size the entire cache
 /// is dropped, forcing subsequent reads to go to the database.
@@ -1389,6 +1437,23 @@ pub static NODE_ANALYZE_MAX_RETRIES: LazyLock<usize> =
 pub static BACKEND_REQUEST_DRAIN_TIMEOUT: LazyLock<Duration> =
     LazyLock::new(|| Duration::from_secs(env_config("BACKEND_REQUEST_DRAIN_TIMEOUT", 15)));
 
+/// Base64-encoded 256-bit key used to encrypt snapshot exports written to
+/// local-dir storage with AES-256-GCM. When unset, exports are stored
+/// unencrypted.
+///
+/// The backend doesn't store this key anywhere. Keep it somewhere durable
+/// (e.g. a secrets manager): if it's lost or changed, exports written under the
+/// previous key can no longer be downloaded. Rotate only once exports written
+/// with the old key have expired or been downloaded.
+///
+/// Read directly from the environment rather than via `env_config` so the key
+/// isn't logged.
+pub static LOCAL_DIR_EXPORTS_ENCRYPTION_KEY: LazyLock<Option<String>> = LazyLock::new(|| {
+    std::env::var("LOCAL_DIR_EXPORTS_ENCRYPTION_KEY")
+        .ok()
+        .filter(|key| !key.is_empty())
+});
+
 /// The kinesis firehose name for streaming usage metrics to the data
 // large body of water.
 pub static BACKEND_USAGE_FIREHOSE_NAME: LazyLock<Option<String>> = LazyLock::new(|| {
@@ -1501,6 +1566,22 @@ pub static MAX_BACKEND_RPC_REQUEST_SIZE: LazyLock<usize> =
 pub static MAX_BACKEND_RPC_RESPONSE_SIZE: LazyLock<usize> =
     LazyLock::new(|| env_config("MAX_BACKEND_RPC_RESPONSE_SIZE", 1 << 25)); // 32 MiB
 
+/// The maximum size of an HTTP action's request body, in bytes. Larger
+/// requests are rejected with 413 Payload Too Large. Defaults to the HTTP
+/// action response body limit, `udf::HTTP_ACTION_BODY_LIMIT`.
+pub static HTTP_ACTION_REQUEST_BODY_LIMIT: LazyLock<usize> =
+    LazyLock::new(|| env_config("HTTP_ACTION_REQUEST_BODY_LIMIT", 20 << 20)); // 20 MiB
+
+/// Overrides HTTP_ACTION_REQUEST_BODY_LIMIT for HTTP action paths under the
+/// given prefixes, e.g. `/upload=104857600,/files/=1073741824`. Prefixes match
+/// whole path segments, and the longest matching prefix applies.
+pub static HTTP_ACTION_REQUEST_BODY_LIMIT_BY_PATH: LazyLock<PathBodyLimits> = LazyLock::new(|| {
+    env_config(
+        "HTTP_ACTION_REQUEST_BODY_LIMIT_BY_PATH",
+        PathBodyLimits::default(),
+    )
+});
+
 /// The maximum size of byte chunks used when transmitting HTTP request/response
 /// bodies as part of HTTP Actions.
 pub static MAX_BACKEND_RPC_HTTP_CHUNK_SIZE: LazyLock<usize> =
@@ -1704,6 +1785,98 @@ pub static MIGRATION_REWRITE_BATCH_SIZE: LazyLock<usize> =
 pub static MAX_IMPORT_AGE: LazyLock<Duration> =
     LazyLock::new(|| Duration::from_secs(env_config("MAX_IMPORT_AGE_SECONDS", 7 * 24 * 60 * 60)));
 
+/// Documents per second assumed when estimating how long an import will take,
+/// before any import has completed on this backend to measure throughput.
+pub static SNAPSHOT_IMPORT_DEFAULT_DOCUMENTS_PER_SECOND: LazyLock<u64> =
+    LazyLock::new(|| env_config("SNAPSHOT_IMPORT_DEFAULT_DOCUMENTS_PER_SECOND", 1000).max(1));
+
+/// Maximum number of new tables a single import may create, counting tables
+/// created dynamically in Append mode. Replacing an existing table doesn't
+/// count towards the limit.
+pub static SNAPSHOT_IMPORT_MAX_TABLES_CREATED: LazyLock<usize> =
+    LazyLock::new(|| env_config("SNAPSHOT_IMPORT_MAX_TABLES_CREATED", 10000));
+
+/// Maximum depth of the component paths an import may write to, counting the
+/// component the import targets. Guards against archives with absurdly deeply
+/// nested `_components/` directories.
+pub static SNAPSHOT_IMPORT_MAX_COMPONENT_DEPTH: LazyLock<usize> =
+    LazyLock::new(|| env_config("SNAPSHOT_IMPORT_MAX_COMPONENT_DEPTH", 32));
+
+/// Maximum number of files a multi-part ZIP import may be split into.
+pub static SNAPSHOT_IMPORT_MAX_PARTS: LazyLock<usize> =
+    LazyLock::new(|| env_config("SNAPSHOT_IMPORT_MAX_PARTS", 100));
+
+/// Maximum number of entries in a ZIP import, including storage files.
+pub static SNAPSHOT_IMPORT_ZIP_MAX_ENTRIES: LazyLock<usize> =
+    LazyLock::new(|| env_config("SNAPSHOT_IMPORT_ZIP_MAX_ENTRIES", 10_000_000));
+
+/// Maximum number of bytes a ZIP import's entries may decompress to in total,
+/// counted as they're read. Guards against decompression bombs.
+pub static SNAPSHOT_IMPORT_ZIP_MAX_UNCOMPRESSED_BYTES: LazyLock<u64> =
+    LazyLock::new(|| env_config("SNAPSHOT_IMPORT_ZIP_MAX_UNCOMPRESSED_BYTES", 1 << 40));
+
+/// Maximum number of bytes a ZIP import reads into memory at once: the whole
+/// of a generated schema or deployment config entry, or one line of a
+/// `documents.jsonl` entry. Also limits the length of a line in a gzipped
+/// JSONL import.
+pub static SNAPSHOT_IMPORT_ZIP_MAX_BUFFERED_ENTRY_BYTES: LazyLock<u64> =
+    LazyLock::new(|| env_config("SNAPSHOT_IMPORT_ZIP_MAX_BUFFERED_ENTRY_BYTES", 256 << 20));
+
+/// Time limit for running an import's transform function on a single
+/// document. Imports fail on the first document that exceeds it.
+pub static SNAPSHOT_IMPORT_TRANSFORM_TIMEOUT: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_secs(env_config("SNAPSHOT_IMPORT_TRANSFORM_TIMEOUT_SECS", 10)));
+
+/// Maximum number of user tables an import writes to at once. Tables listed
+/// in the import's table order and `_storage` tables are always imported one
+/// at a time.
+pub static SNAPSHOT_IMPORT_TABLE_PARALLELISM: LazyLock<usize> =
+    LazyLock::new(|| env_config("SNAPSHOT_IMPORT_TABLE_PARALLELISM", 4).max(1));
+
+/// Maximum number of tables an import activates in one transaction. Imports
+/// with more tables than this are activated over several transactions, so a
+/// single transaction doesn't hit the write limits.
+pub static SNAPSHOT_IMPORT_ACTIVATION_BATCH_SIZE: LazyLock<usize> =
+    LazyLock::new(|| env_config("SNAPSHOT_IMPORT_ACTIVATION_BATCH_SIZE", 1000).max(1));
+
+/// Pause between enabling each backfilled index on a table created by an
+/// import. Zero (the default) enables all of a table's indexes at once;
+/// otherwise indexes on tables with at least
+/// `SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER_MIN_ROWS` rows are enabled one at a
+/// time to spread out the load.
+pub static SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER: LazyLock<Duration> = LazyLock::new(|| {
+    Duration::from_millis(env_config("SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER_MS", 0))
+});
+
+/// Tables with fewer rows than this have all their indexes enabled at once,
+/// even if `SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER` is set, since enabling them
+/// is cheap.
+pub static SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER_MIN_ROWS: LazyLock<u64> =
+    LazyLock::new(|| env_config("SNAPSHOT_IMPORT_INDEX_ENABLE_STAGGER_MIN_ROWS", 100_000));
+
+/// Maximum total size of the documents an import writes in one transaction,
+/// for imports that don't set their own. Defaults to half of
+/// `TRANSACTION_MAX_USER_WRITE_SIZE_BYTES`, leaving headroom for the import's
+/// other writes, and is kept between 1 and that limit.
+pub static SNAPSHOT_IMPORT_BATCH_MAX_SIZE_BYTES: LazyLock<usize> = LazyLock::new(|| {
+    env_config(
+        "SNAPSHOT_IMPORT_BATCH_MAX_SIZE_BYTES",
+        *TRANSACTION_MAX_USER_WRITE_SIZE_BYTES / 2,
+    )
+    .clamp(1, *TRANSACTION_MAX_USER_WRITE_SIZE_BYTES)
+});
+
+/// Maximum number of documents an import writes in one transaction, for
+/// imports that don't set their own. Defaults to half of
+/// `TRANSACTION_MAX_NUM_USER_WRITES` and is kept between 1 and that limit.
+pub static SNAPSHOT_IMPORT_BATCH_MAX_NUM_DOCUMENTS: LazyLock<usize> = LazyLock::new(|| {
+    env_config(
+        "SNAPSHOT_IMPORT_BATCH_MAX_NUM_DOCUMENTS",
+        *TRANSACTION_MAX_NUM_USER_WRITES / 2,
+    )
+    .clamp(1, *TRANSACTION_MAX_NUM_USER_WRITES)
+});
+
 /// Max staleness in seconds of a partition loader result before we allow
 /// refreshing. If a request tries to update the partition loader and this
 /// duration has not passed since the last refresh, a stale value will be used.
@@ -1771,6 +1944,17 @@ pub static EXPORT_MAX_INFLIGHT_PREFETCH_BYTES: LazyLock<usize> = LazyLock::new(|
 pub static EXPORT_WORKER_PAGE_SIZE: LazyLock<usize> =
     LazyLock::new(|| env_config("EXPORT_WORKER_PAGE_SIZE", 1000));
 
+/// The largest part the export worker buffers before writing it to exports
+/// storage. Up to `MAXIMUM_PARALLEL_UPLOADS` parts may be in flight at once,
+/// so this bounds the memory an export holds. S3 allows at most 10,000 parts
+/// per upload, so this also caps the size of an export at ~10,000 times this.
+pub static EXPORT_MAX_UPLOAD_PART_SIZE: LazyLock<usize> = LazyLock::new(|| {
+    env_config(
+        "EXPORT_MAX_UPLOAD_PART_SIZE",
+        64 * 1024 * 1024, // 64MiB
+    )
+});
+
 /// How often to update the client-facing progress message during export.
 pub static EXPORT_PROGRESS_UPDATE_INTERVAL: LazyLock<Duration> =
     LazyLock::new(|| Duration::from_secs(env_config("EXPORT_PROGRESS_UPDATE_INTERVAL_SECS", 5)));
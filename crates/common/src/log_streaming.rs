@@ -874,9 +874,16 @@ pub struct StreamFunctionLogs {
     pub cursor: f64,
     pub session_id: Option<String>,
     pub client_request_counter: Option<u32>,
+    /// Only stream logs from functions in this component. An empty string
+    /// selects the root component.
+    pub component_path: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct StreamUdfExecutionQueryArgs {
     pub cursor: f64,
+    /// Only stream executions of functions in this component. An empty string
+    /// selects the root component.
+    #[serde(rename = "componentPath")]
+    pub component_path: Option<String>,
 }
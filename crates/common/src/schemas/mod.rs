@@ -110,6 +110,8 @@ pub enum SchemaEnforcementError {
     },
 }
 
+impl std::error::Error for SchemaEnforcementError {}
+
 impl SchemaEnforcementError {
     pub fn to_error_metadata(self) -> ErrorMetadata {
         ErrorMetadata::bad_request("SchemaEnforcementError", self.to_string())
@@ -696,6 +696,12 @@ impl ValidationContext {
             None => Self(Some(new_context)),
         }
     }
+
+    /// The path within the document, e.g. `.address.zip`, or `None` for the
+    /// document itself.
+    pub fn path(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
 }
 
 impl Display for ValidationContext {
@@ -957,3 +963,57 @@ Validator: {validator}"
         context: ValidationContext,
     },
 }
+
+impl ValidationError {
+    /// The path of the field that failed validation, if it's inside the
+    /// document, and the validator its value should have matched.
+    pub fn failed_field(&self) -> (Option<String>, String) {
+        match self {
+            ValidationError::TableNamesDoNotMatch {
+                validator_table,
+                context,
+                ..
+            }
+            | ValidationError::SystemTableReference {
+                validator_table,
+                context,
+                ..
+            } => (
+                context.path().map(str::to_string),
+                format!("v.id(\"{validator_table}\")"),
+            ),
+            ValidationError::LiteralValuesDoNotMatch {
+                literal_validator,
+                context,
+                ..
+            } => (
+                context.path().map(str::to_string),
+                format!("v.literal({literal_validator})"),
+            ),
+            ValidationError::MissingRequiredField {
+                field_name,
+                object_validator,
+                context,
+                ..
+            } => (
+                Some(format!("{}.{field_name}", context.path().unwrap_or(""))),
+                object_validator
+                    .0
+                    .get(field_name)
+                    .map(|validator| validator.to_string())
+                    .unwrap_or_default(),
+            ),
+            ValidationError::ExtraField {
+                field_name,
+                context,
+                ..
+            } => (
+                Some(format!("{}.{field_name}", context.path().unwrap_or(""))),
+                "no such field".to_string(),
+            ),
+            ValidationError::NoMatch {
+                validator, context, ..
+            } => (context.path().map(str::to_string), validator.to_string()),
+        }
+    }
+}
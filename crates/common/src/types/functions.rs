@@ -182,6 +182,8 @@ pub enum FunctionCaller {
     // calls other functions, their caller would be `Action`.
     HttpEndpoint,
     Cron,
+    /// An import's transform function, called once per imported document.
+    SnapshotImport,
     Scheduler {
         job_id: DeveloperDocumentId,
         component_id: ComponentId,
@@ -200,6 +202,7 @@ impl FunctionCaller {
             FunctionCaller::Tester(c) => Some(c),
             FunctionCaller::HttpEndpoint
             | FunctionCaller::Cron
+            | FunctionCaller::SnapshotImport
             | FunctionCaller::Scheduler { .. }
             | FunctionCaller::Action { .. } => None,
         }
@@ -212,7 +215,8 @@ impl FunctionCaller {
             | FunctionCaller::HttpApi(_)
             | FunctionCaller::Tester(_)
             | FunctionCaller::HttpEndpoint
-            | FunctionCaller::Cron => None,
+            | FunctionCaller::Cron
+            | FunctionCaller::SnapshotImport => None,
             FunctionCaller::Scheduler {
                 job_id,
                 component_id,
@@ -231,6 +235,7 @@ impl FunctionCaller {
             | FunctionCaller::Tester(_)
             | FunctionCaller::HttpEndpoint
             | FunctionCaller::Cron
+            | FunctionCaller::SnapshotImport
             | FunctionCaller::Scheduler { .. } => None,
             FunctionCaller::Action {
                 parent_execution_id,
@@ -246,6 +251,7 @@ impl FunctionCaller {
             | FunctionCaller::Tester(_)
             | FunctionCaller::HttpEndpoint
             | FunctionCaller::Cron
+            | FunctionCaller::SnapshotImport
             | FunctionCaller::Scheduler { .. } => true,
             FunctionCaller::Action { .. } => false,
         }
@@ -261,6 +267,7 @@ impl FunctionCaller {
             | FunctionCaller::HttpEndpoint
             | FunctionCaller::Tester(_) => true,
             FunctionCaller::Cron
+            | FunctionCaller::SnapshotImport
             | FunctionCaller::Scheduler { .. }
             | FunctionCaller::Action { .. } => false,
         }
@@ -277,6 +284,7 @@ impl FunctionCaller {
             FunctionCaller::HttpEndpoint => AllowedVisibility::PublicOnly,
             FunctionCaller::Tester(_)
             | FunctionCaller::Cron
+            | FunctionCaller::SnapshotImport
             | FunctionCaller::Scheduler { .. }
             | FunctionCaller::Action { .. } => AllowedVisibility::All,
         }
@@ -291,6 +299,7 @@ impl fmt::Display for FunctionCaller {
             FunctionCaller::Tester(_) => "Tester",
             FunctionCaller::HttpEndpoint => "HttpEndpoint",
             FunctionCaller::Cron => "Cron",
+            FunctionCaller::SnapshotImport => "SnapshotImport",
             FunctionCaller::Scheduler { .. } => "Scheduler",
             FunctionCaller::Action { .. } => "Action",
         };
@@ -34,6 +34,25 @@ pub trait VirtualSystemDocMapper: Send + Sync {
     ) -> anyhow::Result<DeveloperDocument>;
 }
 
+/// Maps a system document to a virtual document with the same ID and fields.
+/// Useful as a default mapper for system tables whose virtual table exposes
+/// the system document as-is.
+pub struct PassthroughDocMapper;
+
+#[async_trait]
+impl VirtualSystemDocMapper for PassthroughDocMapper {
+    async fn system_to_virtual_doc(
+        &self,
+        _tx: &mut dyn GetDocument,
+        _virtual_system_mapping: &VirtualSystemMapping,
+        doc: ResolvedDocument,
+        _table_mapping: &TableMapping,
+        _version: Version,
+    ) -> anyhow::Result<DeveloperDocument> {
+        Ok(doc.to_developer())
+    }
+}
+
 /// This trait is used for dependency injection, exposing get_document
 /// (implemented by `Transaction`) to convert system documents joined across
 /// multiple system tables to virtual documents in `VirtualSystemDocMapper`.
@@ -105,13 +124,14 @@ impl AssociatedVirtualTable {
             Self::Secondary(table_name) => table_name,
         }
     }
-
 }
 
 #[derive(Clone, Default)]
 pub struct VirtualSystemMapping {
     system_to_associated_virtual_table: OrdMap<TableName, AssociatedVirtualTable>,
     virtual_to_primary_system_table: OrdMap<TableName, TableName>,
+    /// Mappers used for system tables that don't have a specific doc mapper.
+    default_doc_mappers: OrdMap<TableName, Arc<dyn VirtualSystemDocMapper>>,
 }
 
 impl std::fmt::Debug for VirtualSystemMapping {
@@ -130,11 +150,19 @@ impl PartialEq for VirtualSystemMapping {
 }
 
 impl VirtualSystemMapping {
+    /// Registers `system`'s associated virtual table. `default_doc_mapper`,
+    /// if set, converts `system`'s documents when no specific doc mapper is
+    /// registered for it, e.g. for a newly added table.
     pub fn add_table(
         &mut self,
         system: TableName,
         associated_virtual_table: AssociatedVirtualTable,
+        default_doc_mapper: Option<Arc<dyn VirtualSystemDocMapper>>,
     ) {
+        if let Some(default_doc_mapper) = default_doc_mapper {
+            self.default_doc_mappers
+                .insert(system.clone(), default_doc_mapper);
+        }
         match &associated_virtual_table {
             AssociatedVirtualTable::Primary {
                 virtual_table_name, ..
@@ -227,7 +255,8 @@ impl VirtualSystemMapping {
     }
 
     // Return the doc mapper if the this system table is the primary table backing
-    // a virtual table.
+    // a virtual table, falling back to the default doc mapper registered for
+    // the system table, if any.
     // system_table_name -> (Fn (SystemDoc) -> VirtualDoc)
     pub fn system_to_virtual_doc_mapper(
         &self,
@@ -239,6 +268,7 @@ impl VirtualSystemMapping {
                 AssociatedVirtualTable::Primary { doc_mapper, .. } => Some(doc_mapper),
                 AssociatedVirtualTable::Secondary(_) => None,
             })
+            .or_else(|| self.default_doc_mappers.get(system_table_name))
     }
 
     // Converts a virtual table DeveloperDocumentId to the system table ResolvedId.
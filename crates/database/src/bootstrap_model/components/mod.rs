@@ -53,6 +53,7 @@ use crate::{
         SystemTable,
     },
     ComponentDefinitionsTable,
+    ComponentRegistry,
     Transaction,
     COMPONENT_DEFINITIONS_TABLE,
 };
@@ -94,6 +95,32 @@ impl<'a, RT: Runtime> BootstrapComponentsModel<'a, RT> {
             .component_in_parent(parent_and_name, &mut self.tx.reads)
     }
 
+    /// Looks up the children of `parent` named `names`, like calling
+    /// [Self::component_in_parent] for each name but recording all the reads
+    /// in one batch. Names without a child are left out of the result.
+    pub fn components_in_parent(
+        &mut self,
+        parent: DeveloperDocumentId,
+        names: Vec<ComponentName>,
+    ) -> anyhow::Result<BTreeMap<ComponentName, ParsedDocument<ComponentMetadata>>> {
+        let (index_name, fields) = self.tx.component_registry.by_parent_index()?;
+        let intervals = names
+            .iter()
+            .map(|name| ComponentRegistry::by_parent_interval(&Some((parent, name.clone()))));
+        self.tx
+            .record_indexed_reads(index_name, fields, intervals)?;
+        Ok(names
+            .into_iter()
+            .filter_map(|name| {
+                let component = self
+                    .tx
+                    .component_registry
+                    .find_in_parent(&Some((parent, name.clone())))?;
+                Some((name, component))
+            })
+            .collect())
+    }
+
     pub fn component_children(
         &mut self,
         parent_id: DeveloperDocumentId,
@@ -169,6 +196,16 @@ impl<'a, RT: Runtime> BootstrapComponentsModel<'a, RT> {
         self.tx.get_component_path(component_id)
     }
 
+    /// Resolves the paths of many components at once, which is cheaper than
+    /// calling [Self::get_component_path] for each when they share ancestors.
+    /// Components that don't exist are left out of the result.
+    pub fn get_component_paths(
+        &mut self,
+        component_ids: impl IntoIterator<Item = ComponentId>,
+    ) -> BTreeMap<ComponentId, ComponentPath> {
+        self.tx.get_component_paths(component_ids)
+    }
+
     pub fn must_component_path(
         &mut self,
         component_id: ComponentId,
@@ -28,6 +28,14 @@ use crate::{
     Transaction,
 };
 
+/// What [`ImportFacingModel::upsert_if_changed`] did with a document.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UpsertOutcome {
+    Inserted,
+    Updated,
+    Unchanged,
+}
+
 /// `ImportFacingModel` is similar to `UserFacingModel` but with a few
 /// differences for insertions:
 /// - the table for insertion is chosen by table id, not table name or number.
@@ -44,15 +52,7 @@ impl<'a, RT: Runtime> ImportFacingModel<'a, RT> {
         Self { tx }
     }
 
-    /// Inserts a new document as part of a snapshot import.
-    #[convex_macro::instrument_future]
-    pub async fn insert(
-        &mut self,
-        table_id: TabletIdAndTableNumber,
-        table_name: &TableName,
-        value: ConvexObject,
-        table_mapping_for_schema: &TableMapping,
-    ) -> anyhow::Result<DeveloperDocumentId> {
+    fn check_can_write(&self, table_name: &TableName) -> anyhow::Result<()> {
         if self
             .tx
             .virtual_system_mapping()
@@ -72,6 +72,37 @@ impl<'a, RT: Runtime> ImportFacingModel<'a, RT> {
         self.tx
             .identity
             .require_operation(DeploymentOp::ImportBackups)?;
+        Ok(())
+    }
+
+    /// Makes the creation times generated for documents inserted after this
+    /// call later than `creation_time`. An import that spans several
+    /// transactions uses this so its documents' creation times follow the
+    /// order they were inserted in, even if the clock moves backwards between
+    /// transactions.
+    pub fn generate_creation_times_after(
+        &mut self,
+        creation_time: CreationTime,
+    ) -> anyhow::Result<()> {
+        advance_creation_time_past(&mut self.tx.next_creation_time, creation_time)
+    }
+
+    /// A creation time later than every creation time this transaction has
+    /// generated so far.
+    pub fn next_creation_time(&self) -> CreationTime {
+        self.tx.next_creation_time
+    }
+
+    /// Inserts a new document as part of a snapshot import.
+    #[convex_macro::instrument_future]
+    pub async fn insert(
+        &mut self,
+        table_id: TabletIdAndTableNumber,
+        table_name: &TableName,
+        value: ConvexObject,
+        table_mapping_for_schema: &TableMapping,
+    ) -> anyhow::Result<DeveloperDocumentId> {
+        self.check_can_write(table_name)?;
 
         let id_field = FieldName::from(ID_FIELD.clone());
         let internal_id = if let Some(ConvexValue::String(s)) = value.get(&id_field) {
@@ -129,25 +160,7 @@ impl<'a, RT: Runtime> ImportFacingModel<'a, RT> {
         value: ConvexObject,
         table_mapping_for_schema: &TableMapping,
     ) -> anyhow::Result<DeveloperDocumentId> {
-        if self
-            .tx
-            .virtual_system_mapping()
-            .is_virtual_table(table_name)
-        {
-            anyhow::bail!(ErrorMetadata::bad_request(
-                "ReadOnlyTable",
-                format!("{table_name} is a read-only table"),
-            ));
-        }
-        anyhow::ensure!(
-            bootstrap_system_tables()
-                .iter()
-                .all(|t| t.table_name() != *table_name),
-            "Cannot import into bootstrap system table {table_name}"
-        );
-        self.tx
-            .identity
-            .require_operation(DeploymentOp::ImportBackups)?;
+        self.check_can_write(table_name)?;
 
         let id_field = FieldName::from(ID_FIELD.clone());
         let developer_id = if let Some(ConvexValue::String(s)) = value.get(&id_field) {
@@ -194,31 +207,73 @@ impl<'a, RT: Runtime> ImportFacingModel<'a, RT> {
         Ok(id.into())
     }
 
+    /// Inserts `value` if there's no document with its `_id`, and otherwise
+    /// replaces the existing document only if its contents differ. Documents
+    /// without an `_id` are always inserted. If `value` has no
+    /// `_creationTime`, an existing document keeps its own.
+    #[convex_macro::instrument_future]
+    pub async fn upsert_if_changed(
+        &mut self,
+        table_id: TabletIdAndTableNumber,
+        table_name: &TableName,
+        value: ConvexObject,
+        table_mapping_for_schema: &TableMapping,
+    ) -> anyhow::Result<UpsertOutcome> {
+        self.check_can_write(table_name)?;
+
+        let id_field = FieldName::from(ID_FIELD.clone());
+        let existing_doc = match value.get(&id_field) {
+            Some(ConvexValue::String(s)) => match DeveloperDocumentId::decode(s) {
+                Ok(developer_id) if developer_id.table() == table_id.table_number => {
+                    let id = ResolvedDocumentId::new(table_id.tablet_id, developer_id);
+                    self.tx.get_with_ts(id).await?
+                },
+                // `insert` reports the invalid ID.
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some((existing_doc, existing_ts)) = existing_doc else {
+            self.insert(table_id, table_name, value, table_mapping_for_schema)
+                .await?;
+            return Ok(UpsertOutcome::Inserted);
+        };
+        let id = existing_doc.id();
+        let namespace = self
+            .tx
+            .table_mapping()
+            .tablet_namespace(table_id.tablet_id)?;
+
+        let creation_time_field = FieldName::from(CREATION_TIME_FIELD.clone());
+        let creation_time = if let Some(ConvexValue::Float64(f)) = value.get(&creation_time_field) {
+            CreationTime::try_from(*f)?
+        } else {
+            existing_doc.creation_time()
+        };
+
+        let document = ResolvedDocument::new(id, creation_time, value)?;
+        if document.value() == existing_doc.value() {
+            return Ok(UpsertOutcome::Unchanged);
+        }
+        SchemaModel::new(self.tx, namespace)
+            .enforce_with_table_mapping(&document, &table_mapping_for_schema.namespace(namespace))
+            .await?;
+        self.tx.apply_validated_write(
+            id,
+            Some((existing_doc, existing_ts)),
+            Some(document.into()),
+        )?;
+
+        Ok(UpsertOutcome::Updated)
+    }
+
     pub async fn delete(
         &mut self,
         table_id: TabletIdAndTableNumber,
         table_name: &TableName,
         developer_id: DeveloperDocumentId,
     ) -> anyhow::Result<()> {
-        if self
-            .tx
-            .virtual_system_mapping()
-            .is_virtual_table(table_name)
-        {
-            anyhow::bail!(ErrorMetadata::bad_request(
-                "ReadOnlyTable",
-                format!("{table_name} is a read-only table"),
-            ));
-        }
-        anyhow::ensure!(
-            bootstrap_system_tables()
-                .iter()
-                .all(|t| t.table_name() != *table_name),
-            "Cannot import into bootstrap system table {table_name}"
-        );
-        self.tx
-            .identity
-            .require_operation(DeploymentOp::ImportBackups)?;
+        self.check_can_write(table_name)?;
 
         let id = ResolvedDocumentId::new(table_id.tablet_id, developer_id);
         let existing_doc = self.tx.get_with_ts(id).await?;
@@ -228,3 +283,48 @@ impl<'a, RT: Runtime> ImportFacingModel<'a, RT> {
         Ok(())
     }
 }
+
+/// Moves `next_creation_time` past `floor` if it isn't already.
+fn advance_creation_time_past(
+    next_creation_time: &mut CreationTime,
+    floor: CreationTime,
+) -> anyhow::Result<()> {
+    if *next_creation_time <= floor {
+        *next_creation_time = floor;
+        next_creation_time.increment()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use common::document::CreationTime;
+
+    use super::advance_creation_time_past;
+
+    #[test]
+    fn test_creation_times_follow_batch_order() -> anyhow::Result<()> {
+        // The first batch's transaction starts at t=1000ms and generates
+        // three creation times.
+        let mut next_creation_time = CreationTime::try_from(1000.)?;
+        let mut generated = vec![];
+        for _ in 0..3 {
+            generated.push(next_creation_time.increment()?);
+        }
+        let floor = next_creation_time;
+
+        // The clock moves backwards before the second batch's transaction.
+        let mut next_creation_time = CreationTime::try_from(500.)?;
+        advance_creation_time_past(&mut next_creation_time, floor)?;
+        for _ in 0..3 {
+            generated.push(next_creation_time.increment()?);
+        }
+        assert!(generated.is_sorted_by(|a, b| a < b), "{generated:?}");
+
+        // A transaction that's already past the floor is left alone.
+        let mut next_creation_time = CreationTime::try_from(2000.)?;
+        advance_creation_time_past(&mut next_creation_time, floor)?;
+        assert_eq!(next_creation_time, CreationTime::try_from(2000.)?);
+        Ok(())
+    }
+}
@@ -888,6 +888,45 @@ impl<'a, RT: Runtime> IndexModel<'a, RT> {
             .collect())
     }
 
+    /// The name and state of every index on a table, including system indexes.
+    /// Returns an empty list if the table doesn't exist.
+    ///
+    /// Because of mutated indexes, the same name may appear twice: once for the
+    /// enabled index and once for its pending replacement.
+    pub async fn index_states(
+        &mut self,
+        namespace: TableNamespace,
+        table_name: &TableName,
+    ) -> anyhow::Result<Vec<(IndexName, IndexState)>> {
+        let Some(tablet_id) = self
+            .tx
+            .table_mapping()
+            .namespace(namespace)
+            .id_if_exists(table_name)
+        else {
+            return Ok(vec![]);
+        };
+        self.tablet_index_states(tablet_id)
+            .await?
+            .into_iter()
+            .map(|(name, state)| Ok((name.map_table(&|_| anyhow::Ok(table_name.clone()))?, state)))
+            .collect()
+    }
+
+    /// Like [`Self::index_states`], but for a tablet, which may be hidden (e.g.
+    /// during an import).
+    pub async fn tablet_index_states(
+        &mut self,
+        tablet_id: TabletId,
+    ) -> anyhow::Result<Vec<(TabletIndexName, IndexState)>> {
+        Ok(self
+            .all_indexes_on_table(tablet_id)
+            .await?
+            .into_iter()
+            .map(|index| (index.name.clone(), IndexState::from_config(&index.config)))
+            .collect())
+    }
+
     /// Returns all registered indexes (both system and developer-defined)
     /// including both pending and enabled indexes.
     ///
@@ -1118,6 +1157,33 @@ impl<'a, RT: Runtime> IndexModel<'a, RT> {
         Ok(())
     }
 
+    /// Adds `indexes`, which must all be backfilling, to `target_table`.
+    /// Indexes whose name is already taken on the table are skipped, so
+    /// indexes copied from the table being replaced take precedence.
+    pub async fn add_backfilling_indexes_to_table(
+        &mut self,
+        target_table: TabletId,
+        indexes: Vec<TabletIndexMetadata>,
+    ) -> anyhow::Result<()> {
+        let existing: BTreeSet<_> = self
+            .all_indexes_on_table(target_table)
+            .await?
+            .into_iter()
+            .map(|index| index.name.descriptor().clone())
+            .collect();
+        for index in indexes {
+            anyhow::ensure!(*index.name.table() == target_table);
+            anyhow::ensure!(index.config.is_backfilling());
+            if existing.contains(index.name.descriptor()) {
+                continue;
+            }
+            SystemMetadataModel::new_global(self.tx)
+                .insert_metadata(&INDEX_TABLE, index.try_into()?)
+                .await?;
+        }
+        Ok(())
+    }
+
     // Check if the system index is ready for all the given tables.
     // Useful for streaming import - waiting for the system indexes to be ready
     // for all the tables before proceeding with the import.
@@ -1126,33 +1192,66 @@ impl<'a, RT: Runtime> IndexModel<'a, RT> {
         index_descriptor: &IndexDescriptor,
         indexes: &BTreeSet<TableName>,
     ) -> anyhow::Result<bool> {
-        let index_metadata = indexes
-            .iter()
-            .map(|table_name| {
-                let index_name =
-                    IndexName::new_reserved(table_name.clone(), index_descriptor.clone())?;
-                // We really just want pending indexes here, but since it's convenient, we're
-                // also verifying that all requested tables have the expected
-                // index using enabled_index_metadata.
-                let mut model = IndexModel::new(self.tx);
-                let index_metadata = model
-                    .pending_index_metadata(TableNamespace::root_component(), &index_name)?
-                    .or(model
-                        .enabled_index_metadata(TableNamespace::root_component(), &index_name)?)
-                    .context(ErrorMetadata::bad_request(
-                        "MissingIndex",
-                        format!("Missing index: {index_name}"),
-                    ))?;
-                Ok(index_metadata)
-            })
-            .collect::<anyhow::Result<Vec<_>>>()?;
-        let are_all_indexes_ready = index_metadata
-            .iter()
-            .all(|metadata| !metadata.config.is_backfilling());
+        let mut are_all_indexes_ready = true;
+        for table_name in indexes {
+            let index_name = IndexName::new_reserved(table_name.clone(), index_descriptor.clone())?;
+            // We really just want to know about backfilling indexes here, but
+            // we're also verifying that all requested tables have the expected
+            // index.
+            let states: Vec<_> = self
+                .index_states(TableNamespace::root_component(), table_name)
+                .await?
+                .into_iter()
+                .filter(|(name, _)| *name == index_name)
+                .map(|(_, state)| state)
+                .collect();
+            anyhow::ensure!(
+                !states.is_empty(),
+                ErrorMetadata::bad_request("MissingIndex", format!("Missing index: {index_name}"))
+            );
+            if states.contains(&IndexState::Backfilling) {
+                are_all_indexes_ready = false;
+            }
+        }
         Ok(are_all_indexes_ready)
     }
 }
 
+/// The lifecycle state of an index, as reported by
+/// [`IndexModel::index_states`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexState {
+    /// The index is still being built and can't serve queries yet.
+    Backfilling,
+    /// The index is built and serving queries.
+    Enabled,
+    /// The index is built but not serving queries, either because it's staged
+    /// or because it hasn't been enabled yet.
+    Disabled,
+}
+
+impl IndexState {
+    fn from_config(config: &IndexConfig) -> Self {
+        match config {
+            IndexConfig::Database { on_disk_state, .. } => match on_disk_state {
+                DatabaseIndexState::Backfilling(_) => Self::Backfilling,
+                DatabaseIndexState::Backfilled { .. } => Self::Disabled,
+                DatabaseIndexState::Enabled => Self::Enabled,
+            },
+            IndexConfig::Text { on_disk_state, .. } => match on_disk_state {
+                TextIndexState::Backfilling(_) => Self::Backfilling,
+                TextIndexState::Backfilled { .. } => Self::Disabled,
+                TextIndexState::SnapshottedAt(_) => Self::Enabled,
+            },
+            IndexConfig::Vector { on_disk_state, .. } => match on_disk_state {
+                VectorIndexState::Backfilling(_) => Self::Backfilling,
+                VectorIndexState::Backfilled { .. } => Self::Disabled,
+                VectorIndexState::SnapshottedAt(_) => Self::Enabled,
+            },
+        }
+    }
+}
+
 enum IndexCategory {
     System,
     Application,
@@ -17,6 +17,10 @@ use common::{
         SchemaState,
     },
     document::ResolvedDocument,
+    query::{
+        Order,
+        Query,
+    },
     runtime::Runtime,
     schemas::{
         DatabaseSchema,
@@ -32,9 +36,13 @@ use value::{
     TableNamespace,
 };
 
-use self::types::SchemaDiff;
+use self::types::{
+    SchemaDiff,
+    SchemaDryRunResult,
+};
 use crate::{
     patch_value,
+    query::ResolvedQuery,
     system_tables::{
         SystemIndex,
         SystemTable,
@@ -167,7 +175,10 @@ impl<'a, RT: Runtime> SchemaModel<'a, RT> {
                 self.tx.virtual_system_mapping(),
             )
         {
-            anyhow::bail!(schema_error.to_error_metadata());
+            // Keep the structured error in the chain so callers can report
+            // which field failed, e.g. the row of a snapshot import.
+            let error_metadata = schema_error.clone().to_error_metadata();
+            return Err(anyhow::Error::new(schema_error).context(error_metadata));
         }
         let pending_schema = self.get_by_state(SchemaState::Pending).await?;
         let validated_schema = self.get_by_state(SchemaState::Validated).await?;
@@ -191,6 +202,57 @@ impl<'a, RT: Runtime> SchemaModel<'a, RT> {
         Ok(())
     }
 
+    /// Check whether `schema` would accept the documents currently in its
+    /// tables, without submitting it or changing any schema state.
+    ///
+    /// Unlike the schema worker, this reads documents within the transaction,
+    /// so it stops after `max_documents` documents or `max_violations`
+    /// violations to stay within transaction read limits. Check
+    /// [`SchemaDryRunResult::complete`] to see whether every document was
+    /// checked.
+    pub async fn dry_run_validate(
+        &mut self,
+        schema: &DatabaseSchema,
+        max_documents: usize,
+        max_violations: usize,
+    ) -> anyhow::Result<SchemaDryRunResult> {
+        let mut result = SchemaDryRunResult {
+            violations: vec![],
+            num_documents_checked: 0,
+            complete: true,
+        };
+        if !schema.schema_validation {
+            return Ok(result);
+        }
+        let table_mapping = self.tx.table_mapping().namespace(self.namespace);
+        let virtual_system_mapping = self.tx.virtual_system_mapping().clone();
+        for table_name in schema.tables.keys() {
+            if !table_mapping.name_exists(table_name) {
+                continue;
+            }
+            let query = Query::full_table_scan(table_name.clone(), Order::Asc);
+            let mut query_stream = ResolvedQuery::new(self.tx, self.namespace, query)?;
+            while let Some(doc) = query_stream.next(self.tx, None).await? {
+                if result.num_documents_checked >= max_documents
+                    || result.violations.len() >= max_violations
+                {
+                    result.complete = false;
+                    return Ok(result);
+                }
+                result.num_documents_checked += 1;
+                if let Err(schema_error) = schema.check_existing_document(
+                    &doc,
+                    table_name.clone(),
+                    &table_mapping,
+                    &virtual_system_mapping,
+                ) {
+                    result.violations.push(schema_error);
+                }
+            }
+        }
+        Ok(result)
+    }
+
     pub async fn get_by_state(
         &mut self,
         state: SchemaState,
@@ -1,6 +1,9 @@
 use common::{
     json::JsonForm as _,
-    schemas::DatabaseSchema,
+    schemas::{
+        DatabaseSchema,
+        SchemaValidationError,
+    },
 };
 use serde::{
     Deserialize,
@@ -13,6 +16,19 @@ pub struct SchemaDiff {
     pub next_schema: Option<DatabaseSchema>,
 }
 
+/// The result of checking a schema against existing data without changing any
+/// schema state. See [`super::SchemaModel::dry_run_validate`].
+#[derive(Debug, Clone)]
+pub struct SchemaDryRunResult {
+    /// Documents that don't match the schema, in table name then `_id` order.
+    pub violations: Vec<SchemaValidationError>,
+    /// Number of documents checked.
+    pub num_documents_checked: usize,
+    /// Whether every document in the schema's tables was checked. The scan
+    /// stops early once it reaches its document or violation limit.
+    pub complete: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializedSchemaDiff {
     // NOTE: not camel-case.
@@ -403,10 +403,45 @@ impl<'a, RT: Runtime> TableModel<'a, RT> {
         &mut self,
         tablet_ids: impl IntoIterator<Item = TabletId>,
     ) -> anyhow::Result<u64> {
-        let mut documents_deleted = 0;
-        let mut table_metadatas = vec![];
         // Delete all existing tables before activating the new ones.
         // This ensures that we never have duplicate table numbers, even temporarily.
+        let (documents_deleted, table_metadatas) = self.delete_replaced_tables(tablet_ids).await?;
+        for table_metadata in table_metadatas {
+            self.check_can_activate(
+                table_metadata.namespace,
+                &table_metadata.name,
+                table_metadata.number,
+            )?;
+            let (table_doc_id, mut table_metadata) = table_metadata.into_id_and_value();
+            table_metadata.state = TableState::Active;
+            SystemMetadataModel::new_global(self.tx)
+                .replace(table_doc_id, table_metadata.try_into()?)
+                .await?;
+        }
+        Ok(documents_deleted)
+    }
+
+    /// Deletes the active tables that activating the hidden tables
+    /// `tablet_ids` would replace, returning the number of documents deleted.
+    /// Tables activated over several transactions must have all of their
+    /// replaced tables deleted up front, so no table number is ever used
+    /// twice.
+    pub async fn delete_tables_replaced_by(
+        &mut self,
+        tablet_ids: impl IntoIterator<Item = TabletId>,
+    ) -> anyhow::Result<u64> {
+        let (documents_deleted, _) = self.delete_replaced_tables(tablet_ids).await?;
+        Ok(documents_deleted)
+    }
+
+    /// Returns the number of documents deleted and the metadata of the tables
+    /// in `tablet_ids` that aren't active yet.
+    async fn delete_replaced_tables(
+        &mut self,
+        tablet_ids: impl IntoIterator<Item = TabletId>,
+    ) -> anyhow::Result<(u64, Vec<ParsedDocument<TableMetadata>>)> {
+        let mut documents_deleted = 0;
+        let mut table_metadatas = vec![];
         for tablet_id in tablet_ids {
             let table_metadata = self.get_table_metadata(tablet_id).await?;
             match table_metadata.state {
@@ -431,19 +466,7 @@ impl<'a, RT: Runtime> TableModel<'a, RT> {
             }
             table_metadatas.push(table_metadata);
         }
-        for table_metadata in table_metadatas {
-            self.check_can_activate(
-                table_metadata.namespace,
-                &table_metadata.name,
-                table_metadata.number,
-            )?;
-            let (table_doc_id, mut table_metadata) = table_metadata.into_id_and_value();
-            table_metadata.state = TableState::Active;
-            SystemMetadataModel::new_global(self.tx)
-                .replace(table_doc_id, table_metadata.try_into()?)
-                .await?;
-        }
-        Ok(documents_deleted)
+        Ok((documents_deleted, table_metadatas))
     }
 
     #[async_recursion]
@@ -578,5 +601,4 @@ impl<'a, RT: Runtime> TableModel<'a, RT> {
             })
         }
     }
-
-}
\ No newline at end of file
+}
@@ -18,7 +18,10 @@ use common::{
         StableIndexName,
         WriteTimestamp,
     },
-    version::Version,
+    version::{
+        ClientVersion,
+        Version,
+    },
 };
 use errors::ErrorMetadata;
 use indexing::index_reader::{
@@ -66,11 +69,23 @@ use crate::{
 pub struct UserFacingModel<'a, RT: Runtime> {
     tx: &'a mut Transaction<RT>,
     namespace: TableNamespace,
+    client_version: Option<ClientVersion>,
 }
 
 impl<'a, RT: Runtime> UserFacingModel<'a, RT> {
     pub fn new(tx: &'a mut Transaction<RT>, namespace: TableNamespace) -> Self {
-        Self { tx, namespace }
+        Self {
+            tx,
+            namespace,
+            client_version: None,
+        }
+    }
+
+    /// Sets the client version reported in errors when a virtual table read
+    /// doesn't have an NPM version.
+    pub fn with_client_version(mut self, client_version: Option<ClientVersion>) -> Self {
+        self.client_version = client_version;
+        self
     }
 
     #[fastrace::trace]
@@ -102,6 +117,7 @@ impl<'a, RT: Runtime> UserFacingModel<'a, RT> {
         {
             log_virtual_table_get();
             VirtualTable::new(self.tx)
+                .with_client_version(self.client_version.clone())
                 .get(self.namespace, id, version)
                 .await
         } else {
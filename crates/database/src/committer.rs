@@ -1,6 +1,9 @@
 use std::{
     cmp,
-    collections::BTreeSet,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
     ops::Bound,
     sync::Arc,
 };
@@ -73,6 +76,7 @@ use common::{
         DatabaseIndexUpdate,
         DatabaseIndexValue,
         RepeatableTimestamp,
+        TabletIndexName,
         Timestamp,
         WriteTimestamp,
     },
@@ -203,6 +207,19 @@ pub struct Committer<RT: Runtime> {
     user_documents_size_gauge: Subgauge,
 
     index_cache_handle: IndexCacheHandle,
+
+    occ_conflict_counts: OccConflictCounts,
+}
+
+/// Running totals of the OCC conflicts the committer has rejected since it
+/// started, keyed by the index of the read that conflicted.
+#[derive(Clone, Default)]
+struct OccConflictCounts(Arc<Mutex<BTreeMap<TabletIndexName, u64>>>);
+
+impl OccConflictCounts {
+    fn record(&self, index: &TabletIndexName) {
+        *self.0.lock().entry(index.clone()).or_default() += 1;
+    }
 }
 
 impl<RT: Runtime> Committer<RT> {
@@ -220,6 +237,7 @@ impl<RT: Runtime> Committer<RT> {
         let conflict_checker = PendingWrites::new();
         let (tx, rx) = mpsc::channel(*COMMITTER_QUEUE_SIZE);
         let snapshot_reader = snapshot_manager.reader();
+        let occ_conflict_counts = OccConflictCounts::default();
         let committer = Self {
             pending_writes: conflict_checker,
             log,
@@ -232,6 +250,7 @@ impl<RT: Runtime> Committer<RT> {
             virtual_system_mapping,
             user_documents_size_gauge: user_documents_size_subgauge(),
             index_cache_handle,
+            occ_conflict_counts: occ_conflict_counts.clone(),
         };
         let handle = runtime.spawn("committer", async move {
             if let Err(err) = committer.go(rx).await {
@@ -248,6 +267,7 @@ impl<RT: Runtime> Committer<RT> {
             persistence_reader,
             retention_validator,
             snapshot_reader,
+            occ_conflict_counts,
         }
     }
 
@@ -729,6 +749,8 @@ impl<RT: Runtime> Committer<RT> {
             *transaction.begin_timestamp,
             commit_ts,
         )? {
+            let conflicting_index = &conflicting_read.read.index;
+            self.occ_conflict_counts.record(conflicting_index);
             anyhow::bail!(conflicting_read.into_error(
                 &transaction.table_mapping,
                 &transaction.component_registry,
@@ -1256,9 +1278,15 @@ pub struct CommitterClient {
     persistence_reader: Arc<dyn PersistenceReader>,
     retention_validator: Arc<dyn RetentionValidator>,
     snapshot_reader: Reader<SnapshotManager>,
+    occ_conflict_counts: OccConflictCounts,
 }
 
 impl CommitterClient {
+    /// The number of OCC conflicts on each index since the committer started.
+    pub fn occ_conflict_counts(&self) -> BTreeMap<TabletIndexName, u64> {
+        self.occ_conflict_counts.0.lock().clone()
+    }
+
     pub async fn finish_search_and_vector_bootstrap(
         &self,
         bootstrapped_indexes: BootstrappedSearchIndexes,
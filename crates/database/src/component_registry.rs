@@ -148,6 +148,68 @@ impl ComponentRegistry {
             .with_context(|| format!("Component {component_id:?} not found"))
     }
 
+    /// Resolves the paths of `component_ids` with a single read of the
+    /// components table, reusing the paths of ancestors shared between them.
+    /// Components that don't exist are left out of the result.
+    pub fn get_component_paths(
+        &self,
+        component_ids: impl IntoIterator<Item = ComponentId>,
+        reads: &mut TransactionReadSet,
+    ) -> BTreeMap<ComponentId, ComponentPath> {
+        reads.record_indexed_derived(
+            TabletIndexName::by_id(self.components_tablet),
+            IndexedFields::by_id(),
+            Interval::all(),
+        );
+        let mut memo = BTreeMap::from([(ComponentId::Root, Some(ComponentPath::root()))]);
+        let mut paths = BTreeMap::new();
+        for component_id in component_ids {
+            if let Some(path) = self.memoized_component_path(component_id, &mut memo) {
+                paths.insert(component_id, path);
+            }
+        }
+        paths
+    }
+
+    /// Walks up from `component_id` to the nearest ancestor in `memo`, then
+    /// fills in `memo` for every component on the way back down.
+    fn memoized_component_path(
+        &self,
+        component_id: ComponentId,
+        memo: &mut BTreeMap<ComponentId, Option<ComponentPath>>,
+    ) -> Option<ComponentPath> {
+        let mut unresolved = Vec::new();
+        let mut current = component_id;
+        let mut path = loop {
+            if let Some(path) = memo.get(&current) {
+                break path.clone();
+            }
+            let ComponentId::Child(internal_id) = current else {
+                break Some(ComponentPath::root());
+            };
+            let Some(component_doc) = self.components.get(&internal_id) else {
+                break None;
+            };
+            match &component_doc.component_type {
+                ComponentType::App => {
+                    unresolved.push((current, None));
+                    current = ComponentId::Root;
+                },
+                ComponentType::ChildComponent { parent, name, .. } => {
+                    unresolved.push((current, Some(name.clone())));
+                    current = ComponentId::Child(*parent);
+                },
+            }
+        };
+        for (id, name) in unresolved.into_iter().rev() {
+            if let Some(name) = name {
+                path = path.map(|parent_path| parent_path.join(name));
+            }
+            memo.insert(id, path.clone());
+        }
+        path
+    }
+
     pub fn all_component_paths(
         &self,
         reads: &mut TransactionReadSet,
@@ -253,27 +315,50 @@ impl ComponentRegistry {
         parent_and_name: Option<(DeveloperDocumentId, ComponentName)>,
         reads: &mut TransactionReadSet,
     ) -> anyhow::Result<Option<ParsedDocument<ComponentMetadata>>> {
-        let interval = Interval::prefix(
-            values_to_bytes(&match &parent_and_name {
-                Some((parent, name)) => {
-                    vec![Some(val!(parent.to_string())), Some(val!(name.to_string()))]
-                },
-                None => vec![Some(val!(null))],
-            })
-            .into(),
-        );
+        let (index_name, fields) = self.by_parent_index()?;
         reads.record_indexed_derived(
+            index_name,
+            fields,
+            Self::by_parent_interval(&parent_and_name),
+        );
+        Ok(self.find_in_parent(&parent_and_name))
+    }
+
+    /// The index [Self::component_in_parent] reads.
+    pub(crate) fn by_parent_index(&self) -> anyhow::Result<(TabletIndexName, IndexedFields)> {
+        Ok((
             TabletIndexName::new(
                 self.components_tablet,
                 COMPONENTS_BY_PARENT_INDEX.descriptor().clone(),
             )?,
             vec![PARENT_FIELD.clone(), NAME_FIELD.clone()].try_into()?,
-            interval,
-        );
-        let component = self
-            .components
+        ))
+    }
+
+    /// The interval of [Self::by_parent_index] that
+    /// [Self::component_in_parent] reads for `parent_and_name`.
+    pub(crate) fn by_parent_interval(
+        parent_and_name: &Option<(DeveloperDocumentId, ComponentName)>,
+    ) -> Interval {
+        Interval::prefix(
+            values_to_bytes(&match parent_and_name {
+                Some((parent, name)) => {
+                    vec![Some(val!(parent.to_string())), Some(val!(name.to_string()))]
+                },
+                None => vec![Some(val!(null))],
+            })
+            .into(),
+        )
+    }
+
+    /// Like [Self::component_in_parent], but without recording the read.
+    pub(crate) fn find_in_parent(
+        &self,
+        parent_and_name: &Option<(DeveloperDocumentId, ComponentName)>,
+    ) -> Option<ParsedDocument<ComponentMetadata>> {
+        self.components
             .iter()
-            .find(|(_, doc)| match (&parent_and_name, &doc.component_type) {
+            .find(|(_, doc)| match (parent_and_name, &doc.component_type) {
                 (Some((p, n)), ComponentType::ChildComponent { parent, name, .. })
                     if p == parent && n == name =>
                 {
@@ -282,8 +367,7 @@ impl ComponentRegistry {
                 (None, ComponentType::App) => true,
                 _ => false,
             })
-            .map(|(_, doc)| doc.clone());
-        Ok(component)
+            .map(|(_, doc)| doc.clone())
     }
 
     fn get_component(
@@ -324,3 +408,136 @@ impl Update<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use common::{
+        bootstrap_model::components::{
+            ComponentMetadata,
+            ComponentState,
+            ComponentType,
+        },
+        components::{
+            ComponentId,
+            ComponentName,
+            ComponentPath,
+        },
+        document::{
+            CreationTime,
+            ParseDocument,
+            ResolvedDocument,
+        },
+        value::ResolvedDocumentId,
+    };
+    use imbl::OrdMap;
+    use value::{
+        ConvexObject,
+        DeveloperDocumentId,
+        InternalId,
+        TableNumber,
+        TabletId,
+    };
+
+    use super::ComponentRegistry;
+    use crate::TransactionReadSet;
+
+    fn component_id(n: u8) -> anyhow::Result<DeveloperDocumentId> {
+        Ok(DeveloperDocumentId::new(
+            TableNumber::try_from(1)?,
+            InternalId::from([n; 16]),
+        ))
+    }
+
+    fn component(
+        id: DeveloperDocumentId,
+        component_type: ComponentType,
+    ) -> anyhow::Result<(DeveloperDocumentId, ResolvedDocument)> {
+        let metadata = ComponentMetadata {
+            definition_id: id,
+            component_type,
+            state: ComponentState::Active,
+            http_prefix: None,
+        };
+        let document = ResolvedDocument::new(
+            ResolvedDocumentId::new(TabletId::MIN, id),
+            CreationTime::try_from(1000.)?,
+            ConvexObject::try_from(metadata)?,
+        )?;
+        Ok((id, document))
+    }
+
+    fn child(
+        id: DeveloperDocumentId,
+        parent: DeveloperDocumentId,
+        name: &str,
+    ) -> anyhow::Result<(DeveloperDocumentId, ResolvedDocument)> {
+        component(
+            id,
+            ComponentType::ChildComponent {
+                parent,
+                name: name.parse()?,
+                args: BTreeMap::new(),
+                env: BTreeMap::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_component_paths_match_naive_walk_three_deep() -> anyhow::Result<()> {
+        let (root, a, b, c, missing) = (
+            component_id(1)?,
+            component_id(2)?,
+            component_id(3)?,
+            component_id(4)?,
+            component_id(5)?,
+        );
+        let components = [
+            component(root, ComponentType::App)?,
+            child(a, root, "a")?,
+            child(b, a, "b")?,
+            child(c, b, "c")?,
+        ]
+        .into_iter()
+        .map(|(id, document)| Ok((id, document.parse()?)))
+        .collect::<anyhow::Result<OrdMap<_, _>>>()?;
+        let registry = ComponentRegistry {
+            components_tablet: TabletId::MIN,
+            components,
+        };
+
+        // Ask for the deepest component first so its ancestors are memoized
+        // before they're asked for themselves.
+        let ids = [c, b, a, root, missing]
+            .map(ComponentId::Child)
+            .into_iter()
+            .chain([ComponentId::Root]);
+        let paths = registry.get_component_paths(ids.clone(), &mut TransactionReadSet::new());
+
+        for id in ids {
+            assert_eq!(
+                paths.get(&id),
+                registry
+                    .get_component_path(id, &mut TransactionReadSet::new())
+                    .as_ref(),
+                "{id:?}"
+            );
+        }
+        let names: Vec<ComponentName> = ["a", "b", "c"]
+            .into_iter()
+            .map(str::parse)
+            .collect::<anyhow::Result<_>>()?;
+        assert_eq!(
+            paths[&ComponentId::Child(c)],
+            ComponentPath::from(names.clone())
+        );
+        assert_eq!(
+            paths[&ComponentId::Child(a)],
+            ComponentPath::from(names[..1].to_vec())
+        );
+        assert!(paths[&ComponentId::Child(root)].is_root());
+        assert!(!paths.contains_key(&ComponentId::Child(missing)));
+        Ok(())
+    }
+}
@@ -97,6 +97,7 @@ use common::{
     },
     types::{
         GenericIndexName,
+        IndexDescriptor,
         IndexId,
         IndexName,
         PersistenceVersion,
@@ -1870,10 +1871,8 @@ impl<RT: Runtime> Database<RT> {
         ts: Timestamp,
         usage_tracker: FunctionUsageTracker,
     ) -> anyhow::Result<Transaction<RT>> {
-        let ts = {
-            let snapshot_manager = self.snapshot_manager.lock();
-            snapshot_manager.latest_ts().prior_ts(ts)?
-        };
+        let latest_ts = self.snapshot_manager.lock().latest_ts();
+        let ts = prior_ts_not_in_future(latest_ts, ts)?;
         self.begin_with_repeatable_ts(identity, ts, usage_tracker, None)
             .await
     }
@@ -1965,12 +1964,69 @@ impl<RT: Runtime> Database<RT> {
         Ok(snapshot)
     }
 
+    /// The `k` indexes whose reads hit the most OCC conflicts since this
+    /// backend started, most conflicted first. Indexes on tables that have
+    /// since been deleted are left out.
+    pub fn occ_conflict_hotspots(&self, k: usize) -> anyhow::Result<Vec<OccConflictHotspot>> {
+        let snapshot = self.latest_snapshot()?;
+        let table_mapping = snapshot.table_registry.table_mapping();
+        let mut hotspots = vec![];
+        for (index, num_conflicts) in self.committer.occ_conflict_counts() {
+            let tablet_id = *index.table();
+            let (Ok(table_name), Ok(namespace)) = (
+                table_mapping.tablet_name(tablet_id),
+                table_mapping.tablet_namespace(tablet_id),
+            ) else {
+                continue;
+            };
+            let Some(component_path) = snapshot
+                .component_registry
+                .get_component_path(ComponentId::from(namespace), &mut TransactionReadSet::new())
+            else {
+                continue;
+            };
+            hotspots.push(OccConflictHotspot {
+                component_path,
+                table_name,
+                index: index.descriptor().clone(),
+                num_conflicts,
+            });
+        }
+        hotspots.sort_by(|a, b| b.num_conflicts.cmp(&a.num_conflicts));
+        hotspots.truncate(k);
+        Ok(hotspots)
+    }
+
     pub fn latest_ts_and_snapshot(&self) -> anyhow::Result<(RepeatableTimestamp, Snapshot)> {
         Ok(self.snapshot_manager.lock().latest())
     }
 
     pub fn latest_database_snapshot(&self) -> anyhow::Result<DatabaseSnapshot<RT>> {
         let (ts, snapshot) = self.snapshot_manager.lock().latest();
+        self.database_snapshot(ts, snapshot)
+    }
+
+    /// A read-only snapshot at `ts` that can be shared across tasks, e.g. to
+    /// run concurrent [`TableIterator`]s at one consistent timestamp. Fails if
+    /// `ts` is already out of retention. Later reads are checked by the
+    /// retention validator, so a snapshot held longer than the retention
+    /// window fails with an out-of-retention error instead of observing
+    /// partially deleted history.
+    pub fn snapshot_at(
+        &self,
+        ts: RepeatableTimestamp,
+    ) -> anyhow::Result<Arc<DatabaseSnapshot<RT>>> {
+        self.retention_validator()
+            .optimistic_validate_snapshot(*ts)?;
+        let snapshot = self.snapshot(ts)?;
+        Ok(Arc::new(self.database_snapshot(ts, snapshot)?))
+    }
+
+    fn database_snapshot(
+        &self,
+        ts: RepeatableTimestamp,
+        snapshot: Snapshot,
+    ) -> anyhow::Result<DatabaseSnapshot<RT>> {
         let repeatable_persistence =
             RepeatablePersistence::new(self.reader.clone(), ts, self.retention_validator());
         Ok(DatabaseSnapshot {
@@ -2657,6 +2713,17 @@ pub struct OccRetryStats {
     pub total_duration: Duration,
 }
 
+/// An index whose reads conflicted with concurrent writes, as reported by
+/// [`Database::occ_conflict_hotspots`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OccConflictHotspot {
+    pub component_path: ComponentPath,
+    pub table_name: TableName,
+    pub index: IndexDescriptor,
+    /// Commits rejected because of a conflicting read on this index.
+    pub num_conflicts: u64,
+}
+
 /// The read that conflicted as part of an OCC
 #[derive(Debug, PartialEq, Eq)]
 pub struct ConflictingRead {
@@ -2779,3 +2846,52 @@ impl ConflictingReadWithWriteSource {
 pub fn unauthorized_error(op: &'static str) -> ErrorMetadata {
     ErrorMetadata::forbidden("Unauthorized", format!("Operation {op} not permitted"))
 }
+
+pub fn timestamp_in_future_error(ts: Timestamp, latest_ts: Timestamp) -> ErrorMetadata {
+    ErrorMetadata::bad_request(
+        "TimestampInFuture",
+        format!("Timestamp {ts} is after the latest committed timestamp {latest_ts}"),
+    )
+}
+
+/// `ts` as a repeatable timestamp, or [timestamp_in_future_error] if it's
+/// after `latest_ts`.
+fn prior_ts_not_in_future(
+    latest_ts: RepeatableTimestamp,
+    ts: Timestamp,
+) -> anyhow::Result<RepeatableTimestamp> {
+    anyhow::ensure!(ts <= *latest_ts, timestamp_in_future_error(ts, *latest_ts));
+    latest_ts.prior_ts(ts)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::types::{
+        RepeatableReason,
+        RepeatableTimestamp,
+        Timestamp,
+    };
+    use errors::ErrorMetadataAnyhowExt;
+
+    use super::prior_ts_not_in_future;
+
+    #[test]
+    fn test_begin_ts_rejects_future_timestamp() -> anyhow::Result<()> {
+        let latest_ts = RepeatableTimestamp::new_validated(
+            Timestamp::must(100),
+            RepeatableReason::SnapshotManagerLatest,
+        );
+        assert_eq!(
+            *prior_ts_not_in_future(latest_ts, Timestamp::must(99))?,
+            Timestamp::must(99)
+        );
+        assert_eq!(
+            *prior_ts_not_in_future(latest_ts, Timestamp::must(100))?,
+            Timestamp::must(100)
+        );
+        let err = prior_ts_not_in_future(latest_ts, Timestamp::must(101)).unwrap_err();
+        assert!(err.is_bad_request());
+        assert_eq!(err.short_msg(), "TimestampInFuture");
+        Ok(())
+    }
+}
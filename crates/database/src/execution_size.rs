@@ -1,4 +1,6 @@
 use common::knobs::{
+    TRANSACTION_ABSOLUTE_MAX_READ_SIZE_BYTES,
+    TRANSACTION_ABSOLUTE_MAX_READ_SIZE_ROWS,
     TRANSACTION_MAX_NUM_SCHEDULED,
     TRANSACTION_MAX_NUM_USER_WRITES,
     TRANSACTION_MAX_READ_SET_INTERVALS,
@@ -62,6 +64,27 @@ impl Default for TransactionLimits {
     }
 }
 
+/// Read limits for a single transaction, overriding the defaults so an
+/// expensive query that legitimately reads a lot can opt into a higher
+/// budget. See [`crate::Transaction::override_read_limits`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReadLimits {
+    pub bytes_read: usize,
+    pub documents_read: usize,
+}
+
+impl ReadLimits {
+    /// The highest read limits a transaction can opt into.
+    pub fn absolute_max() -> Self {
+        Self {
+            bytes_read: (*TRANSACTION_ABSOLUTE_MAX_READ_SIZE_BYTES)
+                .max(*TRANSACTION_MAX_READ_SIZE_BYTES),
+            documents_read: (*TRANSACTION_ABSOLUTE_MAX_READ_SIZE_ROWS)
+                .max(*TRANSACTION_MAX_READ_SIZE_ROWS),
+        }
+    }
+}
+
 impl TransactionLimits {
     /// Resolve a per-call `budget` (a delta on top of the transaction's
     /// current `usage`) into an absolute ceiling, clamped to the existing
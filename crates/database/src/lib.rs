@@ -60,6 +60,7 @@ pub use database_index_workers::{
 };
 pub use execution_size::{
     FunctionExecutionSize,
+    ReadLimits,
     TransactionLimits,
 };
 pub use indexing::database_index_snapshot::{
@@ -100,7 +101,10 @@ pub use transaction_index::{
     TextIndexManagerSnapshot,
     TransactionTextSnapshot,
 };
-pub use virtual_tables::VirtualTable;
+pub use virtual_tables::{
+    VirtualCursor,
+    VirtualTable,
+};
 pub use write_limits::BiggestDocumentWrites;
 pub use write_log::{
     LogReader,
@@ -124,9 +128,13 @@ pub use self::{
             COMPONENTS_TABLE,
         },
         defaults,
-        import_facing::ImportFacingModel,
+        import_facing::{
+            ImportFacingModel,
+            UpsertOutcome,
+        },
         index::{
             IndexModel,
+            IndexState,
             IndexTable,
         },
         index_backfills::{
@@ -147,6 +155,7 @@ pub use self::{
         schema::{
             types::{
                 SchemaDiff,
+                SchemaDryRunResult,
                 SerializedSchemaDiff,
             },
             SchemaModel,
@@ -173,11 +182,13 @@ pub use self::{
         user_facing::UserFacingModel,
     },
     database::{
+        timestamp_in_future_error,
         unauthorized_error,
         BootstrapMetadata,
         Database,
         DatabaseSnapshot,
         DocumentDeltas,
+        OccConflictHotspot,
         OccRetryStats,
         SnapshotPage,
         StreamingExportFilter,
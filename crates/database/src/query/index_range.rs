@@ -26,7 +26,10 @@ use common::{
         TabletIndexName,
         WriteTimestamp,
     },
-    version::Version,
+    version::{
+        ClientVersion,
+        Version,
+    },
 };
 use tokio::task;
 use value::TableNamespace;
@@ -92,6 +95,7 @@ pub struct IndexRange {
     soft_maximum_rows_read: usize,
     soft_maximum_bytes_read: usize,
     version: Option<Version>,
+    client_version: Option<ClientVersion>,
 }
 
 impl IndexRange {
@@ -157,6 +161,7 @@ impl IndexRange {
                     .min(*TRANSACTION_MAX_READ_SIZE_BYTES),
             ),
             version,
+            client_version: None,
         }
     }
 
@@ -202,6 +207,7 @@ impl IndexRange {
 
             let v = if matches!(self.stable_index_name, StableIndexName::Virtual(_, _)) {
                 VirtualTable::new(tx)
+                    .with_client_version(self.client_version.clone())
                     .system_to_virtual_doc(v.unpack(), self.version.clone())
                     .await?
             } else {
@@ -348,6 +354,10 @@ impl QueryStream for IndexRange {
     fn printable_index_name(&self) -> &IndexName {
         &self.printable_index_name
     }
+
+    fn set_client_version(&mut self, client_version: Option<ClientVersion>) {
+        self.client_version = client_version;
+    }
 }
 
 impl Drop for IndexRange {
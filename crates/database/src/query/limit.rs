@@ -9,6 +9,7 @@ use common::{
         IndexName,
         TabletIndexName,
     },
+    version::ClientVersion,
 };
 
 use super::{
@@ -80,4 +81,8 @@ impl QueryStream for Limit {
     fn printable_index_name(&self) -> &IndexName {
         self.inner.printable_index_name()
     }
+
+    fn set_client_version(&mut self, client_version: Option<ClientVersion>) {
+        self.inner.set_client_version(client_version);
+    }
 }
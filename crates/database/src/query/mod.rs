@@ -34,13 +34,17 @@ use common::{
         TabletIndexName,
         WriteTimestamp,
     },
-    version::Version,
+    version::{
+        ClientVersion,
+        Version,
+    },
 };
 use errors::ErrorMetadata;
 use futures::{
     future::BoxFuture,
     FutureExt,
 };
+use futures_async_stream::try_stream;
 use indexing::index_reader::BatchKey;
 use maplit::btreemap;
 use value::{
@@ -123,6 +127,10 @@ trait QueryStream: Send {
 
     /// For logging. All queries have an index name.
     fn printable_index_name(&self) -> &IndexName;
+
+    /// Sets the client version reported in errors when a virtual table read
+    /// doesn't have an NPM version.
+    fn set_client_version(&mut self, client_version: Option<ClientVersion>);
 }
 
 pub struct IndexRangeResponse {
@@ -528,6 +536,13 @@ impl<RT: Runtime> DeveloperQuery<RT> {
     pub fn printable_index_name(&self) -> &IndexName {
         self.root.printable_index_name()
     }
+
+    /// Sets the client version reported in errors when a virtual table read
+    /// doesn't have an NPM version.
+    pub fn with_client_version(mut self, client_version: Option<ClientVersion>) -> Self {
+        self.root.set_client_version(client_version);
+        self
+    }
 }
 
 impl<RT: Runtime> ResolvedQuery<RT> {
@@ -554,6 +569,21 @@ impl<RT: Runtime> ResolvedQuery<RT> {
             .context("batch_key missing")?
     }
 
+    /// Streams the query's remaining results, reading each one from `tx` as
+    /// the stream is polled. The stream borrows the query and `tx` until it's
+    /// dropped; afterwards the query can still be resumed with `next`, or its
+    /// cursor read, from where the stream stopped.
+    #[try_stream(ok = ResolvedDocument, error = anyhow::Error)]
+    pub async fn stream<'a>(
+        &'a mut self,
+        tx: &'a mut Transaction<RT>,
+        prefetch_hint: Option<usize>,
+    ) {
+        while let Some(document) = self.next(tx, prefetch_hint).await? {
+            yield document;
+        }
+    }
+
     pub async fn expect_at_most_one(
         &mut self,
         tx: &mut Transaction<RT>,
@@ -765,6 +795,15 @@ impl QueryStream for QueryNode {
             QueryNode::Limit(r) => r.printable_index_name(),
         }
     }
+
+    fn set_client_version(&mut self, client_version: Option<ClientVersion>) {
+        match self {
+            QueryNode::IndexRange(r) => r.set_client_version(client_version),
+            QueryNode::Search(r) => r.set_client_version(client_version),
+            QueryNode::Filter(r) => r.set_client_version(client_version),
+            QueryNode::Limit(r) => r.set_client_version(client_version),
+        }
+    }
 }
 
 /// Return a system limit for reading too many documents in a query
@@ -795,3 +834,62 @@ pub fn invalid_cursor() -> anyhow::Error {
         ))
         .context(ErrorMetadata::bad_request("InvalidCursor", message))
 }
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        bootstrap_model::tables::TABLES_TABLE,
+        query::{
+            Order,
+            Query,
+        },
+    };
+    use futures::{
+        StreamExt,
+        TryStreamExt,
+    };
+    use runtime::testing::TestRuntime;
+    use value::TableNamespace;
+
+    use super::ResolvedQuery;
+    use crate::{
+        test_helpers::DbFixtures,
+        TableModel,
+    };
+
+    #[convex_macro::test_runtime]
+    async fn test_stream_then_resume_with_next(rt: TestRuntime) -> anyhow::Result<()> {
+        let DbFixtures { db, .. } = DbFixtures::new_with_model(&rt).await?;
+        let mut tx = db.begin_system().await?;
+        for table_name in ["messages", "users", "channels"] {
+            TableModel::new(&mut tx)
+                .insert_table_metadata(TableNamespace::root_component(), &table_name.parse()?)
+                .await?;
+        }
+        db.commit_with_write_source(tx, "test").await?;
+
+        let mut tx = db.begin_system().await?;
+        let scan_tables = || Query::full_table_scan(TABLES_TABLE.clone(), Order::Asc);
+        let mut expected = vec![];
+        let mut full_query = ResolvedQuery::new(&mut tx, TableNamespace::Global, scan_tables())?;
+        while let Some(document) = full_query.next(&mut tx, None).await? {
+            expected.push(document.id());
+        }
+        assert!(expected.len() > 3);
+
+        // Read the first few documents through the stream, then pick the rest
+        // up from where it stopped.
+        let mut query = ResolvedQuery::new(&mut tx, TableNamespace::Global, scan_tables())?;
+        let mut actual: Vec<_> = query
+            .stream(&mut tx, None)
+            .take(3)
+            .map_ok(|document| document.id())
+            .try_collect()
+            .await?;
+        while let Some(document) = query.next(&mut tx, None).await? {
+            actual.push(document.id());
+        }
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+}
@@ -16,6 +16,7 @@ use common::{
         WriteTimestamp,
     },
     version::{
+        ClientVersion,
         Version,
         MIN_NPM_VERSION_FOR_FUZZY_SEARCH,
     },
@@ -63,6 +64,7 @@ pub struct SearchQuery {
     /// The start cursor will move as we produce results.
     cursor_interval: CursorInterval,
     version: Option<Version>,
+    client_version: Option<ClientVersion>,
 }
 
 impl SearchQuery {
@@ -78,6 +80,7 @@ impl SearchQuery {
             results: None,
             cursor_interval,
             version,
+            client_version: None,
         }
     }
 
@@ -116,6 +119,7 @@ impl SearchQuery {
             namespace,
             table_number,
             self.version.clone(),
+            self.client_version.clone(),
         ))
     }
 
@@ -188,6 +192,10 @@ impl QueryStream for SearchQuery {
     fn printable_index_name(&self) -> &IndexName {
         &self.query.index_name
     }
+
+    fn set_client_version(&mut self, client_version: Option<ClientVersion>) {
+        self.client_version = client_version;
+    }
 }
 
 #[derive(Clone)]
@@ -198,6 +206,7 @@ struct SearchResultIterator {
     next_index: usize,
     bytes_read: usize,
     version: Option<Version>,
+    client_version: Option<ClientVersion>,
 }
 
 impl SearchResultIterator {
@@ -206,6 +215,7 @@ impl SearchResultIterator {
         namespace: TableNamespace,
         table_number: TableNumber,
         version: Option<Version>,
+        client_version: Option<ClientVersion>,
     ) -> Self {
         Self {
             namespace,
@@ -214,6 +224,7 @@ impl SearchResultIterator {
             next_index: 0,
             bytes_read: 0,
             version,
+            client_version,
         }
     }
 
@@ -250,6 +261,7 @@ impl SearchResultIterator {
 
         let id = DeveloperDocumentId::new(self.table_number, candidate.id);
         let (document, existing_doc_ts) = UserFacingModel::new(tx, self.namespace)
+            .with_client_version(self.client_version.clone())
             .get_with_ts(id, self.version.clone())
             .await?
             .ok_or_else(|| {
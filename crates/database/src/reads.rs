@@ -345,6 +345,19 @@ pub struct TransactionReadSize {
     pub total_document_count: usize,
 }
 
+impl TransactionReadSize {
+    fn saturating_sub(&self, other: &Self) -> Self {
+        Self {
+            total_document_size: self
+                .total_document_size
+                .saturating_sub(other.total_document_size),
+            total_document_count: self
+                .total_document_count
+                .saturating_sub(other.total_document_count),
+        }
+    }
+}
+
 impl TransactionReadSet {
     /// Create a read-set at the given timestamp.
     pub fn new() -> Self {
@@ -360,6 +373,29 @@ impl TransactionReadSet {
         self.read_set
     }
 
+    /// Returns an empty read set that starts with this one's counts, so reads
+    /// recorded into it are checked against the limits for the transaction as
+    /// a whole. Pass this read set to [Self::into_snooped_reads] afterwards.
+    pub fn start_snoop(&self) -> Self {
+        Self {
+            read_set: ReadSet::empty(),
+            num_intervals: self.num_intervals,
+            user_tx_size: self.user_tx_size.clone(),
+            system_tx_size: self.system_tx_size.clone(),
+        }
+    }
+
+    /// Turns a read set from `saved.start_snoop()` into just the reads
+    /// recorded into it, so it can be merged back into `saved`.
+    pub fn into_snooped_reads(self, saved: &Self) -> Self {
+        Self {
+            read_set: self.read_set,
+            num_intervals: self.num_intervals.saturating_sub(saved.num_intervals),
+            user_tx_size: self.user_tx_size.saturating_sub(&saved.user_tx_size),
+            system_tx_size: self.system_tx_size.saturating_sub(&saved.system_tx_size),
+        }
+    }
+
     pub fn read_set(&self) -> &ReadSet {
         &self.read_set
     }
@@ -524,11 +560,24 @@ impl TransactionReadSet {
         fields: IndexedFields,
         interval: Interval,
         limits: &TransactionLimits,
+    ) -> anyhow::Result<()> {
+        self.record_indexed_directly_batch(index_name, fields, [interval], limits)
+    }
+
+    /// Like `record_indexed_directly`, but records many intervals on the same
+    /// index with a single read set lookup and limit check. The resulting
+    /// read set is the same as recording each interval individually.
+    pub fn record_indexed_directly_batch(
+        &mut self,
+        index_name: TabletIndexName,
+        fields: IndexedFields,
+        intervals: impl IntoIterator<Item = Interval>,
+        limits: &TransactionLimits,
     ) -> anyhow::Result<()> {
         let _s = static_span!();
 
         let (num_intervals_before, num_intervals_after) =
-            self._record_indexed(index_name, fields, [interval]);
+            self._record_indexed(index_name, fields, intervals);
 
         self.num_intervals = self.num_intervals.saturating_sub(num_intervals_before);
         self.num_intervals += num_intervals_after;
@@ -587,3 +636,162 @@ impl TransactionReadSet {
         &self.system_tx_size
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        bootstrap_model::index::database_index::IndexedFields,
+        components::ComponentPath,
+        interval::{
+            BinaryKey,
+            Interval,
+        },
+        types::TabletIndexName,
+        virtual_system_mapping::VirtualSystemMapping,
+    };
+    use errors::ErrorMetadataAnyhowExt;
+    use usage_tracking::FunctionUsageTracker;
+    use value::{
+        InternalId,
+        TableName,
+        TabletId,
+    };
+
+    use super::TransactionReadSet;
+    use crate::execution_size::TransactionLimits;
+
+    fn indexed_reads(reads: &TransactionReadSet) -> Vec<(TabletIndexName, Vec<Interval>)> {
+        reads
+            .read_set()
+            .iter_indexed()
+            .map(|(index_name, index_reads)| {
+                (index_name.clone(), index_reads.intervals.iter().collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batched_reads_match_individual_reads() -> anyhow::Result<()> {
+        let index_name = TabletIndexName::by_id(TabletId(InternalId([1; 16])));
+        let limits = TransactionLimits::default();
+        // Overlapping and duplicate intervals should be merged the same way.
+        let intervals = vec![
+            Interval::prefix(BinaryKey::from(vec![1])),
+            Interval::prefix(BinaryKey::from(vec![1, 2])),
+            Interval::prefix(BinaryKey::from(vec![3])),
+            Interval::prefix(BinaryKey::from(vec![3])),
+        ];
+
+        let mut individual = TransactionReadSet::new();
+        for interval in intervals.clone() {
+            individual.record_indexed_directly(
+                index_name.clone(),
+                IndexedFields::by_id(),
+                interval,
+                &limits,
+            )?;
+        }
+        let mut batched = TransactionReadSet::new();
+        batched.record_indexed_directly_batch(
+            index_name,
+            IndexedFields::by_id(),
+            intervals,
+            &limits,
+        )?;
+
+        assert_eq!(indexed_reads(&batched), indexed_reads(&individual));
+        assert_eq!(batched.num_intervals(), individual.num_intervals());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_document_over_lowered_limit() -> anyhow::Result<()> {
+        let limits = TransactionLimits {
+            documents_read: 2,
+            bytes_read: 1000,
+            ..TransactionLimits::default()
+        };
+        let table_name: TableName = "messages".parse()?;
+        let usage_tracker = FunctionUsageTracker::new();
+        let virtual_system_mapping = VirtualSystemMapping::default();
+        let mut reads = TransactionReadSet::new();
+        let read_document = |reads: &mut TransactionReadSet, size: usize| {
+            reads.record_read_document(
+                ComponentPath::root(),
+                table_name.clone(),
+                size,
+                &usage_tracker,
+                &virtual_system_mapping,
+                &limits,
+            )
+        };
+
+        read_document(&mut reads, 100)?;
+        read_document(&mut reads, 100)?;
+        let err = read_document(&mut reads, 100).unwrap_err();
+        assert!(err.is_pagination_limit());
+        assert_eq!(err.short_msg(), "TooManyDocumentsRead");
+        assert!(err.msg().contains("(limit: 2)"), "{}", err.msg());
+
+        let mut reads = TransactionReadSet::new();
+        let err = read_document(&mut reads, 1001).unwrap_err();
+        assert_eq!(err.short_msg(), "TooManyBytesRead");
+        assert!(err.msg().contains("(limit: 1000 bytes)"), "{}", err.msg());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snooped_reads_count_towards_limits() -> anyhow::Result<()> {
+        let limits = TransactionLimits {
+            documents_read: 2,
+            ..TransactionLimits::default()
+        };
+        let index_name = TabletIndexName::by_id(TabletId(InternalId([1; 16])));
+        let table_name: TableName = "messages".parse()?;
+        let usage_tracker = FunctionUsageTracker::new();
+        let virtual_system_mapping = VirtualSystemMapping::default();
+        let read_document = |reads: &mut TransactionReadSet| {
+            reads.record_read_document(
+                ComponentPath::root(),
+                table_name.clone(),
+                100,
+                &usage_tracker,
+                &virtual_system_mapping,
+                &limits,
+            )
+        };
+
+        let mut reads = TransactionReadSet::new();
+        read_document(&mut reads)?;
+        reads.record_indexed_directly(
+            index_name.clone(),
+            IndexedFields::by_id(),
+            Interval::prefix(BinaryKey::from(vec![1])),
+            &limits,
+        )?;
+
+        let mut snooped = reads.start_snoop();
+        assert!(indexed_reads(&snooped).is_empty());
+        read_document(&mut snooped)?;
+        snooped.record_indexed_directly(
+            index_name.clone(),
+            IndexedFields::by_id(),
+            Interval::prefix(BinaryKey::from(vec![2])),
+            &limits,
+        )?;
+        // The transaction has read two documents, so a third is over the
+        // limit even though only one was read while snooping.
+        let err = read_document(&mut snooped.clone()).unwrap_err();
+        assert_eq!(err.short_msg(), "TooManyDocumentsRead");
+
+        let snooped = snooped.into_snooped_reads(&reads);
+        assert_eq!(snooped.user_tx_size().total_document_count, 1);
+        assert_eq!(snooped.user_tx_size().total_document_size, 100);
+        assert_eq!(snooped.num_intervals(), 1);
+        assert_eq!(
+            indexed_reads(&snooped),
+            vec![(index_name, vec![Interval::prefix(BinaryKey::from(vec![2]))])]
+        );
+        Ok(())
+    }
+}
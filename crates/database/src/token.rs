@@ -1,11 +1,38 @@
 //! Externalizable tokens that record the currently-observed state within a
 //! transaction.
 
-use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+};
 
-use common::types::Timestamp;
-use value::heap_size::HeapSize;
-use crate::reads::ReadSet;
+use common::{
+    bootstrap_model::index::database_index::IndexedFields,
+    interval::{
+        End,
+        Interval,
+        IntervalSet,
+        StartIncluded,
+    },
+    paths::FieldPath,
+    types::{
+        TabletIndexName,
+        Timestamp,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use value::{
+    base64,
+    heap_size::HeapSize,
+};
+
+use crate::reads::{
+    IndexReads,
+    ReadSet,
+};
 
 /// Serialized representation of [`Token`].
 pub type SerializedToken = String;
@@ -50,6 +77,103 @@ impl Token {
         assert!(self.ts < ts);
         self.ts = ts;
     }
+
+    /// Serialize the token's timestamp and index reads. Tokens with search
+    /// reads can't be serialized.
+    pub fn serialize(&self) -> anyhow::Result<SerializedToken> {
+        anyhow::ensure!(
+            self.read_set.iter_search().next().is_none(),
+            "Tokens with search reads can't be serialized"
+        );
+        let indexed = self
+            .read_set
+            .iter_indexed()
+            .map(|(index, reads)| SerializedIndexReads {
+                index: index.to_string(),
+                fields: reads.fields.iter().map(|f| f.to_string()).collect(),
+                intervals: reads
+                    .intervals
+                    .iter()
+                    .map(|Interval { start, end }| SerializedInterval {
+                        start: match start {
+                            StartIncluded(key) => base64::encode_urlsafe(&key),
+                        },
+                        end: match end {
+                            End::Excluded(key) => Some(base64::encode_urlsafe(&key)),
+                            End::Unbounded => None,
+                        },
+                    })
+                    .collect(),
+            })
+            .collect();
+        let token = SerializedTokenContents {
+            ts: self.ts.into(),
+            indexed,
+        };
+        Ok(serde_json::to_string(&token)?)
+    }
+
+    pub fn deserialize(token: &str) -> anyhow::Result<Self> {
+        let SerializedTokenContents { ts, indexed } = serde_json::from_str(token)?;
+        let mut reads = BTreeMap::new();
+        for SerializedIndexReads {
+            index,
+            fields,
+            intervals,
+        } in indexed
+        {
+            let index: TabletIndexName = index.parse()?;
+            let fields = fields
+                .iter()
+                .map(|f| f.parse::<FieldPath>())
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let mut interval_set = IntervalSet::new();
+            for SerializedInterval { start, end } in intervals {
+                let start = StartIncluded(base64::decode_urlsafe(&start)?.into());
+                let end = match end {
+                    Some(end) => End::Excluded(base64::decode_urlsafe(&end)?.into()),
+                    None => End::Unbounded,
+                };
+                interval_set.add(Interval { start, end });
+            }
+            let index_reads = IndexReads {
+                fields: IndexedFields::try_from(fields)?,
+                intervals: interval_set,
+                stack_traces: None,
+            };
+            anyhow::ensure!(
+                reads.insert(index, index_reads).is_none(),
+                "Duplicate index in serialized token"
+            );
+        }
+        Ok(Self {
+            read_set: Arc::new(ReadSet::new(reads, BTreeMap::new())),
+            ts: ts.try_into()?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SerializedTokenContents {
+    ts: u64,
+    indexed: Vec<SerializedIndexReads>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SerializedIndexReads {
+    index: String,
+    fields: Vec<String>,
+    intervals: Vec<SerializedInterval>,
+}
+
+/// Keys are URL-safe base64. A missing `end` is unbounded.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SerializedInterval {
+    start: String,
+    end: Option<String>,
 }
 
 impl HeapSize for Token {
@@ -57,3 +181,166 @@ impl HeapSize for Token {
         self.read_set.heap_size()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::Arc,
+    };
+
+    use common::{
+        bootstrap_model::index::database_index::IndexedFields,
+        interval::{
+            BinaryKey,
+            End,
+            Interval,
+            IntervalSet,
+            StartIncluded,
+        },
+        types::{
+            IndexDescriptor,
+            TabletIndexName,
+            Timestamp,
+        },
+    };
+    use search::QueryReads as SearchQueryReads;
+    use serde_json::Value as JsonValue;
+    use value::{
+        InternalId,
+        TabletId,
+    };
+
+    use super::Token;
+    use crate::reads::{
+        IndexReads,
+        ReadSet,
+    };
+
+    fn index_reads(fields: IndexedFields, intervals: Vec<Interval>) -> IndexReads {
+        let mut interval_set = IntervalSet::new();
+        for interval in intervals {
+            interval_set.add(interval);
+        }
+        IndexReads {
+            fields,
+            intervals: interval_set,
+            stack_traces: None,
+        }
+    }
+
+    fn indexed_reads(token: &Token) -> Vec<(TabletIndexName, IndexedFields, Vec<Interval>)> {
+        token
+            .reads()
+            .iter_indexed()
+            .map(|(index, reads)| {
+                (
+                    index.clone(),
+                    reads.fields.clone(),
+                    reads.intervals.iter().collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip_with_multiple_indexes() -> anyhow::Result<()> {
+        let tablet_id = TabletId(InternalId([1; 16]));
+        let by_id = TabletIndexName::by_id(tablet_id);
+        let by_author = TabletIndexName::new(tablet_id, IndexDescriptor::new("by_author")?)?;
+        let reads = BTreeMap::from([
+            (
+                by_id,
+                index_reads(
+                    IndexedFields::by_id(),
+                    vec![
+                        Interval::prefix(BinaryKey::from(vec![1])),
+                        Interval::prefix(BinaryKey::from(vec![3, 4])),
+                    ],
+                ),
+            ),
+            (
+                by_author,
+                index_reads(
+                    IndexedFields::try_from(vec!["author".parse()?])?,
+                    vec![Interval {
+                        start: StartIncluded(BinaryKey::from(vec![5])),
+                        end: End::Unbounded,
+                    }],
+                ),
+            ),
+        ]);
+        let token = Token::new(
+            Arc::new(ReadSet::new(reads, BTreeMap::new())),
+            Timestamp::try_from(1000u64)?,
+        );
+
+        let deserialized = Token::deserialize(&token.serialize()?)?;
+        assert_eq!(deserialized.ts(), token.ts());
+        assert_eq!(indexed_reads(&deserialized), indexed_reads(&token));
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip_unbounded_end() -> anyhow::Result<()> {
+        let index = TabletIndexName::by_id(TabletId(InternalId([1; 16])));
+        let reads = BTreeMap::from([(
+            index,
+            index_reads(IndexedFields::by_id(), vec![Interval::all()]),
+        )]);
+        let token = Token::new(
+            Arc::new(ReadSet::new(reads, BTreeMap::new())),
+            Timestamp::try_from(1000u64)?,
+        );
+
+        let deserialized = Token::deserialize(&token.serialize()?)?;
+        assert_eq!(indexed_reads(&deserialized)[0].2, vec![Interval::all()],);
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_reads_are_rejected() -> anyhow::Result<()> {
+        let index = TabletIndexName::new(
+            TabletId(InternalId([1; 16])),
+            IndexDescriptor::new("search_body")?,
+        )?;
+        let token = Token::new(
+            Arc::new(ReadSet::new(
+                BTreeMap::new(),
+                BTreeMap::from([(index, SearchQueryReads::empty())]),
+            )),
+            Timestamp::try_from(1000u64)?,
+        );
+        let err = token.serialize().unwrap_err();
+        assert!(
+            err.to_string().contains("search reads can't be serialized"),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_index_is_rejected() -> anyhow::Result<()> {
+        let index = TabletIndexName::by_id(TabletId(InternalId([1; 16])));
+        let reads = BTreeMap::from([(
+            index,
+            index_reads(
+                IndexedFields::by_id(),
+                vec![Interval::prefix(BinaryKey::from(vec![1]))],
+            ),
+        )]);
+        let token = Token::new(
+            Arc::new(ReadSet::new(reads, BTreeMap::new())),
+            Timestamp::try_from(1000u64)?,
+        );
+        let mut serialized: JsonValue = serde_json::from_str(&token.serialize()?)?;
+        let indexed = serialized["indexed"]
+            .as_array_mut()
+            .expect("indexed must be an array");
+        indexed.push(indexed[0].clone());
+
+        let err = Token::deserialize(&serialized.to_string()).unwrap_err();
+        assert!(err.to_string().contains("Duplicate index"), "{err}");
+        Ok(())
+    }
+}
@@ -26,6 +26,8 @@ use common::{
     components::{
         ComponentId,
         ComponentPath,
+        Reference,
+        Resource,
     },
     document::{
         CreationTime,
@@ -90,6 +92,7 @@ use search::{
     CandidateRevision,
 };
 use sync_types::{
+    path::PathComponent,
     AuthenticationToken,
     Timestamp,
 };
@@ -117,6 +120,7 @@ use crate::{
     committer::table_dependency_sort_key,
     execution_size::{
         FunctionExecutionSize,
+        ReadLimits,
         ScheduledFunctionsSize,
         TransactionLimits,
     },
@@ -191,6 +195,19 @@ pub struct Transaction<RT: Runtime> {
     pub usage_tracker: FunctionUsageTracker,
     pub(crate) virtual_system_mapping: VirtualSystemMapping,
 
+    /// Memoized component resources, along with the reads made to compute
+    /// them. Cleared by any write to a system table.
+    pub(crate) component_resources_cache: ComponentResourcesCache,
+
+}
+
+/// Transaction-scoped cache for `ComponentsModel::preload_resources` and
+/// `ComponentsModel::preload_exported_resources`.
+#[derive(Clone, Default)]
+pub(crate) struct ComponentResourcesCache {
+    resources: BTreeMap<ComponentId, (BTreeMap<Reference, Resource>, TransactionReadSet)>,
+    exported_resources:
+        BTreeMap<ComponentId, (BTreeMap<Vec<PathComponent>, Resource>, TransactionReadSet)>,
 }
 
 #[async_trait]
@@ -243,6 +260,7 @@ impl<RT: Runtime> Transaction<RT> {
             runtime,
             usage_tracker,
             virtual_system_mapping,
+            component_resources_cache: ComponentResourcesCache::default(),
         }
     }
 
@@ -447,6 +465,32 @@ impl<RT: Runtime> Transaction<RT> {
         self.limits = TransactionLimits::from_budget(budget, &usage, &self.limits);
     }
 
+    /// Replace this transaction's read limits, e.g. to let an expensive
+    /// analytical query read more than the defaults allow. Unlike
+    /// `set_transaction_limits`, this can loosen the limits, but only up to
+    /// [`ReadLimits::absolute_max`].
+    pub fn override_read_limits(&mut self, limits: ReadLimits) -> anyhow::Result<()> {
+        let absolute_max = ReadLimits::absolute_max();
+        anyhow::ensure!(
+            limits.documents_read <= absolute_max.documents_read
+                && limits.bytes_read <= absolute_max.bytes_read,
+            ErrorMetadata::bad_request(
+                "ReadLimitTooHigh",
+                format!(
+                    "Read limits of {} documents and {} bytes exceed the maximum of {} documents \
+                     and {} bytes",
+                    limits.documents_read,
+                    limits.bytes_read,
+                    absolute_max.documents_read,
+                    absolute_max.bytes_read,
+                ),
+            )
+        );
+        self.limits.documents_read = limits.documents_read;
+        self.limits.bytes_read = limits.bytes_read;
+        Ok(())
+    }
+
     pub fn user_tx_read_size(&self) -> &TransactionReadSize {
         self.reads.user_tx_size()
     }
@@ -608,6 +652,65 @@ impl<RT: Runtime> Transaction<RT> {
         Ok(new_document_view)
     }
 
+    /// Applies a patch to each of the given documents in a single pass,
+    /// returning the number of documents that changed.
+    ///
+    /// Every patched document is validated against its table's schema, and the
+    /// combined size of the writes is checked against the transaction's limits
+    /// once, before any of them are applied. So a batch that fails validation
+    /// or is too large leaves no partial writes behind. Each document may only
+    /// appear once in `updates`.
+    #[convex_macro::instrument_future]
+    pub async fn patch_many(
+        &mut self,
+        updates: Vec<(ResolvedDocumentId, PatchValue)>,
+    ) -> anyhow::Result<usize> {
+        let mut seen = BTreeSet::new();
+        let mut patched = Vec::with_capacity(updates.len());
+        let mut user_write_size = self.writes.user_size().clone();
+        for (id, value) in updates {
+            task::consume_budget().await;
+            anyhow::ensure!(
+                seen.insert(id),
+                ErrorMetadata::bad_request(
+                    "DuplicatePatch",
+                    format!("Document ID {id} is patched more than once"),
+                )
+            );
+            let table_name = self.table_mapping().tablet_name(id.tablet_id)?;
+            let namespace = self.table_mapping().tablet_namespace(id.tablet_id)?;
+            let (old_document, old_ts) =
+                self.get_inner(id, table_name)
+                    .await?
+                    .context(ErrorMetadata::bad_request(
+                        "NonexistentDocument",
+                        format!("Update on nonexistent document ID {id}"),
+                    ))?;
+            let old_pending = self.old_pending_document(&old_document, old_ts)?;
+            let new_body = value.apply(old_pending.clone().into_pending_value())?;
+            let new_document = PendingDocument::new(id, old_document.creation_time(), new_body)?;
+            if new_document == old_pending {
+                continue;
+            }
+            let new_document_view = new_document.to_document_with_max_commit_ts()?;
+            SchemaModel::new(self, namespace)
+                .enforce(&new_document_view)
+                .await?;
+            if !self.table_mapping().is_system_tablet(id.tablet_id) {
+                user_write_size.num_writes += 1;
+                user_write_size.size += new_document_view.size();
+            }
+            patched.push((id, (old_document, old_ts), new_document));
+        }
+        user_write_size.check_user_limits(&self.limits)?;
+
+        let num_patched = patched.len();
+        for (id, old_document_and_ts, new_document) in patched {
+            self.apply_validated_write(id, Some(old_document_and_ts), Some(new_document))?;
+        }
+        Ok(num_patched)
+    }
+
     /// The current revision of a document as a [`PendingDocument`]: the staged
     /// write when `old_ts` is pending, the committed document otherwise.
     fn old_pending_document(
@@ -777,6 +880,19 @@ impl<RT: Runtime> Transaction<RT> {
         &self.stats
     }
 
+    /// Records read dependencies on many intervals of one index at once, for
+    /// callers like preloading that resolve many documents from in-memory
+    /// system table state rather than through queries.
+    pub fn record_indexed_reads(
+        &mut self,
+        index_name: TabletIndexName,
+        fields: IndexedFields,
+        intervals: impl IntoIterator<Item = Interval>,
+    ) -> anyhow::Result<()> {
+        self.reads
+            .record_indexed_directly_batch(index_name, fields, intervals, &self.limits)
+    }
+
     pub(crate) fn take_table_mapping_dep(&mut self) {
         let tables_by_id = TabletIndexName::by_id(
             self.metadata
@@ -826,6 +942,14 @@ impl<RT: Runtime> Transaction<RT> {
             .must_component_path(component_id, &mut self.reads)
     }
 
+    pub fn get_component_paths(
+        &mut self,
+        component_ids: impl IntoIterator<Item = ComponentId>,
+    ) -> BTreeMap<ComponentId, ComponentPath> {
+        self.component_registry
+            .get_component_paths(component_ids, &mut self.reads)
+    }
+
     /// Get the component path for a tablet. This might be None when table
     /// namespaces for new components are created in `start_push`,  but
     /// components have not yet been created.
@@ -1051,65 +1175,87 @@ impl<RT: Runtime> Transaction<RT> {
         id: ResolvedDocumentId,
         table_name: TableName,
     ) -> anyhow::Result<Option<(ResolvedDocument, WriteTimestamp)>> {
-        let index_name = TabletIndexName::by_id(id.tablet_id);
-        let printable_index_name = IndexName::by_id(table_name.clone());
-        let index_key = IndexKey::new(vec![], id.into());
-        let interval = Interval::singleton(index_key.to_bytes().into());
-        let range_request = RangeRequest {
-            index_name: index_name.clone(),
-            printable_index_name,
-            interval: interval.clone(),
-            order: Order::Asc,
-            // Request 2 to best-effort verify uniqueness of by_id index.
-            max_size: 2,
-        };
-
         let [result] = self
-            .index
-            .range_batch(&[&range_request])
-            .await
+            .get_inner_batch(vec![(id, table_name)])
+            .await?
             .try_into()
             .map_err(|_| anyhow::anyhow!("expected result"))?;
-        self.reads.record_indexed_directly(
-            index_name,
-            IndexedFields::by_id(),
-            interval,
-            &self.limits,
-        )?;
-        let IndexRangeResponse {
-            page: range_results,
-            cursor,
-        } = result?;
-        if range_results.len() > 1 {
-            Err(anyhow::anyhow!("Got multiple values for id {id:?}"))?;
-        }
-        if !matches!(cursor, CursorPosition::End) {
-            Err(anyhow::anyhow!(
-                "Querying 2 items for a single id didn't exhaust interval for {id:?}"
-            ))?;
-        }
-        let result = match range_results.into_iter().next() {
-            Some((_, doc, timestamp)) => {
-                let component_path = self
-                    .component_path_for_tablet_id(id.tablet_id)?
-                    .unwrap_or_default();
-                self.reads.record_read_document(
-                    component_path,
-                    table_name,
-                    doc.size(),
-                    &self.usage_tracker,
-                    &self.virtual_system_mapping,
-                    &self.limits,
-                )?;
-
-                Some((doc.unpack(), timestamp))
-            },
-            None => None,
-        };
-        self.stats.entry(id.tablet_id).or_default().rows_read += 1;
         Ok(result)
     }
 
+    /// Like [Self::get_inner], but fetches all of `ids` in a single batch of
+    /// index reads. Reads are recorded per ID exactly as `get_inner` records
+    /// them, and results are returned in the same order as `ids`.
+    pub(crate) async fn get_inner_batch(
+        &mut self,
+        ids: Vec<(ResolvedDocumentId, TableName)>,
+    ) -> anyhow::Result<Vec<Option<(ResolvedDocument, WriteTimestamp)>>> {
+        let range_requests: Vec<_> = ids
+            .iter()
+            .map(|(id, table_name)| {
+                let index_key = IndexKey::new(vec![], (*id).into());
+                RangeRequest {
+                    index_name: TabletIndexName::by_id(id.tablet_id),
+                    printable_index_name: IndexName::by_id(table_name.clone()),
+                    interval: Interval::singleton(index_key.to_bytes().into()),
+                    order: Order::Asc,
+                    // Request 2 to best-effort verify uniqueness of by_id index.
+                    max_size: 2,
+                }
+            })
+            .collect();
+        let results = self
+            .index
+            .range_batch(&range_requests.iter().collect::<Vec<_>>())
+            .await;
+        anyhow::ensure!(results.len() == ids.len(), "expected result");
+
+        let mut out = Vec::with_capacity(ids.len());
+        for (((id, table_name), range_request), result) in
+            ids.into_iter().zip(range_requests).zip(results)
+        {
+            self.reads.record_indexed_directly(
+                range_request.index_name,
+                IndexedFields::by_id(),
+                range_request.interval,
+                &self.limits,
+            )?;
+            let IndexRangeResponse {
+                page: range_results,
+                cursor,
+            } = result?;
+            if range_results.len() > 1 {
+                Err(anyhow::anyhow!("Got multiple values for id {id:?}"))?;
+            }
+            if !matches!(cursor, CursorPosition::End) {
+                Err(anyhow::anyhow!(
+                    "Querying 2 items for a single id didn't exhaust interval for {id:?}"
+                ))?;
+            }
+            let result = match range_results.into_iter().next() {
+                Some((_, doc, timestamp)) => {
+                    let component_path = self
+                        .component_path_for_tablet_id(id.tablet_id)?
+                        .unwrap_or_default();
+                    self.reads.record_read_document(
+                        component_path,
+                        table_name,
+                        doc.size(),
+                        &self.usage_tracker,
+                        &self.virtual_system_mapping,
+                        &self.limits,
+                    )?;
+
+                    Some((doc.unpack(), timestamp))
+                },
+                None => None,
+            };
+            self.stats.entry(id.tablet_id).or_default().rows_read += 1;
+            out.push(result);
+        }
+        Ok(out)
+    }
+
     /// Apply a validated write to the [Transaction], updating the
     /// [IndexRegistry] and [TableRegistry]. Validated means the write
     /// has already been checked for schema enforcement.
@@ -1124,6 +1270,11 @@ impl<RT: Runtime> Transaction<RT> {
         // metadata and then let inserting into writes the commit
         // point so that the Transaction is never in an inconsistent state.
         let is_system_document = self.table_mapping().is_system_tablet(id.tablet_id);
+        if is_system_document {
+            // Component resources are derived from system tables
+            // (`_components`, `_component_definitions`, `_modules`).
+            self.component_resources_cache = ComponentResourcesCache::default();
+        }
         let new_document_view = new_document
             .as_ref()
             .map(|d| d.to_document_with_max_commit_ts())
@@ -1305,6 +1456,76 @@ impl<RT: Runtime> Transaction<RT> {
         SnoopedTransaction::new(self)
     }
 
+    /// Like [Self::snoop_reads], but for a borrowed transaction. Returns the
+    /// reads recorded so far, which must be passed to
+    /// [Self::finish_snoop_reads] to resume recording into them. Reads made
+    /// while snooping still count towards the transaction's read limits.
+    pub fn start_snoop_reads(&mut self) -> TransactionReadSet {
+        let snooped_reads = self.reads.start_snoop();
+        mem::replace(&mut self.reads, snooped_reads)
+    }
+
+    /// Restores the reads returned by [Self::start_snoop_reads], merges in the
+    /// reads recorded since, and returns those.
+    pub fn finish_snoop_reads(&mut self, saved_read_set: TransactionReadSet) -> TransactionReadSet {
+        let snooped_reads =
+            mem::replace(&mut self.reads, saved_read_set).into_snooped_reads(&self.reads);
+        self.apply_reads(snooped_reads.clone());
+        snooped_reads
+    }
+
+    /// Returns the cached resources for `component_id`, recording the reads
+    /// made to compute them as if they were recomputed.
+    pub fn cached_component_resources(
+        &mut self,
+        component_id: ComponentId,
+    ) -> Option<BTreeMap<Reference, Resource>> {
+        let (resources, reads) = self
+            .component_resources_cache
+            .resources
+            .get(&component_id)?
+            .clone();
+        self.apply_reads(reads);
+        Some(resources)
+    }
+
+    pub fn cache_component_resources(
+        &mut self,
+        component_id: ComponentId,
+        resources: BTreeMap<Reference, Resource>,
+        reads: TransactionReadSet,
+    ) {
+        self.component_resources_cache
+            .resources
+            .insert(component_id, (resources, reads));
+    }
+
+    /// Returns the cached exported resources for `component_id`, recording the
+    /// reads made to compute them as if they were recomputed.
+    pub fn cached_component_exported_resources(
+        &mut self,
+        component_id: ComponentId,
+    ) -> Option<BTreeMap<Vec<PathComponent>, Resource>> {
+        let (resources, reads) = self
+            .component_resources_cache
+            .exported_resources
+            .get(&component_id)?
+            .clone();
+        self.apply_reads(reads);
+        Some(resources)
+    }
+
+    pub fn cache_component_exported_resources(
+        &mut self,
+        component_id: ComponentId,
+        resources: BTreeMap<Vec<PathComponent>, Resource>,
+        reads: TransactionReadSet,
+    ) {
+        self.component_resources_cache
+            .exported_resources
+            .insert(component_id, (resources, reads));
+    }
+
     pub fn finalize(self) -> anyhow::Result<FinalTransaction> {
         FinalTransaction::new(self)
     }
@@ -1340,6 +1561,7 @@ impl<RT: Runtime> Transaction<RT> {
             runtime: self.runtime.clone(),
             usage_tracker: self.usage_tracker.clone(),
             virtual_system_mapping: self.virtual_system_mapping.clone(),
+            component_resources_cache: ComponentResourcesCache::default(),
 
         }
     }
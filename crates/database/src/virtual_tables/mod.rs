@@ -1,3 +1,8 @@
+use std::collections::{
+    btree_map::Entry,
+    BTreeMap,
+};
+
 use anyhow::Context;
 use async_trait::async_trait;
 use common::{
@@ -5,9 +10,18 @@ use common::{
         DeveloperDocument,
         ResolvedDocument,
     },
+    query::{
+        Cursor,
+        CursorPosition,
+        IndexRange,
+        Query,
+    },
     runtime::Runtime,
     types::WriteTimestamp,
-    version::Version,
+    version::{
+        ClientVersion,
+        Version,
+    },
     virtual_system_mapping::GetDocument,
 };
 use errors::ErrorMetadata;
@@ -17,7 +31,14 @@ use value::{
     TableNamespace,
 };
 
-use crate::Transaction;
+use crate::{
+    query::{
+        PaginationOptions,
+        TableFilter,
+    },
+    DeveloperQuery,
+    Transaction,
+};
 
 #[async_trait]
 impl<RT: Runtime> GetDocument for Transaction<RT> {
@@ -31,11 +52,32 @@ impl<RT: Runtime> GetDocument for Transaction<RT> {
 
 pub struct VirtualTable<'a, RT: Runtime> {
     tx: &'a mut Transaction<RT>,
+    /// The client that issued the request, if known, for error messages.
+    client_version: Option<ClientVersion>,
+}
+
+/// Where a page returned by [VirtualTable::index_range_paginated] stopped.
+/// It carries the index range and order being read, so the next page can be
+/// read with [VirtualTable::continue_paginated] without rebuilding the range.
+#[derive(Clone, Debug)]
+pub struct VirtualCursor {
+    index_range: IndexRange,
+    cursor: Cursor,
 }
 
 impl<'a, RT: Runtime> VirtualTable<'a, RT> {
     pub fn new(tx: &'a mut Transaction<RT>) -> Self {
-        Self { tx }
+        Self {
+            tx,
+            client_version: None,
+        }
+    }
+
+    /// Sets the client version reported in errors when a read doesn't have an
+    /// NPM version.
+    pub fn with_client_version(mut self, client_version: Option<ClientVersion>) -> Self {
+        self.client_version = client_version;
+        self
     }
 
     #[fastrace::trace]
@@ -67,19 +109,147 @@ impl<'a, RT: Runtime> VirtualTable<'a, RT> {
         }
     }
 
+    /// Like [Self::get], but fetches all of `ids` in a single batch of reads.
+    /// Each ID is still recorded as its own read, so bandwidth is accounted
+    /// the same as calling `get` for each. Results are in the same order as
+    /// `ids`, with `None` for missing documents.
+    #[fastrace::trace]
+    pub async fn get_batch(
+        &mut self,
+        namespace: TableNamespace,
+        ids: &[DeveloperDocumentId],
+        version: Option<Version>,
+    ) -> anyhow::Result<Vec<Option<(DeveloperDocument, WriteTimestamp)>>> {
+        let mut system_table_names = BTreeMap::new();
+        let mut resolved_ids = Vec::with_capacity(ids.len());
+        for id in ids {
+            let tablet_id = self
+                .tx
+                .table_mapping()
+                .namespace(namespace)
+                .number_to_tablet()(id.table())?;
+            let system_table_name = match system_table_names.entry(tablet_id) {
+                Entry::Occupied(entry) => entry.get().clone(),
+                Entry::Vacant(entry) => entry
+                    .insert(
+                        self.tx
+                            .table_mapping()
+                            .namespace(namespace)
+                            .tablet_name(tablet_id)?,
+                    )
+                    .clone(),
+            };
+            resolved_ids.push((ResolvedDocumentId::new(tablet_id, *id), system_table_name));
+        }
+
+        let results = self.tx.get_inner_batch(resolved_ids).await?;
+        let mut out = Vec::with_capacity(results.len());
+        for result in results {
+            out.push(match result {
+                Some((doc, ts)) => {
+                    Some((self.system_to_virtual_doc(doc, version.clone()).await?, ts))
+                },
+                None => None,
+            });
+        }
+        Ok(out)
+    }
+
+    /// Reads up to `page_size` documents of a virtual table's index range.
+    /// Returns a cursor for the rest of the range if the page is full.
+    pub async fn index_range_paginated(
+        &mut self,
+        namespace: TableNamespace,
+        index_range: IndexRange,
+        page_size: usize,
+        version: Option<Version>,
+    ) -> anyhow::Result<(
+        Vec<(DeveloperDocument, WriteTimestamp)>,
+        Option<VirtualCursor>,
+    )> {
+        self.read_page(namespace, index_range, None, page_size, version)
+            .await
+    }
+
+    /// Reads the page after `cursor`, as returned by
+    /// [Self::index_range_paginated] or a previous call to this method.
+    pub async fn continue_paginated(
+        &mut self,
+        namespace: TableNamespace,
+        cursor: VirtualCursor,
+        page_size: usize,
+        version: Option<Version>,
+    ) -> anyhow::Result<(
+        Vec<(DeveloperDocument, WriteTimestamp)>,
+        Option<VirtualCursor>,
+    )> {
+        self.read_page(
+            namespace,
+            cursor.index_range,
+            Some(cursor.cursor),
+            page_size,
+            version,
+        )
+        .await
+    }
+
+    async fn read_page(
+        &mut self,
+        namespace: TableNamespace,
+        index_range: IndexRange,
+        start_cursor: Option<Cursor>,
+        page_size: usize,
+        version: Option<Version>,
+    ) -> anyhow::Result<(
+        Vec<(DeveloperDocument, WriteTimestamp)>,
+        Option<VirtualCursor>,
+    )> {
+        anyhow::ensure!(page_size > 0, "page_size must be positive");
+        anyhow::ensure!(
+            self.tx
+                .virtual_system_mapping()
+                .is_virtual_table(index_range.index_name.table()),
+            "{} isn't an index on a virtual table",
+            index_range.index_name
+        );
+        let mut query = DeveloperQuery::new_bounded(
+            self.tx,
+            namespace,
+            Query::index_range(index_range.clone()),
+            PaginationOptions::ManualPagination {
+                start_cursor,
+                maximum_rows_read: None,
+                maximum_bytes_read: None,
+            },
+            version,
+            TableFilter::IncludePrivateSystemTables,
+        )?;
+        let mut page = Vec::with_capacity(page_size);
+        while page.len() < page_size {
+            match query
+                .next_with_ts(self.tx, Some(page_size - page.len()))
+                .await?
+            {
+                Some(result) => page.push(result),
+                None => break,
+            }
+        }
+        let next_cursor =
+            next_page_cursor(page.len(), page_size, query.cursor()).map(|cursor| VirtualCursor {
+                index_range,
+                cursor,
+            });
+        Ok((page, next_cursor))
+    }
+
     pub async fn system_to_virtual_doc(
         &mut self,
         doc: ResolvedDocument,
         version: Option<Version>,
     ) -> anyhow::Result<DeveloperDocument> {
-        if version.is_none() {
-            return Err(ErrorMetadata::bad_request(
-                "InvalidClientVersion",
-                "Upgrade to NPM version 1.6.1 or above to access system tables",
-            )
-            .into());
-        }
-        let version = version.unwrap();
+        let Some(version) = version else {
+            return Err(missing_npm_version_error(self.client_version.as_ref()).into());
+        };
         let virtual_system_mapping = self.tx.virtual_system_mapping().clone();
         let table_mapping = self.tx.table_mapping().clone();
         let system_table_name = table_mapping.tablet_name(doc.id().tablet_id)?;
@@ -97,3 +267,146 @@ impl<'a, RT: Runtime> VirtualTable<'a, RT> {
             .await
     }
 }
+
+/// Functions only send their `convex` package's version from 1.6.1 on, so a
+/// read without one comes from an older client.
+fn missing_npm_version_error(client_version: Option<&ClientVersion>) -> ErrorMetadata {
+    let client_version = client_version.map_or_else(|| "unknown".to_string(), |v| v.to_string());
+    ErrorMetadata::bad_request(
+        "InvalidClientVersion",
+        format!(
+            "Upgrade to NPM version 1.6.1 or above to access system tables (detected client \
+             version: {client_version})"
+        ),
+    )
+}
+
+/// The cursor to resume from after reading a page of `page_len` rows, if more
+/// rows may remain. A page that isn't full reached the end of the range.
+fn next_page_cursor(page_len: usize, page_size: usize, cursor: Option<Cursor>) -> Option<Cursor> {
+    match cursor {
+        Some(cursor) if page_len == page_size && cursor.position != CursorPosition::End => {
+            Some(cursor)
+        },
+        Some(_) | None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        index::IndexKeyBytes,
+        query::{
+            Cursor,
+            CursorPosition,
+        },
+        version::ClientVersion,
+    };
+
+    use super::{
+        missing_npm_version_error,
+        next_page_cursor,
+    };
+
+    /// Reads a page of `keys` after `start` the way a paginated index query
+    /// does, returning the page and the query's cursor.
+    fn read_page(keys: &[u8], start: Option<&Cursor>, page_size: usize) -> (Vec<u8>, Cursor) {
+        let remaining: Vec<u8> = keys
+            .iter()
+            .copied()
+            .filter(|key| match start.map(|cursor| &cursor.position) {
+                Some(CursorPosition::After(after)) => vec![*key] > after.0,
+                Some(CursorPosition::End) => false,
+                None => true,
+            })
+            .collect();
+        let page: Vec<u8> = remaining.iter().copied().take(page_size).collect();
+        let position = match page.last() {
+            Some(last) if remaining.len() > page.len() || page.len() == page_size => {
+                CursorPosition::After(IndexKeyBytes(vec![*last]))
+            },
+            Some(_) | None => CursorPosition::End,
+        };
+        let cursor = Cursor {
+            position,
+            query_fingerprint: vec![],
+        };
+        (page, cursor)
+    }
+
+    fn paginate(keys: &[u8], page_size: usize) -> Vec<Vec<u8>> {
+        let mut pages = vec![];
+        let mut start = None;
+        loop {
+            let (page, cursor) = read_page(keys, start.as_ref(), page_size);
+            let next = next_page_cursor(page.len(), page_size, Some(cursor));
+            pages.push(page);
+            match next {
+                Some(cursor) => start = Some(cursor),
+                None => return pages,
+            }
+        }
+    }
+
+    #[test]
+    fn test_pages_of_two_have_no_gaps_or_overlaps() {
+        for num_keys in 0..=7 {
+            let keys: Vec<u8> = (1..=num_keys).collect();
+            let pages = paginate(&keys, 2);
+            assert!(pages.iter().all(|page| page.len() <= 2), "{pages:?}");
+            assert!(
+                pages[..pages.len() - 1].iter().all(|page| page.len() == 2),
+                "{pages:?}"
+            );
+            assert_eq!(pages.concat(), keys);
+        }
+        assert_eq!(
+            paginate(&[1, 2, 3, 4, 5], 2),
+            vec![vec![1, 2], vec![3, 4], vec![5]]
+        );
+    }
+
+    #[test]
+    fn test_full_last_page_continues_to_empty_page() {
+        // The query can't tell that a full page ended the range, so the
+        // following page is empty and has no cursor.
+        assert_eq!(
+            paginate(&[1, 2, 3, 4], 2),
+            vec![vec![1, 2], vec![3, 4], vec![]]
+        );
+    }
+
+    #[test]
+    fn test_no_cursor_at_end() {
+        let end = Cursor {
+            position: CursorPosition::End,
+            query_fingerprint: vec![],
+        };
+        assert!(next_page_cursor(2, 2, Some(end)).is_none());
+        assert!(next_page_cursor(2, 2, None).is_none());
+    }
+
+    #[test]
+    fn test_missing_npm_version_error_names_client_version() -> anyhow::Result<()> {
+        let client_version: ClientVersion = "npm-1.5.0".parse()?;
+        let err = missing_npm_version_error(Some(&client_version));
+        assert_eq!(err.short_msg, "InvalidClientVersion");
+        assert!(
+            err.msg.ends_with("(detected client version: npm-1.5.0)"),
+            "{}",
+            err.msg
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_npm_version_error_with_unknown_client_version() {
+        let err = missing_npm_version_error(None);
+        assert_eq!(err.short_msg, "InvalidClientVersion");
+        assert!(
+            err.msg.ends_with("(detected client version: unknown)"),
+            "{}",
+            err.msg
+        );
+    }
+}
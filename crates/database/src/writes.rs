@@ -219,6 +219,33 @@ pub struct TransactionWriteSize {
     pub size: usize,
 }
 
+impl TransactionWriteSize {
+    /// Checks that writes of this size to user tables fit within `limits`.
+    pub fn check_user_limits(&self, limits: &TransactionLimits) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.num_writes <= limits.documents_written,
+            ErrorMetadata::pagination_limit(
+                "TooManyWrites",
+                format!(
+                    "Too many writes in a single function execution (limit: {})",
+                    limits.documents_written,
+                )
+            ),
+        );
+        anyhow::ensure!(
+            self.size <= limits.bytes_written,
+            ErrorMetadata::pagination_limit(
+                "TooManyBytesWritten",
+                format!(
+                    "Too many bytes written in a single function execution (limit: {})",
+                    common::fmt::format_bytes(limits.bytes_written as u64),
+                )
+            ),
+        );
+        Ok(())
+    }
+}
+
 impl Writes {
     /// Create an empty write set.
     pub fn new() -> Self {
@@ -283,27 +310,7 @@ impl Writes {
                 tx_size.size
             );
         } else {
-            let tx_size = &self.user_tx_size;
-            anyhow::ensure!(
-                tx_size.num_writes <= limits.documents_written,
-                ErrorMetadata::pagination_limit(
-                    "TooManyWrites",
-                    format!(
-                        "Too many writes in a single function execution (limit: {})",
-                        limits.documents_written,
-                    )
-                ),
-            );
-            anyhow::ensure!(
-                tx_size.size <= limits.bytes_written,
-                ErrorMetadata::pagination_limit(
-                    "TooManyBytesWritten",
-                    format!(
-                        "Too many bytes written in a single function execution (limit: {})",
-                        common::fmt::format_bytes(limits.bytes_written as u64),
-                    )
-                ),
-            );
+            self.user_tx_size.check_user_limits(limits)?;
         }
 
         // The `prev` document/timestamp stored on the new update: the revision
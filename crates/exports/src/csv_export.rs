@@ -0,0 +1,214 @@
+use std::{
+    collections::BTreeSet,
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use common::{
+    async_compat::TokioAsyncWriteCompatExt,
+    components::ComponentPath,
+    document::{
+        CREATION_TIME_FIELD,
+        ID_FIELD,
+    },
+    knobs::{
+        EXPORT_MAX_UPLOAD_PART_SIZE,
+        EXPORT_WORKER_PAGE_SIZE,
+    },
+    persistence::LatestDocument,
+    runtime::Runtime,
+    types::{
+        IndexId,
+        ObjectKey,
+        TableName,
+    },
+};
+use database::{
+    IndexModel,
+    SearchNotEnabled,
+};
+use errors::ErrorMetadata;
+use futures::{
+    pin_mut,
+    try_join,
+    AsyncWriteExt,
+    StreamExt,
+    TryStreamExt,
+};
+use keybroker::Identity;
+use model::virtual_system_mapping;
+use serde_json::Value as JsonValue;
+use storage::{
+    ChannelWriter,
+    Upload,
+    UploadExt,
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use usage_tracking::FunctionUsageTracker;
+use value::{
+    export::ValueFormat,
+    TabletId,
+};
+
+use crate::ExportComponents;
+
+/// Uploads one table to exports_storage as a CSV file at the returned
+/// `ObjectKey`, as of the `DatabaseSnapshot`'s timestamp.
+///
+/// The header row is the union of the table's top-level fields, so the table
+/// is read twice: once to collect the fields and once to write the rows.
+/// `_id` and `_creationTime` are always the first two columns, so importing
+/// the file into the same deployment keeps each document's ID and creation
+/// time. The other columns are sorted by name. See [csv_cell] for how values
+/// are written.
+pub async fn export_table_csv<RT: Runtime>(
+    components: &ExportComponents<RT>,
+    component_path: &ComponentPath,
+    table_name: &TableName,
+) -> anyhow::Result<(ObjectKey, FunctionUsageTracker)> {
+    if table_name.is_system() {
+        anyhow::bail!(ErrorMetadata::bad_request(
+            "InvalidTableName",
+            format!("System table {table_name} can't be exported to CSV"),
+        ));
+    }
+    let (tablet_id, by_id) = {
+        let mut tx = components.database.begin_tx(
+            Identity::system(),
+            Arc::new(SearchNotEnabled),
+            // only used for system reads
+            FunctionUsageTracker::new(),
+            virtual_system_mapping().clone(),
+        )?;
+        let by_id_indexes = IndexModel::new(&mut tx).by_id_indexes().await?;
+        let component_ids_to_paths = components.database.snapshot.component_ids_to_paths();
+        let table_id = component_ids_to_paths
+            .iter()
+            .find(|(_, path)| *path == component_path)
+            .and_then(|(component_id, _)| {
+                components
+                    .database
+                    .snapshot
+                    .table_mapping()
+                    .namespace((*component_id).into())
+                    .id(table_name)
+                    .ok()
+            })
+            .ok_or_else(|| {
+                ErrorMetadata::not_found(
+                    "TableNotFound",
+                    format!(
+                        "Table {table_name}{} not found",
+                        component_path.in_component_str()
+                    ),
+                )
+            })?;
+        let by_id = *by_id_indexes
+            .get(&table_id.tablet_id)
+            .ok_or_else(|| anyhow::anyhow!("no by_id index for {} found", table_id.tablet_id))?;
+        (table_id.tablet_id, by_id)
+    };
+
+    let mut fields = BTreeSet::new();
+    let stream = components
+        .database
+        .table_iterator()
+        .with_page_size(*EXPORT_WORKER_PAGE_SIZE)
+        .stream_documents_in_table(tablet_id, by_id, None);
+    pin_mut!(stream);
+    while let Some(LatestDocument { value: doc, .. }) = stream.try_next().await? {
+        fields.extend(
+            doc.value()
+                .keys()
+                .filter(|field| !field.is_system())
+                .map(|field| field.to_string()),
+        );
+    }
+    let header: Vec<String> = [ID_FIELD.to_string(), CREATION_TIME_FIELD.to_string()]
+        .into_iter()
+        .chain(fields)
+        .collect();
+
+    let mut upload = components.exports_storage.start_upload().await?;
+    upload.limit_max_intermediate_part_size(*EXPORT_MAX_UPLOAD_PART_SIZE);
+    let (sender, receiver) = mpsc::channel::<Bytes>(1);
+    let uploader = upload.try_write_parallel_and_hash(ReceiverStream::new(receiver).map(Ok));
+    let writer = ChannelWriter::new(sender, 5 * (1 << 20));
+    let usage = FunctionUsageTracker::new();
+    let rows = write_csv_rows(
+        components,
+        writer,
+        component_path,
+        table_name,
+        tablet_id,
+        by_id,
+        &header,
+        &usage,
+    );
+    if let Err(e) = try_join!(uploader, rows) {
+        if let Err(abort_err) = upload.abort().await {
+            tracing::warn!("Failed to abort CSV export upload: {abort_err:#}");
+        }
+        return Err(e);
+    }
+    let object_key = upload.complete().await?;
+    Ok((object_key, usage))
+}
+
+async fn write_csv_rows<RT: Runtime>(
+    components: &ExportComponents<RT>,
+    writer: ChannelWriter,
+    component_path: &ComponentPath,
+    table_name: &TableName,
+    tablet_id: TabletId,
+    by_id: IndexId,
+    header: &[String],
+    usage: &FunctionUsageTracker,
+) -> anyhow::Result<()> {
+    let mut out = writer.compat_write();
+    {
+        let mut csv_writer = csv_async::AsyncWriter::from_writer(&mut out);
+        csv_writer.write_record(header).await?;
+        let stream = components
+            .database
+            .table_iterator()
+            .with_page_size(*EXPORT_WORKER_PAGE_SIZE)
+            .stream_documents_in_table(tablet_id, by_id, None);
+        pin_mut!(stream);
+        while let Some(LatestDocument { value: doc, .. }) = stream.try_next().await? {
+            let doc_size = doc.size() as u64;
+            usage.track_database_egress(component_path.clone(), table_name, doc_size, false);
+            usage.track_database_egress_v2(component_path.clone(), table_name, doc_size, false);
+            let JsonValue::Object(mut object) = doc.export(ValueFormat::ConvexCleanJSON) else {
+                anyhow::bail!("exported document isn't an object");
+            };
+            let row = header
+                .iter()
+                .map(|field| object.remove(field).map(csv_cell).unwrap_or_default());
+            csv_writer.write_record(row).await?;
+        }
+        csv_writer.flush().await?;
+    }
+    out.close().await?;
+    Ok(())
+}
+
+/// Writes a value the way CSV import's `parse_csv_cell` reads it back where
+/// it can. Numbers are written as their JSON encoding and import as the same
+/// number. Strings are written as they are, so a string that parses as a
+/// number, like `01234` or `1e3`, imports as that number unless its column is
+/// imported as a string column; CSV has no way to quote it as a string.
+/// CSV import has no other types, so `null` is written as an empty cell and
+/// booleans, arrays and objects are JSON-encoded into the cell, which
+/// imports them as strings.
+pub fn csv_cell(value: JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(s) => s,
+        value @ (JsonValue::Bool(_)
+        | JsonValue::Number(_)
+        | JsonValue::Array(_)
+        | JsonValue::Object(_)) => value.to_string(),
+    }
+}
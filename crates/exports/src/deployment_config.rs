@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+
+use common::{
+    bootstrap_model::{
+        components::ComponentType,
+        schema::SchemaState,
+    },
+    components::{
+        ComponentId,
+        ComponentPath,
+        Resource,
+    },
+    runtime::Runtime,
+    schemas::json::DatabaseSchemaJson,
+};
+use database::{
+    BootstrapComponentsModel,
+    IndexModel,
+    SchemaModel,
+    Transaction,
+};
+use model::{
+    cron_jobs::{
+        types::SerializedCronSpec,
+        CronModel,
+    },
+    deployment_audit_log::developer_index_config::{
+        DeveloperIndexConfig,
+        SerializedDeveloperIndexConfig,
+        SerializedNamedDeveloperIndexConfig,
+    },
+    environment_variables::EnvironmentVariablesModel,
+    exports::deployment_config::{
+        DeploymentConfigManifest,
+        DeploymentConfigSections,
+        SerializedBundledCron,
+        SerializedBundledEnvironmentVariable,
+        SerializedBundledIndex,
+        COMPONENT_ARGS_FILE,
+        CRONS_FILE,
+        DEPLOYMENT_CONFIG_DIR,
+        DEPLOYMENT_CONFIG_VERSION,
+        ENVIRONMENT_VARIABLES_FILE,
+        INDEXES_FILE,
+        MANIFEST_FILE,
+        SCHEMA_FILE,
+    },
+};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::get_export_path_prefix;
+
+/// A file to write into the export ZIP, with its full path.
+pub struct DeploymentConfigFile {
+    pub path: String,
+    pub contents: Vec<u8>,
+}
+
+/// Reads the requested sections of the deployment's configuration as of the
+/// export's snapshot, laid out as described in
+/// [`model::exports::deployment_config`].
+pub async fn read_deployment_config<RT: Runtime>(
+    tx: &mut Transaction<RT>,
+    component_ids_to_paths: &BTreeMap<ComponentId, ComponentPath>,
+    sections: DeploymentConfigSections,
+) -> anyhow::Result<Vec<DeploymentConfigFile>> {
+    let mut files = read_component_args(tx, component_ids_to_paths).await?;
+    if sections.is_empty() {
+        return Ok(files);
+    }
+    files.push(DeploymentConfigFile {
+        path: format!("{DEPLOYMENT_CONFIG_DIR}/{MANIFEST_FILE}"),
+        contents: serde_json::to_vec_pretty(&DeploymentConfigManifest {
+            version: DEPLOYMENT_CONFIG_VERSION,
+            sections,
+        })?,
+    });
+    if sections.environment_variables {
+        let environment_variables: Vec<_> = EnvironmentVariablesModel::new(tx)
+            .get_all()
+            .await?
+            .into_iter()
+            .map(|(name, value)| SerializedBundledEnvironmentVariable {
+                name: name.into(),
+                value: value.into(),
+            })
+            .collect();
+        files.push(DeploymentConfigFile {
+            path: format!("{DEPLOYMENT_CONFIG_DIR}/{ENVIRONMENT_VARIABLES_FILE}"),
+            contents: to_jsonl(&environment_variables)?,
+        });
+    }
+    for (&component_id, component_path) in component_ids_to_paths {
+        let dir = format!(
+            "{}{DEPLOYMENT_CONFIG_DIR}",
+            get_export_path_prefix(component_path)
+        );
+        if sections.schema
+            && let Some((_, schema)) = SchemaModel::new(tx, component_id.into())
+                .get_by_state(SchemaState::Active)
+                .await?
+        {
+            let schema = DatabaseSchemaJson::try_from((*schema).clone())?;
+            files.push(DeploymentConfigFile {
+                path: format!("{dir}/{SCHEMA_FILE}"),
+                contents: serde_json::to_vec_pretty(&schema)?,
+            });
+        }
+        if sections.indexes {
+            let indexes: Vec<_> = IndexModel::new(tx)
+                .get_application_indexes(component_id.into())
+                .await?
+                .into_iter()
+                .map(|index| index.into_value())
+                .filter(|index| {
+                    index.config.is_enabled() && !index.name.is_by_id_or_creation_time()
+                })
+                .map(|index| SerializedBundledIndex {
+                    table: index.name.table().to_string(),
+                    index: SerializedNamedDeveloperIndexConfig {
+                        name: index.name.descriptor().to_string(),
+                        index_config: SerializedDeveloperIndexConfig::from(
+                            DeveloperIndexConfig::from(index.config),
+                        ),
+                    },
+                })
+                .collect();
+            files.push(DeploymentConfigFile {
+                path: format!("{dir}/{INDEXES_FILE}"),
+                contents: to_jsonl(&indexes)?,
+            });
+        }
+        if sections.crons {
+            let crons: Vec<_> = CronModel::new(tx, component_id)
+                .list_metadata()
+                .await?
+                .into_iter()
+                .map(|(name, cron_job)| {
+                    anyhow::Ok(SerializedBundledCron {
+                        name: name.to_string(),
+                        spec: SerializedCronSpec::try_from(cron_job.into_value().cron_spec)?,
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?;
+            files.push(DeploymentConfigFile {
+                path: format!("{dir}/{CRONS_FILE}"),
+                contents: to_jsonl(&crons)?,
+            });
+        }
+    }
+    Ok(files)
+}
+
+/// Reads each child component's value arguments, which are exported regardless
+/// of the requested sections.
+async fn read_component_args<RT: Runtime>(
+    tx: &mut Transaction<RT>,
+    component_ids_to_paths: &BTreeMap<ComponentId, ComponentPath>,
+) -> anyhow::Result<Vec<DeploymentConfigFile>> {
+    let mut files = vec![];
+    for (&component_id, component_path) in component_ids_to_paths {
+        let args = match BootstrapComponentsModel::new(tx)
+            .load_component_type(component_id)
+            .await?
+        {
+            ComponentType::App => continue,
+            ComponentType::ChildComponent { args, .. } => args,
+        };
+        let args: BTreeMap<String, JsonValue> = args
+            .into_iter()
+            .filter_map(|(name, resource)| match resource {
+                Resource::Value(value) => Some((name.to_string(), JsonValue::from(value))),
+                Resource::Function(_) | Resource::ResolvedSystemUdf(_) => None,
+            })
+            .collect();
+        files.push(DeploymentConfigFile {
+            path: format!(
+                "{}{DEPLOYMENT_CONFIG_DIR}/{COMPONENT_ARGS_FILE}",
+                get_export_path_prefix(component_path)
+            ),
+            contents: serde_json::to_vec_pretty(&args)?,
+        });
+    }
+    Ok(files)
+}
+
+fn to_jsonl<T: Serialize>(lines: &[T]) -> anyhow::Result<Vec<u8>> {
+    let mut contents = vec![];
+    for line in lines {
+        serde_json::to_writer(&mut contents, line)?;
+        contents.push(b'\n');
+    }
+    Ok(contents)
+}
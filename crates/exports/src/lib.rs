@@ -18,6 +18,7 @@ use common::{
     },
     fastrace_helpers::get_sampled_span,
     knobs::{
+        EXPORT_MAX_UPLOAD_PART_SIZE,
         EXPORT_PROGRESS_UPDATE_INTERVAL,
         EXPORT_WORKER_PAGE_SIZE,
     },
@@ -61,7 +62,6 @@ use storage::{
     ChannelWriter,
     Storage,
     Upload,
-    UploadExt,
 };
 use thousands::Separable;
 use tokio::sync::mpsc;
@@ -74,10 +74,16 @@ use value::{
 };
 
 use self::{
+    deployment_config::{
+        read_deployment_config,
+        DeploymentConfigFile,
+    },
     export_storage::write_storage_table,
     zip_uploader::ZipSnapshotUpload,
 };
 
+mod csv_export;
+mod deployment_config;
 mod export_storage;
 pub mod interface;
 mod metrics;
@@ -85,6 +91,10 @@ mod zip_uploader;
 
 use crate::metrics::export_timer;
 pub use crate::{
+    csv_export::{
+        csv_cell,
+        export_table_csv,
+    },
     export_storage::FileStorageZipMetadata,
     zip_uploader::README_MD_CONTENTS,
 };
@@ -112,7 +122,15 @@ where
     let timer = export_timer(&components.deployment_name);
     let exports_storage = &components.exports_storage;
     update_progress("Beginning backup".to_string()).await?;
-    let (tables, component_ids_to_paths, by_id_indexes, system_tables, storage_table_counts) = {
+    let ExportFormat::Zip { include_config, .. } = format;
+    let (
+        tables,
+        component_ids_to_paths,
+        by_id_indexes,
+        system_tables,
+        storage_table_counts,
+        deployment_config,
+    ) = {
         let mut tx = components.database.begin_tx(
             Identity::system(),
             Arc::new(SearchNotEnabled),
@@ -121,6 +139,9 @@ where
             virtual_system_mapping().clone(),
         )?;
         let by_id_indexes = IndexModel::new(&mut tx).by_id_indexes().await?;
+        let component_ids_to_paths = components.database.snapshot.component_ids_to_paths();
+        let deployment_config =
+            read_deployment_config(&mut tx, &component_ids_to_paths, include_config).await?;
         drop(tx);
         let mut database = components.database.clone();
         if database.snapshot.table_counts.is_none() {
@@ -143,7 +164,6 @@ where
                 )
             })
             .collect();
-        let component_ids_to_paths = snapshot.component_ids_to_paths();
         let system_tables: BTreeMap<_, _> = snapshot
             .table_registry
             .iter_active_system_tables()
@@ -160,16 +180,25 @@ where
             by_id_indexes,
             system_tables,
             storage_table_counts,
+            deployment_config,
         )
     };
     let export = match format {
-        ExportFormat::Zip { include_storage } => {
+        ExportFormat::Zip {
+            include_storage, ..
+        } => {
             // Start upload.
             let mut upload = exports_storage.start_upload().await?;
+            upload.limit_max_intermediate_part_size(*EXPORT_MAX_UPLOAD_PART_SIZE);
             let (sender, receiver) = mpsc::channel::<Bytes>(1);
-            let uploader =
-                upload.try_write_parallel_and_hash(ReceiverStream::new(receiver).map(Ok));
-            let writer = ChannelWriter::new(sender, 5 * (1 << 20));
+            // Each zip entry is sent once it's complete, in parts the upload
+            // can write without buffering them again.
+            let writer = ChannelWriter::with_part_boundaries(
+                sender,
+                upload.min_intermediate_part_size(),
+                upload.max_intermediate_part_size(),
+            );
+            let uploader = upload.try_write_parts_parallel(ReceiverStream::new(receiver).map(Ok));
             let usage = FunctionUsageTracker::new();
 
             let mut tablet_ids: Vec<_> = tables.keys().copied().collect();
@@ -198,11 +227,20 @@ where
                 system_tables,
                 storage_table_counts,
                 include_storage,
+                deployment_config,
                 usage.clone(),
                 requestor,
                 update_progress,
             );
-            let (_, ()) = try_join!(uploader, zipper)?;
+            if let Err(e) = try_join!(uploader, zipper) {
+                // Abort the upload now rather than leaving it to the storage
+                // backend, so the parts already written are cleaned up
+                // before the export is marked as failed.
+                if let Err(abort_err) = upload.abort().await {
+                    tracing::warn!("Failed to abort export upload: {abort_err:#}");
+                }
+                return Err(e);
+            }
             let zip_object_key = upload.complete().await?;
             (zip_object_key, usage)
         },
@@ -312,6 +350,7 @@ async fn construct_zip_snapshot<F, Fut, RT: Runtime>(
     system_tables: BTreeMap<(TableNamespace, TableName), TabletId>,
     storage_table_counts: BTreeMap<TableNamespace, u64>,
     include_storage: bool,
+    deployment_config: Vec<DeploymentConfigFile>,
     usage: FunctionUsageTracker,
     requestor: ExportRequestor,
     update_progress: F,
@@ -327,6 +366,16 @@ where
     // since `snapshot_ts`. We create many TableIterator while constructing a
     // zip snapshot, so it is helpful to do this.
 
+    // The deployment configuration is small, and was read up front.
+    if !deployment_config.is_empty() {
+        update_progress("Backing up deployment configuration".to_string()).await?;
+    }
+    for DeploymentConfigFile { path, contents } in deployment_config {
+        zip_snapshot_upload
+            .stream_full_file(path, &contents[..])
+            .await?;
+    }
+
     // Backup all the tables-tables. These are generally small.
     for (component_id, component_path) in component_ids_to_paths.iter() {
         let namespace: TableNamespace = (*component_id).into();
@@ -440,7 +489,8 @@ where
         }
     }
 
-    // Complete upload.
+    // Complete upload. The central directory is only sent once the writer is
+    // shut down.
     zip_snapshot_upload.complete().await?;
     writer.compat_write().close().await?;
     Ok(())
@@ -18,7 +18,10 @@ use futures::{
     AsyncWriteExt,
 };
 use serde_json::Value as JsonValue;
-use storage::ChannelWriter;
+use storage::{
+    ChannelWriter,
+    PartBoundary,
+};
 use tokio::io::{
     AsyncBufRead,
     AsyncWriteExt as _,
@@ -46,11 +49,13 @@ ask us in [Discord](http://convex.dev/community).
 // 'b is lifetime of entry writer for a single table.
 pub struct ZipSnapshotTableUpload<'a, 'b> {
     entry_writer: EntryStreamWriter<'b, &'a mut ChannelWriter>,
+    entry_boundary: PartBoundary,
 }
 
 impl<'a, 'b> ZipSnapshotTableUpload<'a, 'b> {
     async fn new(
         zip_writer: &'b mut ZipFileWriter<&'a mut ChannelWriter>,
+        entry_boundary: PartBoundary,
         path_prefix: &str,
         table_name: TableName,
     ) -> anyhow::Result<Self> {
@@ -58,7 +63,10 @@ impl<'a, 'b> ZipSnapshotTableUpload<'a, 'b> {
         let builder = ZipEntryBuilder::new(source_path.into(), Compression::Deflate)
             .unix_permissions(ZIP_ENTRY_PERMISSIONS);
         let entry_writer = zip_writer.write_entry_stream(builder.build()).await?;
-        Ok(Self { entry_writer })
+        Ok(Self {
+            entry_writer,
+            entry_boundary,
+        })
     }
 
     pub async fn write(&mut self, doc: ResolvedDocument) -> anyhow::Result<()> {
@@ -75,18 +83,24 @@ impl<'a, 'b> ZipSnapshotTableUpload<'a, 'b> {
 
     pub async fn complete(self) -> anyhow::Result<()> {
         self.entry_writer.close().await?;
+        self.entry_boundary.mark();
         Ok(())
     }
 }
 
 pub struct ZipSnapshotUpload<'a> {
     writer: ZipFileWriter<&'a mut ChannelWriter>,
+    entry_boundary: PartBoundary,
 }
 
 impl<'a> ZipSnapshotUpload<'a> {
     pub async fn new(out: &'a mut ChannelWriter) -> anyhow::Result<Self> {
+        let entry_boundary = out.part_boundary();
         let writer = ZipFileWriter::with_tokio(out);
-        let mut zip_snapshot_upload = Self { writer };
+        let mut zip_snapshot_upload = Self {
+            writer,
+            entry_boundary,
+        };
         zip_snapshot_upload
             .stream_full_file("README.md".to_owned(), README_MD_CONTENTS.as_bytes())
             .await?;
@@ -109,6 +123,7 @@ impl<'a> ZipSnapshotUpload<'a> {
         pin_mut!(contents);
         tokio::io::copy_buf(&mut contents, &mut entry_writer).await?;
         entry_writer.into_inner().close().await?;
+        self.entry_boundary.mark();
         Ok(())
     }
 
@@ -117,7 +132,13 @@ impl<'a> ZipSnapshotUpload<'a> {
         path_prefix: &str,
         table_name: TableName,
     ) -> anyhow::Result<ZipSnapshotTableUpload<'a, '_>> {
-        ZipSnapshotTableUpload::new(&mut self.writer, path_prefix, table_name).await
+        ZipSnapshotTableUpload::new(
+            &mut self.writer,
+            self.entry_boundary.clone(),
+            path_prefix,
+            table_name,
+        )
+        .await
     }
 
     /// System tables have known shape, so we don't need to serialize it.
@@ -127,7 +148,13 @@ impl<'a> ZipSnapshotUpload<'a> {
         table_name: TableName,
     ) -> anyhow::Result<ZipSnapshotTableUpload<'a, '_>> {
         anyhow::ensure!(table_name.is_system());
-        ZipSnapshotTableUpload::new(&mut self.writer, path_prefix, table_name).await
+        ZipSnapshotTableUpload::new(
+            &mut self.writer,
+            self.entry_boundary.clone(),
+            path_prefix,
+            table_name,
+        )
+        .await
     }
 
     /// Writes a `generated_schema.jsonl` file with a "uniform" marker to
@@ -151,11 +178,117 @@ impl<'a> ZipSnapshotUpload<'a> {
             .await?;
         entry_writer.write_all(b"\n").await?;
         entry_writer.into_inner().close().await?;
+        self.entry_boundary.mark();
         Ok(())
     }
 
+    /// Writes the zip central directory. The [ChannelWriter] still has to be
+    /// shut down afterwards to send it to the upload.
     pub async fn complete(self) -> anyhow::Result<()> {
         self.writer.close().await?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use async_zip::base::read::mem::ZipFileReader;
+    use bytes::Bytes;
+    use futures::StreamExt;
+    use serde_json::json;
+    use storage::ChannelWriter;
+    use tokio::{
+        io::AsyncWriteExt,
+        sync::mpsc,
+    };
+    use tokio_stream::wrappers::ReceiverStream;
+
+    use super::ZipSnapshotUpload;
+
+    const MIN_PART_SIZE: usize = 1 << 10;
+    const MAX_PART_SIZE: usize = 4 << 10;
+
+    /// Lines that deflate can't shrink much, so the tables span several
+    /// parts.
+    fn documents(table: &str, count: u64) -> Vec<String> {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        (0..count)
+            .map(|i| {
+                let noise: String = (0..4)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        format!("{state:016x}")
+                    })
+                    .collect();
+                json!({ "table": table, "i": i, "noise": noise }).to_string()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_zip_snapshot_round_trips_through_parts() -> anyhow::Result<()> {
+        let tables = [
+            ("messages", documents("messages", 200)),
+            ("users", documents("users", 3)),
+        ];
+
+        let (sender, receiver) = mpsc::channel(1);
+        let parts = tokio::spawn(ReceiverStream::new(receiver).collect::<Vec<Bytes>>());
+        let mut writer = ChannelWriter::with_part_boundaries(sender, MIN_PART_SIZE, MAX_PART_SIZE);
+        let mut zip_snapshot_upload = ZipSnapshotUpload::new(&mut writer).await?;
+        for (table_name, documents) in &tables {
+            let table_name = table_name.parse()?;
+            let mut table_upload = zip_snapshot_upload.start_table("", table_name).await?;
+            for document in documents {
+                table_upload
+                    .write_json_line(serde_json::from_str(document)?)
+                    .await?;
+            }
+            table_upload.complete().await?;
+        }
+        zip_snapshot_upload.complete().await?;
+        writer.shutdown().await?;
+        drop(writer);
+        let parts = parts.await?;
+
+        let (last, intermediate) = parts.split_last().expect("no parts");
+        assert!(intermediate.len() > 1);
+        for part in intermediate {
+            assert!(
+                (MIN_PART_SIZE..=MAX_PART_SIZE).contains(&part.len()),
+                "{}",
+                part.len()
+            );
+        }
+        assert!(last.len() <= MAX_PART_SIZE);
+
+        // Read the uploaded bytes back the way an import would, which relies
+        // on the central directory's offsets matching the parts' bytes.
+        let zip = ZipFileReader::new(parts.concat()).await?;
+        let filenames = zip
+            .file()
+            .entries()
+            .iter()
+            .map(|entry| Ok(entry.filename().as_str()?.to_string()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(
+            filenames,
+            vec![
+                "README.md",
+                "messages/documents.jsonl",
+                "users/documents.jsonl"
+            ]
+        );
+        for (index, (_, documents)) in tables.iter().enumerate() {
+            let mut contents = String::new();
+            zip.reader_with_entry(index + 1)
+                .await?
+                .read_to_string_checked(&mut contents)
+                .await?;
+            assert_eq!(contents.lines().collect::<Vec<_>>(), *documents);
+        }
+        Ok(())
+    }
+}
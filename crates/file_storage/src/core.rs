@@ -57,6 +57,7 @@ use model::{
     },
 };
 use storage::{
+    ChecksumVerification,
     Storage,
     StorageExt,
     Upload,
@@ -424,6 +425,36 @@ impl<RT: Runtime> TransactionalFileStorage<RT> {
         Ok(entry)
     }
 
+    /// Checks that the object written by `upload_file` hashes to
+    /// `expected_sha256` in the backing store. `upload_file` hashes the bytes
+    /// as they're sent, so this only catches corruption after that point.
+    /// Objects the backing store can't verify are assumed to match.
+    pub async fn verify_stored_file(
+        &self,
+        entry: &FileStorageEntry,
+        expected_sha256: &Sha256Digest,
+    ) -> anyhow::Result<()> {
+        match self
+            .storage
+            .verify_checksum(&entry.storage_key, expected_sha256)
+            .await?
+        {
+            ChecksumVerification::Matches => Ok(()),
+            ChecksumVerification::Mismatch => anyhow::bail!(
+                "Stored object {:?} doesn't match sha256 {}",
+                entry.storage_key,
+                expected_sha256.as_base64()
+            ),
+            ChecksumVerification::Unverifiable => {
+                tracing::debug!(
+                    "Stored object {:?} has no checksum to verify",
+                    entry.storage_key
+                );
+                Ok(())
+            },
+        }
+    }
+
     /// Stores a file entry generated by upload_file(). The caller is
     /// responsible to track usage. If you are outside of the
     /// isolate environment, it is recommended to use FileStorage::store_file
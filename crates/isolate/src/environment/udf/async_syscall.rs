@@ -1266,8 +1266,10 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
 
         system_table_guard(&table, false)?;
         let component = provider.component()?;
+        let client_version = provider.context().client_version.clone();
         let tx = provider.tx()?;
         let document_id = UserFacingModel::new(tx, component.into())
+            .with_client_version(client_version)
             .insert(table, value)
             .await?;
         let id_str = document_id.encode();
@@ -1287,6 +1289,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
         }
         let table_filter = provider.table_filter();
         let component = provider.component()?;
+        let client_version = provider.context().client_version.clone();
         let tx = provider.tx()?;
         let (id, value, table_name) = with_argument_error("db.patch", || {
             let args: UpdateArgs = serde_json::from_value(args)?;
@@ -1304,6 +1307,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
         system_table_guard(&table_name, false)?;
 
         let document = UserFacingModel::new(tx, component.into())
+            .with_client_version(client_version)
             .patch(id, value)
             .await?;
         developer_document_to_json(tx, component.into(), &document, WriteTimestamp::Pending)
@@ -1322,6 +1326,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
         }
         let table_filter = provider.table_filter();
         let component = provider.component()?;
+        let client_version = provider.context().client_version.clone();
         let tx = provider.tx()?;
         let (id, value, table_name) = with_argument_error("db.replace", || {
             let args: ReplaceArgs = serde_json::from_value(args)?;
@@ -1343,6 +1348,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
         system_table_guard(&table_name, false)?;
 
         let document = UserFacingModel::new(tx, component.into())
+            .with_client_version(client_version)
             .replace(id, value)
             .await?;
         developer_document_to_json(tx, component.into(), &document, WriteTimestamp::Pending)
@@ -1372,6 +1378,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
         }
 
         let table_filter = provider.table_filter();
+        let client_version = provider.context().client_version.clone();
         let mut queries_to_fetch = BTreeMap::new();
         let mut results = BTreeMap::new();
         let batch_size = batch_args.len();
@@ -1400,6 +1407,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
                                     version,
                                     table_filter,
                                 )?
+                                .with_client_version(client_version.clone())
                             },
                             ManagedQuery::Active(local_query) => local_query,
                         };
@@ -1446,7 +1454,8 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
                                         query,
                                         version,
                                         table_filter,
-                                    )?,
+                                    )?
+                                    .with_client_version(client_version.clone()),
                                 ))
                             },
                             Err(_) => {
@@ -1546,6 +1555,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
 
         let table_filter = provider.table_filter();
         let component = provider.component()?;
+        let client_version = provider.context().client_version.clone();
         let tx = provider.tx()?;
         let (id, table_name) = with_argument_error("db.delete", || {
             let args: RemoveArgs = serde_json::from_value(args)?;
@@ -1560,6 +1570,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
         system_table_guard(&table_name, false)?;
 
         let document = UserFacingModel::new(tx, component.into())
+            .with_client_version(client_version)
             .delete(id)
             .await?;
         Ok(document.to_internal_json())
@@ -1868,6 +1879,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsShared<RT, P> {
             ));
         }
 
+        let client_version = provider.context().client_version.clone();
         let tx = provider.tx()?;
 
         let (
@@ -1890,7 +1902,8 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsShared<RT, P> {
                 },
                 version,
                 table_filter,
-            )?;
+            )?
+            .with_client_version(client_version);
             let (page, metadata) = Self::read_page_from_query(query, tx, page_size).await?;
             let page = page
                 .into_iter()
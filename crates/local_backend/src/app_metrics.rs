@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use axum::response::IntoResponse;
 use common::{
     components::{
@@ -18,7 +20,10 @@ use common::{
     },
 };
 use errors::ErrorMetadata;
-use serde::Deserialize;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use sync_types::UdfPath;
 use value::{
     TableMapping,
@@ -27,6 +32,7 @@ use value::{
 
 use crate::{
     authentication::ExtractIdentity,
+    subs::live_websocket_counts,
     LocalAppState,
 };
 
@@ -64,6 +70,65 @@ pub(crate) async fn udf_rate(
     Ok(Json(timeseries))
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WebSocketConnectionsQueryArgs {
+    #[serde(default)]
+    reset_high_water_mark: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WebSocketConnectionsResponse {
+    live: u64,
+    high_water_mark: u64,
+}
+
+/// Sync WebSockets open on this backend process, and the most that were open
+/// at once since it started or the high-water mark was last reset.
+pub(crate) async fn websocket_connections(
+    ExtractIdentity(identity): ExtractIdentity,
+    Query(WebSocketConnectionsQueryArgs {
+        reset_high_water_mark,
+    }): Query<WebSocketConnectionsQueryArgs>,
+) -> Result<impl IntoResponse, HttpResponseError> {
+    identity.require_operation(keybroker::DeploymentOp::ViewMetrics)?;
+    let (live, high_water_mark) = live_websocket_counts(reset_high_water_mark);
+    Ok(Json(WebSocketConnectionsResponse {
+        live,
+        high_water_mark,
+    }))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CronExecutionLagQueryArgs {
+    percentiles: String,
+    window: String,
+}
+
+/// Percentiles of how late scheduled runs of each cron job started, keyed by
+/// cron job name. Consistently late runs mean the cron executor is at its
+/// parallelism limit.
+pub(crate) async fn cron_execution_lag(
+    MtState(st): MtState<LocalAppState>,
+    ExtractIdentity(identity): ExtractIdentity,
+    Query(query_args): Query<CronExecutionLagQueryArgs>,
+) -> Result<impl IntoResponse, HttpResponseError> {
+    let percentiles: Vec<usize> =
+        serde_json::from_str(&query_args.percentiles).map_err(anyhow::Error::new)?;
+    let window_json: serde_json::Value =
+        serde_json::from_str(&query_args.window).map_err(anyhow::Error::new)?;
+    let window = window_json.try_into()?;
+    let lag_by_job: BTreeMap<_, Vec<_>> = st
+        .application
+        .metrics_log(&identity)?
+        .cron_job_lag_percentiles(percentiles, window)?
+        .into_iter()
+        .map(|(name, timeseries)| (name, timeseries.into_iter().collect()))
+        .collect();
+    Ok(Json(lag_by_job))
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct TopKQueryArgs {
@@ -149,6 +214,43 @@ pub(crate) async fn subscription_invalidations_top_k(
     Ok(Json(timeseries))
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OccConflictHotspotsArgs {
+    k: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OccConflictHotspotResponse {
+    component_path: Option<String>,
+    table_name: String,
+    index: String,
+    num_conflicts: u64,
+}
+
+/// The indexes with the most OCC conflicts since the backend started, which
+/// points at documents many mutations contend on.
+pub(crate) async fn occ_conflict_hotspots(
+    MtState(st): MtState<LocalAppState>,
+    ExtractIdentity(identity): ExtractIdentity,
+    Query(OccConflictHotspotsArgs { k }): Query<OccConflictHotspotsArgs>,
+) -> Result<impl IntoResponse, HttpResponseError> {
+    let k = validate_k(k)?;
+    let hotspots = st
+        .application
+        .occ_conflict_hotspots(&identity, k)?
+        .into_iter()
+        .map(|hotspot| OccConflictHotspotResponse {
+            component_path: hotspot.component_path.serialize(),
+            table_name: hotspot.table_name.to_string(),
+            index: hotspot.index.to_string(),
+            num_conflicts: hotspot.num_conflicts,
+        })
+        .collect::<Vec<_>>();
+    Ok(Json(hotspots))
+}
+
 pub(crate) async fn function_call_count_top_k(
     MtState(st): MtState<LocalAppState>,
     ExtractIdentity(identity): ExtractIdentity,
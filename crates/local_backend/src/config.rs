@@ -1,6 +1,8 @@
 use std::{
     fmt,
     net::SocketAddr,
+    num::NonZeroUsize,
+    time::Duration,
 };
 
 use clap::{
@@ -9,9 +11,16 @@ use clap::{
     Subcommand as ClapSubcommand,
 };
 use clusters::DbDriverTag;
-use common::types::{
-    ConvexOrigin,
-    ConvexSite,
+use common::{
+    http::host_filter::{
+        FetchHostFilter,
+        HostPattern,
+    },
+    knobs::NODE_ACTION_USER_TIMEOUT,
+    types::{
+        ConvexOrigin,
+        ConvexSite,
+    },
 };
 use keybroker::{
     DeploymentSecret,
@@ -23,6 +32,8 @@ use model::database_globals::types::StorageTagInitializer;
 use serde_json::Value as JsonValue;
 use url::Url;
 
+use crate::DEFAULT_MAX_CONCURRENT_REQUESTS;
+
 #[derive(Parser, Clone)]
 #[clap(version = &**SERVER_VERSION_STR, author = "Convex, Inc. <no-reply@convex.dev>", group(clap::ArgGroup::new("storage").multiple(false)))]
 pub struct LocalConfig {
@@ -78,6 +89,19 @@ pub struct LocalConfig {
     #[clap(long)]
     pub convex_http_proxy: Option<Url>,
 
+    /// Comma-separated hosts that `fetch` requests from actions may be sent
+    /// to: domain names, `*.` and a domain name to match its subdomains, IP
+    /// addresses, or CIDR ranges. If set, requests to other hosts fail.
+    #[clap(long, env = "FETCH_ALLOWED_HOSTS", value_delimiter = ',')]
+    pub fetch_allowed_hosts: Vec<HostPattern>,
+
+    /// Comma-separated hosts, in the same format as `--fetch-allowed-hosts`,
+    /// that `fetch` requests from actions may not be sent to. Without
+    /// `--convex-http-proxy`, a domain name is also denied if any address it
+    /// resolves to is in a denied range.
+    #[clap(long, env = "FETCH_DENIED_HOSTS", value_delimiter = ',')]
+    pub fetch_denied_hosts: Vec<HostPattern>,
+
     /// Instance name for this backend.
     #[clap(long, requires = "instance_secret")]
     pub instance_name: Option<String>,
@@ -134,6 +158,27 @@ pub struct LocalConfig {
     #[clap(long)]
     pub local_log_sink: Option<String>,
 
+    /// Maximum number of HTTP requests the backend serves at once. Must be at
+    /// least 1.
+    #[clap(
+        long,
+        env = "MAX_CONCURRENT_REQUESTS",
+        default_value_t = DEFAULT_MAX_CONCURRENT_REQUESTS,
+        value_parser = parse_max_concurrent_requests,
+    )]
+    pub max_concurrent_requests: usize,
+
+    /// How long a Node action can run, in seconds, overriding the
+    /// `NODE_ACTION_USER_TIMEOUT` knob. The Node process itself is given five
+    /// more seconds than this so it can report the timeout before it's killed.
+    #[clap(long, env = "NODE_ACTION_TIMEOUT_SECS")]
+    node_action_timeout_secs: Option<u64>,
+
+    /// Maximum number of Node processes to run actions on. Concurrent actions
+    /// share processes once this many are running.
+    #[clap(long, env = "NODE_PROCESS_POOL_SIZE", default_value = "1")]
+    pub node_process_pool_size: NonZeroUsize,
+
     #[clap(subcommand)]
     pub subcommand: Option<Subcommand>,
 }
@@ -164,6 +209,16 @@ pub struct AdminKeyArgs {
     pub instance_secret: String,
 }
 
+fn parse_max_concurrent_requests(s: &str) -> Result<usize, String> {
+    let limit: usize = s
+        .parse()
+        .map_err(|e| format!("expected a positive integer: {e}"))?;
+    if limit == 0 {
+        return Err("the concurrent request limit must be at least 1".to_string());
+    }
+    Ok(limit)
+}
+
 impl fmt::Debug for LocalConfig {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Config")
@@ -179,6 +234,21 @@ impl LocalConfig {
         SocketAddr::new(self.interface.into(), self.port)
     }
 
+    /// Time limit for Node actions: the `--node-action-timeout-secs` override
+    /// if set, otherwise the `NODE_ACTION_USER_TIMEOUT` knob.
+    pub fn node_action_user_timeout(&self) -> Duration {
+        self.node_action_timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(*NODE_ACTION_USER_TIMEOUT)
+    }
+
+    pub fn fetch_host_filter(&self) -> FetchHostFilter {
+        FetchHostFilter::new(
+            self.fetch_allowed_hosts.clone(),
+            self.fetch_denied_hosts.clone(),
+        )
+    }
+
     pub fn site_forward_prefix(&self) -> String {
         format!("http://127.0.0.1:{}/http", self.port)
     }
@@ -250,3 +320,48 @@ impl LocalConfig {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::LocalConfig;
+    use crate::DEFAULT_MAX_CONCURRENT_REQUESTS;
+
+    #[test]
+    fn test_max_concurrent_requests_defaults_to_128() -> anyhow::Result<()> {
+        let config = LocalConfig::try_parse_from(["convex-local-backend"])?;
+        assert_eq!(
+            config.max_concurrent_requests,
+            DEFAULT_MAX_CONCURRENT_REQUESTS
+        );
+        assert_eq!(config.max_concurrent_requests, 128);
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_concurrent_requests_override() -> anyhow::Result<()> {
+        let config = LocalConfig::try_parse_from([
+            "convex-local-backend",
+            "--max-concurrent-requests",
+            "512",
+        ])?;
+        assert_eq!(config.max_concurrent_requests, 512);
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_concurrent_requests_must_be_positive() {
+        for limit in ["0", "-1", "many"] {
+            assert!(
+                LocalConfig::try_parse_from([
+                    "convex-local-backend",
+                    "--max-concurrent-requests",
+                    limit,
+                ])
+                .is_err(),
+                "{limit} should be rejected"
+            );
+        }
+    }
+}
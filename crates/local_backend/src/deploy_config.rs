@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use anyhow::Context;
 use application::{
     deploy_config::{
@@ -14,7 +16,10 @@ use axum::{
     response::IntoResponse,
 };
 use common::{
-    components::ComponentId,
+    components::{
+        CanonicalizedComponentModulePath,
+        ComponentId,
+    },
     execution_context::RequestMetadata,
     http::{
         extract::{
@@ -26,6 +31,7 @@ use common::{
     },
     version::Version,
 };
+use database::BootstrapComponentsModel;
 use errors::{
     ErrorMetadata,
     ErrorMetadataAnyhowExt,
@@ -39,7 +45,12 @@ use model::{
         },
         ConfigModel,
     },
-    source_packages::SourcePackageModel,
+    external_packages::ExternalPackagesModel,
+    modules::ModuleModel,
+    source_packages::{
+        types::MAX_UNZIPPED_PACKAGES_SIZE,
+        SourcePackageModel,
+    },
 };
 use roles::RequireDeploymentOp;
 use runtime::prod::ProdRuntime;
@@ -48,6 +59,7 @@ use serde::{
     Serialize,
 };
 use serde_json::Value as JsonValue;
+use sync_types::ModulePath;
 use value::{
     ConvexObject,
     TableNamespace,
@@ -80,6 +92,34 @@ pub struct GetConfigHashesResponse {
     pub node_version: Option<String>,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSourceSizeResponse {
+    /// Unzipped source size in bytes of each component's current modules,
+    /// keyed by component path.
+    pub components: BTreeMap<String, u64>,
+    /// Unzipped size in bytes of the deployed external node dependencies, if
+    /// any.
+    pub external_deps_size_bytes: Option<u64>,
+    /// The unzipped size in bytes a push is checked against.
+    pub max_size_bytes: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyModuleSourceRequest {
+    pub admin_key: String,
+    pub path: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyModuleSourceResponse {
+    /// Whether the source served from the module cache matches the source in
+    /// storage.
+    pub matches: bool,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ClientPushMetrics {
@@ -257,6 +297,88 @@ pub async fn get_config_hashes(
     }))
 }
 
+/// Returns the current source size of each component so the CLI can warn
+/// before a push approaches the size limit.
+pub async fn get_source_size(
+    MtState(st): MtState<LocalAppState>,
+    Json(req): Json<GetConfigRequest>,
+) -> Result<impl IntoResponse, HttpResponseError> {
+    let identity = must_be_admin_from_key(
+        st.application.app_auth(),
+        st.instance_name.clone(),
+        req.admin_key,
+    )
+    .await?;
+    identity.require_operation(keybroker::DeploymentOp::Deploy)?;
+
+    let mut tx = st.application.begin(identity).await?;
+    let mut components = BTreeMap::new();
+    for (component_id, component_path) in
+        BootstrapComponentsModel::new(&mut tx).all_component_paths()
+    {
+        let size = ModuleModel::new(&mut tx)
+            .total_source_size(component_id)
+            .await?;
+        components.insert(String::from(component_path), size);
+    }
+    let external_deps_package_id = SourcePackageModel::new(&mut tx, TableNamespace::Global)
+        .get_latest()
+        .await?
+        .and_then(|package| package.external_deps_package_id);
+    let external_deps_size_bytes = match external_deps_package_id {
+        Some(id) => Some(
+            ExternalPackagesModel::new(&mut tx)
+                .get(id)
+                .await?
+                .package_size
+                .unzipped_size_bytes as u64,
+        ),
+        None => None,
+    };
+    Ok(Json(GetSourceSizeResponse {
+        components,
+        external_deps_size_bytes,
+        max_size_bytes: MAX_UNZIPPED_PACKAGES_SIZE as u64,
+    }))
+}
+
+/// Debugging endpoint that checks the module cache is serving the deployed
+/// source for a module.
+pub async fn verify_module_source(
+    MtState(st): MtState<LocalAppState>,
+    Json(req): Json<VerifyModuleSourceRequest>,
+) -> Result<impl IntoResponse, HttpResponseError> {
+    let identity = must_be_admin_from_key(
+        st.application.app_auth(),
+        st.instance_name.clone(),
+        req.admin_key,
+    )
+    .await?;
+    identity.require_operation(keybroker::DeploymentOp::Deploy)?;
+
+    let module_path: ModulePath = req.path.parse().map_err(|e: anyhow::Error| {
+        e.context(ErrorMetadata::bad_request(
+            "InvalidModulePath",
+            format!("Invalid module path: {}", req.path),
+        ))
+    })?;
+    let path = CanonicalizedComponentModulePath {
+        component: ComponentId::Root, // This endpoint is only used pre-components.
+        module_path: module_path.canonicalize(),
+    };
+    let matches = st
+        .application
+        .module_source_matches_storage(identity, path)
+        .await?
+        .ok_or_else(|| {
+            anyhow::anyhow!(ErrorMetadata::not_found(
+                "ModuleNotFound",
+                format!("Module {} not found", req.path),
+            ))
+        })?;
+    Ok(Json(VerifyModuleSourceResponse { matches }))
+}
+
 #[debug_handler]
 pub async fn push_config(
     State(st): State<LocalAppState>,
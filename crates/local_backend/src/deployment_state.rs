@@ -15,7 +15,6 @@ use common::{
 };
 use errors::ErrorMetadata;
 use http::StatusCode;
-use model::backend_state::BackendStateModel;
 use roles::RequireDeploymentOp;
 use utoipa_axum::router::OpenApiRouter;
 
@@ -51,11 +50,7 @@ pub async fn pause_deployment(
 ) -> Result<impl IntoResponse, HttpResponseError> {
     identity.require_operation(keybroker::DeploymentOp::PauseDeployment)?;
 
-    let mut tx = st.application.begin(identity.clone()).await?;
-    let current_state = BackendStateModel::new(&mut tx)
-        .get_backend_state()
-        .await?
-        .into_value();
+    let current_state = st.application.backend_state(identity.clone()).await?;
     if current_state.system != SystemStopState::None {
         return Err(anyhow::anyhow!(ErrorMetadata::bad_request(
             "PauseDeploymentFailed",
@@ -95,11 +90,7 @@ pub async fn unpause_deployment(
 ) -> Result<impl IntoResponse, HttpResponseError> {
     identity.require_operation(keybroker::DeploymentOp::UnpauseDeployment)?;
 
-    let mut tx = st.application.begin(identity.clone()).await?;
-    let current_state = BackendStateModel::new(&mut tx)
-        .get_backend_state()
-        .await?
-        .into_value();
+    let current_state = st.application.backend_state(identity.clone()).await?;
     if current_state.system != SystemStopState::None {
         return Err(anyhow::anyhow!(ErrorMetadata::bad_request(
             "UnpauseDeploymentFailed",
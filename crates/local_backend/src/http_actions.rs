@@ -28,6 +28,10 @@ use axum::{
 use axum_extra::extract::Host;
 use common::{
     http::{
+        body_limit::{
+            request_body_too_large,
+            RequestBodyLimit,
+        },
         ExtractRequestId,
         ExtractRequestMetadata,
         ExtractResolvedHostname,
@@ -35,6 +39,10 @@ use common::{
         OriginalHttpUri,
         ResolvedHostname,
     },
+    knobs::{
+        HTTP_ACTION_REQUEST_BODY_LIMIT,
+        HTTP_ACTION_REQUEST_BODY_LIMIT_BY_PATH,
+    },
     types::FunctionCaller,
     RequestContext,
 };
@@ -50,7 +58,10 @@ use futures::{
 };
 use futures_async_stream::try_stream;
 use http::{
-    header::FORWARDED,
+    header::{
+        CONTENT_LENGTH,
+        FORWARDED,
+    },
     HeaderMap,
     Method,
     StatusCode,
@@ -71,7 +82,12 @@ use crate::{
     RouterState,
 };
 
-pub struct ExtractHttpRequestMetadata(pub HttpActionRequest);
+pub struct ExtractHttpRequestMetadata {
+    pub request: HttpActionRequest,
+    /// The limit on `request`'s body, which records whether the body passed it
+    /// while the action read it.
+    pub body_limit: RequestBodyLimit,
+}
 
 const X_FORWARDED_PROTO: &str = "x-forwarded-proto";
 
@@ -127,31 +143,57 @@ impl FromRequest<RouterState, axum::body::Body> for ExtractHttpRequestMetadata {
 
         // Construct the URL we provide in the HTTP request object.
         let url = Url::parse(&format!("{scheme}://{host}{uri}")).context("Invalid URL")?;
+        let body_limit = RequestBodyLimit::new(
+            HTTP_ACTION_REQUEST_BODY_LIMIT_BY_PATH
+                .limit_for(url.path(), *HTTP_ACTION_REQUEST_BODY_LIMIT),
+        );
 
         if method == Method::GET || method == Method::OPTIONS || method == Method::HEAD {
-            return Ok(ExtractHttpRequestMetadata(HttpActionRequest {
+            return Ok(ExtractHttpRequestMetadata {
+                request: HttpActionRequest {
+                    head: HttpActionRequestHead {
+                        headers,
+                        url,
+                        method,
+                    },
+                    body: None,
+                },
+                body_limit,
+            });
+        }
+
+        // Reject requests that declare a body over the limit up front. Bodies
+        // without a `Content-Length` (or that exceed it after decompression)
+        // fail the action's read of the body once they pass the limit, and
+        // `http_any_method` responds with a 413 then.
+        let content_length = headers
+            .get(CONTENT_LENGTH)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|s| s.parse::<u64>().ok());
+        body_limit
+            .check_content_length(content_length)
+            .map_err(body_too_large_response)?;
+        let body =
+            body_limit.limit_stream(req.into_body().into_data_stream().map_err(|e| e.into()));
+
+        Ok(ExtractHttpRequestMetadata {
+            request: HttpActionRequest {
                 head: HttpActionRequestHead {
                     headers,
                     url,
                     method,
                 },
-                body: None,
-            }));
-        }
-
-        let body = req.into_body();
-
-        Ok(ExtractHttpRequestMetadata(HttpActionRequest {
-            head: HttpActionRequestHead {
-                headers,
-                url,
-                method,
+                body: Some(body),
             },
-            body: Some(Box::pin(body.into_data_stream().map_err(|e| e.into()))),
-        }))
+            body_limit,
+        })
     }
 }
 
+fn body_too_large_response(err: anyhow::Error) -> HttpResponseError {
+    HttpResponseError::with_status_code(err, StatusCode::PAYLOAD_TOO_LARGE)
+}
+
 #[fastrace::trace(properties = { "udf_type": "http_action"})]
 #[debug_handler]
 pub async fn http_any_method(
@@ -160,7 +202,10 @@ pub async fn http_any_method(
     ExtractRequestId(request_id): ExtractRequestId,
     ExtractRequestMetadata(request_metadata): ExtractRequestMetadata,
     ExtractResolvedHostname(host): ExtractResolvedHostname,
-    ExtractHttpRequestMetadata(http_request_metadata): ExtractHttpRequestMetadata,
+    ExtractHttpRequestMetadata {
+        request: http_request_metadata,
+        body_limit,
+    }: ExtractHttpRequestMetadata,
 ) -> Result<impl IntoResponse, HttpResponseError> {
     // The `Authorization` header for the request may contain a token corresponding
     // to Convex auth, or it could be something separate managed by the developer.
@@ -175,7 +220,15 @@ pub async fn http_any_method(
         identity,
         st.api.clone(),
     );
-    let head = http_response_stream.try_next().await?;
+    let head = http_response_stream.try_next().await;
+    // However the action handled failing to read a body that passed its limit,
+    // respond with a 413 as long as the response hasn't started.
+    if body_limit.exceeded() {
+        return Err(body_too_large_response(anyhow::anyhow!(
+            request_body_too_large(body_limit.limit())
+        )));
+    }
+    let head = head?;
     let Some(HttpActionResponsePart::Head(response_head)) = head else {
         return Err(anyhow::anyhow!("Did not receive HTTP response head first").into());
     };
@@ -31,7 +31,6 @@ use common::{
     knobs::{
         DOCUMENT_RETENTION_RATE_LIMIT,
         INDEX_CACHE_SIZE,
-        NODE_ACTION_USER_TIMEOUT,
         UDF_CACHE_MAX_SIZE,
     },
     persistence::Persistence,
@@ -113,7 +112,8 @@ pub mod streaming_import;
 pub mod subs;
 pub mod usage_limits;
 
-pub const MAX_CONCURRENT_REQUESTS: usize = 128;
+/// Default for `LocalConfig::max_concurrent_requests`.
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 128;
 
 #[derive(Clone)]
 pub struct LocalAppState {
@@ -198,18 +198,22 @@ pub async fn make_app(
         region: None,
         class: DeploymentClass::S16,
     };
-    let node_process_timeout = *NODE_ACTION_USER_TIMEOUT + Duration::from_secs(5);
-    let node_executor = Arc::new(LocalNodeExecutor::new(node_process_timeout).await?);
+    let node_action_user_timeout = config.node_action_user_timeout();
+    let node_process_timeout = node_action_user_timeout + Duration::from_secs(5);
+    let node_executor = Arc::new(
+        LocalNodeExecutor::new(node_process_timeout, config.node_process_pool_size).await?,
+    );
     let node_actions = NodeActions::new(
         node_executor,
         config.convex_origin_url()?,
-        *NODE_ACTION_USER_TIMEOUT,
+        node_action_user_timeout,
         runtime.clone(),
         deployment.clone(),
     );
 
+    let fetch_host_filter = config.fetch_host_filter();
     #[cfg(not(debug_assertions))]
-    if config.convex_http_proxy.is_none() {
+    if config.convex_http_proxy.is_none() && fetch_host_filter.is_empty() {
         tracing::warn!(
             "Running without a proxy in release mode -- UDF `fetch` requests are unrestricted!"
         );
@@ -218,6 +222,7 @@ pub async fn make_app(
         config.convex_http_proxy.clone(),
         config.name(),
         reqwest::redirect::Policy::none(),
+        fetch_host_filter,
     ));
     let oidc_http_client = CachedHttpClient::new(
         config.convex_http_proxy.clone(),
@@ -8,6 +8,7 @@ use application::function_log::{
 };
 use axum::response::IntoResponse;
 use common::{
+    components::ComponentPath,
     http::{
         extract::{
             Json,
@@ -41,7 +42,12 @@ pub async fn stream_udf_execution(
     Query(query_args): Query<StreamUdfExecutionQueryArgs>,
 ) -> Result<impl IntoResponse, HttpResponseError> {
     let function_log = st.application.function_log(&identity)?;
-    let entries_future = function_log.stream(query_args.cursor);
+    let component = query_args
+        .component_path
+        .as_deref()
+        .map(|path| ComponentPath::deserialize(Some(path)))
+        .transpose()?;
+    let entries_future = function_log.stream(query_args.cursor, component.as_ref());
     let mut zombify_rx = st.zombify_rx.clone();
     futures::select_biased! {
         entries_result = entries_future.fuse() => {
@@ -83,7 +89,12 @@ pub async fn stream_function_logs(
     Query(query_args): Query<StreamFunctionLogs>,
 ) -> Result<impl IntoResponse, HttpResponseError> {
     let function_log = st.application.function_log(&identity)?;
-    let entries_future = function_log.stream_parts(query_args.cursor);
+    let component = query_args
+        .component_path
+        .as_deref()
+        .map(|path| ComponentPath::deserialize(Some(path)))
+        .transpose()?;
+    let entries_future = function_log.stream_parts(query_args.cursor, component.as_ref());
     let mut zombify_rx = st.zombify_rx.clone();
     let request_id = match (query_args.session_id, query_args.client_request_counter) {
         (Some(session_id), Some(client_request_counter)) => Some(RequestId::new_for_ws_session(
@@ -243,7 +254,11 @@ fn execution_to_json(
                 environment,
             }
         },
-        UdfParams::Http { result, identifier } => {
+        UdfParams::Http {
+            result,
+            identifier,
+            component,
+        } => {
             let identifier: String = identifier.to_string();
             let (success, error) = match result {
                 Ok(v) => (Some(JsonValue::from(v)), None),
@@ -251,7 +266,7 @@ fn execution_to_json(
             };
             FunctionExecutionJson::Completion {
                 udf_type: execution.udf_type.into(),
-                component_path: None,
+                component_path: component.serialize(),
                 identifier,
                 log_lines: execution
                     .log_lines
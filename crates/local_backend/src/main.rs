@@ -41,7 +41,6 @@ use local_backend::{
     proxy::dev_site_proxy,
     router::router,
     HttpActionRouteMapper,
-    MAX_CONCURRENT_REQUESTS,
 };
 use runtime::prod::ProdRuntime;
 use tokio::{
@@ -169,7 +168,7 @@ async fn run_server_inner(runtime: ProdRuntime, config: LocalConfig) -> anyhow::
         router,
         "backend",
         SERVER_VERSION_STR.to_string(),
-        MAX_CONCURRENT_REQUESTS,
+        config.max_concurrent_requests,
         *HTTP_SERVER_TIMEOUT_DURATION,
         HttpActionRouteMapper,
     );
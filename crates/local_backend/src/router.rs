@@ -27,6 +27,8 @@ use common::{
     },
     knobs::{
         AIRBYTE_STREAMING_IMPORT_REQUEST_SIZE_LIMIT,
+        HTTP_ACTION_REQUEST_BODY_LIMIT,
+        HTTP_ACTION_REQUEST_BODY_LIMIT_BY_PATH,
         MAX_BACKEND_RPC_REQUEST_SIZE,
         MAX_ECHO_BYTES,
         MAX_PUSH_BYTES,
@@ -46,7 +48,6 @@ use tower_http::{
     },
     decompression::RequestDecompressionLayer,
 };
-use udf::HTTP_ACTION_BODY_LIMIT;
 use utoipa::{
     openapi::security::{
         ApiKey,
@@ -62,14 +63,17 @@ use crate::{
     app_metrics::{
         cache_hit_percentage,
         cache_hit_percentage_top_k,
+        cron_execution_lag,
         failure_percentage_top_k,
         function_call_count_top_k,
         function_concurrency,
         latency_percentiles,
+        occ_conflict_hotspots,
         scheduled_job_lag,
         subscription_invalidations_top_k,
         table_rate,
         udf_rate,
+        websocket_connections,
     },
     canonical_urls::update_canonical_url,
     dashboard::{
@@ -80,7 +84,9 @@ use crate::{
     deploy_config::{
         get_config,
         get_config_hashes,
+        get_source_size,
         push_config,
+        verify_module_source,
     },
     deploy_config2,
     environment_variables::{
@@ -112,6 +118,7 @@ use crate::{
     scheduling::{
         cancel_all_jobs,
         cancel_job,
+        run_cron_job,
     },
     schema::{
         prepare_schema,
@@ -309,6 +316,7 @@ pub fn router(st: LocalAppState) -> Router {
         // Scheduled jobs routes
         .route("/cancel_all_jobs", post(cancel_all_jobs))
         .route("/cancel_job", post(cancel_job))
+        .route("/run_cron_job", post(run_cron_job))
         .route("/dashboard_openapi.json", axum::routing::get({
             move || async { dashboard_openapi_json }
         }))
@@ -342,6 +350,8 @@ pub fn router(st: LocalAppState) -> Router {
         )
         .route("/get_config", post(get_config))
         .route("/get_config_hashes", post(get_config_hashes))
+        .route("/get_source_size", post(get_source_size))
+        .route("/verify_module_source", post(verify_module_source))
         .route("/schema_state/{schema_id}", get(schema_state))
         .route("/stream_udf_execution", get(stream_udf_execution))
         .route("/stream_function_logs", get(stream_function_logs))
@@ -483,7 +493,10 @@ pub fn http_action_routes() -> Router<RouterState> {
     Router::new()
         .route("/{*rest}", http_action_handler())
         .route("/", http_action_handler())
-        .layer(DefaultBodyLimit::max(HTTP_ACTION_BODY_LIMIT))
+        // `ExtractHttpRequestMetadata` applies each path's limit itself.
+        .layer(DefaultBodyLimit::max(
+            HTTP_ACTION_REQUEST_BODY_LIMIT_BY_PATH.max_limit(*HTTP_ACTION_REQUEST_BODY_LIMIT),
+        ))
 }
 
 pub fn app_metrics_routes<S>() -> Router<S>
@@ -507,9 +520,12 @@ where
         )
         .route("/cache_hit_percentage", get(cache_hit_percentage))
         .route("/table_rate", get(table_rate))
+        .route("/websocket_connections", get(websocket_connections))
+        .route("/cron_execution_lag", get(cron_execution_lag))
         .route("/latency_percentiles", get(latency_percentiles))
         .route("/scheduled_job_lag", get(scheduled_job_lag))
         .route("/function_concurrency", get(function_concurrency))
+        .route("/occ_conflict_hotspots", get(occ_conflict_hotspots))
 }
 
 // Routes with the same handlers for the local backend + closed source backend
@@ -539,6 +555,8 @@ where
             get(|MtState(st): MtState<LocalAppState>| async move { st.instance_name.clone() }),
         )
         .route("/instance_version", get(|| async move { version }))
+        .route("/healthz", get(|| async { "ok" }))
+        .route("/readyz", get(readyz))
         .route(
             "/",
             get(|| async { "This Convex deployment is running. See https://docs.convex.dev/." }),
@@ -552,6 +570,21 @@ where
         .layer(cors())
 }
 
+/// Readiness probe: 200 if persistence and the search and modules storages
+/// are reachable, otherwise 503 listing the subsystems that aren't.
+async fn readyz(MtState(st): MtState<LocalAppState>) -> (StatusCode, String) {
+    let failures = st.application.check_readiness().await;
+    if failures.is_empty() {
+        return (StatusCode::OK, "ok".to_string());
+    }
+    let message = failures
+        .into_iter()
+        .map(|(subsystem, e)| format!("{} is not ready: {e:#}", subsystem.as_str()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    (StatusCode::SERVICE_UNAVAILABLE, message)
+}
+
 // IMPORTANT NOTE: Those routes are proxied by Usher. Any changes to the router,
 // such as adding or removing a route, or changing limits, also need to be
 // applied to `crates_private/usher/src/proxy.rs`.
@@ -23,6 +23,7 @@ use common::{
 use errors::ErrorMetadata;
 use http::StatusCode;
 use model::{
+    cron_jobs::types::CronIdentifier,
     deployment_audit_log::types::DeploymentAuditLogEvent,
     scheduled_jobs::{
         types::ScheduledJobMetadata,
@@ -35,9 +36,14 @@ use serde::{
     Deserialize,
     Serialize,
 };
+use serde_json::Value as JsonValue;
 use sync_types::Timestamp;
 use utoipa::ToSchema;
-use value::TableNamespace;
+use value::{
+    export::ValueFormat,
+    ConvexObject,
+    TableNamespace,
+};
 
 use crate::{
     authentication::ExtractIdentity,
@@ -179,6 +185,49 @@ pub async fn cancel_job(
     Ok(StatusCode::OK)
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCronJobRequest {
+    pub name: String,
+    pub component_id: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCronJobResponse {
+    /// The outcome of the run, in the same shape as the `status` of a cron
+    /// job log.
+    pub status: JsonValue,
+    pub log_lines: Vec<String>,
+    pub log_lines_truncated: bool,
+    /// Execution time in seconds.
+    pub execution_time: f64,
+}
+
+/// Runs a cron job once right away, leaving its schedule untouched.
+#[debug_handler]
+pub async fn run_cron_job(
+    State(st): State<LocalAppState>,
+    ExtractIdentity(identity): ExtractIdentity,
+    Json(RunCronJobRequest { name, component_id }): Json<RunCronJobRequest>,
+) -> Result<impl IntoResponse, HttpResponseError> {
+    let name: CronIdentifier = name.parse().context(ErrorMetadata::bad_request(
+        "InvalidCronName",
+        "RunCronJob requires a valid cron job name",
+    ))?;
+    let component_id = ComponentId::deserialize_from_string(component_id.as_deref())?;
+    let log = st
+        .application
+        .run_cron_job_now(identity, component_id, name)
+        .await?;
+    Ok(Json(RunCronJobResponse {
+        status: ConvexObject::try_from(log.status)?.export(ValueFormat::ConvexCleanJSON),
+        log_lines: log.log_lines.log_lines.into(),
+        log_lines_truncated: log.log_lines.is_truncated,
+        execution_time: log.execution_time,
+    }))
+}
+
 #[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteScheduledFunctionsTableRequest {
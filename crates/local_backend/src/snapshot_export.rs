@@ -32,6 +32,7 @@ use http::StatusCode;
 use model::{
     deployment_audit_log::types::DeploymentAuditLogEvent,
     exports::{
+        deployment_config::DeploymentConfigSections,
         types::{
             ExportFormat,
             ExportRequestor,
@@ -59,6 +60,18 @@ const MAX_CACHE_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 30);
 pub struct RequestZipExport {
     #[serde(default)]
     pub include_storage: bool,
+    /// Bundle the active schema of each component.
+    #[serde(default)]
+    pub include_schema: bool,
+    /// Bundle the index definitions of each component's tables.
+    #[serde(default)]
+    pub include_indexes: bool,
+    /// Bundle the cron jobs of each component.
+    #[serde(default)]
+    pub include_crons: bool,
+    /// Bundle the deployment's environment variables, including their values.
+    #[serde(default)]
+    pub include_environment_variables: bool,
     pub component: Option<String>,
 }
 
@@ -69,6 +82,10 @@ pub async fn request_zip_export(
     ExtractRequestMetadata(request_metadata): ExtractRequestMetadata,
     Query(RequestZipExport {
         include_storage,
+        include_schema,
+        include_indexes,
+        include_crons,
+        include_environment_variables,
         component,
     }): Query<RequestZipExport>,
 ) -> Result<impl IntoResponse, HttpResponseError> {
@@ -77,7 +94,15 @@ pub async fn request_zip_export(
         .request_export(
             identity,
             request_metadata,
-            ExportFormat::Zip { include_storage },
+            ExportFormat::Zip {
+                include_storage,
+                include_config: DeploymentConfigSections {
+                    schema: include_schema,
+                    indexes: include_indexes,
+                    crons: include_crons,
+                    environment_variables: include_environment_variables,
+                },
+            },
             component,
             ExportRequestor::SnapshotExport,
             None,
@@ -1,4 +1,8 @@
-use std::str::FromStr;
+use std::{
+    collections::BTreeMap,
+    str::FromStr,
+    time::Duration,
+};
 
 use anyhow::Context;
 use application::snapshot_import::{
@@ -25,12 +29,20 @@ use futures::{
     StreamExt,
     TryStreamExt,
 };
-use model::snapshot_imports::types::{
-    ImportFormat,
-    ImportMode,
+use model::{
+    exports::deployment_config::DeploymentConfigSections,
+    snapshot_imports::types::{
+        ComponentArgsPolicy,
+        ImportFormat,
+        ImportMode,
+        ImportOptions,
+        PartitionBy,
+        UnknownFieldsPolicy,
+    },
 };
 use roles::RequireDeploymentOp;
 use serde::{
+    de::IntoDeserializer,
     Deserialize,
     Serialize,
 };
@@ -38,8 +50,10 @@ use storage::{
     ClientDrivenUploadPartToken,
     ClientDrivenUploadToken,
 };
+use sync_types::CanonicalizedUdfPath;
 use value::{
     id_v6::DeveloperDocumentId,
+    FieldName,
     TableName,
 };
 
@@ -56,6 +70,83 @@ pub struct ImportQueryArgs {
     format: ImportFormatArg,
     #[serde(default)]
     mode: ImportMode,
+    /// Comma-separated list of tables to import first, in order.
+    #[serde(default)]
+    table_order: Option<String>,
+    /// Maximum total size in bytes of the documents written per transaction.
+    #[serde(default)]
+    batch_max_size_bytes: Option<usize>,
+    /// Maximum number of documents written per transaction.
+    #[serde(default)]
+    batch_max_num_documents: Option<usize>,
+    /// Fail the import if any document is missing an `_id` field.
+    #[serde(default)]
+    require_id_field: bool,
+    /// For JSON array imports, a JSON pointer (e.g. `/records`) to the array
+    /// of documents within the file.
+    #[serde(default)]
+    json_pointer: Option<String>,
+    /// Comma-separated `table:mode` pairs (e.g. `users:replace,logs:append`)
+    /// overriding `mode` for individual tables.
+    #[serde(default)]
+    per_table_mode: Option<String>,
+    /// Insert each table's documents in order of this field. JSON array
+    /// imports are sorted by it. Other formats are streamed, so they must
+    /// already be sorted by it or the import fails.
+    #[serde(default)]
+    order_by: Option<String>,
+    /// Fail the import if any `v.id()` reference in the imported documents
+    /// doesn't resolve once every table has been written.
+    #[serde(default)]
+    defer_reference_checks: bool,
+    /// Route each document to a table chosen by the value of this field.
+    /// Requires `partition_tables`.
+    #[serde(default)]
+    partition_by: Option<String>,
+    /// Comma-separated `value:table` pairs (e.g. `us:users_us,eu:users_eu`)
+    /// for `partition_by`. Documents with other values go to `table_name`.
+    #[serde(default)]
+    partition_tables: Option<String>,
+    /// For ZIP imports of an export with bundled deployment configuration,
+    /// restore each component's schema.
+    #[serde(default)]
+    restore_schema: bool,
+    /// Restore the bundled index definitions of the imported tables.
+    #[serde(default)]
+    restore_indexes: bool,
+    /// Restore the bundled cron jobs after the data is imported.
+    #[serde(default)]
+    restore_crons: bool,
+    /// Restore the bundled environment variables, overwriting existing
+    /// variables with the same name.
+    #[serde(default)]
+    restore_environment_variables: bool,
+    /// What to do with top-level fields that the table's schema doesn't
+    /// define: `error` (the default) or `drop`.
+    #[serde(default)]
+    unknown_fields: UnknownFieldsPolicy,
+    /// For append imports, insert documents whose `_id` doesn't exist yet,
+    /// replace documents that changed and skip unchanged ones. This reads
+    /// each existing document, so it's slower than a plain append.
+    #[serde(default)]
+    diff_import: bool,
+    /// Whether a ZIP import fails when a child component it writes to has
+    /// different arguments than the component it was exported from: `check`
+    /// (the default) or `ignore`.
+    #[serde(default)]
+    component_args: ComponentArgsPolicy,
+    /// Path of a query (e.g. `imports:transform`) in the component being
+    /// imported into. It's called with each document, and its return value
+    /// is imported in place of the document.
+    #[serde(default)]
+    transform: Option<String>,
+    /// For CSV imports, a comma-separated list of columns to import as
+    /// strings even when their values look like numbers.
+    #[serde(default)]
+    csv_string_columns: Option<String>,
+    /// Overall time limit for a synchronous import, in seconds.
+    #[serde(default)]
+    deadline_secs: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -72,6 +163,18 @@ pub struct ImportFinishUploadArgs {
 
     upload_token: String,
     part_tokens: Vec<String>,
+    /// The remaining files of a ZIP export that was split into several files,
+    /// in order. Each one is uploaded separately, and all of them are
+    /// imported together as one archive.
+    #[serde(default)]
+    additional_uploads: Vec<ImportUploadArgs>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportUploadArgs {
+    upload_token: String,
+    part_tokens: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
@@ -79,6 +182,7 @@ pub struct ImportFinishUploadArgs {
 enum ImportFormatArg {
     Csv,
     JsonLines,
+    JsonLinesGzip,
     JsonArray,
     Zip,
 }
@@ -121,24 +225,275 @@ fn parse_format_arg(
         ImportFormatArg::JsonLines => ImportFormat::JsonLines(table_name.context(
             ErrorMetadata::bad_request("InvalidName", "JSONL import requires table name"),
         )?),
+        ImportFormatArg::JsonLinesGzip => ImportFormat::JsonLinesGzip(table_name.context(
+            ErrorMetadata::bad_request("InvalidName", "JSONL import requires table name"),
+        )?),
     };
     Ok(inner_format)
 }
 
+/// An import request's arguments, parsed and validated.
+struct ParsedImportArgs {
+    format: ImportFormat,
+    mode: ImportMode,
+    component_path: ComponentPath,
+    options: ImportOptions,
+    deadline: Option<Duration>,
+}
+
+impl ImportQueryArgs {
+    fn parse(self) -> anyhow::Result<ParsedImportArgs> {
+        let ImportQueryArgs {
+            table_name,
+            component_path,
+            format,
+            mode,
+            table_order,
+            batch_max_size_bytes,
+            batch_max_num_documents,
+            require_id_field,
+            json_pointer,
+            per_table_mode,
+            order_by,
+            defer_reference_checks,
+            partition_by,
+            partition_tables,
+            restore_schema,
+            restore_indexes,
+            restore_crons,
+            restore_environment_variables,
+            unknown_fields,
+            diff_import,
+            component_args,
+            transform,
+            csv_string_columns,
+            deadline_secs,
+        } = self;
+        Ok(ParsedImportArgs {
+            format: parse_format_arg(table_name, format)?,
+            mode,
+            component_path: ComponentPath::deserialize(component_path.as_deref())?,
+            options: parse_import_options(ImportOptionsArgs {
+                table_order,
+                batch_max_size_bytes,
+                batch_max_num_documents,
+                require_id_field,
+                json_pointer,
+                per_table_mode,
+                order_by,
+                defer_reference_checks,
+                partition_by,
+                partition_tables,
+                restore_config: DeploymentConfigSections {
+                    schema: restore_schema,
+                    indexes: restore_indexes,
+                    crons: restore_crons,
+                    environment_variables: restore_environment_variables,
+                },
+                unknown_fields,
+                diff_import,
+                component_args,
+                transform,
+                csv_string_columns,
+            })?,
+            deadline: deadline_secs.map(Duration::from_secs),
+        })
+    }
+}
+
+/// The import options of an [ImportQueryArgs], before they're parsed.
+struct ImportOptionsArgs {
+    table_order: Option<String>,
+    batch_max_size_bytes: Option<usize>,
+    batch_max_num_documents: Option<usize>,
+    require_id_field: bool,
+    json_pointer: Option<String>,
+    per_table_mode: Option<String>,
+    order_by: Option<String>,
+    defer_reference_checks: bool,
+    partition_by: Option<String>,
+    partition_tables: Option<String>,
+    restore_config: DeploymentConfigSections,
+    unknown_fields: UnknownFieldsPolicy,
+    diff_import: bool,
+    component_args: ComponentArgsPolicy,
+    transform: Option<String>,
+    csv_string_columns: Option<String>,
+}
+
+fn parse_import_options(
+    ImportOptionsArgs {
+        table_order,
+        batch_max_size_bytes,
+        batch_max_num_documents,
+        require_id_field,
+        json_pointer,
+        per_table_mode,
+        order_by,
+        defer_reference_checks,
+        partition_by,
+        partition_tables,
+        restore_config,
+        unknown_fields,
+        diff_import,
+        component_args,
+        transform,
+        csv_string_columns,
+    }: ImportOptionsArgs,
+) -> anyhow::Result<ImportOptions> {
+    let table_order = table_order
+        .iter()
+        .flat_map(|table_order| table_order.split(','))
+        .map(|table_name| {
+            let table_name = table_name.trim();
+            TableName::from_str(table_name).map_err(|e| {
+                anyhow::anyhow!(ErrorMetadata::bad_request(
+                    "ImportInvalidName",
+                    format!("invalid table name {table_name}: {e}"),
+                ))
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+    let mut per_table_mode_map = BTreeMap::new();
+    for entry in per_table_mode.iter().flat_map(|s| s.split(',')) {
+        let (table_name, mode) = entry.split_once(':').with_context(|| {
+            ErrorMetadata::bad_request(
+                "InvalidPerTableMode",
+                format!("expected `table:mode`, got {:?}", entry.trim()),
+            )
+        })?;
+        let table_name = table_name.trim();
+        let table_name = TableName::from_str(table_name).map_err(|e| {
+            anyhow::anyhow!(ErrorMetadata::bad_request(
+                "ImportInvalidName",
+                format!("invalid table name {table_name}: {e}"),
+            ))
+        })?;
+        let mode = ImportMode::deserialize(mode.trim().into_deserializer()).map_err(
+            |e: serde::de::value::Error| {
+                anyhow::anyhow!(ErrorMetadata::bad_request(
+                    "InvalidPerTableMode",
+                    format!("invalid mode for table {table_name}: {e}"),
+                ))
+            },
+        )?;
+        if per_table_mode_map
+            .insert(table_name.clone(), mode)
+            .is_some()
+        {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidPerTableMode",
+                format!("Table \"{table_name}\" has more than one mode"),
+            ));
+        }
+    }
+    let order_by = order_by
+        .map(|field| {
+            FieldName::from_str(&field).map_err(|e| {
+                anyhow::anyhow!(ErrorMetadata::bad_request(
+                    "InvalidOrderBy",
+                    format!("invalid field name {field}: {e}"),
+                ))
+            })
+        })
+        .transpose()?;
+    let transform = transform
+        .map(|path| {
+            path.parse::<CanonicalizedUdfPath>().map_err(|e| {
+                anyhow::anyhow!(ErrorMetadata::bad_request(
+                    "InvalidTransform",
+                    format!("invalid transform function path {path}: {e}"),
+                ))
+            })
+        })
+        .transpose()?;
+    let csv_string_columns = csv_string_columns
+        .iter()
+        .flat_map(|columns| columns.split(','))
+        .map(|column| {
+            let column = column.trim();
+            FieldName::from_str(column).map_err(|e| {
+                anyhow::anyhow!(ErrorMetadata::bad_request(
+                    "InvalidCsvStringColumns",
+                    format!("invalid column name {column}: {e}"),
+                ))
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+    let partition_by = match (partition_by, partition_tables) {
+        (None, None) => None,
+        (Some(field), Some(partition_tables)) => {
+            let field = FieldName::from_str(&field).map_err(|e| {
+                anyhow::anyhow!(ErrorMetadata::bad_request(
+                    "InvalidPartitionBy",
+                    format!("invalid field name {field}: {e}"),
+                ))
+            })?;
+            let mut tables = BTreeMap::new();
+            for entry in partition_tables.split(',') {
+                // Table names can't contain `:`, so split on the last one.
+                let (value, table_name) = entry.rsplit_once(':').with_context(|| {
+                    ErrorMetadata::bad_request(
+                        "InvalidPartitionBy",
+                        format!("expected `value:table`, got {:?}", entry.trim()),
+                    )
+                })?;
+                let value = value.trim();
+                let table_name = table_name.trim();
+                let table_name = TableName::from_str(table_name).map_err(|e| {
+                    anyhow::anyhow!(ErrorMetadata::bad_request(
+                        "ImportInvalidName",
+                        format!("invalid table name {table_name}: {e}"),
+                    ))
+                })?;
+                if tables.insert(value.to_string(), table_name).is_some() {
+                    anyhow::bail!(ErrorMetadata::bad_request(
+                        "InvalidPartitionBy",
+                        format!("Value {value:?} has more than one table"),
+                    ));
+                }
+            }
+            Some(PartitionBy { field, tables })
+        },
+        (Some(_), None) | (None, Some(_)) => anyhow::bail!(ErrorMetadata::bad_request(
+            "InvalidPartitionBy",
+            "partition_by and partition_tables must be used together",
+        )),
+    };
+    Ok(ImportOptions {
+        table_order,
+        batch_max_size_bytes,
+        batch_max_num_documents,
+        require_id_field,
+        json_pointer,
+        per_table_mode: per_table_mode_map,
+        order_by,
+        defer_reference_checks,
+        partition_by,
+        restore_config,
+        unknown_fields,
+        diff_import,
+        component_args,
+        transform,
+        csv_string_columns,
+        include_tables: None,
+    })
+}
+
 pub async fn import(
     MtState(st): MtState<LocalAppState>,
     ExtractIdentity(identity): ExtractIdentity,
-    Query(ImportQueryArgs {
-        table_name,
-        component_path,
-        format,
-        mode,
-    }): Query<ImportQueryArgs>,
+    Query(args): Query<ImportQueryArgs>,
     stream: Body,
 ) -> Result<impl IntoResponse, HttpResponseError> {
     identity.require_operation(keybroker::DeploymentOp::ImportBackups)?;
-    let format = parse_format_arg(table_name, format)?;
-    let component_path = ComponentPath::deserialize(component_path.as_deref())?;
+    let ParsedImportArgs {
+        format,
+        mode,
+        component_path,
+        options,
+        deadline,
+    } = args.parse()?;
     let body_stream = stream
         .into_data_stream()
         .map_err(anyhow::Error::from)
@@ -149,7 +504,9 @@ pub async fn import(
         format,
         mode,
         component_path,
+        options,
         body_stream,
+        deadline,
     )
     .await?;
     Ok(Json(ImportResponse { num_written }))
@@ -214,19 +571,28 @@ pub async fn import_finish_upload(
     MtState(st): MtState<LocalAppState>,
     ExtractIdentity(identity): ExtractIdentity,
     Json(ImportFinishUploadArgs {
-        import:
-            ImportQueryArgs {
-                table_name,
-                component_path,
-                format,
-                mode,
-            },
+        import,
         upload_token,
         part_tokens,
+        additional_uploads,
     }): Json<ImportFinishUploadArgs>,
 ) -> Result<impl IntoResponse, HttpResponseError> {
-    let format = parse_format_arg(table_name, format)?;
-    let component_path = ComponentPath::deserialize(component_path.as_deref())?;
+    let ParsedImportArgs {
+        format,
+        mode,
+        component_path,
+        options,
+        deadline,
+    } = import.parse()?;
+    if deadline.is_some() {
+        // Stored imports run in the background, so there's nothing for a
+        // deadline to bound.
+        return Err(anyhow::anyhow!(ErrorMetadata::bad_request(
+            "InvalidDeadline",
+            "deadlineSecs is only supported for synchronous imports",
+        ))
+        .into());
+    }
     let import_id = st
         .application
         .import_finish_upload(
@@ -234,11 +600,25 @@ pub async fn import_finish_upload(
             format,
             mode,
             component_path,
+            options,
             ClientDrivenUploadToken(upload_token),
             part_tokens
                 .into_iter()
                 .map(ClientDrivenUploadPartToken)
                 .collect(),
+            additional_uploads
+                .into_iter()
+                .map(|upload| {
+                    (
+                        ClientDrivenUploadToken(upload.upload_token),
+                        upload
+                            .part_tokens
+                            .into_iter()
+                            .map(ClientDrivenUploadPartToken)
+                            .collect(),
+                    )
+                })
+                .collect(),
         )
         .await?;
     Ok(Json(ImportFinishUploadResponse {
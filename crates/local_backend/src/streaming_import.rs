@@ -37,7 +37,10 @@ use fivetran_destination::api_types::{
     TruncateTableArgs,
 };
 use http::StatusCode;
-use model::snapshot_imports::types::ImportRequestor;
+use model::snapshot_imports::types::{
+    ClearTablesAuditLog,
+    ImportRequestor,
+};
 use roles::RequireDeploymentOp;
 use serde::{
     Deserialize,
@@ -142,13 +145,21 @@ pub async fn fivetran_create_table(
 #[serde(rename_all = "camelCase")]
 pub struct ClearTableArgs {
     table_names: Vec<String>,
+    /// Skip the `ClearTables` deployment audit log entry. Admin-only, and
+    /// intended for automated resets of ephemeral test deployments. Leave this
+    /// unset for production deployments.
+    #[serde(default)]
+    skip_audit: bool,
 }
 
 pub async fn clear_tables(
     MtState(st): MtState<LocalAppState>,
     ExtractIdentity(identity): ExtractIdentity,
     ExtractRequestMetadata(request_metadata): ExtractRequestMetadata,
-    Json(ClearTableArgs { table_names }): Json<ClearTableArgs>,
+    Json(ClearTableArgs {
+        table_names,
+        skip_audit,
+    }): Json<ClearTableArgs>,
 ) -> Result<impl IntoResponse, HttpResponseError> {
     identity.require_operation(keybroker::DeploymentOp::ImportBackups)?;
 
@@ -171,6 +182,11 @@ pub async fn clear_tables(
             request_metadata,
             table_names,
             ImportRequestor::StreamingImport,
+            if skip_audit {
+                ClearTablesAuditLog::Skip
+            } else {
+                ClearTablesAuditLog::Record
+            },
             usage.clone(),
         )
         .await?;
@@ -218,6 +234,7 @@ pub async fn fivetran_truncate_table(
                 request_metadata,
                 vec![(ComponentPath::root(), table_name.0)],
                 ImportRequestor::StreamingImport,
+                ClearTablesAuditLog::Record,
                 usage.clone(),
             )
             .await?;
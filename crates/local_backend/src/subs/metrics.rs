@@ -5,6 +5,7 @@ use metrics::{
     log_counter_with_labels,
     log_distribution,
     log_distribution_with_labels,
+    log_gauge,
     register_convex_counter,
     register_convex_gauge,
     register_convex_histogram,
@@ -146,6 +147,15 @@ pub fn log_sync_protocol_websockets_total(partition_id: &str, delta: i8) {
         .add(delta as f64)
 }
 
+register_convex_gauge!(
+    SYNC_PROTOCOL_WEBSOCKETS_HIGH_WATER_MARK,
+    "Most WebSockets connected to this backend at once since it started or the high-water mark \
+     was reset",
+);
+pub fn log_sync_protocol_websockets_high_water_mark(value: u64) {
+    log_gauge(&SYNC_PROTOCOL_WEBSOCKETS_HIGH_WATER_MARK, value as f64)
+}
+
 register_convex_counter!(pub WEBSOCKET_CONNECTION_RESET_TOTAL, "Number of websocket connection resets");
 pub fn log_websocket_connection_reset() {
     log_counter(&WEBSOCKET_CONNECTION_RESET_TOTAL, 1)
@@ -1,6 +1,12 @@
-use std::time::{
-    Duration,
-    Instant,
+use std::{
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 use ::errors::{
@@ -69,6 +75,7 @@ use tokio::sync::mpsc;
 mod metrics;
 
 use metrics::{
+    log_sync_protocol_websockets_high_water_mark,
     log_sync_protocol_websockets_total,
     log_websocket_client_timeout,
     log_websocket_closed,
@@ -92,6 +99,27 @@ const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 /// How long before lack of client response causes a timeout.
 const CLIENT_TIMEOUT: Duration = Duration::from_secs(120);
 
+/// Number of sync WebSockets open in this process, across all partitions.
+static LIVE_WEBSOCKETS: AtomicU64 = AtomicU64::new(0);
+/// Most sync WebSockets open at once since the process started or
+/// [live_websocket_counts] last reset it.
+static LIVE_WEBSOCKETS_HIGH_WATER_MARK: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the number of open sync WebSockets and the high-water mark. If
+/// `reset_high_water_mark` is set, the high-water mark starts over from the
+/// current count after it's read.
+pub fn live_websocket_counts(reset_high_water_mark: bool) -> (u64, u64) {
+    let live = LIVE_WEBSOCKETS.load(Ordering::SeqCst);
+    let high_water_mark = if reset_high_water_mark {
+        let high_water_mark = LIVE_WEBSOCKETS_HIGH_WATER_MARK.swap(live, Ordering::SeqCst);
+        log_sync_protocol_websockets_high_water_mark(live);
+        high_water_mark
+    } else {
+        LIVE_WEBSOCKETS_HIGH_WATER_MARK.load(Ordering::SeqCst)
+    };
+    (live, high_water_mark)
+}
+
 struct SyncSocketDropToken {
     partition_id_label: String,
 }
@@ -100,6 +128,11 @@ struct SyncSocketDropToken {
 impl SyncSocketDropToken {
     fn new(partition_id_label: String) -> Self {
         log_sync_protocol_websockets_total(&partition_id_label, 1);
+        let live = LIVE_WEBSOCKETS.fetch_add(1, Ordering::SeqCst) + 1;
+        let high_water_mark = LIVE_WEBSOCKETS_HIGH_WATER_MARK
+            .fetch_max(live, Ordering::SeqCst)
+            .max(live);
+        log_sync_protocol_websockets_high_water_mark(high_water_mark);
         SyncSocketDropToken { partition_id_label }
     }
 }
@@ -107,6 +140,7 @@ impl SyncSocketDropToken {
 impl Drop for SyncSocketDropToken {
     fn drop(&mut self) {
         log_sync_protocol_websockets_total(&self.partition_id_label, -1);
+        LIVE_WEBSOCKETS.fetch_sub(1, Ordering::SeqCst);
     }
 }
 
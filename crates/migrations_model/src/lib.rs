@@ -33,7 +33,7 @@ pub type DatabaseVersion = i64;
 // migrations unless explicitly dropping support.
 // Add a user name next to the version when you make a change to highlight merge
 // conflicts.
-pub const DATABASE_VERSION: DatabaseVersion = 128; // ari
+pub const DATABASE_VERSION: DatabaseVersion = 129; // ari
 
 pub struct MigrationExecutor<RT: Runtime> {
     pub db: Database<RT>,
@@ -121,6 +121,11 @@ impl<RT: Runtime> MigrationExecutor<RT> {
                 // which is backfilled automatically on startup.
                 MigrationCompletionCriterion::MigrationComplete(to_version)
             },
+            129 => {
+                // This is an empty migration because we added a new system
+                // table, _snapshot_import_rows_written.
+                MigrationCompletionCriterion::MigrationComplete(to_version)
+            },
             // NOTE: Make sure to increase DATABASE_VERSION when adding new migrations.
             _ => anyhow::bail!("Version did not define a migration! {}", to_version),
         };
@@ -82,12 +82,15 @@ impl<'a, RT: Runtime> ComponentsModel<'a, RT> {
                         "Can't use an argument reference in the app"
                     ))
                 };
-                let resource = args.get(attribute).ok_or_else(|| {
-                    ErrorMetadata::bad_request(
+                let Some(resource) = args.get(attribute) else {
+                    let chain = self.resolution_chain(component_id, [attribute.to_string()])?;
+                    anyhow::bail!(ErrorMetadata::bad_request(
                         "InvalidReference",
-                        format!("Component argument '{attribute}' not found"),
-                    )
-                })?;
+                        format!(
+                            "while resolving {chain}, component argument '{attribute}' not found"
+                        ),
+                    ));
+                };
                 resource.clone()
             },
             Reference::Function(udf_path) => {
@@ -114,21 +117,39 @@ impl<'a, RT: Runtime> ComponentsModel<'a, RT> {
                     ComponentId::Child(id) => id,
                 };
                 let parent = (internal_id, child_component.clone());
-                let child_component = m.component_in_parent(Some(parent))?.ok_or_else(|| {
-                    ErrorMetadata::bad_request(
-                        "InvalidReference",
-                        format!("Child component {child_component:?} not found"),
-                    )
-                })?;
-                let child_id = ComponentId::Child(child_component.id().into());
-                let Some(resource) = self.resolve_export(child_id, attributes).await? else {
+                let Some(child) = m.component_in_parent(Some(parent))? else {
+                    let chain =
+                        self.resolution_chain(component_id, [child_component.to_string()])?;
                     anyhow::bail!(ErrorMetadata::bad_request(
                         "InvalidReference",
                         format!(
-                            "Child component {child_component:?} does not export {attributes:?}"
+                            "while resolving {chain}, child component {child_component:?} not \
+                             found"
                         ),
                     ));
                 };
+                let child_id = ComponentId::Child(child.id().into());
+                let resource = match self.resolve_export(child_id, attributes).await? {
+                    Some(resource) => Some(resource),
+                    // Not one of the child's own functions, so it may be
+                    // re-exported from a grandchild.
+                    None => self.resolve_re_export(child_id, attributes).await?,
+                };
+                let Some(resource) = resource else {
+                    let chain = self.resolution_chain(
+                        child_id,
+                        attributes.iter().map(|attribute| attribute.to_string()),
+                    )?;
+                    let export = attributes
+                        .iter()
+                        .map(|attribute| attribute.to_string())
+                        .collect::<Vec<_>>()
+                        .join(".");
+                    anyhow::bail!(ErrorMetadata::bad_request(
+                        "InvalidReference",
+                        format!("while resolving {chain}, export '{export}' not found"),
+                    ));
+                };
                 return Ok(resource);
             },
             Reference::CurrentSystemUdfInComponent {
@@ -164,6 +185,22 @@ impl<'a, RT: Runtime> ComponentsModel<'a, RT> {
         Ok(result)
     }
 
+    /// Describes the path traversed to resolve a reference, e.g. `app →
+    /// waitlist → ratelimit → foo`, for error messages.
+    fn resolution_chain(
+        &mut self,
+        component_id: ComponentId,
+        segments: impl IntoIterator<Item = String>,
+    ) -> anyhow::Result<String> {
+        let component_path =
+            BootstrapComponentsModel::new(self.tx).must_component_path(component_id)?;
+        let chain: Vec<String> = std::iter::once("app".to_string())
+            .chain(component_path.iter().map(|name| name.to_string()))
+            .chain(segments)
+            .collect();
+        Ok(chain.join(" → "))
+    }
+
     pub async fn load_component_exports(
         &mut self,
         component_id: ComponentId,
@@ -213,6 +250,117 @@ impl<'a, RT: Runtime> ComponentsModel<'a, RT> {
         Ok(None)
     }
 
+    /// Resolves `attributes` against `component_id`'s export tree, where the
+    /// path may stop at a branch, e.g. a re-exported namespace. Returns every
+    /// resource under that branch keyed by its path relative to the branch, or
+    /// a single entry with an empty path if `attributes` names a leaf.
+    /// References to child components are followed into the child's exports,
+    /// so a leaf may itself point at another component's branch.
+    #[async_recursion]
+    pub async fn resolve_export_subtree(
+        &mut self,
+        component_id: ComponentId,
+        attributes: &[PathComponent],
+    ) -> anyhow::Result<Option<BTreeMap<Vec<PathComponent>, Resource>>> {
+        let exports = self.load_component_exports(component_id).await?;
+        let leaves = match lookup_export_subtree(&exports, attributes) {
+            None => return Ok(None),
+            Some(ExportSubtree::Leaf { reference, rest }) => {
+                return self
+                    .resolve_reference_subtree(component_id, reference, rest)
+                    .await;
+            },
+            Some(ExportSubtree::Branch(leaves)) => leaves,
+        };
+        let mut result = BTreeMap::new();
+        for (path, reference) in leaves {
+            let Some(resources) = self
+                .resolve_reference_subtree(component_id, reference, &[])
+                .await?
+            else {
+                continue;
+            };
+            for (sub_path, resource) in resources {
+                let mut full_path = path.clone();
+                full_path.extend(sub_path);
+                result.insert(full_path, resource);
+            }
+        }
+        Ok(Some(result))
+    }
+
+    /// Resolves `attributes` to a single resource through
+    /// [Self::resolve_export_subtree], failing if they name a namespace of
+    /// several exports rather than one.
+    async fn resolve_re_export(
+        &mut self,
+        component_id: ComponentId,
+        attributes: &[PathComponent],
+    ) -> anyhow::Result<Option<Resource>> {
+        let Some(mut resources) = self
+            .resolve_export_subtree(component_id, attributes)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let leaf_path: &[PathComponent] = &[];
+        if let Some(resource) = resources.remove(leaf_path) {
+            return Ok(Some(resource));
+        }
+        if resources.is_empty() {
+            return Ok(None);
+        }
+        let chain = self.resolution_chain(
+            component_id,
+            attributes.iter().map(|attribute| attribute.to_string()),
+        )?;
+        anyhow::bail!(ErrorMetadata::bad_request(
+            "InvalidReference",
+            format!(
+                "while resolving {chain}, found a namespace of {} exports rather than a single \
+                 function",
+                resources.len()
+            ),
+        ));
+    }
+
+    /// Resolves an export leaf's `reference` followed by the `rest` of the
+    /// attribute path, for [Self::resolve_export_subtree].
+    async fn resolve_reference_subtree(
+        &mut self,
+        component_id: ComponentId,
+        reference: &Reference,
+        rest: &[PathComponent],
+    ) -> anyhow::Result<Option<BTreeMap<Vec<PathComponent>, Resource>>> {
+        match reference {
+            Reference::ChildComponent {
+                component,
+                attributes,
+            } => {
+                let Some(child_id) = self
+                    .component_children_ids(component_id)
+                    .await?
+                    .remove(component)
+                else {
+                    return Ok(None);
+                };
+                let mut child_attributes = attributes.clone();
+                child_attributes.extend(rest.iter().cloned());
+                self.resolve_export_subtree(child_id, &child_attributes)
+                    .await
+            },
+            Reference::ComponentArgument { .. }
+            | Reference::Function(_)
+            | Reference::CurrentSystemUdfInComponent { .. } => {
+                if !rest.is_empty() {
+                    return Ok(None);
+                }
+                let resource = self.resolve(component_id, None, reference).await?;
+                Ok(Some(BTreeMap::from([(vec![], resource)])))
+            },
+        }
+    }
+
     #[fastrace::trace]
     pub async fn resolve_public_export_path(
         &mut self,
@@ -247,9 +395,28 @@ impl<'a, RT: Runtime> ComponentsModel<'a, RT> {
         Ok(path)
     }
 
+    /// Resolves every reference available to `component_id`. Results are
+    /// cached for the rest of the transaction, until a system table is
+    /// written, and a cache hit records the same reads as recomputing.
     pub async fn preload_resources(
         &mut self,
         component_id: ComponentId,
+    ) -> anyhow::Result<BTreeMap<Reference, Resource>> {
+        if let Some(resources) = self.tx.cached_component_resources(component_id) {
+            return Ok(resources);
+        }
+        let saved_reads = self.tx.start_snoop_reads();
+        let result = self.load_resources(component_id).await;
+        let reads = self.tx.finish_snoop_reads(saved_reads);
+        let resources = result?;
+        self.tx
+            .cache_component_resources(component_id, resources.clone(), reads);
+        Ok(resources)
+    }
+
+    async fn load_resources(
+        &mut self,
+        component_id: ComponentId,
     ) -> anyhow::Result<BTreeMap<Reference, Resource>> {
         let mut m = BootstrapComponentsModel::new(self.tx);
         let component_type = m.load_component_type(component_id).await?;
@@ -325,26 +492,48 @@ impl<'a, RT: Runtime> ComponentsModel<'a, RT> {
         let definition_id = m.component_definition(component_id).await?;
         let definition = m.load_definition_metadata(definition_id).await?;
 
-        let mut result = BTreeMap::new();
-        if let Some(component) = component {
-            for instantiation in definition.child_components {
-                let parent = (component.id().into(), instantiation.name.clone());
-                let Some(child_component) = m.component_in_parent(Some(parent.clone()))? else {
-                    // This shouldn't happen, but it is possible because of a bug where we
-                    // weren't deleting child_components when we unmounted the child component.
-                    tracing::error!("Missing child component: {:?}", parent);
-                    continue;
-                };
-                result.insert(instantiation.name, child_component);
+        let Some(component) = component else {
+            return Ok(BTreeMap::new());
+        };
+        let parent = component.id().into();
+        let names: Vec<_> = definition
+            .child_components
+            .into_iter()
+            .map(|instantiation| instantiation.name)
+            .collect();
+        let result = m.components_in_parent(parent, names.clone())?;
+        for name in names {
+            if !result.contains_key(&name) {
+                // This shouldn't happen, but it is possible because of a bug where we
+                // weren't deleting child_components when we unmounted the child component.
+                tracing::error!("Missing child component: {:?}", (parent, name));
             }
         }
 
         Ok(result)
     }
 
+    /// Resolves every resource exported by `component_id`, cached like
+    /// [Self::preload_resources].
     pub async fn preload_exported_resources(
         &mut self,
         component_id: ComponentId,
+    ) -> anyhow::Result<BTreeMap<Vec<PathComponent>, Resource>> {
+        if let Some(resources) = self.tx.cached_component_exported_resources(component_id) {
+            return Ok(resources);
+        }
+        let saved_reads = self.tx.start_snoop_reads();
+        let result = self.load_exported_resources(component_id).await;
+        let reads = self.tx.finish_snoop_reads(saved_reads);
+        let resources = result?;
+        self.tx
+            .cache_component_exported_resources(component_id, resources.clone(), reads);
+        Ok(resources)
+    }
+
+    async fn load_exported_resources(
+        &mut self,
+        component_id: ComponentId,
     ) -> anyhow::Result<BTreeMap<Vec<PathComponent>, Resource>> {
         let exports = self.load_component_exports(component_id).await?;
         let mut stack = vec![(vec![], &exports)];
@@ -370,3 +559,224 @@ impl<'a, RT: Runtime> ComponentsModel<'a, RT> {
         Ok(result)
     }
 }
+
+/// Where an attribute path ends up in a component's export tree.
+#[derive(Debug, PartialEq)]
+enum ExportSubtree<'a> {
+    /// The path reached a leaf. `rest` is the rest of the path, to resolve
+    /// against whatever the leaf references.
+    Leaf {
+        reference: &'a Reference,
+        rest: &'a [PathComponent],
+    },
+    /// The path stopped at a branch. Holds every leaf under it, keyed by its
+    /// path relative to the branch.
+    Branch(Vec<(Vec<PathComponent>, &'a Reference)>),
+}
+
+/// Walks `attributes` down `exports`, returning None if they don't name an
+/// export.
+fn lookup_export_subtree<'a>(
+    exports: &'a BTreeMap<PathComponent, ComponentExport>,
+    attributes: &'a [PathComponent],
+) -> Option<ExportSubtree<'a>> {
+    let mut node = exports;
+    for (i, attribute) in attributes.iter().enumerate() {
+        match node.get(attribute)? {
+            ComponentExport::Branch(children) => node = children,
+            ComponentExport::Leaf(reference) => {
+                return Some(ExportSubtree::Leaf {
+                    reference,
+                    rest: &attributes[i + 1..],
+                });
+            },
+        }
+    }
+    let mut stack = vec![(vec![], node)];
+    let mut leaves = vec![];
+    while let Some((path, internal_node)) = stack.pop() {
+        for (name, export) in internal_node {
+            let mut new_path: Vec<PathComponent> = path.clone();
+            new_path.push(name.clone());
+            match export {
+                ComponentExport::Branch(children) => stack.push((new_path, children)),
+                ComponentExport::Leaf(reference) => leaves.push((new_path, reference)),
+            }
+        }
+    }
+    Some(ExportSubtree::Branch(leaves))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use common::{
+        bootstrap_model::components::definition::ComponentExport,
+        components::{
+            ComponentName,
+            Reference,
+        },
+    };
+    use sync_types::path::PathComponent;
+
+    use super::{
+        lookup_export_subtree,
+        ExportSubtree,
+    };
+
+    fn path(s: &str) -> anyhow::Result<Vec<PathComponent>> {
+        s.split('.')
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    fn function(udf_path: &str) -> anyhow::Result<ComponentExport> {
+        Ok(ComponentExport::Leaf(Reference::Function(
+            udf_path.parse()?,
+        )))
+    }
+
+    fn branch(
+        children: impl IntoIterator<Item = (&'static str, ComponentExport)>,
+    ) -> anyhow::Result<ComponentExport> {
+        Ok(ComponentExport::Branch(exports(children)?))
+    }
+
+    fn exports(
+        children: impl IntoIterator<Item = (&'static str, ComponentExport)>,
+    ) -> anyhow::Result<BTreeMap<PathComponent, ComponentExport>> {
+        children
+            .into_iter()
+            .map(|(name, export)| Ok((name.parse()?, export)))
+            .collect()
+    }
+
+    /// Resolves `attributes` in `component`, following references to child
+    /// components, the way `ComponentsModel::resolve_export_subtree` does.
+    fn resolve(
+        components: &BTreeMap<&str, BTreeMap<PathComponent, ComponentExport>>,
+        component: &str,
+        attributes: &[PathComponent],
+    ) -> Option<BTreeMap<Vec<PathComponent>, String>> {
+        let resolve_reference = |reference: &Reference, rest: &[PathComponent]| match reference {
+            Reference::ChildComponent {
+                component,
+                attributes,
+            } => {
+                let mut child_attributes = attributes.clone();
+                child_attributes.extend(rest.iter().cloned());
+                resolve(components, &component[..], &child_attributes)
+            },
+            Reference::Function(udf_path) if rest.is_empty() => Some(BTreeMap::from([(
+                vec![],
+                format!("{component}/{udf_path}"),
+            )])),
+            Reference::Function(_)
+            | Reference::ComponentArgument { .. }
+            | Reference::CurrentSystemUdfInComponent { .. } => None,
+        };
+        match lookup_export_subtree(&components[component], attributes)? {
+            ExportSubtree::Leaf { reference, rest } => resolve_reference(reference, rest),
+            ExportSubtree::Branch(leaves) => Some(
+                leaves
+                    .into_iter()
+                    .filter_map(|(path, reference)| {
+                        let resources = resolve_reference(reference, &[])?;
+                        Some(resources.into_iter().map(move |(sub_path, resource)| {
+                            let mut full_path = path.clone();
+                            full_path.extend(sub_path);
+                            (full_path, resource)
+                        }))
+                    })
+                    .flatten()
+                    .collect(),
+            ),
+        }
+    }
+
+    fn child(component: &str, attributes: &str) -> anyhow::Result<ComponentExport> {
+        Ok(ComponentExport::Leaf(Reference::ChildComponent {
+            component: component.parse::<ComponentName>()?,
+            attributes: path(attributes)?,
+        }))
+    }
+
+    #[test]
+    fn test_lookup_stops_at_branch() -> anyhow::Result<()> {
+        let exports = exports([(
+            "messages",
+            branch([
+                ("list", function("messages.js:list")?),
+                ("admin", branch([("clear", function("admin.js:clear")?)])?),
+            ])?,
+        )])?;
+        let Some(ExportSubtree::Branch(mut leaves)) =
+            lookup_export_subtree(&exports, &path("messages")?)
+        else {
+            anyhow::bail!("expected a branch");
+        };
+        leaves.sort();
+        let paths: Vec<_> = leaves.into_iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, vec![path("admin.clear")?, path("list")?]);
+
+        let leaf_path = path("messages.list")?;
+        let Some(ExportSubtree::Leaf { rest, .. }) = lookup_export_subtree(&exports, &leaf_path)
+        else {
+            anyhow::bail!("expected a leaf");
+        };
+        assert!(rest.is_empty());
+        assert_eq!(lookup_export_subtree(&exports, &path("missing")?), None);
+        assert_eq!(
+            lookup_export_subtree(&exports, &path("messages.list.extra")?),
+            Some(ExportSubtree::Leaf {
+                reference: &Reference::Function("messages.js:list".parse()?),
+                rest: &path("extra")?,
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_two_level_re_export() -> anyhow::Result<()> {
+        // `app` re-exports `waitlist.queue`, which `waitlist` itself
+        // re-exports from its `ratelimit` child.
+        let components = BTreeMap::from([
+            ("app", exports([("queue", child("waitlist", "queue")?)])?),
+            (
+                "waitlist",
+                exports([
+                    ("queue", child("ratelimit", "limits")?),
+                    ("join", function("waitlist.js:join")?),
+                ])?,
+            ),
+            (
+                "ratelimit",
+                exports([(
+                    "limits",
+                    branch([
+                        ("check", function("limits.js:check")?),
+                        ("reset", function("limits.js:reset")?),
+                    ])?,
+                )])?,
+            ),
+        ]);
+        assert_eq!(
+            resolve(&components, "app", &path("queue")?),
+            Some(BTreeMap::from([
+                (path("check")?, "ratelimit/limits.js:check".to_string()),
+                (path("reset")?, "ratelimit/limits.js:reset".to_string()),
+            ]))
+        );
+        assert_eq!(
+            resolve(&components, "app", &path("queue.check")?),
+            Some(BTreeMap::from([(
+                vec![],
+                "ratelimit/limits.js:check".to_string()
+            )]))
+        );
+        assert_eq!(resolve(&components, "app", &path("queue.missing")?), None);
+        Ok(())
+    }
+}
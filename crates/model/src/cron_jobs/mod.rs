@@ -11,6 +11,7 @@ use common::{
         ParsedDocument,
         ResolvedDocument,
     },
+    knobs::CRON_JOB_LOGS_PER_JOB,
     query::{
         IndexRange,
         IndexRangeExpression,
@@ -18,6 +19,7 @@ use common::{
         Query,
     },
     runtime::Runtime,
+    types::Timestamp,
 };
 use database::{
     ResolvedQuery,
@@ -132,7 +134,9 @@ impl SystemTable for CronNextRunTable {
     }
 }
 
-const MAX_LOGS_PER_CRON: usize = 5;
+fn max_logs_per_cron() -> usize {
+    (*CRON_JOB_LOGS_PER_JOB).max(1)
+}
 
 pub struct CronModel<'a, RT: Runtime> {
     pub tx: &'a mut Transaction<RT>,
@@ -191,19 +195,56 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
         Ok(cron_diff)
     }
 
+    /// Creates or updates each of `crons`, leaving crons that aren't listed
+    /// untouched. Unlike [`Self::apply`], this doesn't need the crons to be
+    /// defined in `crons.js`, so it's used to restore crons from a snapshot.
+    pub async fn restore(
+        &mut self,
+        crons: &BTreeMap<CronIdentifier, CronSpec>,
+    ) -> anyhow::Result<CronDiff> {
+        let old_crons = self.list_metadata().await?;
+        let mut added_crons: Vec<&CronIdentifier> = vec![];
+        let mut updated_crons: Vec<&CronIdentifier> = vec![];
+        for (name, cron_spec) in crons {
+            match old_crons.get(name) {
+                Some(cron_job) => {
+                    if cron_job.cron_spec != *cron_spec {
+                        self.update(cron_job.clone(), cron_spec.clone()).await?;
+                        updated_crons.push(name);
+                    }
+                },
+                None => {
+                    self.create(name.clone(), cron_spec.clone()).await?;
+                    added_crons.push(name);
+                },
+            }
+        }
+        tracing::info!("Crons restored: {added_crons:?}, Updated: {updated_crons:?}");
+        Ok(CronDiff::new(added_crons, updated_crons, vec![]))
+    }
+
     pub async fn create(
         &mut self,
         name: CronIdentifier,
         cron_spec: CronSpec,
     ) -> anyhow::Result<()> {
-        let now = self.runtime().generate_timestamp()?;
-        let next_ts = compute_next_ts(&cron_spec, None, now, &mut self.runtime().rng())?;
-        let cron = CronJobMetadata { name, cron_spec };
+        let cron = CronJobMetadata {
+            name,
+            cron_spec: cron_spec.clone(),
+        };
 
         let cron_job_id = SystemMetadataModel::new(self.tx, self.component.into())
             .insert(&CRON_JOBS_TABLE, cron.try_into()?)
             .await?
             .developer_id;
+        let now = self.runtime().generate_timestamp()?;
+        let next_ts = compute_next_ts(
+            &cron_spec,
+            cron_job_id,
+            None,
+            now,
+            &mut self.runtime().rng(),
+        )?;
 
         let next_run = CronNextRun {
             cron_job_id,
@@ -244,17 +285,28 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
         mut cron_job: ParsedDocument<CronJobMetadata>,
         new_cron_spec: CronSpec,
     ) -> anyhow::Result<()> {
-        if new_cron_spec.cron_schedule != cron_job.cron_spec.cron_schedule {
+        if new_cron_spec.cron_schedule != cron_job.cron_spec.cron_schedule
+            || new_cron_spec.run_window != cron_job.cron_spec.run_window
+            || new_cron_spec.timezone != cron_job.cron_spec.timezone
+            || new_cron_spec.jitter != cron_job.cron_spec.jitter
+        {
             // Skip updating the next run ts, if the runs are close together on the old
             // schedule. This is a heuristic to avoid OCC with existing cron
             // jobs running/changing state. True solution would be to move this
             // logic to the async worker, but quickfix for now is to skip the
             // `update_job_state`.
+            let cron_job_id = cron_job.id().developer_id;
             let now = self.runtime().generate_timestamp()?;
-            let next_ts =
-                compute_next_ts(&cron_job.cron_spec, None, now, &mut self.runtime().rng())?;
+            let next_ts = compute_next_ts(
+                &cron_job.cron_spec,
+                cron_job_id,
+                None,
+                now,
+                &mut self.runtime().rng(),
+            )?;
             let next_next_run = compute_next_ts(
                 &cron_job.cron_spec,
+                cron_job_id,
                 Some(next_ts),
                 next_ts,
                 &mut self.runtime().rng(),
@@ -262,15 +314,20 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
             if next_next_run.secs_since_f64(now) > 30.0 {
                 // Read in next-run to the readset and update it.
                 let mut next_run = self
-                    .next_run(cron_job.id().developer_id)
+                    .next_run(cron_job_id)
                     .await?
                     .context("No next run found")?
                     .into_value();
 
                 // Recalculate on the new schedule.
                 let now = self.runtime().generate_timestamp()?;
-                next_run.next_ts =
-                    compute_next_ts(&new_cron_spec, None, now, &mut self.runtime().rng())?;
+                next_run.next_ts = compute_next_ts(
+                    &new_cron_spec,
+                    cron_job_id,
+                    None,
+                    now,
+                    &mut self.runtime().rng(),
+                )?;
                 self.update_job_state(next_run).await?;
             }
         }
@@ -312,7 +369,7 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
 
     /// Create space in the CronJobLogsTable for a new run of `job`
     pub async fn prepare_insert_cron_job_log(&mut self, job: &CronJob) -> anyhow::Result<()> {
-        self.apply_job_log_retention(&job.name, MAX_LOGS_PER_CRON - 1)
+        self.apply_job_log_retention(&job.name, max_logs_per_cron() - 1)
             .await?;
         Ok(())
     }
@@ -336,7 +393,7 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
         SystemMetadataModel::new(self.tx, self.component.into())
             .insert_metadata(&CRON_JOB_LOGS_TABLE, cron_job_log.try_into()?)
             .await?;
-        self.apply_job_log_retention(&job.name, MAX_LOGS_PER_CRON)
+        self.apply_job_log_retention(&job.name, max_logs_per_cron())
             .await?;
         Ok(())
     }
@@ -373,6 +430,39 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
         Ok(cron_jobs)
     }
 
+    pub async fn get_by_name(&mut self, name: &CronIdentifier) -> anyhow::Result<Option<CronJob>> {
+        Ok(self.list().await?.remove(name))
+    }
+
+    /// Returns the log written by the run of the cron `name` at `ts`, if it
+    /// hasn't been removed by log retention.
+    pub async fn job_log(
+        &mut self,
+        name: &CronIdentifier,
+        ts: Timestamp,
+    ) -> anyhow::Result<Option<CronJobLog>> {
+        let index_query = Query::index_range(IndexRange {
+            index_name: CRON_JOB_LOGS_INDEX_BY_NAME_TS.name(),
+            range: vec![
+                IndexRangeExpression::Eq(
+                    CRON_JOB_LOGS_NAME_FIELD.clone(),
+                    ConvexValue::try_from(name.to_string())?.into(),
+                ),
+                IndexRangeExpression::Eq(
+                    CRON_JOB_LOGS_TS_FIELD.clone(),
+                    ConvexValue::Int64(ts.into()).into(),
+                ),
+            ],
+            order: Order::Asc,
+        });
+        let mut query_stream = ResolvedQuery::new(self.tx, self.component.into(), index_query)?;
+        let Some(doc) = query_stream.expect_at_most_one(self.tx).await? else {
+            return Ok(None);
+        };
+        let log: ParsedDocument<CronJobLog> = doc.parse()?;
+        Ok(Some(log.into_value()))
+    }
+
     pub async fn list_metadata(
         &mut self,
     ) -> anyhow::Result<BTreeMap<CronIdentifier, ParsedDocument<CronJobMetadata>>> {
@@ -405,15 +495,11 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
             order: Order::Desc,
         });
         let mut query_stream = ResolvedQuery::new(self.tx, self.component.into(), index_query)?;
-        let mut num_logs = 0;
-        let mut to_delete = Vec::new();
+        let mut newest_first = Vec::new();
         while let Some(doc) = query_stream.next(self.tx, None).await? {
-            num_logs += 1;
-            if num_logs > limit {
-                to_delete.push(doc.id());
-            }
+            newest_first.push(doc.id());
         }
-        for doc_id in to_delete.into_iter() {
+        for doc_id in logs_to_prune(newest_first, limit) {
             SystemMetadataModel::new(self.tx, self.component.into())
                 .delete(doc_id)
                 .await?;
@@ -422,6 +508,11 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
     }
 }
 
+/// The logs to delete to keep the newest `limit` of `newest_first`.
+fn logs_to_prune<T>(newest_first: Vec<T>, limit: usize) -> Vec<T> {
+    newest_first.into_iter().skip(limit).collect()
+}
+
 #[try_stream(boxed, ok = CronJob, error = anyhow::Error)]
 pub async fn stream_cron_jobs_to_run<'a, RT: Runtime>(tx: &'a mut Transaction<RT>) {
     let namespaces: Vec<_> = tx
@@ -470,3 +561,38 @@ pub async fn stream_cron_jobs_to_run<'a, RT: Runtime>(tx: &'a mut Transaction<RT
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::logs_to_prune;
+
+    /// Logs `runs` runs the way `CronModel` does: pruning to make space
+    /// before the run, then pruning again after its log is inserted.
+    fn log_runs(runs: u32, limit: usize) -> Vec<u32> {
+        fn prune(newest_first: &mut Vec<u32>, limit: usize) {
+            let pruned = logs_to_prune(newest_first.clone(), limit);
+            newest_first.retain(|log| !pruned.contains(log));
+        }
+        let mut newest_first = Vec::new();
+        for run in 0..runs {
+            prune(&mut newest_first, limit - 1);
+            newest_first.insert(0, run);
+            prune(&mut newest_first, limit);
+        }
+        newest_first
+    }
+
+    #[test]
+    fn test_pruning_keeps_most_recent_logs() {
+        assert_eq!(log_runs(8, 5), vec![7, 6, 5, 4, 3]);
+        assert_eq!(log_runs(3, 5), vec![2, 1, 0]);
+        assert_eq!(log_runs(4, 1), vec![3]);
+    }
+
+    #[test]
+    fn test_logs_to_prune_drops_oldest() {
+        assert_eq!(logs_to_prune(vec![9, 8, 7, 6], 2), vec![7, 6]);
+        assert_eq!(logs_to_prune(vec![9, 8], 2), Vec::<u32>::new());
+        assert_eq!(logs_to_prune(vec![9, 8], 0), vec![9, 8]);
+    }
+}
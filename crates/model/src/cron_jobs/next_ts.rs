@@ -2,16 +2,23 @@ use std::time::Duration;
 
 use anyhow::Context;
 use chrono::{
+    DateTime,
+    LocalResult,
+    Offset,
+    TimeDelta,
     TimeZone,
     Utc,
 };
+use chrono_tz::Tz;
 use common::knobs::CRON_SPLAY_SECONDS;
 use rand::Rng;
 use saffron::Cron;
 use sync_types::Timestamp;
+use value::DeveloperDocumentId;
 
 use super::types::{
     CronSchedule,
+    CronScheduleMode,
     CronSpec,
 };
 
@@ -31,7 +38,8 @@ struct Splay {
 }
 
 fn cron_splay(
-    cron_schedule: &CronSchedule,
+    cron_spec: &CronSpec,
+    cron_job_id: DeveloperDocumentId,
     prev_ts: Option<Timestamp>,
     rng: &mut impl Rng,
 ) -> Splay {
@@ -39,7 +47,7 @@ fn cron_splay(
     // the schedule's occurrences. The delay is read back as the previous
     // timestamp's offset within its occurrence, so `max_splay` must never
     // exceed `period`.
-    let (max_splay, period) = match cron_schedule {
+    let (max_splay, period) = match &cron_spec.cron_schedule {
         // Interval runs are anchored to deploy time, which is already spread
         // out.
         CronSchedule::Interval { .. } => return Splay::default(),
@@ -75,6 +83,21 @@ fn cron_splay(
             Duration::from_secs(60),
         ),
     };
+    // A job with its own jitter always gets the same delay, derived from its
+    // ID, so jobs sharing a schedule stay spread out without any state.
+    if let Some(jitter) = cron_spec.jitter {
+        let max_jitter = jitter.min(period).as_secs();
+        if max_jitter == 0 {
+            return Splay::default();
+        }
+        let internal_id = cron_job_id.internal_id().0;
+        let seed = u64::from_le_bytes(internal_id[..8].try_into().expect("16 byte internal id"));
+        let delay = Duration::from_secs(seed % max_jitter);
+        return Splay {
+            previous_delay: delay,
+            next_delay: delay,
+        };
+    }
     if max_splay.is_zero() {
         return Splay::default();
     }
@@ -108,6 +131,7 @@ fn cron_splay(
 
 pub fn compute_next_ts(
     cron_spec: &CronSpec,
+    cron_job_id: DeveloperDocumentId,
     prev_ts: Option<Timestamp>,
     now: Timestamp,
     rng: &mut impl Rng,
@@ -118,7 +142,7 @@ pub fn compute_next_ts(
                 Some(prev_ts) => prev_ts.add(Duration::from_secs(seconds as u64))?,
                 None => now,
             };
-            return Ok(next_ts);
+            return apply_run_window(cron_spec, next_ts);
         },
         CronSchedule::Hourly { minute_utc } => format!("{} * * * *", minute_utc.unwrap_or(0))
             .parse()
@@ -155,14 +179,289 @@ pub fn compute_next_ts(
     let Splay {
         previous_delay,
         next_delay,
-    } = cron_splay(&cron_spec.cron_schedule, prev_ts, rng);
+    } = cron_splay(cron_spec, cron_job_id, prev_ts, rng);
     let search_after = now.sub(previous_delay).unwrap_or(now);
-    let occurrence_utc = cron
-        .next_after(Utc.timestamp_nanos(search_after.into()))
-        .context("Could not compute next timestamp for cron")?;
+    let occurrence_utc = next_occurrence(
+        &cron,
+        cron_spec.timezone,
+        Utc.timestamp_nanos(search_after.into()),
+    )?;
     let occurrence: Timestamp = occurrence_utc
         .timestamp_nanos_opt()
         .context("Unable to get nanos from UTC")?
         .try_into()?;
-    occurrence.add(next_delay)
+    apply_run_window(cron_spec, occurrence.add(next_delay)?)
+}
+
+/// The first occurrence of `cron` after `after`. With a timezone, the schedule
+/// follows that zone's wall clock, so it keeps its local time across daylight
+/// saving changes:
+/// - A time skipped when clocks go forward runs as far past the skipped time as
+///   the clocks jumped, e.g. a 2:30 job runs at 3:30 on the day the clocks go
+///   from 2:00 to 3:00.
+/// - A time repeated when clocks go back only runs the first time.
+fn next_occurrence(
+    cron: &Cron,
+    timezone: Option<Tz>,
+    after: DateTime<Utc>,
+) -> anyhow::Result<DateTime<Utc>> {
+    let Some(timezone) = timezone else {
+        return cron
+            .next_after(after)
+            .context("Could not compute next timestamp for cron");
+    };
+    // Saffron only knows UTC, so search the local wall clock as if it were UTC.
+    let mut wall_clock = after.with_timezone(&timezone).naive_local();
+    loop {
+        let next_wall_clock = cron
+            .next_after(Utc.from_utc_datetime(&wall_clock))
+            .context("Could not compute next timestamp for cron")?
+            .naive_utc();
+        let occurrence = match timezone.from_local_datetime(&next_wall_clock) {
+            LocalResult::Single(occurrence) => occurrence.with_timezone(&Utc),
+            LocalResult::Ambiguous(earliest, _) => earliest.with_timezone(&Utc),
+            LocalResult::None => {
+                // Read the skipped time with the offset from before the jump.
+                // Transitions are months apart, so a day earlier is before it.
+                let offset_before = timezone
+                    .offset_from_utc_datetime(&(next_wall_clock - TimeDelta::days(1)))
+                    .fix();
+                Utc.from_utc_datetime(
+                    &(next_wall_clock - TimeDelta::seconds(offset_before.local_minus_utc().into())),
+                )
+            },
+        };
+        if occurrence > after {
+            return Ok(occurrence);
+        }
+        // `after` is in the second pass through a repeated hour, whose first
+        // pass already had this occurrence.
+        wall_clock = next_wall_clock;
+    }
+}
+
+/// The timestamp to pass as `prev_ts` to [`compute_next_ts`] once a run that
+/// was due at `prev_ts` finishes at `now`.
+pub fn scheduled_from_ts(cron_spec: &CronSpec, prev_ts: Timestamp, now: Timestamp) -> Timestamp {
+    match (cron_spec.schedule_mode, &cron_spec.cron_schedule) {
+        // Count the interval from the end of the run rather than from when it
+        // was due.
+        (CronScheduleMode::FixedDelay, CronSchedule::Interval { .. }) => now,
+        // Calendar schedules already run at the first occurrence after `now`,
+        // and their splay is recovered from the previous run's timestamp.
+        (CronScheduleMode::FixedDelay, _) | (CronScheduleMode::FixedRate, _) => prev_ts,
+    }
+}
+
+/// Delays a run that falls outside the cron's run window to the window's next
+/// start.
+fn apply_run_window(cron_spec: &CronSpec, next_ts: Timestamp) -> anyhow::Result<Timestamp> {
+    match cron_spec.run_window {
+        Some(run_window) => run_window.next_allowed_ts(next_ts),
+        None => Ok(next_ts),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::DateTime;
+    use chrono_tz::America::New_York;
+    use sync_types::{
+        types::SerializedArgs,
+        Timestamp,
+    };
+    use value::DeveloperDocumentId;
+
+    use super::{
+        compute_next_ts,
+        scheduled_from_ts,
+    };
+    use crate::cron_jobs::types::{
+        CronOverlapPolicy,
+        CronRunWindow,
+        CronSchedule,
+        CronScheduleMode,
+        CronSpec,
+    };
+
+    fn cron_spec(
+        cron_schedule: CronSchedule,
+        schedule_mode: CronScheduleMode,
+    ) -> anyhow::Result<CronSpec> {
+        Ok(CronSpec {
+            udf_path: "crons:run".parse()?,
+            udf_args: SerializedArgs::from_args(vec![])?,
+            cron_schedule,
+            run_window: None,
+            schedule_mode,
+            overlap_policy: CronOverlapPolicy::default(),
+            timezone: None,
+            jitter: None,
+        })
+    }
+
+    fn secs(secs: u64) -> Timestamp {
+        Timestamp::MIN
+            .add(Duration::from_secs(secs))
+            .expect("timestamp in range")
+    }
+
+    /// The next run of a job that was due at `prev_ts` and finished at `now`.
+    fn next_run(
+        cron_spec: &CronSpec,
+        prev_ts: Timestamp,
+        now: Timestamp,
+    ) -> anyhow::Result<Timestamp> {
+        compute_next_ts(
+            cron_spec,
+            DeveloperDocumentId::MIN,
+            Some(scheduled_from_ts(cron_spec, prev_ts, now)),
+            now,
+            &mut rand::rng(),
+        )
+    }
+
+    #[test]
+    fn test_fixed_rate_and_fixed_delay_after_slow_run() -> anyhow::Result<()> {
+        let every_minute = CronSchedule::Interval { seconds: 60 };
+        // The run was due at 1000s and took 150s, longer than its interval.
+        let (prev_ts, now) = (secs(1000), secs(1150));
+
+        // Fixed rate keeps to the schedule, so the next run is already overdue
+        // and the runs in between are skipped.
+        let fixed_rate = cron_spec(every_minute.clone(), CronScheduleMode::FixedRate)?;
+        assert_eq!(next_run(&fixed_rate, prev_ts, now)?, secs(1060));
+
+        // Fixed delay waits a full interval after the run finished.
+        let fixed_delay = cron_spec(every_minute, CronScheduleMode::FixedDelay)?;
+        assert_eq!(next_run(&fixed_delay, prev_ts, now)?, secs(1210));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixed_delay_after_fast_run() -> anyhow::Result<()> {
+        let every_minute = CronSchedule::Interval { seconds: 60 };
+        let (prev_ts, now) = (secs(1000), secs(1010));
+        let fixed_rate = cron_spec(every_minute.clone(), CronScheduleMode::FixedRate)?;
+        assert_eq!(next_run(&fixed_rate, prev_ts, now)?, secs(1060));
+        let fixed_delay = cron_spec(every_minute, CronScheduleMode::FixedDelay)?;
+        assert_eq!(next_run(&fixed_delay, prev_ts, now)?, secs(1070));
+        Ok(())
+    }
+
+    fn utc(rfc3339: &str) -> anyhow::Result<Timestamp> {
+        let nanos = DateTime::parse_from_rfc3339(rfc3339)?
+            .timestamp_nanos_opt()
+            .expect("timestamp in range");
+        nanos.try_into()
+    }
+
+    /// A daily run at `hour:minute` New York time, without any splay.
+    fn new_york_daily(hour: i64, minute: i64) -> anyhow::Result<CronSpec> {
+        let daily = CronSchedule::Daily {
+            hour_utc: hour,
+            minute_utc: Some(minute),
+        };
+        Ok(CronSpec {
+            timezone: Some(New_York),
+            jitter: Some(Duration::ZERO),
+            ..cron_spec(daily, CronScheduleMode::FixedRate)?
+        })
+    }
+
+    fn next_after(cron_spec: &CronSpec, now: Timestamp) -> anyhow::Result<Timestamp> {
+        compute_next_ts(
+            cron_spec,
+            DeveloperDocumentId::MIN,
+            None,
+            now,
+            &mut rand::rng(),
+        )
+    }
+
+    #[test]
+    fn test_daily_run_in_spring_forward_gap() -> anyhow::Result<()> {
+        // On 2024-03-10, New York clocks go from 2:00 EST to 3:00 EDT, so 2:30
+        // doesn't happen and the run moves an hour later to 3:30 EDT.
+        let spec = new_york_daily(2, 30)?;
+        let gap_run = next_after(&spec, utc("2024-03-09T12:00:00Z")?)?;
+        assert_eq!(gap_run, utc("2024-03-10T07:30:00Z")?);
+        // The next day is back to 2:30, now in EDT.
+        assert_eq!(next_after(&spec, gap_run)?, utc("2024-03-11T06:30:00Z")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_daily_run_in_fall_back_repeat() -> anyhow::Result<()> {
+        // On 2024-11-03, New York clocks go from 2:00 EDT back to 1:00 EST, so
+        // 1:30 happens twice. The run only happens on the first pass.
+        let spec = new_york_daily(1, 30)?;
+        let first_pass = next_after(&spec, utc("2024-11-02T12:00:00Z")?)?;
+        assert_eq!(first_pass, utc("2024-11-03T05:30:00Z")?);
+        let next_run = utc("2024-11-04T06:30:00Z")?;
+        assert_eq!(next_after(&spec, first_pass)?, next_run);
+        // Even from inside the second pass, the next run is the next day's.
+        assert_eq!(next_after(&spec, utc("2024-11-03T06:15:00Z")?)?, next_run);
+        Ok(())
+    }
+
+    fn new_york_window(
+        start_hour: u64,
+        start_minute: u64,
+        end_hour: u64,
+    ) -> anyhow::Result<CronRunWindow> {
+        let time_of_day = |hour: u64, minute: u64| Duration::from_secs((hour * 60 + minute) * 60);
+        CronRunWindow::new(
+            time_of_day(start_hour, start_minute),
+            time_of_day(end_hour, 0),
+            New_York,
+        )
+    }
+
+    #[test]
+    fn test_run_window_follows_daylight_saving() -> anyhow::Result<()> {
+        let window = new_york_window(9, 0, 17)?;
+        // 9:00 New York time is 13:00 UTC in summer and 14:00 UTC in winter.
+        assert_eq!(
+            window.next_allowed_ts(utc("2024-07-01T12:00:00Z")?)?,
+            utc("2024-07-01T13:00:00Z")?
+        );
+        assert_eq!(
+            window.next_allowed_ts(utc("2024-01-02T12:00:00Z")?)?,
+            utc("2024-01-02T14:00:00Z")?
+        );
+        // 10:00 EST is inside the window.
+        let inside = utc("2024-01-02T15:00:00Z")?;
+        assert_eq!(window.next_allowed_ts(inside)?, inside);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_window_start_in_spring_forward_gap() -> anyhow::Result<()> {
+        // The window from 2:30 opens when the clocks jump to 3:00 EDT.
+        let window = new_york_window(2, 30, 4)?;
+        assert_eq!(
+            window.next_allowed_ts(utc("2024-03-10T06:00:00Z")?)?,
+            utc("2024-03-10T07:00:00Z")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_window_start_in_fall_back_repeat() -> anyhow::Result<()> {
+        let window = new_york_window(1, 30, 2)?;
+        // From 1:00 EDT, the window opens on the first pass through 1:30.
+        assert_eq!(
+            window.next_allowed_ts(utc("2024-11-03T05:00:00Z")?)?,
+            utc("2024-11-03T05:30:00Z")?
+        );
+        // From 1:00 EST the first pass has gone by, so it opens on the second.
+        assert_eq!(
+            window.next_allowed_ts(utc("2024-11-03T06:00:00Z")?)?,
+            utc("2024-11-03T06:30:00Z")?
+        );
+        Ok(())
+    }
 }
@@ -4,12 +4,24 @@ use std::{
     mem,
     ops::Deref,
     str::FromStr,
+    time::Duration,
 };
 
 use anyhow::{
     bail,
     Context,
 };
+use chrono::{
+    LocalResult,
+    NaiveDateTime,
+    NaiveTime,
+    Offset,
+    TimeDelta,
+    TimeZone,
+    Timelike,
+    Utc,
+};
+use chrono_tz::Tz;
 use common::{
     components::ComponentId,
     document::{
@@ -203,6 +215,50 @@ pub struct CronSpec {
     pub udf_path: CanonicalizedUdfPath,
     pub udf_args: SerializedArgs,
     pub cron_schedule: CronSchedule,
+    // Restricts runs to a daily time-of-day window (see `compute_next_ts`).
+    pub run_window: Option<CronRunWindow>,
+    pub schedule_mode: CronScheduleMode,
+    pub overlap_policy: CronOverlapPolicy,
+    // IANA timezone whose wall clock calendar schedules follow. Their
+    // `hour_utc`/`minute_utc` fields are local times in this zone.
+    pub timezone: Option<Tz>,
+    // Upper bound on a fixed, per-job delay applied to calendar schedules'
+    // runs in place of the `CRON_SPLAY_SECONDS` splay (see `compute_next_ts`).
+    pub jitter: Option<Duration>,
+}
+
+/// What a cron's next run is scheduled relative to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CronScheduleMode {
+    /// Runs follow the schedule, whenever the previous run finished. Runs
+    /// that a slow run pushed into the past are skipped.
+    #[default]
+    FixedRate,
+    /// The next run is scheduled from when the previous run finished, so
+    /// an interval schedule waits a full interval between runs. Calendar
+    /// schedules behave the same in both modes, since their next run is
+    /// always the first occurrence after the previous run finished.
+    FixedDelay,
+}
+
+/// What happens when a cron's next run is due while its previous run is still
+/// in progress.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CronOverlapPolicy {
+    /// Runs that come due while the previous run is in progress don't
+    /// execute. Once it finishes, they're logged as canceled and the job moves
+    /// on to its next run after the current time.
+    #[default]
+    Skip,
+    /// A run that comes due while the previous run is in progress starts as
+    /// soon as it finishes. Any further runs that came due are skipped.
+    Queue,
+    /// Each run starts when it's due, even if earlier runs are still in
+    /// progress, up to the cron executor's parallelism. The job moves on to
+    /// its next run when a run starts rather than when it finishes.
+    Allow,
 }
 
 impl HeapSize for CronSpec {
@@ -218,6 +274,21 @@ pub struct SerializedCronSpec {
     #[serde(with = "serde_bytes")]
     udf_args: Option<Vec<u8>>,
     cron_schedule: SerializedCronSchedule,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    run_window: Option<SerializedCronRunWindow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    schedule_mode: Option<CronScheduleMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    overlap_policy: Option<CronOverlapPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    jitter_seconds: Option<u64>,
 }
 
 impl TryFrom<CronSpec> for SerializedCronSpec {
@@ -231,6 +302,17 @@ impl TryFrom<CronSpec> for SerializedCronSpec {
             udf_path: String::from(spec.udf_path),
             udf_args: Some(udf_args_bytes),
             cron_schedule: spec.cron_schedule.try_into()?,
+            run_window: spec.run_window.map(SerializedCronRunWindow::from),
+            schedule_mode: match spec.schedule_mode {
+                CronScheduleMode::FixedRate => None,
+                CronScheduleMode::FixedDelay => Some(spec.schedule_mode),
+            },
+            overlap_policy: match spec.overlap_policy {
+                CronOverlapPolicy::Skip => None,
+                CronOverlapPolicy::Queue | CronOverlapPolicy::Allow => Some(spec.overlap_policy),
+            },
+            timezone: spec.timezone.map(|timezone| timezone.name().to_string()),
+            jitter_seconds: spec.jitter.map(|jitter| jitter.as_secs()),
         })
     }
 }
@@ -245,10 +327,16 @@ impl TryFrom<SerializedCronSpec> for CronSpec {
             None => ConvexArray::empty().into_serialized_args()?,
         };
         let cron_schedule = value.cron_schedule.try_into()?;
+        let run_window = value.run_window.map(CronRunWindow::try_from).transpose()?;
         Ok(Self {
             udf_path,
             udf_args,
             cron_schedule,
+            run_window,
+            schedule_mode: value.schedule_mode.unwrap_or_default(),
+            overlap_policy: value.overlap_policy.unwrap_or_default(),
+            timezone: value.timezone.as_deref().map(parse_timezone).transpose()?,
+            jitter: value.jitter_seconds.map(Duration::from_secs),
         })
     }
 }
@@ -322,6 +410,17 @@ impl CronSpec {
             Cron { cron: String },
         }
 
+        // Times of day are "HH:MM" in the window's timezone, which defaults to
+        // the cron's own timezone and then to UTC.
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RunWindowJson {
+            start: String,
+            end: String,
+            #[serde(default)]
+            timezone: Option<String>,
+        }
+
         // The JavaScript object produced by crons.export() uses different names:
         // name -> udf_path, schedule -> cron_schedule, args -> udf_args
         #[derive(Deserialize)]
@@ -330,6 +429,16 @@ impl CronSpec {
             name: String,
             args: JsonValue,
             schedule: ScheduleJson,
+            #[serde(default)]
+            run_window: Option<RunWindowJson>,
+            #[serde(default)]
+            schedule_mode: CronScheduleMode,
+            #[serde(default)]
+            overlap_policy: CronOverlapPolicy,
+            #[serde(default)]
+            timezone: Option<String>,
+            #[serde(default)]
+            jitter_seconds: Option<i64>,
         }
         let j: CronSpecJson = serde_json::from_value(value.clone())
             .with_context(|| CronValidationError::InvalidJson)?;
@@ -458,6 +567,47 @@ impl CronSpec {
             },
         };
 
+        let run_window = j
+            .run_window
+            .map(|window| {
+                let parse_time_of_day = |time: &str| -> anyhow::Result<Duration> {
+                    let (hour, minute) = time
+                        .split_once(':')
+                        .and_then(|(hour, minute)| Some((hour.parse().ok()?, minute.parse().ok()?)))
+                        .filter(|(hour, minute): &(i64, i64)| {
+                            (0..=23).contains(hour) && (0..=59).contains(minute)
+                        })
+                        .with_context(|| {
+                            format!(
+                                "runWindow times must be HH:MM between 00:00 and 23:59, got \
+                                 {time:?}"
+                            )
+                        })?;
+                    Ok(Duration::from_secs(((hour * 60 + minute) * 60) as u64))
+                };
+                let timezone = match window.timezone.as_deref().or(j.timezone.as_deref()) {
+                    Some(timezone) => parse_timezone(timezone)?,
+                    None => Tz::UTC,
+                };
+                CronRunWindow::new(
+                    parse_time_of_day(&window.start)?,
+                    parse_time_of_day(&window.end)?,
+                    timezone,
+                )
+            })
+            .transpose()?;
+
+        let jitter = match j.jitter_seconds {
+            None | Some(0) => None,
+            Some(jitter_seconds) if jitter_seconds > 0 => {
+                Some(Duration::from_secs(jitter_seconds as u64))
+            },
+            Some(_) => anyhow::bail!(
+                "jitterSeconds must not be negative in {}",
+                serde_json::to_string_pretty(&value).unwrap()
+            ),
+        };
+
         let udf_path: UdfPath = j.name.parse()?;
         let udf_path_canonicalized = udf_path.canonicalize();
         let udf_args = ConvexArray::try_from(j.args)?;
@@ -472,10 +622,21 @@ impl CronSpec {
             udf_path: udf_path_canonicalized,
             udf_args: udf_args.into_serialized_args()?,
             cron_schedule: schedule,
+            run_window,
+            schedule_mode: j.schedule_mode,
+            overlap_policy: j.overlap_policy,
+            timezone: j.timezone.as_deref().map(parse_timezone).transpose()?,
+            jitter,
         })
     }
 }
 
+fn parse_timezone(timezone: &str) -> anyhow::Result<Tz> {
+    timezone.parse().map_err(|e| {
+        anyhow::anyhow!("{timezone:?} isn't an IANA timezone like \"America/New_York\": {e}")
+    })
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum CronJobState {
@@ -642,6 +803,148 @@ impl TryFrom<SerializedCronSchedule> for CronSchedule {
     }
 }
 
+/// A daily window of local time that a cron's runs are restricted to. Runs
+/// scheduled outside the window are delayed to the window's next start. The
+/// window wraps past midnight when `end` is before `start`.
+///
+/// Local time follows `timezone`'s wall clock, so the window keeps its local
+/// hours across daylight saving changes:
+/// - A start skipped when clocks go forward opens the window when they jump,
+///   e.g. a window from 2:30 opens at 3:00 on the day the clocks go from 2:00
+///   to 3:00.
+/// - A start repeated when clocks go back opens the window on the first pass,
+///   unless that pass has already gone by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CronRunWindow {
+    // Time since local midnight, inclusive.
+    pub start: Duration,
+    // Time since local midnight, exclusive.
+    pub end: Duration,
+    pub timezone: Tz,
+}
+
+impl CronRunWindow {
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    pub fn new(start: Duration, end: Duration, timezone: Tz) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            start < Self::DAY && end < Self::DAY,
+            "runWindow start and end must be within a day"
+        );
+        anyhow::ensure!(start != end, "runWindow start and end must differ");
+        Ok(Self {
+            start,
+            end,
+            timezone,
+        })
+    }
+
+    /// Returns `ts` if it falls inside the window, and otherwise the next
+    /// start of the window after `ts`.
+    pub fn next_allowed_ts(&self, ts: Timestamp) -> anyhow::Result<Timestamp> {
+        let utc = Utc.timestamp_nanos(ts.into());
+        let local = utc.with_timezone(&self.timezone).naive_local();
+        let secs_of_day = u64::from(local.num_seconds_from_midnight());
+        let (start, end) = (self.start.as_secs(), self.end.as_secs());
+        let inside = if start < end {
+            (start..end).contains(&secs_of_day)
+        } else {
+            secs_of_day >= start || secs_of_day < end
+        };
+        if inside {
+            return Ok(ts);
+        }
+        // Outside the window, the next start is later today unless today's
+        // start has already passed.
+        let start_date = if secs_of_day < start {
+            local.date()
+        } else {
+            local
+                .date()
+                .succ_opt()
+                .context("runWindow start is out of range")?
+        };
+        let start_time = NaiveTime::from_num_seconds_from_midnight_opt(start as u32, 0)
+            .context("runWindow start is out of range")?;
+        let start_local = start_date.and_time(start_time);
+        let start_utc = match self.timezone.from_local_datetime(&start_local) {
+            LocalResult::Single(start) => start.naive_utc(),
+            LocalResult::Ambiguous(earliest, latest) => {
+                if earliest.with_timezone(&Utc) > utc {
+                    earliest.naive_utc()
+                } else {
+                    latest.naive_utc()
+                }
+            },
+            LocalResult::None => self.end_of_skipped_time(start_local),
+        };
+        start_utc
+            .and_utc()
+            .timestamp_nanos_opt()
+            .context("Unable to get nanos from UTC")?
+            .try_into()
+    }
+
+    /// The UTC time at which clocks jumped past `local`, a wall-clock time
+    /// skipped when they went forward.
+    fn end_of_skipped_time(&self, local: NaiveDateTime) -> NaiveDateTime {
+        // Reading `local` with the offsets from a day either side of the jump
+        // gives a time before it and a time after it. Transitions are months
+        // apart, so each day is on its own side.
+        let read_with_offset_on = |day: NaiveDateTime| {
+            let offset = self.timezone.offset_from_utc_datetime(&day).fix();
+            local - TimeDelta::seconds(offset.local_minus_utc().into())
+        };
+        let mut before = read_with_offset_on(local + TimeDelta::days(1));
+        let mut after = read_with_offset_on(local - TimeDelta::days(1));
+        while after - before > TimeDelta::seconds(1) {
+            let mid = before + TimeDelta::seconds((after - before).num_seconds() / 2);
+            if self.timezone.from_utc_datetime(&mid).naive_local() < local {
+                before = mid;
+            } else {
+                after = mid;
+            }
+        }
+        after
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializedCronRunWindow {
+    start_minute: i64,
+    end_minute: i64,
+    timezone: String,
+}
+
+impl From<CronRunWindow> for SerializedCronRunWindow {
+    fn from(window: CronRunWindow) -> Self {
+        Self {
+            start_minute: (window.start.as_secs() / 60) as i64,
+            end_minute: (window.end.as_secs() / 60) as i64,
+            timezone: window.timezone.name().to_string(),
+        }
+    }
+}
+
+impl TryFrom<SerializedCronRunWindow> for CronRunWindow {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SerializedCronRunWindow) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            value.start_minute >= 0 && value.end_minute >= 0,
+            "Invalid run window {}..{}",
+            value.start_minute,
+            value.end_minute
+        );
+        Self::new(
+            Duration::from_secs(value.start_minute as u64 * 60),
+            Duration::from_secs(value.end_minute as u64 * 60),
+            parse_timezone(&value.timezone)?,
+        )
+    }
+}
+
 mod codegen_cron_schedule {
     use value::codegen_convex_serialization;
 
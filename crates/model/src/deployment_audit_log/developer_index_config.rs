@@ -40,6 +40,16 @@ pub enum DeveloperIndexSpec {
     Vector(VectorIndexSpec),
 }
 
+impl DeveloperIndexConfig {
+    pub fn is_staged(&self) -> bool {
+        self.staged
+    }
+
+    pub fn into_spec(self) -> DeveloperIndexSpec {
+        self.spec
+    }
+}
+
 impl From<IndexConfig> for DeveloperIndexConfig {
     fn from(value: IndexConfig) -> Self {
         let staged = value.is_staged();
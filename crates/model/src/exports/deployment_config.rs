@@ -0,0 +1,101 @@
+//! Deployment configuration bundled alongside the data in a snapshot export,
+//! so that a snapshot import can restore a complete deployment.
+//!
+//! The configuration lives in a `_deployment/` directory next to each
+//! component's tables in the ZIP:
+//! - `_deployment/manifest.json` (root component only): a
+//!   [`DeploymentConfigManifest`] listing the sections that were exported.
+//! - `_deployment/schema.json`: the component's active schema.
+//! - `_deployment/indexes.jsonl`: one [`SerializedBundledIndex`] per line.
+//! - `_deployment/crons.jsonl`: one [`SerializedBundledCron`] per line.
+//! - `_deployment/environment_variables.jsonl` (root component only): one
+//!   [`SerializedBundledEnvironmentVariable`] per line.
+//! - `_deployment/component_args.json` (child components only): a JSON object
+//!   of the component's arguments, each encoded as internal JSON. Unlike the
+//!   other files, this is always exported, so an import can check that it's
+//!   writing into a component configured like the one it was exported from.
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    cron_jobs::types::SerializedCronSpec,
+    deployment_audit_log::developer_index_config::SerializedNamedDeveloperIndexConfig,
+};
+
+pub const DEPLOYMENT_CONFIG_DIR: &str = "_deployment";
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const SCHEMA_FILE: &str = "schema.json";
+pub const INDEXES_FILE: &str = "indexes.jsonl";
+pub const CRONS_FILE: &str = "crons.jsonl";
+pub const ENVIRONMENT_VARIABLES_FILE: &str = "environment_variables.jsonl";
+pub const COMPONENT_ARGS_FILE: &str = "component_args.json";
+
+/// Bumped whenever the bundle layout changes incompatibly. Imports reject
+/// bundles with a version they don't know.
+pub const DEPLOYMENT_CONFIG_VERSION: u32 = 1;
+
+/// The parts of a deployment's configuration to export, or to restore on
+/// import.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentConfigSections {
+    #[serde(default)]
+    pub schema: bool,
+    #[serde(default)]
+    pub indexes: bool,
+    #[serde(default)]
+    pub crons: bool,
+    #[serde(default)]
+    pub environment_variables: bool,
+}
+
+impl DeploymentConfigSections {
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            schema,
+            indexes,
+            crons,
+            environment_variables,
+        } = *self;
+        !(schema || indexes || crons || environment_variables)
+    }
+
+    /// Whether every section in `other` is also in `self`.
+    pub fn contains(&self, other: &Self) -> bool {
+        (self.schema || !other.schema)
+            && (self.indexes || !other.indexes)
+            && (self.crons || !other.crons)
+            && (self.environment_variables || !other.environment_variables)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentConfigManifest {
+    pub version: u32,
+    pub sections: DeploymentConfigSections,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializedBundledIndex {
+    pub table: String,
+    pub index: SerializedNamedDeveloperIndexConfig,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializedBundledCron {
+    pub name: String,
+    pub spec: SerializedCronSpec,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializedBundledEnvironmentVariable {
+    pub name: String,
+    pub value: String,
+}
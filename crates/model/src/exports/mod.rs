@@ -47,6 +47,7 @@ use crate::{
     SystemTable,
 };
 
+pub mod deployment_config;
 pub mod types;
 
 pub const EXPORTS_TABLE: TableName = TableName::const_new("_exports");
@@ -14,6 +14,8 @@ use serde::{
 use sync_types::Timestamp;
 use value::codegen_convex_serialization;
 
+use crate::exports::deployment_config::DeploymentConfigSections;
+
 #[derive(Clone, Debug, PartialEq)]
 /// The export state machine. A new export starts as `Requested` and the valid
 /// transitions are:
@@ -333,7 +335,12 @@ impl Export {
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum ExportFormat {
     /// zip file containing a CleanJsonl for each table, and sidecar type info.
-    Zip { include_storage: bool },
+    /// `include_config` selects the deployment configuration bundled with the
+    /// data; see [`crate::exports::deployment_config`].
+    Zip {
+        include_storage: bool,
+        include_config: DeploymentConfigSections,
+    },
 }
 
 #[derive(Serialize, Deserialize)]
@@ -341,20 +348,37 @@ pub enum ExportFormat {
 #[serde(tag = "format")]
 #[serde(rename_all = "snake_case")]
 pub enum SerializedExportFormat {
-    Zip { include_storage: bool },
+    Zip {
+        include_storage: bool,
+        #[serde(skip_serializing_if = "DeploymentConfigSections::is_empty")]
+        #[serde(default)]
+        include_config: DeploymentConfigSections,
+    },
 }
 
 impl From<ExportFormat> for SerializedExportFormat {
     fn from(value: ExportFormat) -> Self {
-        let ExportFormat::Zip { include_storage } = value;
-        SerializedExportFormat::Zip { include_storage }
+        let ExportFormat::Zip {
+            include_storage,
+            include_config,
+        } = value;
+        SerializedExportFormat::Zip {
+            include_storage,
+            include_config,
+        }
     }
 }
 
 impl From<SerializedExportFormat> for ExportFormat {
     fn from(value: SerializedExportFormat) -> Self {
-        let SerializedExportFormat::Zip { include_storage } = value;
-        ExportFormat::Zip { include_storage }
+        let SerializedExportFormat::Zip {
+            include_storage,
+            include_config,
+        } = value;
+        ExportFormat::Zip {
+            include_storage,
+            include_config,
+        }
     }
 }
 
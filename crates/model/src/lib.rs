@@ -169,8 +169,11 @@ use session_requests::{
     SESSION_REQUESTS_TABLE,
 };
 use snapshot_imports::{
+    SnapshotImportRowsWrittenTable,
     SnapshotImportsTable,
     SNAPSHOT_IMPORTS_TABLE,
+    SNAPSHOT_IMPORT_ROWS_WRITTEN_INDEX_BY_TABLET,
+    SNAPSHOT_IMPORT_ROWS_WRITTEN_TABLE,
 };
 use source_packages::{
     SourcePackagesTable,
@@ -292,9 +295,10 @@ enum DefaultTableNumber {
     AuditLogConfig = 39,
     UsageLimits = 40,
     DataSyncProgress = 41,
+    SnapshotImportRowsWritten = 42,
     // Keep this number and your user name up to date. The number makes it easy to know
     // what to use next. The username on the same line detects merge conflicts
-    // Next Number - 42 - nipunn
+    // Next Number - 43 - nipunn
 }
 
 impl From<DefaultTableNumber> for TableNumber {
@@ -342,6 +346,7 @@ impl From<DefaultTableNumber> for &'static dyn ErasedSystemTable {
             DefaultTableNumber::AuditLogConfig => &AuditLogConfigTable,
             DefaultTableNumber::UsageLimits => &UsageLimitsTable,
             DefaultTableNumber::DataSyncProgress => &DataSyncProgressTable,
+            DefaultTableNumber::SnapshotImportRowsWritten => &SnapshotImportRowsWrittenTable,
         }
     }
 }
@@ -573,6 +578,7 @@ pub fn app_system_tables() -> Vec<&'static dyn ErasedSystemTable> {
         &BackendStateTable,
         &ExportsTable,
         &SnapshotImportsTable,
+        &SnapshotImportRowsWrittenTable,
         &FunctionHandlesTable,
         &CanonicalUrlsTable,
         &LogSinksTable,
@@ -626,7 +632,7 @@ pub fn virtual_system_mapping() -> &'static VirtualSystemMapping {
         let mut mapping = VirtualSystemMapping::default();
         for table in app_system_tables() {
             if let Some(associated_virtual_table) = table.virtual_table() {
-                mapping.add_table(table.table_name().clone(), associated_virtual_table)
+                mapping.add_table(table.table_name().clone(), associated_virtual_table, None)
             }
         }
         mapping
@@ -670,6 +676,7 @@ pub static FIRST_SEEN_TABLE: LazyLock<BTreeMap<TableName, DatabaseVersion>> = La
         AUDIT_LOG_CONFIG_TABLE.clone() => 124,
         USAGE_LIMITS_TABLE.clone() => 126,
         DATA_SYNC_PROGRESS_TABLE.clone() => 127,
+        SNAPSHOT_IMPORT_ROWS_WRITTEN_TABLE.clone() => 129,
     }
 });
 
@@ -700,5 +707,6 @@ pub static FIRST_SEEN_INDEX: LazyLock<BTreeMap<IndexName, DatabaseVersion>> = La
         DATA_SYNC_PROGRESS_INDEX_BY_SYNC_ID.name() => 127,
         DATA_SYNC_PROGRESS_INDEX_BY_LAST_UPDATED.name() => 127,
         AUDIT_LOG_INDEX_BY_ACTION.name() => 128,
+        SNAPSHOT_IMPORT_ROWS_WRITTEN_INDEX_BY_TABLET.name() => 129,
     }
 });
@@ -18,8 +18,12 @@ use common::{
         ResolvedComponentFunctionPath,
     },
     document::ParsedDocument,
+    knobs::MODULE_DELETION_RETENTION,
     runtime::Runtime,
-    types::ModuleEnvironment,
+    types::{
+        ModuleEnvironment,
+        Timestamp,
+    },
     value::ResolvedDocumentId,
 };
 use database::{
@@ -44,6 +48,7 @@ use self::{
         AnalyzedFunction,
         AnalyzedModule,
         ModuleSource,
+        ModuleSourceSize,
         SourceMap,
     },
     types::ModuleMetadata,
@@ -61,7 +66,10 @@ use crate::{
         },
     },
     source_packages::{
-        types::SourcePackageId,
+        types::{
+            SourcePackage,
+            SourcePackageId,
+        },
         SourcePackageModel,
     },
     SystemIndex,
@@ -128,6 +136,10 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
 
         let mut added_modules = BTreeSet::new();
 
+        // Deleted modules at a path being pushed again are replaced in place, so
+        // there's only ever one document per path.
+        let mut deleted_modules = self.purge_expired_deleted(component).await?;
+
         // Add new modules.
         let mut remaining_modules: BTreeMap<_, _> = self
             .get_application_metadata(component)
@@ -137,9 +149,10 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
             .collect();
         for module in modules {
             let path = module.path.canonicalize();
-            let existing_module_id = remaining_modules.remove(&path);
+            let mut existing_module_id = remaining_modules.remove(&path);
             if existing_module_id.is_none() {
                 added_modules.insert(path.clone());
+                existing_module_id = deleted_modules.remove(&path);
             }
             let analyze_result = if !path.is_deps() {
                 // We expect AnalyzeResult to always be set for non-dependency modules.
@@ -176,10 +189,26 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
     }
 
     /// Returns the registered modules metadata, including system modules.
+    /// Deleted modules are skipped.
     #[fastrace::trace]
     pub async fn get_all_metadata(
         &mut self,
         component: ComponentId,
+    ) -> anyhow::Result<Vec<Arc<ParsedDocument<ModuleMetadata>>>> {
+        let modules = self
+            .get_all_metadata_including_deleted(component)
+            .await?
+            .into_iter()
+            .filter(|metadata| metadata.deleted_ts.is_none())
+            .collect();
+        Ok(modules)
+    }
+
+    /// Returns the registered modules metadata, including modules that were
+    /// deleted but can still be restored.
+    pub async fn get_all_metadata_including_deleted(
+        &mut self,
+        component: ComponentId,
     ) -> anyhow::Result<Vec<Arc<ParsedDocument<ModuleMetadata>>>> {
         // Hacky: It's important that we scan the _by_id index instead of the
         // _by_creation_time index (which is used by `Query::full_table_scan`).
@@ -193,6 +222,39 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
         Ok(modules)
     }
 
+    /// Modules deleted before this timestamp are past
+    /// [MODULE_DELETION_RETENTION].
+    fn deletion_cutoff(&self) -> Timestamp {
+        // Deployments younger than the retention window have nothing to expire.
+        (*self.tx.begin_timestamp())
+            .sub(*MODULE_DELETION_RETENTION)
+            .unwrap_or(Timestamp::MIN)
+    }
+
+    /// Permanently removes the component's modules that were deleted more than
+    /// [MODULE_DELETION_RETENTION] ago, and returns the IDs of the remaining
+    /// deleted modules by path.
+    async fn purge_expired_deleted(
+        &mut self,
+        component: ComponentId,
+    ) -> anyhow::Result<BTreeMap<CanonicalizedModulePath, ResolvedDocumentId>> {
+        let delete_before_ts = self.deletion_cutoff();
+        let mut deleted_modules = BTreeMap::new();
+        for metadata in self.get_all_metadata_including_deleted(component).await? {
+            let Some(deleted_ts) = metadata.deleted_ts else {
+                continue;
+            };
+            if deleted_ts < delete_before_ts {
+                SystemMetadataModel::new(self.tx, component.into())
+                    .delete(metadata.id())
+                    .await?;
+            } else {
+                deleted_modules.insert(metadata.path.clone(), metadata.id());
+            }
+        }
+        Ok(deleted_modules)
+    }
+
     pub async fn get_application_metadata(
         &mut self,
         component: ComponentId,
@@ -206,41 +268,126 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
         Ok(modules)
     }
 
+    /// Returns the analyze results of the component's application modules,
+    /// reading only module metadata and not the modules' source. Modules
+    /// without analyze results (e.g. `_deps` modules) are omitted.
+    pub async fn list_analyzed(
+        &mut self,
+        component: ComponentId,
+    ) -> anyhow::Result<BTreeMap<CanonicalizedModulePath, AnalyzedModule>> {
+        let analyzed = self
+            .get_application_metadata(component)
+            .await?
+            .into_iter()
+            .filter_map(|metadata| {
+                let analyze_result = metadata.analyze_result.clone()?;
+                Some((metadata.path.clone(), analyze_result))
+            })
+            .collect();
+        Ok(analyzed)
+    }
+
+    /// Returns the combined unzipped size in bytes of the source packages
+    /// backing the component's current modules, which is what a push's
+    /// unzipped size limit is checked against. Sizes are only tracked per
+    /// source package, so each package is counted once. See
+    /// [Self::source_sizes] for a per-module breakdown.
+    pub async fn total_source_size(&mut self, component: ComponentId) -> anyhow::Result<u64> {
+        if !(self.tx.identity().is_admin() || self.tx.identity().is_system()) {
+            anyhow::bail!(unauthorized_error("total_source_size"));
+        }
+        let (_, source_packages) = self.modules_with_source_packages(component).await?;
+        let total_size = source_packages
+            .values()
+            .map(|source_package| source_package.package_size.unzipped_size_bytes as u64)
+            .sum();
+        Ok(total_size)
+    }
+
+    /// The component's current modules and the source packages storing them,
+    /// reading each package once.
+    async fn modules_with_source_packages(
+        &mut self,
+        component: ComponentId,
+    ) -> anyhow::Result<(
+        Vec<Arc<ParsedDocument<ModuleMetadata>>>,
+        BTreeMap<SourcePackageId, Arc<ParsedDocument<SourcePackage>>>,
+    )> {
+        let modules = self.get_all_metadata(component).await?;
+        let source_packages = SourcePackageModel::new(self.tx, component.into())
+            .get_many(modules.iter().map(|metadata| metadata.source_package_id))
+            .await?;
+        Ok((modules, source_packages))
+    }
+
     /// Returns all registered modules that aren't system modules.
     pub async fn get_application_modules(
         &mut self,
         component: ComponentId,
         module_loader: &dyn ModuleLoader<RT>,
     ) -> anyhow::Result<BTreeMap<CanonicalizedModulePath, ModuleConfig>> {
+        let application_metadata = self.get_application_metadata(component).await?;
+        // Load each source package once up front instead of looking up every
+        // module's metadata and package again through `ModuleLoader::get_module`.
+        let source_packages = SourcePackageModel::new(self.tx, component.into())
+            .get_many(
+                application_metadata
+                    .iter()
+                    .map(|metadata| metadata.source_package_id),
+            )
+            .await?;
         let mut modules = BTreeMap::new();
-        for metadata in self.get_all_metadata(component).await? {
+        for metadata in application_metadata {
             let path = metadata.path.clone();
-            if !path.is_system() {
-                let environment = metadata.environment;
-                let full_source = module_loader
-                    .get_module(
-                        self.tx,
-                        CanonicalizedComponentModulePath {
-                            component,
-                            module_path: metadata.path.clone(),
-                        },
-                    )
-                    .await?
-                    .context("Module source does not exist")?;
-                let module_config = ModuleConfig {
-                    path: path.clone().into(),
-                    source: full_source.source.clone(),
-                    source_map: full_source.source_map.clone(),
-                    environment,
-                };
-                if modules.insert(path.clone(), module_config).is_some() {
-                    panic!("Duplicate application module at {path:?}");
-                }
+            let environment = metadata.environment;
+            let source_package = source_packages
+                .get(&metadata.source_package_id)
+                .context("Module source package does not exist")?;
+            let full_source = module_loader
+                .get_module_with_metadata(&metadata, source_package)
+                .await?;
+            let module_config = ModuleConfig {
+                path: path.clone().into(),
+                source: full_source.source.clone(),
+                source_map: full_source.source_map.clone(),
+                environment,
+            };
+            if modules.insert(path.clone(), module_config).is_some() {
+                panic!("Duplicate application module at {path:?}");
             }
         }
         Ok(modules)
     }
 
+    /// Returns the size of each application module's source and source map,
+    /// e.g. to show which modules count most toward the `ModulesTooLarge`
+    /// limit. Reads the same modules and source packages as
+    /// [Self::total_source_size].
+    pub async fn source_sizes(
+        &mut self,
+        component: ComponentId,
+        module_loader: &dyn ModuleLoader<RT>,
+    ) -> anyhow::Result<BTreeMap<CanonicalizedModulePath, ModuleSourceSize>> {
+        if !(self.tx.identity().is_admin() || self.tx.identity().is_system()) {
+            anyhow::bail!(unauthorized_error("source_sizes"));
+        }
+        let (modules, source_packages) = self.modules_with_source_packages(component).await?;
+        let mut sizes = BTreeMap::new();
+        for metadata in modules {
+            if metadata.path.is_system() {
+                continue;
+            }
+            let source_package = source_packages
+                .get(&metadata.source_package_id)
+                .context("Module source package does not exist")?;
+            let full_source = module_loader
+                .get_module_with_metadata(&metadata, source_package)
+                .await?;
+            sizes.insert(metadata.path.clone(), ModuleSourceSize::from(&*full_source));
+        }
+        Ok(sizes)
+    }
+
     pub async fn get_metadata_for_function(
         &mut self,
         path: CanonicalizedComponentFunctionPath,
@@ -324,6 +471,7 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
             environment,
             analyze_result: analyze_result.clone(),
             sha256,
+            deleted_ts: None,
         };
         let module_id = match module_id {
             Some(module_id) => {
@@ -335,6 +483,7 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
                 // Doesn't change the source package id if the contents are identical to what is
                 // already deployed
                 if let Some(metadata) = current
+                    && metadata.deleted_ts.is_none()
                     && metadata.matches_module_contents(&new_metadata)
                 {
                     let current_src_pkg = SourcePackageModel::new(self.tx, path.component.into())
@@ -368,6 +517,8 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
     }
 
     /// Delete a module, making it inaccessible for subsequent transactions.
+    /// Its metadata is kept for [MODULE_DELETION_RETENTION] so it can be
+    /// brought back with [Self::restore].
     pub async fn delete(
         &mut self,
         component: ComponentId,
@@ -377,8 +528,57 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
             anyhow::bail!(unauthorized_error("delete_module"));
         }
         let namespace = component.into();
+        let (id, mut metadata) = self
+            .tx
+            .get_system::<ModulesTable>(namespace, module_id.developer_id)
+            .await?
+            .context("Module to delete does not exist")?
+            .as_ref()
+            .clone()
+            .into_id_and_value();
+        if metadata.deleted_ts.is_none() {
+            metadata.deleted_ts = Some(*self.tx.begin_timestamp());
+            SystemMetadataModel::new(self.tx, namespace)
+                .replace(id, metadata.try_into()?)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Restore a module deleted within the last [MODULE_DELETION_RETENTION].
+    /// The module keeps pointing at the source package it was last pushed in,
+    /// so it comes back with the same source and analyze result.
+    pub async fn restore(&mut self, path: CanonicalizedComponentModulePath) -> anyhow::Result<()> {
+        if !(self.tx.identity().is_admin() || self.tx.identity().is_system()) {
+            anyhow::bail!(unauthorized_error("restore_module"));
+        }
+        let namespace = path.component.into();
+        let not_found = || {
+            ErrorMetadata::not_found(
+                "DeletedModuleNotFound",
+                format!(
+                    "There's no deleted module at {} to restore",
+                    path.module_path.as_str()
+                ),
+            )
+        };
+        let Some(document) = self.module_metadata_including_deleted(path.clone()).await? else {
+            anyhow::bail!(not_found());
+        };
+        let (id, mut metadata) = document.as_ref().clone().into_id_and_value();
+        let Some(deleted_ts) = metadata.deleted_ts else {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "ModuleNotDeleted",
+                format!("Module {} isn't deleted", path.module_path.as_str()),
+            ));
+        };
+        let delete_before_ts = self.deletion_cutoff();
+        if deleted_ts < delete_before_ts {
+            anyhow::bail!(not_found());
+        }
+        metadata.deleted_ts = None;
         SystemMetadataModel::new(self.tx, namespace)
-            .delete(module_id)
+            .replace(id, metadata.try_into()?)
             .await?;
         Ok(())
     }
@@ -387,6 +587,17 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
     async fn module_metadata(
         &mut self,
         path: CanonicalizedComponentModulePath,
+    ) -> anyhow::Result<Option<Arc<ParsedDocument<ModuleMetadata>>>> {
+        let module_document = self
+            .module_metadata_including_deleted(path)
+            .await?
+            .filter(|metadata| metadata.deleted_ts.is_none());
+        Ok(module_document)
+    }
+
+    async fn module_metadata_including_deleted(
+        &mut self,
+        path: CanonicalizedComponentModulePath,
     ) -> anyhow::Result<Option<Arc<ParsedDocument<ModuleMetadata>>>> {
         let namespace = path.component.into();
         let module_document = self
@@ -479,3 +690,191 @@ pub fn hash_module_source(source: &ModuleSource, source_map: Option<&SourceMap>)
     }
     hasher.finalize()
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context;
+    use common::{
+        components::{
+            CanonicalizedComponentFunctionPath,
+            CanonicalizedComponentModulePath,
+            ComponentId,
+            ComponentPath,
+        },
+        types::{
+            ModuleEnvironment,
+            UdfType,
+        },
+    };
+    use database::{
+        test_helpers::DbFixtures,
+        Transaction,
+    };
+    use errors::ErrorMetadataAnyhowExt;
+    use runtime::testing::TestRuntime;
+    use sync_types::FunctionName;
+    use value::sha256::Sha256Digest;
+
+    use super::{
+        function_validators::{
+            ArgsValidator,
+            ReturnsValidator,
+        },
+        module_versions::{
+            AnalyzedFunction,
+            AnalyzedModule,
+            Visibility,
+        },
+        ModuleModel,
+    };
+    use crate::source_packages::{
+        types::{
+            PackageSize,
+            SourcePackage,
+            SourcePackageId,
+        },
+        SourcePackageModel,
+    };
+
+    fn module_path(path: &str) -> anyhow::Result<CanonicalizedComponentModulePath> {
+        Ok(CanonicalizedComponentModulePath {
+            component: ComponentId::Root,
+            module_path: path.parse()?,
+        })
+    }
+
+    async fn put_source_package(
+        tx: &mut Transaction<TestRuntime>,
+    ) -> anyhow::Result<SourcePackageId> {
+        SourcePackageModel::new(tx, ComponentId::Root.into())
+            .put(SourcePackage {
+                storage_key: "source_package".to_string().try_into()?,
+                sha256: Sha256Digest::hash(b"source_package"),
+                external_deps_package_id: None,
+                package_size: PackageSize::default(),
+                node_version: None,
+            })
+            .await
+    }
+
+    /// Pushes a module at `path` exporting a public query named `function`.
+    async fn put_module(
+        tx: &mut Transaction<TestRuntime>,
+        source_package_id: SourcePackageId,
+        path: &str,
+        function: &str,
+    ) -> anyhow::Result<()> {
+        let analyze_result = AnalyzedModule {
+            functions: vec![AnalyzedFunction::new(
+                function.parse()?,
+                None,
+                UdfType::Query,
+                Some(Visibility::Public),
+                ArgsValidator::Unvalidated,
+                ReturnsValidator::Unvalidated,
+            )?]
+            .into(),
+            http_routes: None,
+            cron_specs: None,
+            source_index: None,
+            reuse_context: false,
+        };
+        ModuleModel::new(tx)
+            .put(
+                None,
+                module_path(path)?,
+                format!("export const {function} = 1;").as_str().into(),
+                source_package_id,
+                None,
+                Some(analyze_result),
+                ModuleEnvironment::Isolate,
+            )
+            .await
+    }
+
+    async fn delete_module(tx: &mut Transaction<TestRuntime>, path: &str) -> anyhow::Result<()> {
+        let module_id = ModuleModel::new(tx)
+            .get_metadata(module_path(path)?)
+            .await?
+            .context("Module to delete is missing")?
+            .id();
+        ModuleModel::new(tx)
+            .delete(ComponentId::Root, module_id)
+            .await
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_restored_module_is_callable(rt: TestRuntime) -> anyhow::Result<()> {
+        let DbFixtures { db, .. } = DbFixtures::new_with_model(&rt).await?;
+        let mut tx = db.begin_system().await?;
+        let source_package_id = put_source_package(&mut tx).await?;
+        put_module(&mut tx, source_package_id, "messages.js", "list").await?;
+        db.commit_with_write_source(tx, "test").await?;
+
+        let mut tx = db.begin_system().await?;
+        delete_module(&mut tx, "messages.js").await?;
+        db.commit_with_write_source(tx, "test").await?;
+
+        let function_path = CanonicalizedComponentFunctionPath {
+            component: ComponentPath::root(),
+            udf_path: "messages:list".parse()?,
+        };
+        let mut tx = db.begin_system().await?;
+        let err = ModuleModel::new(&mut tx)
+            .get_analyzed_function(&function_path)
+            .await?
+            .unwrap_err();
+        assert_eq!(err.short_msg(), "ModuleNotFound");
+        ModuleModel::new(&mut tx)
+            .restore(module_path("messages.js")?)
+            .await?;
+        db.commit_with_write_source(tx, "test").await?;
+
+        let mut tx = db.begin_system().await?;
+        let function = ModuleModel::new(&mut tx)
+            .get_analyzed_function(&function_path)
+            .await??;
+        assert_eq!(function.name, "list".parse::<FunctionName>()?);
+
+        // Only deleted modules can be restored.
+        let err = ModuleModel::new(&mut tx)
+            .restore(module_path("messages.js")?)
+            .await
+            .unwrap_err();
+        assert_eq!(err.short_msg(), "ModuleNotDeleted");
+        Ok(())
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_purge_keeps_modules_within_retention(rt: TestRuntime) -> anyhow::Result<()> {
+        let DbFixtures { db, .. } = DbFixtures::new_with_model(&rt).await?;
+        let mut tx = db.begin_system().await?;
+        let source_package_id = put_source_package(&mut tx).await?;
+        put_module(&mut tx, source_package_id, "messages.js", "list").await?;
+        put_module(&mut tx, source_package_id, "users.js", "get").await?;
+        db.commit_with_write_source(tx, "test").await?;
+
+        let mut tx = db.begin_system().await?;
+        delete_module(&mut tx, "users.js").await?;
+        db.commit_with_write_source(tx, "test").await?;
+
+        // `users.js` was just deleted, so it's kept and can still be restored.
+        let mut tx = db.begin_system().await?;
+        let deleted = ModuleModel::new(&mut tx)
+            .purge_expired_deleted(ComponentId::Root)
+            .await?;
+        assert_eq!(
+            deleted.into_keys().collect::<Vec<_>>(),
+            vec!["users.js".parse()?]
+        );
+        let num_modules = ModuleModel::new(&mut tx)
+            .get_all_metadata_including_deleted(ComponentId::Root)
+            .await?
+            .len();
+        assert_eq!(num_modules, 2);
+        ModuleModel::new(&mut tx)
+            .restore(module_path("users.js")?)
+            .await?;
+        Ok(())
+    }
+}
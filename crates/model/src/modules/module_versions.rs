@@ -89,6 +89,22 @@ impl SizedValue for FullModuleSource {
     }
 }
 
+/// Uncompressed size of a module's source and source map, in bytes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub struct ModuleSourceSize {
+    pub source_bytes: usize,
+    pub source_map_bytes: usize,
+}
+
+impl From<&FullModuleSource> for ModuleSourceSize {
+    fn from(module: &FullModuleSource) -> Self {
+        Self {
+            source_bytes: module.source.len(),
+            source_map_bytes: module.source_map.as_ref().map_or(0, |map| map.len()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct AnalyzedModule {
     pub functions: WithHeapSize<Vec<AnalyzedFunction>>,
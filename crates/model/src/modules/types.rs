@@ -1,4 +1,7 @@
-use common::types::ModuleEnvironment;
+use common::types::{
+    ModuleEnvironment,
+    Timestamp,
+};
 use serde::{
     Deserialize,
     Serialize,
@@ -37,6 +40,10 @@ pub struct ModuleMetadata {
     pub analyze_result: Option<AnalyzedModule>,
     // This is a hash of source + source_map.
     pub sha256: Sha256Digest,
+
+    // Modules removed by a push are kept around until
+    // `MODULE_DELETION_RETENTION` has passed so they can be restored.
+    pub deleted_ts: Option<Timestamp>,
 }
 
 impl ModuleMetadata {
@@ -79,6 +86,8 @@ pub struct SerializedModuleMetadata {
     pub environment: String,
     pub analyze_result: Option<SerializedAnalyzedModule>,
     pub sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_ts: Option<i64>,
 }
 
 impl TryFrom<SerializedModuleMetadata> for ModuleMetadata {
@@ -94,6 +103,7 @@ impl TryFrom<SerializedModuleMetadata> for ModuleMetadata {
             environment: m.environment.parse()?,
             analyze_result: m.analyze_result.map(|s| s.try_into()).transpose()?,
             sha256: Sha256Digest::from_base64(&m.sha256)?,
+            deleted_ts: m.deleted_ts.map(|ts| ts.try_into()).transpose()?,
         })
     }
 }
@@ -108,6 +118,7 @@ impl TryFrom<ModuleMetadata> for SerializedModuleMetadata {
             environment: m.environment.to_string(),
             analyze_result: m.analyze_result.map(|s| s.try_into()).transpose()?,
             sha256: m.sha256.as_base64(),
+            deleted_ts: m.deleted_ts.map(|ts| ts.into()),
         })
     }
 }
@@ -1,9 +1,18 @@
+use std::{
+    sync::{
+        Arc,
+        LazyLock,
+    },
+    time::Duration,
+};
+
 use anyhow::Context;
 use common::{
     components::ComponentPath,
     document::{
         ParseDocument,
         ParsedDocument,
+        CREATION_TIME_FIELD_PATH,
     },
     maybe_val,
     query::{
@@ -26,6 +35,7 @@ use types::ImportRequestor;
 use value::{
     ConvexObject,
     ConvexValue,
+    FieldPath,
     ResolvedDocumentId,
     TableName,
     TableNamespace,
@@ -35,8 +45,10 @@ use value::{
 use self::types::{
     ImportFormat,
     ImportMode,
+    ImportOptions,
     ImportState,
     ImportTableCheckpoint,
+    ImportTableRowsWritten,
     SnapshotImport,
 };
 use crate::{
@@ -59,6 +71,38 @@ impl SystemTable for SnapshotImportsTable {
     }
 }
 
+pub const SNAPSHOT_IMPORT_ROWS_WRITTEN_TABLE: TableName =
+    TableName::const_new("_snapshot_import_rows_written");
+
+pub static SNAPSHOT_IMPORT_ROWS_WRITTEN_INDEX_BY_TABLET: LazyLock<
+    SystemIndex<SnapshotImportRowsWrittenTable>,
+> = LazyLock::new(|| {
+    SystemIndex::new(
+        "by_import_id_and_tablet_id",
+        [
+            &IMPORT_ID_FIELD,
+            &TABLET_ID_FIELD,
+            &CREATION_TIME_FIELD_PATH,
+        ],
+    )
+    .unwrap()
+});
+static IMPORT_ID_FIELD: LazyLock<FieldPath> =
+    LazyLock::new(|| "importId".parse().expect("invalid importId field"));
+static TABLET_ID_FIELD: LazyLock<FieldPath> =
+    LazyLock::new(|| "tabletId".parse().expect("invalid tabletId field"));
+
+pub struct SnapshotImportRowsWrittenTable;
+impl SystemTable for SnapshotImportRowsWrittenTable {
+    type Metadata = ImportTableRowsWritten;
+
+    const TABLE_NAME: TableName = SNAPSHOT_IMPORT_ROWS_WRITTEN_TABLE;
+
+    fn indexes() -> Vec<SystemIndex<Self>> {
+        vec![SNAPSHOT_IMPORT_ROWS_WRITTEN_INDEX_BY_TABLET.clone()]
+    }
+}
+
 pub struct SnapshotImportModel<'a, RT: Runtime> {
     tx: &'a mut Transaction<RT>,
 }
@@ -100,7 +144,9 @@ impl<'a, RT: Runtime> SnapshotImportModel<'a, RT> {
         mode: ImportMode,
         component_path: ComponentPath,
         object_key: FullyQualifiedObjectKey,
+        additional_object_keys: Vec<FullyQualifiedObjectKey>,
         requestor: ImportRequestor,
+        options: ImportOptions,
     ) -> anyhow::Result<ResolvedDocumentId> {
         let snapshot_import = SnapshotImport {
             state: ImportState::Uploaded,
@@ -108,9 +154,11 @@ impl<'a, RT: Runtime> SnapshotImportModel<'a, RT> {
             mode,
             component_path,
             object_key: Ok(object_key),
+            additional_object_keys,
             member_id: self.tx.identity().member_id(),
             checkpoints: None,
             requestor,
+            options,
         };
         let id = SystemMetadataModel::new_global(self.tx)
             .insert(
@@ -185,11 +233,13 @@ impl<'a, RT: Runtime> SnapshotImportModel<'a, RT> {
         id: ResolvedDocumentId,
         info_message: String,
         require_manual_confirmation: bool,
+        estimated_duration: Option<Duration>,
         new_checkpoints: Vec<ImportTableCheckpoint>,
     ) -> anyhow::Result<()> {
         self.update_state(id, move |_| ImportState::WaitingForConfirmation {
             info_message,
             require_manual_confirmation,
+            estimated_duration,
         })
         .await?;
         self.update_checkpoints(id, move |checkpoints| {
@@ -242,7 +292,8 @@ impl<'a, RT: Runtime> SnapshotImportModel<'a, RT> {
             ts,
             num_rows_written: num_rows_written as i64,
         })
-        .await
+        .await?;
+        self.delete_rows_written(id).await
     }
 
     pub async fn fail_import(
@@ -251,7 +302,8 @@ impl<'a, RT: Runtime> SnapshotImportModel<'a, RT> {
         error_message: String,
     ) -> anyhow::Result<()> {
         self.update_state(id, move |_| ImportState::Failed(error_message))
-            .await
+            .await?;
+        self.delete_rows_written(id).await
     }
 
     pub async fn checkpoint_tablet_created(
@@ -271,6 +323,92 @@ impl<'a, RT: Runtime> SnapshotImportModel<'a, RT> {
         .await
     }
 
+    async fn get_rows_written(
+        &mut self,
+        id: ResolvedDocumentId,
+        tablet_id: TabletId,
+    ) -> anyhow::Result<Option<Arc<ParsedDocument<ImportTableRowsWritten>>>> {
+        let import_id = id.developer_id.encode();
+        let tablet_id = tablet_id.to_string();
+        self.tx
+            .query_system(
+                TableNamespace::Global,
+                &SNAPSHOT_IMPORT_ROWS_WRITTEN_INDEX_BY_TABLET,
+            )?
+            .eq(&[import_id.as_str(), tablet_id.as_str()])?
+            .unique()
+            .await
+    }
+
+    /// How many rows of the table being imported into `tablet_id` have been
+    /// written, as recorded by [`Self::checkpoint_rows_written`].
+    pub async fn rows_written(
+        &mut self,
+        id: ResolvedDocumentId,
+        tablet_id: TabletId,
+    ) -> anyhow::Result<i64> {
+        Ok(self
+            .get_rows_written(id, tablet_id)
+            .await?
+            .map_or(0, |rows_written| rows_written.num_rows_written))
+    }
+
+    /// Records that the first `num_rows_written` rows of the table being
+    /// imported into `tablet_id` have been written. Call this in the same
+    /// transaction as the rows, so an import resumed after a restart can skip
+    /// exactly that many.
+    pub async fn checkpoint_rows_written(
+        &mut self,
+        id: ResolvedDocumentId,
+        tablet_id: TabletId,
+        num_rows_written: i64,
+    ) -> anyhow::Result<()> {
+        let existing = self.get_rows_written(id, tablet_id).await?;
+        let rows_written = ImportTableRowsWritten {
+            import_id: id.developer_id,
+            tablet_id,
+            num_rows_written,
+        };
+        let mut model = SystemMetadataModel::new_global(self.tx);
+        match existing {
+            Some(existing) if existing.num_rows_written >= num_rows_written => {},
+            Some(existing) => {
+                model
+                    .replace(existing.id(), rows_written.try_into()?)
+                    .await?;
+            },
+            None => {
+                model
+                    .insert(
+                        &SNAPSHOT_IMPORT_ROWS_WRITTEN_TABLE,
+                        rows_written.try_into()?,
+                    )
+                    .await?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Deletes the import's row counts once it can no longer be resumed.
+    async fn delete_rows_written(&mut self, id: ResolvedDocumentId) -> anyhow::Result<()> {
+        let import_id = id.developer_id.encode();
+        let rows_written = self
+            .tx
+            .query_system(
+                TableNamespace::Global,
+                &SNAPSHOT_IMPORT_ROWS_WRITTEN_INDEX_BY_TABLET,
+            )?
+            .eq(&[import_id.as_str()])?
+            .all()
+            .await?;
+        for rows_written in rows_written {
+            SystemMetadataModel::new_global(self.tx)
+                .delete(rows_written.id())
+                .await?;
+        }
+        Ok(())
+    }
+
     pub async fn get_table_checkpoint(
         &mut self,
         id: ResolvedDocumentId,
@@ -1,6 +1,15 @@
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    time::Duration,
+};
+
 use common::{
     components::ComponentPath,
     types::{
+        FieldName,
         FullyQualifiedObjectKey,
         MemberId,
         ObjectKey,
@@ -11,12 +20,18 @@ use serde::{
     Deserialize,
     Serialize,
 };
-use sync_types::Timestamp;
+use sync_types::{
+    CanonicalizedUdfPath,
+    Timestamp,
+};
 use value::{
     codegen_convex_serialization,
+    DeveloperDocumentId,
     TabletId,
 };
 
+use crate::exports::deployment_config::DeploymentConfigSections;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct SnapshotImport {
     pub state: ImportState,
@@ -25,9 +40,13 @@ pub struct SnapshotImport {
     pub component_path: ComponentPath,
     // TODO: this should always be FullyQualifiedObjectKey
     pub object_key: Result<FullyQualifiedObjectKey, ObjectKey>,
+    /// The remaining parts of a ZIP import that was split into several files,
+    /// in the order they follow `object_key`. Empty for single-file imports.
+    pub additional_object_keys: Vec<FullyQualifiedObjectKey>,
     pub member_id: Option<MemberId>,
     pub checkpoints: Option<Vec<ImportTableCheckpoint>>,
     pub requestor: ImportRequestor,
+    pub options: ImportOptions,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -42,9 +61,15 @@ pub struct SerializedSnapshotImport {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     fq_object_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    additional_fq_object_keys: Option<Vec<String>>,
     member_id: Option<i64>,
     checkpoints: Option<Vec<SerializedImportTableCheckpoint>>,
     requestor: SerializedImportRequestor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    options: Option<SerializedImportOptions>,
 }
 
 impl From<SnapshotImport> for SerializedSnapshotImport {
@@ -60,11 +85,19 @@ impl From<SnapshotImport> for SerializedSnapshotImport {
             component_path: import.component_path.serialize(),
             object_key,
             fq_object_key,
+            additional_fq_object_keys: (!import.additional_object_keys.is_empty()).then(|| {
+                import
+                    .additional_object_keys
+                    .into_iter()
+                    .map(Into::into)
+                    .collect()
+            }),
             member_id: import.member_id.map(|member_id| member_id.0 as i64),
             checkpoints: import
                 .checkpoints
                 .map(|checkpoints| checkpoints.into_iter().map(Into::into).collect()),
             requestor: import.requestor.into(),
+            options: (import.options != ImportOptions::default()).then(|| import.options.into()),
         }
     }
 }
@@ -85,22 +118,313 @@ impl TryFrom<SerializedSnapshotImport> for SnapshotImport {
             mode: import.mode.parse()?,
             component_path: ComponentPath::deserialize(import.component_path.as_deref())?,
             object_key,
+            additional_object_keys: import
+                .additional_fq_object_keys
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
             member_id: import.member_id.map(|member_id| MemberId(member_id as u64)),
             checkpoints: import
                 .checkpoints
                 .map(|checkpoints| checkpoints.into_iter().map(TryInto::try_into).try_collect())
                 .transpose()?,
             requestor: import.requestor.into(),
+            options: import
+                .options
+                .map(ImportOptions::try_from)
+                .transpose()?
+                .unwrap_or_default(),
         })
     }
 }
 
 codegen_convex_serialization!(SnapshotImport, SerializedSnapshotImport);
 
+/// Optional settings for an import, chosen by the requestor when the import is
+/// started and applied by the import worker.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ImportOptions {
+    /// Tables to import first, in this order. Tables that aren't listed are
+    /// imported afterwards in the order they appear in the import file.
+    pub table_order: Vec<TableName>,
+    /// Maximum total size of the documents written in a single import
+    /// transaction. Defaults to `SNAPSHOT_IMPORT_BATCH_MAX_SIZE_BYTES`.
+    pub batch_max_size_bytes: Option<usize>,
+    /// Maximum number of documents written in a single import transaction.
+    /// Defaults to `SNAPSHOT_IMPORT_BATCH_MAX_NUM_DOCUMENTS`.
+    pub batch_max_num_documents: Option<usize>,
+    /// Fail the import on the first document without an `_id` field instead
+    /// of assigning it a new id.
+    pub require_id_field: bool,
+    /// For JSON array imports, a JSON pointer (e.g. `/records`) to the array
+    /// of documents within the file.
+    pub json_pointer: Option<String>,
+    /// Overrides of the import's mode for individual tables, keyed by the
+    /// table name as it appears in the import. Tables that aren't listed use
+    /// the import's mode.
+    pub per_table_mode: BTreeMap<TableName, ImportMode>,
+    /// Insert each table's documents in order of this field. Only JSON array
+    /// imports, which are held in memory in full, are sorted by it. CSV,
+    /// JSONL and ZIP imports are streamed, so they're checked instead: the
+    /// import fails at the first user table document that's out of order.
+    pub order_by: Option<FieldName>,
+    /// After all documents are written, check that every `v.id()` reference
+    /// in the imported tables points to a document that exists, and fail the
+    /// import if any are dangling.
+    pub defer_reference_checks: bool,
+    /// Route each document of a single-table import to a table chosen by the
+    /// value of one of its fields.
+    pub partition_by: Option<PartitionBy>,
+    /// The deployment configuration bundled in a ZIP import to restore
+    /// alongside its data.
+    pub restore_config: DeploymentConfigSections,
+    /// What to do with top-level fields that the table's schema doesn't
+    /// define.
+    pub unknown_fields: UnknownFieldsPolicy,
+    /// Sync the imported documents into the existing tables: documents whose
+    /// `_id` doesn't exist are inserted, documents that differ from the
+    /// existing document are replaced, and identical documents are skipped.
+    /// Only valid for tables imported in Append mode. Every document with an
+    /// `_id` costs a read of the existing document, so this is slower than a
+    /// plain Append import.
+    pub diff_import: bool,
+    /// Whether to check the component arguments recorded in a ZIP import
+    /// against the arguments of the components it's imported into.
+    pub component_args: ComponentArgsPolicy,
+    /// A query in the component being imported into that each document is
+    /// passed through. The object it returns is imported instead.
+    pub transform: Option<CanonicalizedUdfPath>,
+    /// For CSV imports, columns whose values are always imported as strings,
+    /// even when they look like numbers (e.g. phone numbers or zip codes).
+    pub csv_string_columns: BTreeSet<FieldName>,
+    /// For ZIP imports, the only tables to import, keyed by component. Other
+    /// tables in the archive, and their `_tables` entries, are skipped.
+    pub include_tables: Option<BTreeSet<(ComponentPath, TableName)>>,
+}
+
+/// Splits a single-table import across several tables by the value of
+/// `field`. Documents whose value isn't in `tables`, or that don't have the
+/// field, are imported into the import's own table.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartitionBy {
+    pub field: FieldName,
+    /// Destination table for each field value. Non-string values are matched
+    /// by their JSON encoding, e.g. `1` or `true`.
+    pub tables: BTreeMap<String, TableName>,
+}
+
+impl ImportOptions {
+    /// The mode that `table_name` is imported with, given the import's mode.
+    pub fn mode_for_table(&self, mode: ImportMode, table_name: &TableName) -> ImportMode {
+        self.per_table_mode.get(table_name).copied().unwrap_or(mode)
+    }
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SerializedImportOptions {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    table_order: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    batch_max_size_bytes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    batch_max_num_documents: Option<i64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default)]
+    require_id_field: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    json_pointer: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    per_table_mode: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    order_by: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default)]
+    defer_reference_checks: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    partition_by: Option<SerializedPartitionBy>,
+    #[serde(skip_serializing_if = "DeploymentConfigSections::is_empty")]
+    #[serde(default)]
+    restore_config: DeploymentConfigSections,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    unknown_fields: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default)]
+    diff_import: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    component_args: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    transform: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    csv_string_columns: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    include_tables: Option<Vec<SerializedIncludedTable>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SerializedIncludedTable {
+    component_path: Option<String>,
+    table: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SerializedPartitionBy {
+    field: String,
+    tables: BTreeMap<String, String>,
+}
+
+impl From<ImportOptions> for SerializedImportOptions {
+    fn from(options: ImportOptions) -> Self {
+        SerializedImportOptions {
+            table_order: options
+                .table_order
+                .into_iter()
+                .map(|table_name| table_name.to_string())
+                .collect(),
+            batch_max_size_bytes: options.batch_max_size_bytes.map(|n| n as i64),
+            batch_max_num_documents: options.batch_max_num_documents.map(|n| n as i64),
+            require_id_field: options.require_id_field,
+            json_pointer: options.json_pointer,
+            per_table_mode: options
+                .per_table_mode
+                .into_iter()
+                .map(|(table_name, mode)| (table_name.to_string(), mode.to_string()))
+                .collect(),
+            order_by: options.order_by.map(String::from),
+            defer_reference_checks: options.defer_reference_checks,
+            partition_by: options
+                .partition_by
+                .map(|partition_by| SerializedPartitionBy {
+                    field: partition_by.field.into(),
+                    tables: partition_by
+                        .tables
+                        .into_iter()
+                        .map(|(value, table_name)| (value, table_name.to_string()))
+                        .collect(),
+                }),
+            restore_config: options.restore_config,
+            unknown_fields: match options.unknown_fields {
+                UnknownFieldsPolicy::Error => None,
+                UnknownFieldsPolicy::Drop => Some(options.unknown_fields.to_string()),
+            },
+            diff_import: options.diff_import,
+            component_args: match options.component_args {
+                ComponentArgsPolicy::Check => None,
+                ComponentArgsPolicy::Ignore => Some(options.component_args.to_string()),
+            },
+            transform: options.transform.map(String::from),
+            csv_string_columns: options
+                .csv_string_columns
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            include_tables: options.include_tables.map(|tables| {
+                tables
+                    .into_iter()
+                    .map(|(component_path, table_name)| SerializedIncludedTable {
+                        component_path: component_path.serialize(),
+                        table: table_name.to_string(),
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+impl TryFrom<SerializedImportOptions> for ImportOptions {
+    type Error = anyhow::Error;
+
+    fn try_from(options: SerializedImportOptions) -> anyhow::Result<Self> {
+        Ok(ImportOptions {
+            table_order: options
+                .table_order
+                .into_iter()
+                .map(|table_name| table_name.parse::<TableName>())
+                .try_collect()?,
+            batch_max_size_bytes: options
+                .batch_max_size_bytes
+                .map(usize::try_from)
+                .transpose()?,
+            batch_max_num_documents: options
+                .batch_max_num_documents
+                .map(usize::try_from)
+                .transpose()?,
+            require_id_field: options.require_id_field,
+            json_pointer: options.json_pointer,
+            per_table_mode: options
+                .per_table_mode
+                .into_iter()
+                .map(|(table_name, mode)| anyhow::Ok((table_name.parse()?, mode.parse()?)))
+                .try_collect()?,
+            order_by: options.order_by.map(FieldName::try_from).transpose()?,
+            defer_reference_checks: options.defer_reference_checks,
+            partition_by: options
+                .partition_by
+                .map(|partition_by| {
+                    anyhow::Ok(PartitionBy {
+                        field: partition_by.field.try_into()?,
+                        tables: partition_by
+                            .tables
+                            .into_iter()
+                            .map(|(value, table_name)| anyhow::Ok((value, table_name.parse()?)))
+                            .try_collect()?,
+                    })
+                })
+                .transpose()?,
+            restore_config: options.restore_config,
+            unknown_fields: options
+                .unknown_fields
+                .map(|policy| policy.parse())
+                .transpose()?
+                .unwrap_or_default(),
+            diff_import: options.diff_import,
+            component_args: options
+                .component_args
+                .map(|policy| policy.parse())
+                .transpose()?
+                .unwrap_or_default(),
+            transform: options.transform.map(|path| path.parse()).transpose()?,
+            csv_string_columns: options
+                .csv_string_columns
+                .into_iter()
+                .map(FieldName::try_from)
+                .try_collect()?,
+            include_tables: options
+                .include_tables
+                .map(|tables| {
+                    tables
+                        .into_iter()
+                        .map(|table| {
+                            anyhow::Ok((
+                                ComponentPath::deserialize(table.component_path.as_deref())?,
+                                table.table.parse()?,
+                            ))
+                        })
+                        .try_collect::<BTreeSet<_>>()
+                })
+                .transpose()?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ImportFormat {
     Csv(TableName),
     JsonLines(TableName),
+    /// JSON lines compressed with gzip, e.g. a `.jsonl.gz` file.
+    JsonLinesGzip(TableName),
     JsonArray(TableName),
     Zip,
 }
@@ -112,6 +436,8 @@ pub enum SerializedImportFormat {
     Csv { table: String },
     #[serde(rename = "jsonl")]
     JsonLines { table: String },
+    #[serde(rename = "jsonl_gzip")]
+    JsonLinesGzip { table: String },
     #[serde(rename = "json_array")]
     JsonArray { table: String },
     #[serde(rename = "zip")]
@@ -127,6 +453,9 @@ impl From<ImportFormat> for SerializedImportFormat {
             ImportFormat::JsonLines(table) => SerializedImportFormat::JsonLines {
                 table: table.to_string(),
             },
+            ImportFormat::JsonLinesGzip(table) => SerializedImportFormat::JsonLinesGzip {
+                table: table.to_string(),
+            },
             ImportFormat::JsonArray(table) => SerializedImportFormat::JsonArray {
                 table: table.to_string(),
             },
@@ -144,6 +473,9 @@ impl TryFrom<SerializedImportFormat> for ImportFormat {
             SerializedImportFormat::JsonLines { table } => {
                 Ok(ImportFormat::JsonLines(table.parse()?))
             },
+            SerializedImportFormat::JsonLinesGzip { table } => {
+                Ok(ImportFormat::JsonLinesGzip(table.parse()?))
+            },
             SerializedImportFormat::JsonArray { table } => {
                 Ok(ImportFormat::JsonArray(table.parse()?))
             },
@@ -197,6 +529,8 @@ pub enum ImportState {
     WaitingForConfirmation {
         info_message: String,
         require_manual_confirmation: bool,
+        /// Approximately how long the import will take once confirmed.
+        estimated_duration: Option<Duration>,
     },
     InProgress {
         progress_message: String,
@@ -216,6 +550,9 @@ pub enum SerializedImportState {
     WaitingForConfirmation {
         message_to_confirm: Option<String>,
         require_manual_confirmation: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        estimated_duration_ms: Option<i64>,
     },
     InProgress {
         progress_message: Option<String>,
@@ -237,9 +574,12 @@ impl From<ImportState> for SerializedImportState {
             ImportState::WaitingForConfirmation {
                 info_message,
                 require_manual_confirmation,
+                estimated_duration,
             } => SerializedImportState::WaitingForConfirmation {
                 message_to_confirm: Some(info_message),
                 require_manual_confirmation: Some(require_manual_confirmation),
+                estimated_duration_ms: estimated_duration
+                    .map(|duration| duration.as_millis().try_into().unwrap_or(i64::MAX)),
             },
             ImportState::InProgress {
                 progress_message,
@@ -271,9 +611,13 @@ impl TryFrom<SerializedImportState> for ImportState {
             SerializedImportState::WaitingForConfirmation {
                 message_to_confirm,
                 require_manual_confirmation,
+                estimated_duration_ms,
             } => Ok(ImportState::WaitingForConfirmation {
                 info_message: message_to_confirm.unwrap_or_default(),
                 require_manual_confirmation: require_manual_confirmation.unwrap_or(true),
+                estimated_duration: estimated_duration_ms
+                    .map(|ms| anyhow::Ok(Duration::from_millis(ms.try_into()?)))
+                    .transpose()?,
             }),
             SerializedImportState::InProgress {
                 progress_message,
@@ -376,6 +720,49 @@ impl TryFrom<SerializedImportTableCheckpoint> for ImportTableCheckpoint {
     }
 }
 
+/// How many rows an import has written to one of its tables. Each table has
+/// its own document, written in the same transaction as the rows, so an import
+/// resumed after a restart can skip exactly that many, and tables imported
+/// concurrently don't conflict on the import's own document.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ImportTableRowsWritten {
+    pub import_id: DeveloperDocumentId,
+    pub tablet_id: TabletId,
+    pub num_rows_written: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializedImportTableRowsWritten {
+    import_id: String,
+    tablet_id: String,
+    num_rows_written: i64,
+}
+
+impl From<ImportTableRowsWritten> for SerializedImportTableRowsWritten {
+    fn from(rows_written: ImportTableRowsWritten) -> Self {
+        Self {
+            import_id: rows_written.import_id.encode(),
+            tablet_id: rows_written.tablet_id.to_string(),
+            num_rows_written: rows_written.num_rows_written,
+        }
+    }
+}
+
+impl TryFrom<SerializedImportTableRowsWritten> for ImportTableRowsWritten {
+    type Error = anyhow::Error;
+
+    fn try_from(rows_written: SerializedImportTableRowsWritten) -> anyhow::Result<Self> {
+        Ok(Self {
+            import_id: rows_written.import_id.parse()?,
+            tablet_id: rows_written.tablet_id.parse()?,
+            num_rows_written: rows_written.num_rows_written,
+        })
+    }
+}
+
+codegen_convex_serialization!(ImportTableRowsWritten, SerializedImportTableRowsWritten);
+
 #[derive(
     Debug, Default, Deserialize, Clone, Copy, Eq, PartialEq, strum::EnumString, strum::Display,
 )]
@@ -388,6 +775,34 @@ pub enum ImportMode {
     RequireEmpty,
 }
 
+/// How an import treats top-level fields of a document that aren't defined by
+/// the table's schema. Tables without a schema accept any field.
+#[derive(
+    Debug, Default, Deserialize, Clone, Copy, Eq, PartialEq, strum::EnumString, strum::Display,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum UnknownFieldsPolicy {
+    /// Fail schema validation, as for any other write.
+    #[default]
+    Error,
+    /// Remove the fields before the document is inserted.
+    Drop,
+}
+
+/// How a ZIP import treats the arguments it recorded for each child component
+/// when the component it's imported into was instantiated with different ones.
+#[derive(
+    Debug, Default, Deserialize, Clone, Copy, Eq, PartialEq, strum::EnumString, strum::Display,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum ComponentArgsPolicy {
+    /// Fail the import before any data is written.
+    #[default]
+    Check,
+    /// Import anyway, keeping the target component's arguments.
+    Ignore,
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum ImportRequestor {
     SnapshotImport,
@@ -395,6 +810,17 @@ pub enum ImportRequestor {
     StreamingImport,
 }
 
+/// Whether clearing tables records a `ClearTables` deployment audit log
+/// entry.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ClearTablesAuditLog {
+    Record,
+    /// Suppresses the audit log entry. Only admins may request this, and it is
+    /// intended for automated resets of ephemeral test deployments, not
+    /// production deployments.
+    Skip,
+}
+
 impl ImportRequestor {
     pub fn usage_tag(&self) -> &'static str {
         match self {
@@ -1,5 +1,9 @@
 use std::{
-    collections::HashSet,
+    collections::{
+        btree_map,
+        BTreeMap,
+        HashSet,
+    },
     sync::Arc,
 };
 
@@ -77,6 +81,21 @@ impl<'a, RT: Runtime> SourcePackageModel<'a, RT> {
             .context("Couldn't find source package")
     }
 
+    /// Returns the source packages with the given IDs, reading each distinct
+    /// package once.
+    pub async fn get_many(
+        &mut self,
+        source_package_ids: impl IntoIterator<Item = SourcePackageId>,
+    ) -> anyhow::Result<BTreeMap<SourcePackageId, Arc<ParsedDocument<SourcePackage>>>> {
+        let mut source_packages = BTreeMap::new();
+        for source_package_id in source_package_ids {
+            if let btree_map::Entry::Vacant(entry) = source_packages.entry(source_package_id) {
+                entry.insert(self.get(source_package_id).await?);
+            }
+        }
+        Ok(source_packages)
+    }
+
     pub async fn get_latest(
         &mut self,
     ) -> anyhow::Result<Option<Arc<ParsedDocument<SourcePackage>>>> {
@@ -1,7 +1,14 @@
 use std::{
     fs,
+    num::NonZeroUsize,
     path::PathBuf,
-    sync::Arc,
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+    },
     time::Duration,
 };
 
@@ -15,6 +22,7 @@ use futures::{
 };
 use futures_async_stream::try_stream;
 use isolate::bundled_js::node_executor_file;
+use parking_lot::Mutex;
 use rand::Rng;
 use reqwest::Client;
 use serde_json::Value as JsonValue;
@@ -24,10 +32,7 @@ use tokio::{
         Child,
         Command as TokioCommand,
     },
-    sync::{
-        mpsc,
-        Mutex,
-    },
+    sync::mpsc,
 };
 
 use crate::{
@@ -39,6 +44,7 @@ use crate::{
         EXECUTE_TIMEOUT_RESPONSE_JSON,
     },
     handle_node_executor_stream,
+    metrics::log_local_node_processes,
     NodeExecutorStreamPart,
 };
 
@@ -47,7 +53,7 @@ const HEALTH_CHECK_INTERVAL: Duration = Duration::from_millis(100);
 const MAX_HEALTH_CHECK_ATTEMPTS: u32 = 50;
 
 pub struct LocalNodeExecutor {
-    inner: Arc<Mutex<Option<InnerLocalNodeExecutor>>>,
+    pool: NodeProcessPool<InnerLocalNodeExecutor>,
     config: LocalNodeExecutorConfig,
 }
 
@@ -55,6 +61,112 @@ struct LocalNodeExecutorConfig {
     node_process_timeout: Duration,
 }
 
+/// The Node processes actions run on. An action runs on an idle process if
+/// there is one, otherwise on a new process if there are fewer than
+/// `max_size`, otherwise on the process running the fewest actions. Processes
+/// are started lazily and kept until they crash or say they're exiting.
+///
+/// Generic over the process so the pool can be tested without starting Node.
+struct NodeProcessPool<P> {
+    processes: Mutex<Vec<Arc<NodeProcess<P>>>>,
+    /// Held while starting a process, so a burst of actions doesn't start more
+    /// processes than it needs.
+    spawn_lock: tokio::sync::Mutex<()>,
+    max_size: usize,
+}
+
+struct NodeProcess<P> {
+    inner: P,
+    /// Number of actions running on this process. Only changed while holding
+    /// the pool's `processes` lock.
+    running: AtomicUsize,
+}
+
+/// An action's claim on a [NodeProcess], released when dropped.
+struct NodeProcessLease<'a, P> {
+    pool: &'a NodeProcessPool<P>,
+    process: Arc<NodeProcess<P>>,
+}
+
+impl<P> NodeProcessPool<P> {
+    fn new(max_size: NonZeroUsize) -> Self {
+        Self {
+            processes: Mutex::new(Vec::new()),
+            spawn_lock: tokio::sync::Mutex::new(()),
+            max_size: max_size.get(),
+        }
+    }
+
+    /// Leases a process, calling `start` if a new one should be started.
+    async fn lease(
+        &self,
+        start: impl AsyncFnOnce() -> anyhow::Result<P>,
+    ) -> anyhow::Result<NodeProcessLease<'_, P>> {
+        if let Some(lease) = self.try_lease() {
+            return Ok(lease);
+        }
+        let _spawning = self.spawn_lock.lock().await;
+        // Another action may have started a process while we were waiting.
+        if let Some(lease) = self.try_lease() {
+            return Ok(lease);
+        }
+        let inner = start().await?;
+        let process = Arc::new(NodeProcess {
+            inner,
+            running: AtomicUsize::new(1),
+        });
+        let mut processes = self.processes.lock();
+        processes.push(process.clone());
+        Self::log_processes(&processes);
+        Ok(NodeProcessLease {
+            pool: self,
+            process,
+        })
+    }
+
+    /// Leases an existing process, or returns `None` if a new one should be
+    /// started.
+    fn try_lease(&self) -> Option<NodeProcessLease<'_, P>> {
+        let processes = self.processes.lock();
+        let process = processes
+            .iter()
+            .min_by_key(|process| process.running.load(Ordering::Relaxed))?;
+        if process.running.load(Ordering::Relaxed) > 0 && processes.len() < self.max_size {
+            return None;
+        }
+        process.running.fetch_add(1, Ordering::Relaxed);
+        Self::log_processes(&processes);
+        Some(NodeProcessLease {
+            pool: self,
+            process: process.clone(),
+        })
+    }
+
+    /// Stops running new actions on `process`. It's killed once the actions
+    /// already running on it finish.
+    fn retire(&self, process: &Arc<NodeProcess<P>>) {
+        let mut processes = self.processes.lock();
+        processes.retain(|p| !Arc::ptr_eq(p, process));
+        Self::log_processes(&processes);
+    }
+
+    fn log_processes(processes: &[Arc<NodeProcess<P>>]) {
+        let in_use = processes
+            .iter()
+            .filter(|process| process.running.load(Ordering::Relaxed) > 0)
+            .count();
+        log_local_node_processes(in_use, processes.len() - in_use);
+    }
+}
+
+impl<P> Drop for NodeProcessLease<'_, P> {
+    fn drop(&mut self) {
+        let processes = self.pool.processes.lock();
+        self.process.running.fetch_sub(1, Ordering::Relaxed);
+        NodeProcessPool::log_processes(&processes);
+    }
+}
+
 struct InnerLocalNodeExecutor {
     _source_dir: TempDir,
     client: reqwest::Client,
@@ -189,9 +301,14 @@ impl InnerLocalNodeExecutor {
 }
 
 impl LocalNodeExecutor {
-    pub async fn new(node_process_timeout: Duration) -> anyhow::Result<Self> {
+    /// Runs actions on up to `pool_size` Node processes, each of which is given
+    /// `node_process_timeout` to run an action.
+    pub async fn new(
+        node_process_timeout: Duration,
+        pool_size: NonZeroUsize,
+    ) -> anyhow::Result<Self> {
         let executor = Self {
-            inner: Arc::new(Mutex::new(None)),
+            pool: NodeProcessPool::new(pool_size),
             config: LocalNodeExecutorConfig {
                 node_process_timeout,
             },
@@ -248,18 +365,15 @@ impl NodeExecutor for LocalNodeExecutor {
         request: ExecutorRequest,
         log_line_sender: mpsc::UnboundedSender<LogLine>,
     ) -> anyhow::Result<InvokeResponse> {
-        let client = {
-            let mut inner = self.inner.lock().await;
-            if inner.is_none() {
-                *inner = Some(
-                    InnerLocalNodeExecutor::new()
-                        .await
-                        .context("Failed to create inner local node executor")?,
-                )
-            }
-            let inner = inner.as_ref().unwrap();
-            inner.client.clone()
-        };
+        let lease = self
+            .pool
+            .lease(async || {
+                InnerLocalNodeExecutor::new()
+                    .await
+                    .context("Failed to create inner local node executor")
+            })
+            .await?;
+        let client = lease.process.inner.client.clone();
         let request_json = JsonValue::try_from(request)?;
 
         let response_result = client
@@ -278,9 +392,9 @@ impl NodeExecutor for LocalNodeExecutor {
                     });
                 } else if e.is_connect() {
                     // Connection error likely means the Node server crashed (e.g., OOM).
-                    // Drop the dead server so it will be restarted on next invoke.
+                    // Retire the dead server so a new one is started if needed.
                     tracing::warn!("Node server connection failed, dropping server: {e}");
-                    self.inner.lock().await.take();
+                    self.pool.retire(&lease.process);
                     return Err(anyhow::anyhow!(e).context("Node server request failed"));
                 } else {
                     return Err(anyhow::anyhow!(e).context("Node server request failed"));
@@ -310,8 +424,8 @@ impl NodeExecutor for LocalNodeExecutor {
                     .and_then(|v| v.as_bool())
                     .unwrap_or(false)
                 {
-                    // Drop the server if it claims to be exiting.
-                    self.inner.lock().await.take();
+                    // Retire the server if it claims to be exiting.
+                    self.pool.retire(&lease.process);
                 }
                 Ok(InvokeResponse {
                     response: payload,
@@ -324,3 +438,123 @@ impl NodeExecutor for LocalNodeExecutor {
 
     fn shutdown(&self) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroUsize,
+        sync::atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        task::Poll,
+    };
+
+    use futures::{
+        executor::block_on,
+        future::{
+            join_all,
+            poll_fn,
+        },
+    };
+
+    use super::NodeProcessPool;
+
+    /// Stands in for a Node process, identified by the order it was started
+    /// in.
+    type StubProcess = usize;
+
+    fn pool(max_size: usize) -> NodeProcessPool<StubProcess> {
+        NodeProcessPool::new(NonZeroUsize::new(max_size).unwrap())
+    }
+
+    async fn yield_now() {
+        let mut yielded = false;
+        poll_fn(|cx| {
+            if yielded {
+                return Poll::Ready(());
+            }
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await
+    }
+
+    fn start(started: &AtomicUsize) -> impl AsyncFnOnce() -> anyhow::Result<StubProcess> {
+        async move || {
+            // Let other actions run while the process starts.
+            yield_now().await;
+            Ok(started.fetch_add(1, Ordering::SeqCst))
+        }
+    }
+
+    #[test]
+    fn test_concurrent_actions_never_exceed_pool_size() {
+        for max_size in [1, 3, 8] {
+            let pool = pool(max_size);
+            let started = AtomicUsize::new(0);
+            let max_seen = AtomicUsize::new(0);
+            block_on(join_all((0..32).map(|_| async {
+                let lease = pool.lease(start(&started)).await.unwrap();
+                max_seen.fetch_max(pool.processes.lock().len(), Ordering::SeqCst);
+                // Run for longer than starting every process takes, so
+                // processes aren't freed up before the pool fills.
+                for _ in 0..100 {
+                    yield_now().await;
+                }
+                drop(lease);
+            })));
+            assert_eq!(started.load(Ordering::SeqCst), max_size);
+            assert_eq!(max_seen.load(Ordering::SeqCst), max_size);
+        }
+    }
+
+    #[test]
+    fn test_idle_process_is_reused_before_starting_another() -> anyhow::Result<()> {
+        let pool = pool(2);
+        let started = AtomicUsize::new(0);
+        let first = block_on(pool.lease(start(&started)))?;
+        assert_eq!(first.process.inner, 0);
+        drop(first);
+        let second = block_on(pool.lease(start(&started)))?;
+        assert_eq!(second.process.inner, 0);
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_pool_leases_least_busy_process() -> anyhow::Result<()> {
+        let pool = pool(2);
+        let started = AtomicUsize::new(0);
+        let a = block_on(pool.lease(start(&started)))?;
+        let b = block_on(pool.lease(start(&started)))?;
+        assert_eq!((a.process.inner, b.process.inner), (0, 1));
+        // Both processes run one action, so either may be picked.
+        let c = block_on(pool.lease(start(&started)))?;
+        let less_busy = 1 - c.process.inner;
+        let d = block_on(pool.lease(start(&started)))?;
+        assert_eq!(d.process.inner, less_busy);
+        assert_eq!(started.load(Ordering::SeqCst), 2);
+
+        // Once an action finishes, its process is the least busy.
+        let finished = if a.process.inner == less_busy { a } else { b };
+        drop(finished);
+        let e = block_on(pool.lease(start(&started)))?;
+        assert_eq!(e.process.inner, less_busy);
+        Ok(())
+    }
+
+    #[test]
+    fn test_retired_process_is_replaced() -> anyhow::Result<()> {
+        let pool = pool(1);
+        let started = AtomicUsize::new(0);
+        let lease = block_on(pool.lease(start(&started)))?;
+        pool.retire(&lease.process);
+        let replacement = block_on(pool.lease(start(&started)))?;
+        assert_eq!(replacement.process.inner, 1);
+        drop(lease);
+        assert_eq!(pool.processes.lock().len(), 1);
+        Ok(())
+    }
+}
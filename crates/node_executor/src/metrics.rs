@@ -4,7 +4,9 @@ use metrics::{
     log_counter,
     log_distribution,
     log_distribution_with_labels,
+    log_gauge,
     register_convex_counter,
+    register_convex_gauge,
     register_convex_histogram,
     StaticMetricLabel,
     StatusTimer,
@@ -118,3 +120,16 @@ pub fn log_external_deps_size_bytes_total(pkg_size: PackageSize) {
         vec![unzipped_label],
     );
 }
+
+register_convex_gauge!(
+    NODE_EXECUTOR_LOCAL_PROCESSES_IN_USE_TOTAL,
+    "Number of local Node executor processes running at least one action"
+);
+register_convex_gauge!(
+    NODE_EXECUTOR_LOCAL_PROCESSES_IDLE_TOTAL,
+    "Number of local Node executor processes not running any actions"
+);
+pub fn log_local_node_processes(in_use: usize, idle: usize) {
+    log_gauge(&NODE_EXECUTOR_LOCAL_PROCESSES_IN_USE_TOTAL, in_use as f64);
+    log_gauge(&NODE_EXECUTOR_LOCAL_PROCESSES_IDLE_TOTAL, idle as f64);
+}
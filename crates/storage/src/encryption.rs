@@ -0,0 +1,396 @@
+//! At-rest encryption for [`crate::LocalDirStorage`].
+//!
+//! Objects are split into fixed-size plaintext chunks, and each chunk is
+//! sealed independently with AES-256-GCM as `nonce || ciphertext || tag`.
+//! Fixed-size chunks keep range reads cheap: a read only decrypts the chunks
+//! overlapping the requested range. The chunk index and whether it is the
+//! final chunk are bound in as associated data, so chunks can't be reordered
+//! and an object can't be silently truncated at a chunk boundary.
+
+use std::{
+    fs::File,
+    io::{
+        Read,
+        Seek,
+        SeekFrom,
+    },
+    ops::Range,
+    str::FromStr,
+};
+
+use anyhow::Context as _;
+use openssl::{
+    rand::rand_bytes,
+    symm::{
+        decrypt_aead,
+        encrypt_aead,
+        Cipher,
+    },
+};
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const CHUNK_OVERHEAD: usize = NONCE_LEN + TAG_LEN;
+
+/// Plaintext bytes per encrypted chunk.
+pub const ENCRYPTION_CHUNK_SIZE: usize = 64 * 1024;
+const ENCRYPTED_CHUNK_SIZE: usize = ENCRYPTION_CHUNK_SIZE + CHUNK_OVERHEAD;
+
+/// A 256-bit AES key, parsed from its base64 encoding.
+#[derive(Clone)]
+pub struct LocalDirEncryptionKey([u8; KEY_LEN]);
+
+impl FromStr for LocalDirEncryptionKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let bytes = base64::decode(s.trim()).context("Encryption key isn't valid base64")?;
+        let key: [u8; KEY_LEN] = bytes.try_into().map_err(|bytes: Vec<u8>| {
+            anyhow::anyhow!(
+                "Encryption key must be {KEY_LEN} bytes, got {}",
+                bytes.len()
+            )
+        })?;
+        Ok(Self(key))
+    }
+}
+
+impl std::fmt::Debug for LocalDirEncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LocalDirEncryptionKey(..)")
+    }
+}
+
+impl LocalDirEncryptionKey {
+    fn associated_data(index: u64, is_last: bool) -> [u8; 9] {
+        let mut aad = [0; 9];
+        aad[..8].copy_from_slice(&index.to_be_bytes());
+        aad[8] = is_last as u8;
+        aad
+    }
+
+    fn seal_chunk(&self, index: u64, is_last: bool, plaintext: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut nonce = [0; NONCE_LEN];
+        rand_bytes(&mut nonce)?;
+        let mut tag = [0; TAG_LEN];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.0,
+            Some(&nonce[..]),
+            &Self::associated_data(index, is_last),
+            plaintext,
+            &mut tag,
+        )?;
+        let mut sealed = Vec::with_capacity(CHUNK_OVERHEAD + ciphertext.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        sealed.extend_from_slice(&tag);
+        Ok(sealed)
+    }
+
+    fn open_chunk(&self, index: u64, is_last: bool, sealed: &[u8]) -> anyhow::Result<Vec<u8>> {
+        anyhow::ensure!(
+            sealed.len() >= CHUNK_OVERHEAD,
+            "Encrypted chunk {index} is truncated"
+        );
+        let (nonce, rest) = sealed.split_at(NONCE_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+        decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.0,
+            Some(nonce),
+            &Self::associated_data(index, is_last),
+            ciphertext,
+            tag,
+        )
+        .with_context(|| format!("Failed to decrypt chunk {index}. Was the key changed?"))
+    }
+
+    /// Decrypts `range` of the plaintext stored in `file`.
+    pub fn read_range(&self, file: &mut File, range: Range<u64>) -> anyhow::Result<Vec<u8>> {
+        let encrypted_len = file.metadata()?.len();
+        let num_chunks = encrypted_len.div_ceil(ENCRYPTED_CHUNK_SIZE as u64);
+        let plaintext_len = plaintext_len(encrypted_len)?;
+        anyhow::ensure!(
+            range.end <= plaintext_len,
+            "Range {range:?} is out of bounds for an object of {plaintext_len} bytes"
+        );
+        if range.start >= range.end {
+            return Ok(vec![]);
+        }
+        let first_chunk = range.start / ENCRYPTION_CHUNK_SIZE as u64;
+        let last_chunk = (range.end - 1) / ENCRYPTION_CHUNK_SIZE as u64;
+        let mut plaintext = Vec::with_capacity((range.end - range.start) as usize);
+        let mut sealed = vec![0; ENCRYPTED_CHUNK_SIZE];
+        file.seek(SeekFrom::Start(first_chunk * ENCRYPTED_CHUNK_SIZE as u64))?;
+        for index in first_chunk..=last_chunk {
+            let chunk_start = index * ENCRYPTED_CHUNK_SIZE as u64;
+            let sealed_len = (encrypted_len - chunk_start).min(ENCRYPTED_CHUNK_SIZE as u64);
+            let sealed = &mut sealed[..sealed_len as usize];
+            file.read_exact(sealed)?;
+            let chunk = self.open_chunk(index, index == num_chunks - 1, sealed)?;
+            let chunk_offset = index * ENCRYPTION_CHUNK_SIZE as u64;
+            let start = range.start.saturating_sub(chunk_offset) as usize;
+            let end = ((range.end - chunk_offset) as usize).min(chunk.len());
+            plaintext.extend_from_slice(&chunk[start..end]);
+        }
+        Ok(plaintext)
+    }
+}
+
+/// The plaintext size of an encrypted object that is `encrypted_len` bytes on
+/// disk.
+pub fn plaintext_len(encrypted_len: u64) -> anyhow::Result<u64> {
+    let num_chunks = encrypted_len.div_ceil(ENCRYPTED_CHUNK_SIZE as u64);
+    let last_chunk_len = encrypted_len - num_chunks.saturating_sub(1) * ENCRYPTED_CHUNK_SIZE as u64;
+    anyhow::ensure!(
+        num_chunks > 0 && last_chunk_len >= CHUNK_OVERHEAD as u64,
+        "Encrypted object of {encrypted_len} bytes is truncated"
+    );
+    Ok(encrypted_len - num_chunks * CHUNK_OVERHEAD as u64)
+}
+
+/// Incrementally encrypts an object written in arbitrarily sized pieces.
+pub struct ChunkEncryptor {
+    key: LocalDirEncryptionKey,
+    next_index: u64,
+    pending: Vec<u8>,
+}
+
+impl ChunkEncryptor {
+    pub fn new(key: LocalDirEncryptionKey) -> Self {
+        Self {
+            key,
+            next_index: 0,
+            pending: Vec::with_capacity(ENCRYPTION_CHUNK_SIZE),
+        }
+    }
+
+    /// Buffers `data` and returns the encrypted bytes for any chunks that are
+    /// complete. A full chunk is held back until more data arrives, since
+    /// until then we don't know whether it's the last one.
+    pub fn push(&mut self, mut data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut sealed = vec![];
+        while !data.is_empty() {
+            if self.pending.len() == ENCRYPTION_CHUNK_SIZE {
+                sealed.extend(self.seal_pending(false)?);
+            }
+            let n = (ENCRYPTION_CHUNK_SIZE - self.pending.len()).min(data.len());
+            self.pending.extend_from_slice(&data[..n]);
+            data = &data[n..];
+        }
+        Ok(sealed)
+    }
+
+    /// Returns the encrypted bytes for the final chunk. Empty objects are
+    /// still written as a single empty chunk.
+    pub fn finish(mut self) -> anyhow::Result<Vec<u8>> {
+        self.seal_pending(true)
+    }
+
+    fn seal_pending(&mut self, is_last: bool) -> anyhow::Result<Vec<u8>> {
+        let sealed = self
+            .key
+            .seal_chunk(self.next_index, is_last, &self.pending)?;
+        self.next_index += 1;
+        self.pending.clear();
+        Ok(sealed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        io::{
+            Seek,
+            Write,
+        },
+    };
+
+    use super::{
+        plaintext_len,
+        ChunkEncryptor,
+        LocalDirEncryptionKey,
+        ENCRYPTED_CHUNK_SIZE,
+        ENCRYPTION_CHUNK_SIZE,
+    };
+
+    fn key(byte: u8) -> LocalDirEncryptionKey {
+        LocalDirEncryptionKey([byte; super::KEY_LEN])
+    }
+
+    fn plaintext(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    /// Encrypts `plaintext` in pieces of `piece_len` bytes, as an upload
+    /// writing parts would.
+    fn encrypt(key: &LocalDirEncryptionKey, plaintext: &[u8], piece_len: usize) -> Vec<u8> {
+        let mut encryptor = ChunkEncryptor::new(key.clone());
+        let mut encrypted = vec![];
+        for piece in plaintext.chunks(piece_len) {
+            encrypted.extend(encryptor.push(piece).unwrap());
+        }
+        encrypted.extend(encryptor.finish().unwrap());
+        encrypted
+    }
+
+    fn to_file(encrypted: &[u8]) -> anyhow::Result<File> {
+        let mut file = tempfile::tempfile()?;
+        file.write_all(encrypted)?;
+        file.rewind()?;
+        Ok(file)
+    }
+
+    #[test]
+    fn test_round_trip() -> anyhow::Result<()> {
+        let key = key(7);
+        for len in [
+            0,
+            1,
+            ENCRYPTION_CHUNK_SIZE - 1,
+            ENCRYPTION_CHUNK_SIZE,
+            ENCRYPTION_CHUNK_SIZE + 1,
+            3 * ENCRYPTION_CHUNK_SIZE,
+            3 * ENCRYPTION_CHUNK_SIZE + 17,
+        ] {
+            let plaintext = plaintext(len);
+            for piece_len in [1000, ENCRYPTION_CHUNK_SIZE, 5 * ENCRYPTION_CHUNK_SIZE] {
+                let encrypted = encrypt(&key, &plaintext, piece_len);
+                assert_eq!(plaintext_len(encrypted.len() as u64)?, len as u64);
+                let mut file = to_file(&encrypted)?;
+                assert_eq!(
+                    key.read_range(&mut file, 0..len as u64)?,
+                    plaintext,
+                    "{len}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_object_is_one_chunk() -> anyhow::Result<()> {
+        let key = key(7);
+        let encrypted = encrypt(&key, &[], 1);
+        assert_eq!(encrypted.len(), super::CHUNK_OVERHEAD);
+        let mut file = to_file(&encrypted)?;
+        assert!(key.read_range(&mut file, 0..0)?.is_empty());
+        assert!(key.read_range(&mut file, 0..1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_exact_multiple_of_chunk_size() -> anyhow::Result<()> {
+        let key = key(7);
+        let plaintext = plaintext(2 * ENCRYPTION_CHUNK_SIZE);
+        let encrypted = encrypt(&key, &plaintext, 4096);
+        // No empty trailing chunk: the last full chunk is marked as the last.
+        assert_eq!(encrypted.len(), 2 * ENCRYPTED_CHUNK_SIZE);
+        let mut file = to_file(&encrypted)?;
+        assert_eq!(
+            key.read_range(&mut file, 0..plaintext.len() as u64)?,
+            plaintext
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ranges_within_and_across_chunks() -> anyhow::Result<()> {
+        let key = key(7);
+        let chunk = ENCRYPTION_CHUNK_SIZE as u64;
+        let plaintext = plaintext(3 * ENCRYPTION_CHUNK_SIZE + 100);
+        let mut file = to_file(&encrypt(&key, &plaintext, 10_000))?;
+        for range in [
+            0..1,
+            5..100,
+            chunk - 1..chunk + 1,
+            chunk..2 * chunk,
+            100..2 * chunk + 100,
+            chunk - 10..3 * chunk + 100,
+            3 * chunk..3 * chunk + 100,
+            3 * chunk + 99..3 * chunk + 100,
+            50..50,
+        ] {
+            assert_eq!(
+                key.read_range(&mut file, range.clone())?,
+                &plaintext[range.start as usize..range.end as usize],
+                "{range:?}"
+            );
+        }
+        assert!(key
+            .read_range(&mut file, 0..plaintext.len() as u64 + 1)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncated_object_fails() -> anyhow::Result<()> {
+        let key = key(7);
+        let plaintext = plaintext(2 * ENCRYPTION_CHUNK_SIZE + 10);
+        let encrypted = encrypt(&key, &plaintext, 4096);
+
+        // Dropping the final chunk leaves a valid-looking prefix, but the
+        // new last chunk wasn't sealed as the last one.
+        let truncated = &encrypted[..2 * ENCRYPTED_CHUNK_SIZE];
+        let mut file = to_file(truncated)?;
+        let len = plaintext_len(truncated.len() as u64)?;
+        assert!(key.read_range(&mut file, 0..len).is_err());
+
+        // Cutting into a chunk fails that chunk's authentication.
+        let truncated = &encrypted[..encrypted.len() - 1];
+        let mut file = to_file(truncated)?;
+        let len = plaintext_len(truncated.len() as u64)?;
+        assert!(key.read_range(&mut file, 0..len).is_err());
+
+        // Too short to hold even a chunk's nonce and tag.
+        assert!(plaintext_len(super::CHUNK_OVERHEAD as u64 - 1).is_err());
+        assert!(plaintext_len(0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reordered_chunks_fail() -> anyhow::Result<()> {
+        let key = key(7);
+        let plaintext = plaintext(3 * ENCRYPTION_CHUNK_SIZE);
+        let encrypted = encrypt(&key, &plaintext, ENCRYPTION_CHUNK_SIZE);
+        let mut swapped = encrypted.clone();
+        swapped[..ENCRYPTED_CHUNK_SIZE]
+            .copy_from_slice(&encrypted[ENCRYPTED_CHUNK_SIZE..2 * ENCRYPTED_CHUNK_SIZE]);
+        swapped[ENCRYPTED_CHUNK_SIZE..2 * ENCRYPTED_CHUNK_SIZE]
+            .copy_from_slice(&encrypted[..ENCRYPTED_CHUNK_SIZE]);
+        let mut file = to_file(&swapped)?;
+        let chunk = ENCRYPTION_CHUNK_SIZE as u64;
+        assert!(key.read_range(&mut file, 0..10).is_err());
+        assert!(key.read_range(&mut file, chunk..chunk + 10).is_err());
+        // The untouched last chunk still decrypts.
+        assert_eq!(
+            key.read_range(&mut file, 2 * chunk..2 * chunk + 10)?,
+            &plaintext[2 * ENCRYPTION_CHUNK_SIZE..2 * ENCRYPTION_CHUNK_SIZE + 10]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrong_key_fails() -> anyhow::Result<()> {
+        let plaintext = plaintext(100);
+        let mut file = to_file(&encrypt(&key(7), &plaintext, 100))?;
+        let err = key(8).read_range(&mut file, 0..100).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Was the key changed?"),
+            "{err:#}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_key() -> anyhow::Result<()> {
+        let key: LocalDirEncryptionKey = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=".parse()?;
+        assert_eq!(key.0, std::array::from_fn(|i| i as u8));
+        assert!("not base64!".parse::<LocalDirEncryptionKey>().is_err());
+        assert!("dG9vIHNob3J0".parse::<LocalDirEncryptionKey>().is_err());
+        Ok(())
+    }
+}
@@ -27,7 +27,13 @@ use std::{
         PathBuf,
     },
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        Arc,
+    },
     task::{
         Context,
         Poll,
@@ -43,7 +49,11 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use common::{
     errors::report_error,
-    runtime::Runtime,
+    knobs::LOCAL_DIR_EXPORTS_ENCRYPTION_KEY,
+    runtime::{
+        tokio_spawn_blocking,
+        Runtime,
+    },
     try_anyhow,
     types::{
         FullyQualifiedObjectKey,
@@ -90,6 +100,14 @@ use value::sha256::{
     Sha256Digest,
 };
 
+pub use crate::encryption::LocalDirEncryptionKey;
+use crate::encryption::{
+    plaintext_len,
+    ChunkEncryptor,
+};
+
+mod encryption;
+
 pub const LOCAL_DIR_MIN_PART_SIZE: usize = 5 * (1 << 20);
 pub const LOCAL_DIR_MAX_PART_SIZE: usize = 8 * (1 << 30);
 pub const MAX_NUM_PARTS: usize = 10000;
@@ -210,6 +228,36 @@ pub trait Storage: Send + Sync + Debug {
 
     /// List all objects whose key begins with `key_prefix`.
     async fn list_objects(&self, key_prefix: &str) -> anyhow::Result<Vec<ObjectListing>>;
+
+    /// Checks whether the object at `key` hashes to `expected`, without
+    /// streaming it back through the backend. Returns
+    /// [ChecksumVerification::Unverifiable] if the backend has no checksum it
+    /// can compare.
+    async fn verify_checksum(
+        &self,
+        key: &ObjectKey,
+        expected: &Sha256Digest,
+    ) -> anyhow::Result<ChecksumVerification>;
+}
+
+/// The result of [Storage::verify_checksum].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChecksumVerification {
+    Matches,
+    Mismatch,
+    /// The object has no stored SHA-256 of its whole contents, e.g. because
+    /// it was uploaded in parts or with another checksum algorithm.
+    Unverifiable,
+}
+
+impl ChecksumVerification {
+    pub fn from_match(matches: bool) -> Self {
+        if matches {
+            ChecksumVerification::Matches
+        } else {
+            ChecksumVerification::Mismatch
+        }
+    }
 }
 
 pub struct ObjectAttributes {
@@ -344,6 +392,7 @@ impl<T: Upload> UploadExt for T {
 pub struct BufferedUpload {
     upload: Box<dyn Upload>,
     buffer: Vec<u8>,
+    min_intermediate_part_size: usize,
     max_intermediate_part_size: usize,
     target_intermediate_part_size: usize,
 }
@@ -358,11 +407,52 @@ impl BufferedUpload {
         Self {
             upload: Box::new(upload),
             buffer,
+            min_intermediate_part_size,
             max_intermediate_part_size,
             target_intermediate_part_size: min_intermediate_part_size,
         }
     }
 
+    /// Lowers the largest part this upload buffers before writing it, trading
+    /// more parts for less memory held per part. This never raises the limit
+    /// or lowers it below the current target part size.
+    pub fn limit_max_intermediate_part_size(&mut self, max_intermediate_part_size: usize) {
+        self.max_intermediate_part_size = cmp::min(
+            self.max_intermediate_part_size,
+            cmp::max(
+                max_intermediate_part_size,
+                self.target_intermediate_part_size,
+            ),
+        );
+    }
+
+    /// The smallest part the underlying upload accepts, other than the last.
+    pub fn min_intermediate_part_size(&self) -> usize {
+        self.min_intermediate_part_size
+    }
+
+    /// The largest part this upload writes to the underlying upload.
+    pub fn max_intermediate_part_size(&self) -> usize {
+        self.max_intermediate_part_size
+    }
+
+    /// Writes each item of `parts` to the underlying upload as a part, without
+    /// buffering them. Every part but the last must be between
+    /// [Self::min_intermediate_part_size] and
+    /// [Self::max_intermediate_part_size] bytes, like the parts
+    /// [ChannelWriter::with_part_boundaries] sends.
+    pub async fn try_write_parts_parallel(
+        &mut self,
+        parts: impl Stream<Item = anyhow::Result<Bytes>> + Send,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.buffer.is_empty(),
+            "Can't write parts after buffered writes"
+        );
+        let mut boxed = parts.boxed();
+        self.upload.try_write_parallel(&mut boxed).await
+    }
+
     fn update_buffer_and_get_next(&mut self, data: Bytes) -> Option<Bytes> {
         Self::_update_buffer_and_get_next(
             &mut self.buffer,
@@ -483,20 +573,57 @@ impl Upload for BufferedUpload {
     }
 }
 
+/// Marks a point in the bytes written to a [ChannelWriter] where it may end
+/// a part early, like the end of a zip entry.
+#[derive(Clone, Default)]
+pub struct PartBoundary(Arc<AtomicBool>);
+
+impl PartBoundary {
+    pub fn mark(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    fn take(&self) -> bool {
+        self.0.swap(false, Ordering::AcqRel)
+    }
+}
+
 pub struct ChannelWriter {
     parts: PollSender<Bytes>,
     current_part: Vec<u8>,
+    min_part_size: usize,
     part_size: usize,
+    part_boundary: PartBoundary,
+    end_part: bool,
 }
 
 impl ChannelWriter {
     pub fn new(sender: mpsc::Sender<Bytes>, part_size: usize) -> Self {
+        Self::with_part_boundaries(sender, part_size, part_size)
+    }
+
+    /// Sends parts of at most `max_part_size` bytes, and also ends a part at
+    /// the first [PartBoundary] marked once it has `min_part_size` bytes, so
+    /// what's written is sent soon after a boundary rather than held until a
+    /// full part is buffered.
+    pub fn with_part_boundaries(
+        sender: mpsc::Sender<Bytes>,
+        min_part_size: usize,
+        max_part_size: usize,
+    ) -> Self {
         Self {
             parts: PollSender::new(sender),
-            current_part: Vec::with_capacity(part_size),
-            part_size,
+            current_part: Vec::with_capacity(min_part_size),
+            min_part_size,
+            part_size: max_part_size,
+            part_boundary: PartBoundary::default(),
+            end_part: false,
         }
     }
+
+    pub fn part_boundary(&self) -> PartBoundary {
+        self.part_boundary.clone()
+    }
 }
 
 impl AsyncWrite for ChannelWriter {
@@ -506,8 +633,11 @@ impl AsyncWrite for ChannelWriter {
         buf: &[u8],
     ) -> Poll<Result<usize, IoError>> {
         let self_ = self.get_mut();
+        if self_.part_boundary.take() && self_.current_part.len() >= self_.min_part_size {
+            self_.end_part = !self_.current_part.is_empty();
+        }
         loop {
-            if self_.current_part.len() < self_.part_size {
+            if !self_.end_part && self_.current_part.len() < self_.part_size {
                 let n = cmp::min(buf.len(), self_.part_size - self_.current_part.len());
                 self_.current_part.extend_from_slice(&buf[..n]);
                 return Poll::Ready(Ok(n));
@@ -516,12 +646,13 @@ impl AsyncWrite for ChannelWriter {
                 .parts
                 .poll_reserve(cx)
                 .map_err(|_| IoError::new(IoErrorKind::BrokenPipe, "Channel closed")))?;
-            let next_buf = Vec::with_capacity(self_.part_size);
+            let next_buf = Vec::with_capacity(self_.min_part_size);
             let buf = mem::replace(&mut self_.current_part, next_buf);
             self_
                 .parts
                 .send_item(buf.into())
                 .map_err(|_| IoError::new(IoErrorKind::BrokenPipe, "Channel closed"))?;
+            self_.end_part = false;
         }
     }
 
@@ -815,12 +946,14 @@ pub struct LocalDirStorage<RT: Runtime> {
     rt: RT,
     dir: PathBuf,
     _temp_dir: Option<Arc<TempDir>>,
+    encryption_key: Option<LocalDirEncryptionKey>,
 }
 
 impl<RT: Runtime> std::fmt::Debug for LocalDirStorage<RT> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("LocalDirStorage")
             .field("dir", &self.dir)
+            .field("encrypted", &self.encryption_key.is_some())
             .finish()
     }
 }
@@ -834,6 +967,7 @@ impl<RT: Runtime> LocalDirStorage<RT> {
             rt,
             dir: temp_dir.path().to_owned(),
             _temp_dir: Some(Arc::new(temp_dir)),
+            encryption_key: None,
         };
         Ok(storage)
     }
@@ -850,6 +984,7 @@ impl<RT: Runtime> LocalDirStorage<RT> {
             rt,
             dir,
             _temp_dir: None,
+            encryption_key: None,
         };
         Ok(storage)
     }
@@ -863,12 +998,45 @@ impl<RT: Runtime> LocalDirStorage<RT> {
         String::from(key) + ".blob"
     }
 
+    /// Encrypt objects at rest with AES-256-GCM. Objects written before the
+    /// key was set (or under a different key) can't be read back.
+    pub fn with_encryption_key(mut self, key: LocalDirEncryptionKey) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
     pub fn for_use_case(rt: RT, dir: &str, use_case: StorageUseCase) -> anyhow::Result<Self> {
+        Self::for_use_case_with_exports_key(
+            rt,
+            dir,
+            use_case,
+            LOCAL_DIR_EXPORTS_ENCRYPTION_KEY.as_deref(),
+        )
+    }
+
+    fn for_use_case_with_exports_key(
+        rt: RT,
+        dir: &str,
+        use_case: StorageUseCase,
+        exports_key: Option<&str>,
+    ) -> anyhow::Result<Self> {
         let use_case_str = use_case.to_string();
         anyhow::ensure!(!dir.is_empty());
-        let storage = LocalDirStorage::new_at_path(rt, PathBuf::from(dir).join(use_case_str))?;
+        let mut storage = LocalDirStorage::new_at_path(rt, PathBuf::from(dir).join(use_case_str))?;
+        if use_case == StorageUseCase::Exports
+            && let Some(key) = exports_key
+        {
+            let key = key
+                .parse()
+                .context("Invalid LOCAL_DIR_EXPORTS_ENCRYPTION_KEY")?;
+            storage = storage.with_encryption_key(key);
+        }
         Ok(storage)
     }
+
+    fn encryptor(&self) -> Option<ChunkEncryptor> {
+        self.encryption_key.clone().map(ChunkEncryptor::new)
+    }
 }
 
 struct ClientDrivenUpload {
@@ -937,12 +1105,17 @@ impl<RT: Runtime> Storage for LocalDirStorage<RT> {
             object_key,
             file: Some(file),
             num_parts: 0,
+            encryptor: self.encryptor(),
         };
         let upload = BufferedUpload::new(upload, LOCAL_DIR_MIN_PART_SIZE, LOCAL_DIR_MAX_PART_SIZE);
         Ok(Box::new(upload))
     }
 
     async fn start_client_driven_upload(&self) -> anyhow::Result<ClientDrivenUploadToken> {
+        anyhow::ensure!(
+            self.encryption_key.is_none(),
+            "Client-driven uploads aren't supported for encrypted local storage"
+        );
         let object_key: ObjectKey = self.rt.new_uuid_v4().to_string().try_into()?;
         let key = self.filename_for_key(object_key.clone());
         let filepath = self.dir.join(key);
@@ -983,6 +1156,7 @@ impl<RT: Runtime> Storage for LocalDirStorage<RT> {
             object_key,
             file: Some(file),
             num_parts: 0, // unused
+            encryptor: None,
         };
         upload.write(part).await?;
         Ok(ClientDrivenUploadPartToken(String::new()))
@@ -1001,6 +1175,11 @@ impl<RT: Runtime> Storage for LocalDirStorage<RT> {
     }
 
     async fn signed_url(&self, key: ObjectKey, _expires_in: Duration) -> anyhow::Result<String> {
+        // The URL points directly at the file, which would bypass decryption.
+        anyhow::ensure!(
+            self.encryption_key.is_none(),
+            "Signed URLs aren't supported for encrypted local storage"
+        );
         let key = self.filename_for_key(key);
         let path = self.dir.join(key);
         let url = Url::from_file_path(&path)
@@ -1050,14 +1229,20 @@ impl<RT: Runtime> Storage for LocalDirStorage<RT> {
         bytes_range: Range<u64>,
     ) -> BoxFuture<'static, anyhow::Result<StorageGetStream>> {
         let path = Path::new(key.as_str()).to_owned();
+        let encryption_key = self.encryption_key.clone();
         async move {
-            let mut buf = vec![0; (bytes_range.end - bytes_range.start) as usize];
             let mut file = File::open(path.clone()).context(format!(
                 "Local dir storage couldn't open {}",
                 path.display()
             ))?;
-            file.seek(SeekFrom::Start(bytes_range.start))?;
-            file.read_exact(&mut buf)?;
+            let buf = if let Some(encryption_key) = encryption_key {
+                encryption_key.read_range(&mut file, bytes_range.clone())?
+            } else {
+                let mut buf = vec![0; (bytes_range.end - bytes_range.start) as usize];
+                file.seek(SeekFrom::Start(bytes_range.start))?;
+                file.read_exact(&mut buf)?;
+                buf
+            };
             Ok(StorageGetStream {
                 content_length: (bytes_range.end - bytes_range.start) as i64,
                 stream: stream::once(async move { Ok(buf.into()) }).boxed(),
@@ -1078,9 +1263,38 @@ impl<RT: Runtime> Storage for LocalDirStorage<RT> {
         }
         let mut file = result.unwrap();
         file.read_to_end(&mut buf)?;
-        Ok(Some(ObjectAttributes {
-            size: buf.len() as u64,
-        }))
+        let size = if self.encryption_key.is_some() {
+            plaintext_len(buf.len() as u64)?
+        } else {
+            buf.len() as u64
+        };
+        Ok(Some(ObjectAttributes { size }))
+    }
+
+    async fn verify_checksum(
+        &self,
+        key: &ObjectKey,
+        expected: &Sha256Digest,
+    ) -> anyhow::Result<ChecksumVerification> {
+        let path = self.dir.join(self.filename_for_key(key.clone()));
+        let encryption_key = self.encryption_key.clone();
+        let sha256 = tokio_spawn_blocking("local_dir_storage_verify_checksum", move || {
+            let mut file = File::open(&path).context(format!(
+                "Local dir storage couldn't open {}",
+                path.display()
+            ))?;
+            let contents = if let Some(encryption_key) = &encryption_key {
+                let plaintext_len = plaintext_len(file.metadata()?.len())?;
+                encryption_key.read_range(&mut file, 0..plaintext_len)?
+            } else {
+                let mut buf = vec![];
+                file.read_to_end(&mut buf)?;
+                buf
+            };
+            anyhow::Ok(Sha256::hash(&contents))
+        })
+        .await??;
+        Ok(ChecksumVerification::from_match(sha256 == *expected))
     }
 
     fn storage_type_proto(&self) -> pb::searchlight::StorageType {
@@ -1107,7 +1321,12 @@ impl<RT: Runtime> Storage for LocalDirStorage<RT> {
             "LocalDirStorage file creation failed. Perhaps the storage object key isn't valid?",
         )?;
         let mut file = File::create(filepath)?;
-        file.write_all(&bytes)?;
+        if let Some(mut encryptor) = self.encryptor() {
+            file.write_all(&encryptor.push(&bytes)?)?;
+            file.write_all(&encryptor.finish()?)?;
+        } else {
+            file.write_all(&bytes)?;
+        }
         Ok(())
     }
 
@@ -1152,6 +1371,7 @@ pub struct LocalDirUpload {
     object_key: ObjectKey,
     file: Option<File>,
     num_parts: usize,
+    encryptor: Option<ChunkEncryptor>,
 }
 
 #[async_trait]
@@ -1164,7 +1384,11 @@ impl Upload for LocalDirUpload {
             .as_mut()
             .ok_or_else(|| anyhow::anyhow!("Upload not active"))?;
 
-        file.write_all(&data)?;
+        if let Some(encryptor) = &mut self.encryptor {
+            file.write_all(&encryptor.push(&data)?)?;
+        } else {
+            file.write_all(&data)?;
+        }
 
         self.num_parts += 1;
 
@@ -1190,7 +1414,10 @@ impl Upload for LocalDirUpload {
     async fn complete(mut self: Box<Self>) -> anyhow::Result<ObjectKey> {
         let object_key = self.object_key;
 
-        let file = self.file.take().context("Completing inactive file")?;
+        let mut file = self.file.take().context("Completing inactive file")?;
+        if let Some(encryptor) = self.encryptor.take() {
+            file.write_all(&encryptor.finish()?)?;
+        }
         file.sync_all()?;
         Ok(object_key)
     }
@@ -1222,3 +1449,136 @@ impl Display for StorageUseCase {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures::{
+        StreamExt,
+        TryStreamExt,
+    };
+    use runtime::testing::TestRuntime;
+    use tempfile::TempDir;
+    use tokio::{
+        io::AsyncWriteExt,
+        sync::mpsc,
+    };
+    use tokio_stream::wrappers::ReceiverStream;
+
+    use crate::{
+        ChannelWriter,
+        LocalDirStorage,
+        Storage,
+        StorageUseCase,
+    };
+
+    const EXPORTS_KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+
+    #[tokio::test]
+    async fn test_channel_writer_ends_parts_at_boundaries() -> anyhow::Result<()> {
+        let (sender, receiver) = mpsc::channel(8);
+        let mut writer = ChannelWriter::with_part_boundaries(sender, 4, 8);
+        let boundary = writer.part_boundary();
+
+        writer.write_all(b"abc").await?;
+        // Too small to end a part yet.
+        boundary.mark();
+        writer.write_all(b"de").await?;
+        boundary.mark();
+        writer.write_all(b"f").await?;
+        // Parts are never longer than the maximum.
+        writer.write_all(b"ghijklmno").await?;
+        writer.shutdown().await?;
+        drop(writer);
+
+        let parts: Vec<Bytes> = ReceiverStream::new(receiver).collect().await;
+        assert_eq!(
+            parts,
+            vec![
+                Bytes::from_static(b"abcde"),
+                Bytes::from_static(b"fghijklm"),
+                Bytes::from_static(b"no"),
+            ]
+        );
+        Ok(())
+    }
+
+    async fn put_and_read(
+        storage: &LocalDirStorage<TestRuntime>,
+        contents: &'static [u8],
+    ) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+        let key = "object".try_into()?;
+        storage
+            .put_object(key, Bytes::from_static(contents))
+            .await?;
+        let on_disk = std::fs::read(storage.path().join("object.blob"))?;
+        let stream = storage
+            .get_small_range(
+                &storage.fully_qualified_key(&"object".try_into()?),
+                0..contents.len() as u64,
+            )
+            .await?;
+        let chunks: Vec<Bytes> = stream.stream.try_collect().await?;
+        Ok((on_disk, chunks.concat()))
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_only_exports_are_encrypted(rt: TestRuntime) -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        let dir = dir.path().to_str().unwrap();
+        let contents = b"a snapshot export";
+
+        let exports = LocalDirStorage::for_use_case_with_exports_key(
+            rt.clone(),
+            dir,
+            StorageUseCase::Exports,
+            Some(EXPORTS_KEY),
+        )?;
+        let (on_disk, read) = put_and_read(&exports, contents).await?;
+        assert_eq!(read, contents);
+        assert!(on_disk.len() > contents.len());
+        assert!(!on_disk.windows(contents.len()).any(|w| w == contents));
+
+        for use_case in [
+            StorageUseCase::SnapshotImports,
+            StorageUseCase::Modules,
+            StorageUseCase::Files,
+            StorageUseCase::SearchIndexes,
+        ] {
+            let storage = LocalDirStorage::for_use_case_with_exports_key(
+                rt.clone(),
+                dir,
+                use_case,
+                Some(EXPORTS_KEY),
+            )?;
+            let (on_disk, read) = put_and_read(&storage, contents).await?;
+            assert_eq!(on_disk, contents);
+            assert_eq!(read, contents);
+        }
+
+        // Without a key, exports are plaintext too.
+        let dir = TempDir::new()?;
+        let exports = LocalDirStorage::for_use_case_with_exports_key(
+            rt,
+            dir.path().to_str().unwrap(),
+            StorageUseCase::Exports,
+            None,
+        )?;
+        let (on_disk, _) = put_and_read(&exports, contents).await?;
+        assert_eq!(on_disk, contents);
+        Ok(())
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_invalid_exports_key_is_rejected(rt: TestRuntime) -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        let result = LocalDirStorage::for_use_case_with_exports_key(
+            rt,
+            dir.path().to_str().unwrap(),
+            StorageUseCase::Exports,
+            Some("dG9vIHNob3J0"),
+        );
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -1167,4 +1167,6 @@ fn is_retriable_sync_worker_error(err: &anyhow::Error) -> bool {
         || err.is_operational_internal_server_error()
         || err.is_overloaded()
         || err.is_rejected_before_execution()
+        // The backend serving the query hasn't caught up to `new_ts` yet.
+        || err.short_msg() == "TimestampInFuture"
 }
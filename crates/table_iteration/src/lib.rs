@@ -4,7 +4,9 @@ pub mod data_sync;
 mod table_iterator;
 
 pub use crate::table_iterator::{
+    InterruptReason,
     MultiTableIterator,
+    TableIterationInterrupted,
     TableIterator,
     TableScanCursor,
 };
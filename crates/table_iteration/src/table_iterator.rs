@@ -4,8 +4,10 @@ use std::{
         BTreeMap,
         BTreeSet,
     },
+    fmt,
     ops::Deref,
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Context;
@@ -47,14 +49,20 @@ use common::{
 };
 use errors::ErrorMetadataAnyhowExt;
 use futures::{
-    future::Either,
+    future::{
+        self,
+        Either,
+    },
     pin_mut,
+    select_biased,
     stream,
+    FutureExt,
     Stream,
     StreamExt,
     TryStreamExt,
 };
 use futures_async_stream::try_stream;
+use tokio_util::sync::CancellationToken;
 use value::{
     InternalDocumentId,
     InternalId,
@@ -71,9 +79,14 @@ pub struct TableScanCursor {
 
 impl TableScanCursor {
     pub fn interval(&self) -> Interval {
+        self.interval_in_order(Order::Asc)
+    }
+
+    /// The part of the table a scan in `order` hasn't walked yet.
+    pub fn interval_in_order(&self, order: Order) -> Interval {
         match &self.index_key {
             Some(index_key) => {
-                let (_, remaining) = Interval::all().split(index_key.clone(), Order::Asc);
+                let (_, remaining) = Interval::all().split(index_key.clone(), order);
                 remaining
             },
             None => Interval::all(),
@@ -81,22 +94,76 @@ impl TableScanCursor {
     }
 
     pub fn advance(&mut self, index_key: CursorPosition) -> anyhow::Result<()> {
+        self.advance_in_order(index_key, Order::Asc)
+    }
+
+    pub fn advance_in_order(
+        &mut self,
+        index_key: CursorPosition,
+        order: Order,
+    ) -> anyhow::Result<()> {
         if let Some(existing_key) = &self.index_key {
-            anyhow::ensure!(index_key > existing_key);
+            let advanced = match (order, existing_key, &index_key) {
+                (Order::Asc, ..) => index_key > *existing_key,
+                (Order::Desc, CursorPosition::After(existing), CursorPosition::After(new)) => {
+                    new < existing
+                },
+                (Order::Desc, CursorPosition::After(_), CursorPosition::End) => true,
+                (Order::Desc, CursorPosition::End, _) => false,
+            };
+            anyhow::ensure!(advanced);
         }
         self.index_key = Some(index_key);
         Ok(())
     }
 }
 
-fn cursor_has_walked(cursor: Option<&CursorPosition>, key: &IndexKeyBytes) -> bool {
+fn cursor_has_walked(cursor: Option<&CursorPosition>, key: &IndexKeyBytes, order: Order) -> bool {
     match cursor {
         None => false,
         Some(CursorPosition::End) => true,
-        Some(CursorPosition::After(cursor)) => key <= cursor,
+        Some(CursorPosition::After(cursor)) => match order {
+            Order::Asc => key <= cursor,
+            Order::Desc => key >= cursor,
+        },
     }
 }
 
+/// Why a [`TableIterator`] stopped before reaching the end of a table.
+#[derive(Clone, Copy, Debug)]
+pub enum InterruptReason {
+    /// The iterator's cancellation token was canceled.
+    Canceled,
+    /// Fetching a single page took longer than the configured timeout.
+    PageTimeout(Duration),
+}
+
+/// Returned (inside an `anyhow::Error`) when a [`TableIterator`] is
+/// interrupted. Every document up to and including `cursor` has been yielded,
+/// so passing `cursor` back to `stream_documents_in_table_by_index` resumes the
+/// iteration where it stopped instead of restarting it.
+#[derive(Clone, Debug)]
+pub struct TableIterationInterrupted {
+    pub reason: InterruptReason,
+    pub cursor: Option<CursorPosition>,
+}
+
+impl fmt::Display for TableIterationInterrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.reason {
+            InterruptReason::Canceled => write!(f, "Table iteration was canceled"),
+            InterruptReason::PageTimeout(timeout) => {
+                write!(
+                    f,
+                    "Table iteration timed out fetching a page after {timeout:?}"
+                )
+            },
+        }
+    }
+}
+
+impl std::error::Error for TableIterationInterrupted {}
+
 pub struct TableIterator<RT: Runtime> {
     inner: TableIteratorInner<RT>,
 }
@@ -116,6 +183,9 @@ impl<RT: Runtime> TableIterator<RT> {
                 retention_validator,
                 page_size,
                 snapshot_ts,
+                cancellation: None,
+                page_timeout: None,
+                order: Order::Asc,
             },
         }
     }
@@ -125,6 +195,32 @@ impl<RT: Runtime> TableIterator<RT> {
         self
     }
 
+    /// Stop iterating once `cancellation` is canceled. The token is checked
+    /// between pages, and also interrupts a page that's being fetched. The
+    /// stream then fails with a [`TableIterationInterrupted`] error.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.inner.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Fail with a [`TableIterationInterrupted`] error if fetching any single
+    /// page (including its retries) takes longer than `page_timeout`.
+    pub fn with_page_timeout(mut self, page_timeout: Duration) -> Self {
+        self.inner.page_timeout = Some(page_timeout);
+        self
+    }
+
+    /// Walk tables in `order` of the index being iterated, e.g. newest
+    /// documents first with `Order::Desc` on `by_id`. Both orders return the
+    /// documents as of the same snapshot and read the document log the same
+    /// way, so they have the same retention requirements. Cursors, including
+    /// the one in a [`TableIterationInterrupted`] error, are only meaningful to
+    /// an iterator with the same order.
+    pub fn with_order(mut self, order: Order) -> Self {
+        self.inner.order = order;
+        self
+    }
+
     /// Create a `MultiTableIterator`, which can iterate multiple tables at the
     /// same snapshot timestamp. This is more efficient than creating a separate
     /// `TableIterator` for each table since each table can share the work of
@@ -225,6 +321,9 @@ struct TableIteratorInner<RT: Runtime> {
     retention_validator: Arc<dyn RetentionValidator>,
     page_size: usize,
     snapshot_ts: RepeatableTimestamp,
+    cancellation: Option<CancellationToken>,
+    page_timeout: Option<Duration>,
+    order: Order,
 }
 pub struct MultiTableIterator<RT: Runtime> {
     inner: TableIteratorInner<RT>,
@@ -378,13 +477,14 @@ impl<RT: Runtime> MultiTableIterator<RT> {
             );
         }
 
+        let order = self.inner.order;
         loop {
             let pause_client = self.inner.runtime.pause_client();
             pause_client.wait("before_index_page").await;
             let page_start = cursor.index_key.clone();
             let (page, new_end_ts) = self
                 .inner
-                .fetch_page(index_id, tablet_id, &mut cursor)
+                .fetch_page_interruptible(index_id, tablet_id, &mut cursor)
                 .await?;
             anyhow::ensure!(*new_end_ts >= self.end_ts);
             let page_end = cursor
@@ -418,18 +518,22 @@ impl<RT: Runtime> MultiTableIterator<RT> {
                     &mut self.buffered_documents,
                 )
                 .await?;
-            if let Some((first_skipped_key, _)) = skipped_keys.iter().next() {
+            if let Some(first_skipped_key) = skipped_keys.first_key(order) {
                 // Check all skipped ids are after the old cursor,
                 // which ensures the yielded output is in index key order.
-                anyhow::ensure!(!cursor_has_walked(page_start.as_ref(), first_skipped_key));
+                anyhow::ensure!(!cursor_has_walked(
+                    page_start.as_ref(),
+                    first_skipped_key,
+                    order
+                ));
             }
             self.end_ts = new_end_ts;
             // Extract the documents from skipped_keys that should be returned in
             // the current page.
             let page_skipped_keys = {
                 let mut page_skipped_keys = BTreeMap::new();
-                while let Some(first_skipped_key) = skipped_keys.keys().next()
-                    && cursor_has_walked(Some(page_end), first_skipped_key)
+                while let Some(first_skipped_key) = skipped_keys.first_key(order)
+                    && cursor_has_walked(Some(page_end), first_skipped_key, order)
                 {
                     let (key, value) = skipped_keys
                         .remove(&first_skipped_key.clone())
@@ -459,13 +563,15 @@ impl<RT: Runtime> MultiTableIterator<RT> {
             );
             anyhow::ensure!(
                 merged_page.keys().all(|key| {
-                    !cursor_has_walked(page_start.as_ref(), key)
-                        && cursor_has_walked(Some(page_end), key)
+                    !cursor_has_walked(page_start.as_ref(), key, order)
+                        && cursor_has_walked(Some(page_end), key, order)
                 }),
                 "document outside page in table iterator {merged_page:?}"
             );
 
-            let mut merged_page_docs = self.inner.reload_revisions_at_snapshot_ts(merged_page);
+            let mut merged_page_docs = self
+                .inner
+                .reload_revisions_at_snapshot_ts(merged_page, order);
             while let Some((key, rev)) = merged_page_docs.try_next().await? {
                 // The caller will likely consume the documents in a CPU-intensive loop,
                 // and `merged_page_docs.try_next().await` will often be Ready
@@ -487,7 +593,7 @@ impl<RT: Runtime> MultiTableIterator<RT> {
 impl<RT: Runtime> TableIteratorInner<RT> {
     /// A document may be skipped if:
     /// 1. it is in the correct table
-    /// 2. at the snapshot, it had a key higher than what we've walked so far
+    /// 2. at the snapshot, it had a key we haven't walked past so far
     /// 3. it was modified after the snapshot but before we walked its key
     /// range.
     #[fastrace::trace(properties = {"start_ts": "{start_ts}", "end_ts": "{end_ts}"})]
@@ -514,7 +620,7 @@ impl<RT: Runtime> TableIteratorInner<RT> {
         pin_mut!(revisions_at_snapshot);
         while let Some(rev) = revisions_at_snapshot.try_next().await? {
             let index_key = rev.value.index_key(indexed_fields).to_bytes();
-            if !cursor_has_walked(lower_bound, &index_key) {
+            if !cursor_has_walked(lower_bound, &index_key, self.order) {
                 output.insert(index_key, rev.ts, rev.value, rev.prev_ts);
             }
         }
@@ -582,6 +688,53 @@ impl<RT: Runtime> TableIteratorInner<RT> {
         ))
     }
 
+    /// `fetch_page`, but stops early if the iterator is canceled or the page
+    /// times out. The cursor is only advanced if the page is fetched.
+    async fn fetch_page_interruptible(
+        &self,
+        index_id: IndexId,
+        tablet_id: TabletId,
+        cursor: &mut TableScanCursor,
+    ) -> anyhow::Result<(Vec<(IndexKeyBytes, LatestDocument)>, RepeatableTimestamp)> {
+        let interruption = TableIterationInterrupted {
+            reason: InterruptReason::Canceled,
+            cursor: cursor.index_key.clone(),
+        };
+        if let Some(cancellation) = &self.cancellation
+            && cancellation.is_cancelled()
+        {
+            anyhow::bail!(interruption);
+        }
+        let canceled = async {
+            match &self.cancellation {
+                Some(cancellation) => cancellation.cancelled().await,
+                None => future::pending().await,
+            }
+        }
+        .fuse();
+        let timed_out = async {
+            match self.page_timeout {
+                Some(page_timeout) => {
+                    self.runtime.wait(page_timeout).await;
+                    page_timeout
+                },
+                None => future::pending().await,
+            }
+        }
+        .fuse();
+        let fetch = self.fetch_page(index_id, tablet_id, cursor).fuse();
+        pin_mut!(canceled, timed_out, fetch);
+        select_biased! {
+            _ = canceled => Err(interruption.into()),
+            page_timeout = timed_out => Err(TableIterationInterrupted {
+                reason: InterruptReason::PageTimeout(page_timeout),
+                ..interruption
+            }
+            .into()),
+            result = fetch => result,
+        }
+    }
+
     #[fastrace::trace]
     async fn fetch_page(
         &self,
@@ -600,8 +753,8 @@ impl<RT: Runtime> TableIteratorInner<RT> {
             let stream = reader.index_scan(
                 index_id,
                 tablet_id,
-                &cursor.interval(),
-                Order::Asc,
+                &cursor.interval_in_order(self.order),
+                self.order,
                 self.page_size,
             );
             let documents_in_page: Vec<_> = match stream.take(self.page_size).try_collect().await {
@@ -616,9 +769,9 @@ impl<RT: Runtime> TableIteratorInner<RT> {
                 Err(e) => return Err(e),
             };
             if documents_in_page.len() < self.page_size {
-                cursor.advance(CursorPosition::End)?;
+                cursor.advance_in_order(CursorPosition::End, self.order)?;
             } else if let Some((index_key, ..)) = documents_in_page.last() {
-                cursor.advance(CursorPosition::After(index_key.clone()))?;
+                cursor.advance_in_order(CursorPosition::After(index_key.clone()), self.order)?;
             }
             return Ok((documents_in_page, ts));
         }
@@ -709,11 +862,17 @@ impl<RT: Runtime> TableIteratorInner<RT> {
     }
 
     /// Like `load_revisions_at_snapshot_ts` but doesn't need to fetch
-    /// if the IterationDocument has the Full document.
+    /// if the IterationDocument has the Full document. Documents are yielded
+    /// in index key `order`.
     #[try_stream(boxed, ok = (IndexKeyBytes, LatestDocument), error = anyhow::Error)]
-    async fn reload_revisions_at_snapshot_ts(&self, documents: IterationDocuments) {
+    async fn reload_revisions_at_snapshot_ts(&self, documents: IterationDocuments, order: Order) {
+        let mut documents: Vec<_> = documents.into_iter().collect();
+        match order {
+            Order::Asc => {},
+            Order::Desc => documents.reverse(),
+        }
         let mut current_batch = Vec::new();
-        for (key, (ts, doc)) in documents.into_iter() {
+        for (key, (ts, doc)) in documents {
             match doc {
                 IterationDocument::Full { value, prev_ts } => {
                     let mut flush = self.load_index_entries_at_snapshot_ts(current_batch);
@@ -799,6 +958,14 @@ impl IterationDocuments {
         }
     }
 
+    /// The first key that an iteration in `order` reaches.
+    fn first_key(&self, order: Order) -> Option<&IndexKeyBytes> {
+        match order {
+            Order::Asc => self.docs.keys().next(),
+            Order::Desc => self.docs.keys().next_back(),
+        }
+    }
+
     fn remove(
         &mut self,
         index_key: &IndexKeyBytes,
@@ -828,3 +995,414 @@ impl Deref for IterationDocuments {
         &self.docs
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{
+            BTreeMap,
+            BTreeSet,
+        },
+        sync::Arc,
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use common::{
+        bootstrap_model::index::database_index::IndexedFields,
+        document::{
+            CreationTime,
+            ResolvedDocument,
+        },
+        index::{
+            IndexKey,
+            IndexKeyBytes,
+        },
+        interval::Interval,
+        persistence::{
+            DocumentLogEntry,
+            DocumentPrevTsQuery,
+            DocumentStream,
+            IndexStream,
+            LatestDocument,
+            NoopRetentionValidator,
+            PersistenceGlobalKey,
+            PersistenceReader,
+            RetentionValidator,
+            TimestampRange,
+        },
+        query::{
+            CursorPosition,
+            Order,
+        },
+        types::{
+            IndexId,
+            PersistenceVersion,
+            RepeatableReason,
+            RepeatableTimestamp,
+            Timestamp,
+        },
+        value::ResolvedDocumentId,
+    };
+    use futures::{
+        pin_mut,
+        stream,
+        StreamExt,
+        TryStreamExt,
+    };
+    use runtime::testing::TestRuntime;
+    use serde_json::Value as JsonValue;
+    use tokio_util::sync::CancellationToken;
+    use value::{
+        ConvexObject,
+        DeveloperDocumentId,
+        InternalDocumentId,
+        InternalId,
+        TableNumber,
+        TabletId,
+    };
+
+    use super::{
+        cursor_has_walked,
+        InterruptReason,
+        TableIterationInterrupted,
+        TableIterator,
+        TableScanCursor,
+    };
+
+    fn keys(n: u8) -> Vec<IndexKeyBytes> {
+        (0..n).map(|i| IndexKeyBytes(vec![i, 0xff])).collect()
+    }
+
+    /// Pages through `keys` in `order` the way `fetch_page` walks an index,
+    /// stopping after `max_pages` pages if given.
+    fn walk(
+        keys: &[IndexKeyBytes],
+        order: Order,
+        page_size: usize,
+        cursor: &mut TableScanCursor,
+        max_pages: Option<usize>,
+    ) -> anyhow::Result<Vec<IndexKeyBytes>> {
+        let mut walked = Vec::new();
+        let mut pages = 0;
+        while !matches!(cursor.index_key, Some(CursorPosition::End))
+            && max_pages.is_none_or(|max_pages| pages < max_pages)
+        {
+            let interval = cursor.interval_in_order(order);
+            let mut page: Vec<_> = keys
+                .iter()
+                .filter(|key| interval.contains(&key.0))
+                .cloned()
+                .collect();
+            match order {
+                Order::Asc => {},
+                Order::Desc => page.reverse(),
+            }
+            page.truncate(page_size);
+            for key in &page {
+                assert!(!cursor_has_walked(cursor.index_key.as_ref(), key, order));
+            }
+            match page.last() {
+                Some(last) if page.len() == page_size => {
+                    cursor.advance_in_order(CursorPosition::After(last.clone()), order)?
+                },
+                _ => cursor.advance_in_order(CursorPosition::End, order)?,
+            }
+            for key in &page {
+                assert!(cursor_has_walked(cursor.index_key.as_ref(), key, order));
+            }
+            walked.extend(page);
+            pages += 1;
+        }
+        Ok(walked)
+    }
+
+    #[test]
+    fn test_forward_and_reverse_walk_the_same_keys() -> anyhow::Result<()> {
+        let keys = keys(10);
+        let mut reversed = keys.clone();
+        reversed.reverse();
+        for page_size in [1, 3, 10, 11] {
+            let forward = walk(
+                &keys,
+                Order::Asc,
+                page_size,
+                &mut TableScanCursor::default(),
+                None,
+            )?;
+            let reverse = walk(
+                &keys,
+                Order::Desc,
+                page_size,
+                &mut TableScanCursor::default(),
+                None,
+            )?;
+            assert_eq!(forward, keys, "page size {page_size}");
+            assert_eq!(reverse, reversed, "page size {page_size}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_reverse_walk_resumes_from_cursor() -> anyhow::Result<()> {
+        let keys = keys(10);
+        let mut cursor = TableScanCursor::default();
+        let mut walked = walk(&keys, Order::Desc, 3, &mut cursor, Some(2))?;
+        assert_eq!(
+            cursor.index_key,
+            Some(CursorPosition::After(keys[4].clone()))
+        );
+
+        // Resume from a copy, like an iterator restarted after
+        // `TableIterationInterrupted`.
+        let mut resumed = cursor.clone();
+        walked.extend(walk(&keys, Order::Desc, 3, &mut resumed, None)?);
+        let mut reversed = keys.clone();
+        reversed.reverse();
+        assert_eq!(walked, reversed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reverse_cursor_only_advances_downwards() -> anyhow::Result<()> {
+        let keys = keys(10);
+        let mut cursor = TableScanCursor::default();
+        cursor.advance_in_order(CursorPosition::After(keys[5].clone()), Order::Desc)?;
+        assert!(cursor
+            .advance_in_order(CursorPosition::After(keys[7].clone()), Order::Desc)
+            .is_err());
+        assert!(cursor
+            .advance_in_order(CursorPosition::After(keys[5].clone()), Order::Desc)
+            .is_err());
+        cursor.advance_in_order(CursorPosition::After(keys[2].clone()), Order::Desc)?;
+        cursor.advance_in_order(CursorPosition::End, Order::Desc)?;
+        assert!(cursor
+            .advance_in_order(CursorPosition::After(keys[0].clone()), Order::Desc)
+            .is_err());
+        Ok(())
+    }
+
+    const TABLET_ID: TabletId = TabletId(InternalId([1; 16]));
+    const BY_ID: IndexId = IndexId(InternalId([2; 16]));
+
+    /// A table whose `by_id` index holds `documents`, all written before the
+    /// snapshot. The document log is empty. While `stalled` is set, index
+    /// scans never return.
+    struct IndexOnlyPersistence {
+        documents: Vec<(IndexKeyBytes, LatestDocument)>,
+        stalled: bool,
+    }
+
+    #[async_trait]
+    impl PersistenceReader for IndexOnlyPersistence {
+        fn load_documents(
+            &self,
+            _range: TimestampRange,
+            _order: Order,
+            _page_size: u32,
+            _retention_validator: Arc<dyn RetentionValidator>,
+        ) -> DocumentStream<'_> {
+            stream::empty().boxed()
+        }
+
+        async fn previous_revisions(
+            &self,
+            _ids: BTreeSet<(InternalDocumentId, Timestamp)>,
+            _retention_validator: Arc<dyn RetentionValidator>,
+        ) -> anyhow::Result<BTreeMap<(InternalDocumentId, Timestamp), DocumentLogEntry>> {
+            Ok(BTreeMap::new())
+        }
+
+        async fn previous_revisions_of_documents(
+            &self,
+            _ids: BTreeSet<DocumentPrevTsQuery>,
+            _retention_validator: Arc<dyn RetentionValidator>,
+        ) -> anyhow::Result<BTreeMap<DocumentPrevTsQuery, DocumentLogEntry>> {
+            Ok(BTreeMap::new())
+        }
+
+        fn index_scan(
+            &self,
+            _index_id: IndexId,
+            _tablet_id: TabletId,
+            _read_timestamp: Timestamp,
+            range: &Interval,
+            order: Order,
+            _size_hint: usize,
+            _retention_validator: Arc<dyn RetentionValidator>,
+        ) -> IndexStream<'_> {
+            if self.stalled {
+                return stream::pending().boxed();
+            }
+            let mut page: Vec<_> = self
+                .documents
+                .iter()
+                .filter(|(key, _)| range.contains(&key.0))
+                .cloned()
+                .map(anyhow::Ok)
+                .collect();
+            match order {
+                Order::Asc => {},
+                Order::Desc => page.reverse(),
+            }
+            stream::iter(page).boxed()
+        }
+
+        async fn get_persistence_global(
+            &self,
+            _key: PersistenceGlobalKey,
+        ) -> anyhow::Result<Option<JsonValue>> {
+            Ok(None)
+        }
+
+        fn version(&self) -> PersistenceVersion {
+            PersistenceVersion::V5
+        }
+    }
+
+    fn persistence(num_documents: u8, stalled: bool) -> anyhow::Result<IndexOnlyPersistence> {
+        let table_number = TableNumber::try_from(10001)?;
+        let documents = (0..num_documents)
+            .map(|i| {
+                let id = ResolvedDocumentId::new(
+                    TABLET_ID,
+                    DeveloperDocumentId::new(table_number, InternalId([i; 16])),
+                );
+                let value =
+                    ResolvedDocument::new(id, CreationTime::try_from(1.)?, ConvexObject::empty())?;
+                let key = IndexKey::new(vec![], id.into()).to_bytes();
+                let document = LatestDocument {
+                    ts: Timestamp::must(1),
+                    value,
+                    prev_ts: None,
+                };
+                Ok((key, document))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(IndexOnlyPersistence { documents, stalled })
+    }
+
+    fn table_iterator(
+        rt: TestRuntime,
+        persistence: &Arc<IndexOnlyPersistence>,
+    ) -> TableIterator<TestRuntime> {
+        let snapshot_ts = RepeatableTimestamp::new_validated(
+            Timestamp::must(10),
+            RepeatableReason::MaxRepeatableTsPersistence,
+        );
+        TableIterator::new(
+            rt,
+            snapshot_ts,
+            persistence.clone(),
+            Arc::new(NoopRetentionValidator),
+            2,
+        )
+    }
+
+    /// Iterates the table from `cursor`, canceling `cancellation` once
+    /// `cancel_after` documents have been yielded.
+    async fn iterate(
+        iterator: TableIterator<TestRuntime>,
+        cursor: Option<CursorPosition>,
+        cancellation: &CancellationToken,
+        cancel_after: usize,
+    ) -> (Vec<IndexKeyBytes>, anyhow::Result<()>) {
+        let stream = iterator
+            .with_cancellation(cancellation.clone())
+            .stream_documents_in_table_by_index(TABLET_ID, BY_ID, IndexedFields::by_id(), cursor);
+        pin_mut!(stream);
+        let mut walked = Vec::new();
+        loop {
+            match stream.try_next().await {
+                Ok(Some((key, _))) => {
+                    walked.push(key);
+                    if walked.len() == cancel_after {
+                        cancellation.cancel();
+                    }
+                },
+                Ok(None) => return (walked, Ok(())),
+                Err(e) => return (walked, Err(e)),
+            }
+        }
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_cancel_mid_iteration(rt: TestRuntime) -> anyhow::Result<()> {
+        let persistence = Arc::new(persistence(10, false)?);
+        let (walked, result) = iterate(
+            table_iterator(rt, &persistence),
+            None,
+            &CancellationToken::new(),
+            3,
+        )
+        .await;
+        let err = result.unwrap_err();
+        let interrupted = err
+            .downcast_ref::<TableIterationInterrupted>()
+            .expect("iteration should be interrupted");
+        assert!(matches!(interrupted.reason, InterruptReason::Canceled));
+        // Cancellation is noticed between pages, so the page that was already
+        // fetched is finished first.
+        assert_eq!(walked.len(), 4);
+        assert_eq!(
+            interrupted.cursor,
+            Some(CursorPosition::After(walked[3].clone()))
+        );
+        Ok(())
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_page_timeout(rt: TestRuntime) -> anyhow::Result<()> {
+        let persistence = Arc::new(persistence(10, true)?);
+        let stream = table_iterator(rt, &persistence)
+            .with_page_timeout(Duration::from_secs(1))
+            .stream_documents_in_table_by_index(TABLET_ID, BY_ID, IndexedFields::by_id(), None);
+        pin_mut!(stream);
+        let err = stream.try_next().await.unwrap_err();
+        let interrupted = err
+            .downcast_ref::<TableIterationInterrupted>()
+            .expect("iteration should be interrupted");
+        assert!(matches!(
+            interrupted.reason,
+            InterruptReason::PageTimeout(timeout) if timeout == Duration::from_secs(1)
+        ));
+        // Nothing was fetched, so resuming starts from the beginning.
+        assert_eq!(interrupted.cursor, None);
+        Ok(())
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_resume_from_interrupted_cursor(rt: TestRuntime) -> anyhow::Result<()> {
+        let persistence = Arc::new(persistence(10, false)?);
+        let all_keys: Vec<_> = persistence
+            .documents
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        let mut walked = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (page, result) = iterate(
+                table_iterator(rt.clone(), &persistence),
+                cursor.clone(),
+                &CancellationToken::new(),
+                1,
+            )
+            .await;
+            walked.extend(page);
+            match result {
+                Ok(()) => break,
+                Err(e) => {
+                    let interrupted = e
+                        .downcast_ref::<TableIterationInterrupted>()
+                        .expect("iteration should be interrupted");
+                    cursor = interrupted.cursor.clone();
+                },
+            }
+        }
+        // Every restart picks up right after the last document yielded.
+        assert_eq!(walked, all_keys);
+        Ok(())
+    }
+}
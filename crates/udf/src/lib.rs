@@ -36,7 +36,11 @@ pub use crate::{
         HTTP_ACTION_BODY_LIMIT,
     },
     syscall_stats::SyscallStats,
-    syscall_trace::SyscallTrace,
+    syscall_trace::{
+        NamedSyscallStats,
+        SyscallTrace,
+        SyscallTraceSummary,
+    },
     udf_outcome::{
         NestedUdfOutcome,
         UdfOutcome,
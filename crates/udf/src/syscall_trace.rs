@@ -1,4 +1,5 @@
 use std::{
+    cmp::Reverse,
     collections::BTreeMap,
     time::Duration,
 };
@@ -90,6 +91,70 @@ impl SyscallTrace {
                 .mutate_entry_or_default(name.clone(), |s| s.merge(syscall));
         }
     }
+
+    /// Rolls the trace up into totals and the `limit` syscalls with the most
+    /// invocations and the most total time, e.g. to explain why a function
+    /// was slow.
+    pub fn summary(&self, limit: usize) -> SyscallTraceSummary {
+        SyscallTraceSummary {
+            invocations: self
+                .async_syscalls
+                .values()
+                .map(|stats| u64::from(stats.invocations))
+                .sum(),
+            errors: self
+                .async_syscalls
+                .values()
+                .map(|stats| u64::from(stats.errors))
+                .sum(),
+            total_duration: self
+                .async_syscalls
+                .values()
+                .map(|stats| stats.total_duration)
+                .sum(),
+            top_by_invocations: self.top_syscalls(limit, |stats| stats.invocations),
+            top_by_duration: self.top_syscalls(limit, |stats| stats.total_duration),
+        }
+    }
+
+    fn top_syscalls<K: Ord>(
+        &self,
+        limit: usize,
+        key: impl Fn(&SyscallStats) -> K,
+    ) -> Vec<NamedSyscallStats> {
+        let mut syscalls: Vec<_> = self
+            .async_syscalls
+            .iter()
+            .map(|(name, stats)| NamedSyscallStats {
+                name: name.clone(),
+                stats: stats.clone(),
+            })
+            .collect();
+        // The sort is stable, so ties stay in name order.
+        syscalls.sort_by_key(|syscall| Reverse(key(&syscall.stats)));
+        syscalls.truncate(limit);
+        syscalls
+    }
+}
+
+/// Totals across every syscall in a [SyscallTrace], plus its busiest
+/// syscalls. See [SyscallTrace::summary].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SyscallTraceSummary {
+    pub invocations: u64,
+    pub errors: u64,
+    pub total_duration: Duration,
+    /// Syscalls with the most invocations, most first.
+    pub top_by_invocations: Vec<NamedSyscallStats>,
+    /// Syscalls with the most total time, most first.
+    pub top_by_duration: Vec<NamedSyscallStats>,
+}
+
+/// One syscall's stats in a [SyscallTraceSummary].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NamedSyscallStats {
+    pub name: String,
+    pub stats: SyscallStats,
 }
 
 impl From<SyscallTrace> for JsonValue {
@@ -103,3 +168,96 @@ impl From<SyscallTrace> for JsonValue {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{
+        NamedSyscallStats,
+        SyscallTrace,
+    };
+
+    fn trace(calls: &[(&str, u64, bool)]) -> SyscallTrace {
+        let mut trace = SyscallTrace::new();
+        for &(name, millis, is_success) in calls {
+            trace.log_async_syscall(name.to_string(), Duration::from_millis(millis), is_success);
+        }
+        trace
+    }
+
+    fn names(syscalls: &[NamedSyscallStats]) -> Vec<&str> {
+        syscalls
+            .iter()
+            .map(|syscall| syscall.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_summary_totals() {
+        let summary = trace(&[
+            ("1.0/db/get", 5, true),
+            ("1.0/db/get", 7, false),
+            ("1.0/storage/get", 20, true),
+        ])
+        .summary(10);
+        assert_eq!(summary.invocations, 3);
+        assert_eq!(summary.errors, 1);
+        assert_eq!(summary.total_duration, Duration::from_millis(32));
+        let db_get = &summary.top_by_invocations[0];
+        assert_eq!(db_get.name, "1.0/db/get");
+        assert_eq!(db_get.stats.invocations, 2);
+        assert_eq!(db_get.stats.errors, 1);
+        assert_eq!(db_get.stats.total_duration, Duration::from_millis(12));
+    }
+
+    #[test]
+    fn test_summary_orders_by_invocations_and_duration() {
+        let summary = trace(&[
+            ("a", 1, true),
+            ("a", 1, true),
+            ("a", 1, true),
+            ("b", 50, true),
+            ("c", 10, true),
+            ("c", 10, true),
+        ])
+        .summary(10);
+        assert_eq!(names(&summary.top_by_invocations), ["a", "c", "b"]);
+        assert_eq!(names(&summary.top_by_duration), ["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_summary_ties_are_in_name_order() {
+        let summary = trace(&[("c", 10, true), ("a", 10, true), ("b", 10, true)]).summary(10);
+        assert_eq!(names(&summary.top_by_invocations), ["a", "b", "c"]);
+        assert_eq!(names(&summary.top_by_duration), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_summary_truncates_to_limit() {
+        let trace = trace(&[
+            ("a", 1, true),
+            ("b", 2, true),
+            ("b", 2, true),
+            ("c", 3, true),
+            ("c", 3, true),
+            ("c", 3, true),
+        ]);
+        let summary = trace.summary(2);
+        assert_eq!(names(&summary.top_by_invocations), ["c", "b"]);
+        assert_eq!(names(&summary.top_by_duration), ["c", "b"]);
+        // Totals still cover every syscall.
+        assert_eq!(summary.invocations, 6);
+        assert!(trace.summary(0).top_by_invocations.is_empty());
+        assert_eq!(trace.summary(5).top_by_duration.len(), 3);
+    }
+
+    #[test]
+    fn test_empty_trace_summary() {
+        let summary = SyscallTrace::new().summary(5);
+        assert_eq!(summary.invocations, 0);
+        assert_eq!(summary.total_duration, Duration::ZERO);
+        assert!(summary.top_by_invocations.is_empty());
+        assert!(summary.top_by_duration.is_empty());
+    }
+}
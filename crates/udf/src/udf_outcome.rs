@@ -170,6 +170,24 @@ impl UdfOutcome {
     }
 
     pub(crate) fn from_proto(
+        outcome: UdfOutcomeProto,
+        path_and_args: ValidatedPathAndArgs,
+        identity: InertIdentity,
+    ) -> anyhow::Result<Self> {
+        let (path, arguments, udf_server_version) = path_and_args.consume();
+        Self::from_proto_with_path(
+            outcome,
+            path.for_logging(),
+            arguments,
+            identity,
+            udf_server_version,
+        )
+    }
+
+    /// Like `from_proto`, for outcomes that were serialized along with the
+    /// path and arguments they're logged with rather than a
+    /// `ValidatedPathAndArgs`.
+    pub fn from_proto_with_path(
         UdfOutcomeProto {
             rng_seed,
             observed_rng,
@@ -184,8 +202,10 @@ impl UdfOutcome {
             memory_in_mb,
             user_execution_time,
         }: UdfOutcomeProto,
-        path_and_args: ValidatedPathAndArgs,
+        path: CanonicalizedComponentFunctionPath,
+        arguments: SerializedArgs,
         identity: InertIdentity,
+        udf_server_version: Option<semver::Version>,
     ) -> anyhow::Result<Self> {
         let rng_seed = rng_seed.context("Missing rng_seed")?;
         let rng_seed = rng_seed
@@ -200,14 +220,13 @@ impl UdfOutcome {
             Some(FunctionResultTypeProto::JsError(js_error)) => Err(js_error.try_into()?),
             None => anyhow::bail!("Missing result"),
         };
-        let (path, arguments, udf_server_version) = path_and_args.consume();
         let log_lines = log_lines.into_iter().map(LogLine::try_from).try_collect()?;
         let audit_log_lines = audit_log_lines
             .into_iter()
             .map(AuditLogLine::try_from)
             .try_collect()?;
         Ok(Self {
-            path: path.for_logging(),
+            path,
             arguments,
             identity,
             rng_seed,